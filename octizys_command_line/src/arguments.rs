@@ -41,6 +41,46 @@ pub enum AvailableRenderers {
     AnsiC24,
 }
 
+#[derive(
+    ValueEnum, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum EmitKind {
+    #[clap(
+        name = "tokens",
+        help = "The tokens of the file, one per line with its position."
+    )]
+    Tokens,
+    #[clap(name = "cst", help = "The Concrete Syntax Tree of the file.")]
+    Cst,
+    #[clap(
+        name = "sast",
+        help = "The Simplified Abstract Syntax Tree of the file after the type checking."
+    )]
+    Sast,
+    #[clap(
+        name = "core",
+        help = "The core module of the file, the prelude included."
+    )]
+    Core,
+    #[clap(
+        name = "artefact",
+        help = "A compiled module that the run command can load."
+    )]
+    Artefact,
+}
+
+#[derive(
+    ValueEnum, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum DocumentationFormatKind {
+    #[clap(name = "html", help = "Static HTML pages.")]
+    Html,
+    #[clap(name = "markdown", help = "Markdown pages.")]
+    Markdown,
+    #[clap(name = "all", help = "Both HTML and Markdown pages.")]
+    All,
+}
+
 #[derive(Parser, Clone, Debug)]
 pub struct FormatterConfiguration {
     #[arg(
@@ -112,6 +152,12 @@ becomes
 This also disables the color output."
     )]
     pub use_machine_representation: bool,
+    #[arg(
+        long = "json",
+        help = "Emit the errors as JSON objects (one per line), including the suggested fixes.
+This takes precedence over the machine representation."
+    )]
+    pub use_json_diagnostics: bool,
     #[arg(
         long = "advanced-errors",
         help = "With this option all the errors become more technical and less beginner friendly."
@@ -136,6 +182,20 @@ If you want to disable colors at all use: plain."
 Formatter will try to respect this as much as we can."
     )]
     pub column_width: usize,
+    #[arg(
+        long = "tab-width",
+        default_value = "4",
+        help = "The amount of columns between two tab stops, used to compute the width of the text.
+If 0 we would use 4."
+    )]
+    pub tab_width: usize,
+    #[arg(
+        long = "source-root",
+        name = "SOURCE_ROOT",
+        help = "A directory where we look for the imported modules, it can be given more than once.
+The module a::b::c is the file a/b/c.oct inside one of them, the directory of the compiled file is searched last."
+    )]
+    pub source_roots: Vec<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -148,10 +208,25 @@ pub enum Commands {
         #[arg(
             short = 'p',
             long = "path",
-            default_value = "a.out",
-            help = "A file to place the compilation output."
+            help = "A file to place the compilation output.
+By default the artefact goes next to the source with the octc extension and the other outputs go to standard out."
         )]
         output: Option<PathBuf>,
+        #[arg(
+            long = "emit",
+            default_value = "artefact",
+            help = "The stage of the compilation to output.
+Everything but the artefact is a dump meant for bug reports, it respects the renderer and the column width."
+        )]
+        emit: EmitKind,
+    },
+    #[command(name = "run")]
+    #[command(about = "Evaluate the main definition of a file.")]
+    Run {
+        #[arg(
+            help = "A file with a main definition or a module compiled to a artefact."
+        )]
+        path: PathBuf,
     },
     #[command(name = "format")]
     // TODO: allow format of multiple directories and files.
@@ -167,12 +242,60 @@ pub enum Commands {
         )]
         output: Option<PathBuf>,
     },
+    #[command(name = "fix")]
+    #[command(
+        about = "Apply the fixes that the compiler is sure about to a file."
+    )]
+    Fix {
+        #[arg(help = "A file to be fixed.")]
+        path: PathBuf,
+        #[arg(
+            short = 'o',
+            long = "output",
+            help = "A file to place the fixed code, if not provided we overwrite the original file."
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(name = "doc")]
+    #[command(
+        about = "Generate the documentation of a file and the modules it imports."
+    )]
+    Doc {
+        #[arg(help = "The entry file of the program.")]
+        path: PathBuf,
+        #[arg(
+            short = 'o',
+            long = "output",
+            default_value = "doc",
+            help = "The folder to place the pages, it is created if needed."
+        )]
+        output: PathBuf,
+        #[arg(
+            long = "format",
+            default_value = "all",
+            help = "The format of the pages."
+        )]
+        format: DocumentationFormatKind,
+        #[arg(
+            long = "private",
+            help = "Document the items that aren't public too."
+        )]
+        private: bool,
+    },
+    #[command(name = "test")]
+    #[command(about = "Run the tests of a file and the modules it imports.")]
+    Test {
+        #[arg(help = "The entry file of the program.")]
+        path: PathBuf,
+        #[arg(
+            long = "doc",
+            help = "Run the examples of the documentation comments."
+        )]
+        doc: bool,
+    },
     #[command(name = "repl")]
     #[command(about = "Start a REPL (Read Eval Print Loop) for octizys")]
     REPL {
-        // TODO: Add other commands like:
-        // - Load file
-        // - History (with maximum history and path to store it.)
         // TODO: FIXME Sanitize prompt, it cannot have line breaks (just because is weird).
         #[arg(
             short = 'p',
@@ -181,6 +304,22 @@ pub enum Commands {
             help = "The text that appears at the beginning of every line in the repl."
         )]
         prompt: String,
+        #[arg(
+            long = "history",
+            help = "A file to store the history of the repl, by default we use ~/.octizys_history ."
+        )]
+        history: Option<PathBuf>,
+        #[arg(
+            long = "history-size",
+            default_value = "1000",
+            help = "The maximum amount of entries to keep in the history."
+        )]
+        history_size: usize,
+        #[arg(
+            long = "no-history",
+            help = "Don't read or write the history file."
+        )]
+        no_history: bool,
     },
 }
 
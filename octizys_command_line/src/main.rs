@@ -1,30 +1,52 @@
 mod arguments;
+mod pipeline;
+mod repl;
 
-use arguments::FormatterConfiguration;
+use arguments::{
+    AvailableRenderers, DocumentationFormatKind, EmitKind,
+    FormatterConfiguration,
+};
 use clap::Parser;
+use octizys_common::dump::Dump;
 use octizys_common::equivalence::Equivalence;
 use octizys_common::report::{
-    create_error_report, IOError, ReportTarget, ReportUserKind,
+    apply_suggestions, create_error_report, create_json_report, Applicability,
+    IOError, ReportFormat, ReportRequest, ReportTarget, ReportUserKind,
+    Suggestion,
+};
+use octizys_common::source_map::{FileId, SourceMap};
+use octizys_core::artefact::{
+    decode_module, encode_module, ARTEFACT_EXTENSION,
 };
+use octizys_core::checker::check_module;
+use octizys_core::evaluation::{with_stack, DEFAULT_MAX_DEPTH};
 use octizys_cst::top::Top;
+use octizys_doc::configuration::{
+    DocumentationConfiguration, DocumentationFormat,
+};
+use octizys_doc::doctest::{module_examples, DoctestError, Example};
+use octizys_doc::documentation::generate_documentation;
+use octizys_doc::extraction::extract_documentation;
 use octizys_formatter::{cst::PrettyCSTConfiguration, to_document::ToDocument};
-use octizys_parser::parser::{parse_file, parse_string};
+use octizys_parser::parser::{
+    lex_source, parse_file, parse_overlay, parse_source, parse_string,
+};
 use octizys_pretty::{
-    combinators::{external_text, foreground},
+    combinators::{hard_break, intersperse},
     document::Document,
     highlight::{
-        base_colors::MODERATE_GREEN, EmptyRender, Highlight, HighlightRenderer,
-        TerminalRender24, TerminalRender4, TerminalRender8,
+        EmptyRender, Highlight, HighlightRenderer, TerminalRender24,
+        TerminalRender4, TerminalRender8,
     },
 };
-use octizys_text_store::store::Store;
+use octizys_sast::modules::interface;
+use octizys_sast::top::Sast;
+use octizys_text_store::{store::Store, width::set_tab_width};
 use simplelog;
 use std::cell::RefCell;
+use std::path::PathBuf;
+use std::process::ExitCode;
 use std::rc::Rc;
-use std::{
-    io::{self, Write},
-    path::PathBuf,
-};
 
 //TODO:
 //TEst the following :
@@ -57,6 +79,20 @@ struct GlobalOptions {
     highlight: fn(&Highlight) -> (String, String),
     pretty_configuration: PrettyCSTConfiguration,
     target: ReportTarget,
+    json_diagnostics: bool,
+    /// The directories with the imported modules.
+    source_roots: Vec<PathBuf>,
+}
+
+fn renderer_highlight(
+    renderer: AvailableRenderers,
+) -> fn(&Highlight) -> (String, String) {
+    match renderer {
+        AvailableRenderers::PlainText => EmptyRender::render_highlight,
+        AvailableRenderers::AnsiC4 => TerminalRender4::render_highlight,
+        AvailableRenderers::AnsiC8 => TerminalRender8::render_highlight,
+        AvailableRenderers::AnsiC24 => TerminalRender24::render_highlight,
+    }
 }
 
 impl From<FormatterConfiguration> for GlobalOptions {
@@ -74,20 +110,7 @@ impl From<FormatterConfiguration> for GlobalOptions {
                 separe_comments_by: value.separe_by,
                 compact_comments: value.compact_comments,
             };
-        let mut highlight = match value.renderer {
-            arguments::AvailableRenderers::PlainText => {
-                EmptyRender::render_highlight
-            }
-            arguments::AvailableRenderers::AnsiC4 => {
-                TerminalRender4::render_highlight
-            }
-            arguments::AvailableRenderers::AnsiC8 => {
-                TerminalRender8::render_highlight
-            }
-            arguments::AvailableRenderers::AnsiC24 => {
-                TerminalRender24::render_highlight
-            }
-        };
+        let mut highlight = renderer_highlight(value.renderer);
         let userkind = if value.use_advanced_errors {
             ReportUserKind::Advanced
         } else {
@@ -104,43 +127,209 @@ impl From<FormatterConfiguration> for GlobalOptions {
             highlight,
             pretty_configuration,
             target,
+            json_diagnostics: value.use_json_diagnostics,
+            source_roots: value.source_roots,
         }
     }
 }
 
-fn render_with<'store>(
+fn render_with(
     document: &Document,
     store: Rc<RefCell<Store>>,
     options: &GlobalOptions,
-) -> String
-where
-{
+) -> String {
     document.render_to_string(
         options.column_width,
         options.highlight,
-        &*(*store).borrow(),
+        &store.borrow(),
     )
 }
 
+/// Renders a report according to the user choice (human, machine or json).
+fn render_report<R: ReportFormat>(
+    request: &ReportRequest<R>,
+    store: Rc<RefCell<Store>>,
+    options: &GlobalOptions,
+) -> String {
+    if options.json_diagnostics {
+        create_json_report(request, &store.borrow())
+    } else {
+        let report = create_error_report(request);
+        render_with(&report, store, options)
+    }
+}
+
+/// Writes the output of a command, reporting the failures.
+/// Returns if it could write it.
+fn write_output(
+    destination: PathBuf,
+    content: &[u8],
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    if std::fs::write(&destination, content).is_err() {
+        let error = IOError::FileLoadError { path: destination };
+        let request = error.build_report_request(
+            options.target,
+            String::from("OctizysCommandLine"),
+            options.column_width,
+        );
+        eprintln!("{}", render_report(&request, store, options));
+        return false;
+    }
+    true
+}
+
+/// Writes a dump of the compiler to the output or to standard out.
+fn write_dump(
+    document: &Document,
+    output: Option<PathBuf>,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    let text = render_with(document, store.clone(), options) + "\n";
+    match output {
+        Some(destination) => {
+            write_output(destination, text.as_bytes(), options, store)
+        }
+        None => {
+            print!("{}", text);
+            true
+        }
+    }
+}
+
+/// Compiles the file or dumps one of its stages, returns if it
+/// succeeded.
 fn compile_file(
     source_path: PathBuf,
-    _output: Option<PathBuf>,
+    output: Option<PathBuf>,
+    emit: EmitKind,
     options: &GlobalOptions,
     store: Rc<RefCell<Store>>,
-) -> () {
-    match parse_file(source_path, store.clone()) {
-        Ok(_top) => (),
+) -> bool {
+    if emit != EmitKind::Tokens && emit != EmitKind::Cst {
+        return compile_program(source_path, output, emit, options, store);
+    }
+    let mut source_map = SourceMap::new();
+    let file = match source_map.load(&source_path) {
+        Ok(file) => file,
         Err(e) => {
             let request = e.build_report_request(
                 options.target,
                 String::from("OctizysCommandLine"),
                 options.column_width,
             );
-            let report = create_error_report(&request);
-            let report_str = render_with(&report, store, options);
-            eprintln!("{}", report_str)
+            let report_str = render_report(&request, store, options);
+            eprintln!("{}", report_str);
+            return false;
+        }
+    };
+    // The tokens are useful even if the file can't be parsed.
+    let document = if emit == EmitKind::Tokens {
+        lex_source(&source_map, file, store.clone()).map(|tokens| {
+            intersperse(tokens.iter().map(Dump::dump), hard_break())
+        })
+    } else {
+        parse_source(&source_map, file, store.clone())
+            .map(|top| top.represent())
+    };
+    match document {
+        Ok(document) => write_dump(&document, output, options, store),
+        Err(e) => {
+            pipeline::report_parser_error(&e, options, store);
+            false
+        }
+    }
+}
+
+/// The stages of the compilation after the parsing, they include
+/// the modules imported by the file. Returns if they succeeded.
+fn compile_program(
+    source_path: PathBuf,
+    output: Option<PathBuf>,
+    emit: EmitKind,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    let mut source_map = SourceMap::new();
+    let Some((graph, order)) = pipeline::load_program(
+        &source_path,
+        &mut source_map,
+        options,
+        store.clone(),
+    ) else {
+        return false;
+    };
+    let Some(program) = pipeline::check_program(
+        &graph,
+        &order,
+        &source_map,
+        options,
+        store.clone(),
+    ) else {
+        return false;
+    };
+    if emit == EmitKind::Sast {
+        return write_dump(&program.dump(), output, options, store);
+    }
+    let Some(module) =
+        pipeline::lower_program(&program, &source_map, options, store.clone())
+    else {
+        return false;
+    };
+    if emit == EmitKind::Artefact {
+        let destination = output
+            .unwrap_or_else(|| source_path.with_extension(ARTEFACT_EXTENSION));
+        let bytes = encode_module(&module);
+        return write_output(destination, &bytes, options, store);
+    }
+    write_dump(&module.dump(), output, options, store)
+}
+
+/// Runs a module compiled with the compile command, returns if it
+/// succeeded.
+fn run_artefact(
+    source_path: PathBuf,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    let bytes = match std::fs::read(&source_path) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let error = IOError::FileLoadError { path: source_path };
+            let request = error.build_report_request(
+                options.target,
+                String::from("OctizysCommandLine"),
+                options.column_width,
+            );
+            eprintln!("{}", render_report(&request, store, options));
+            return false;
         }
+    };
+    let module = match decode_module(&bytes) {
+        Ok(module) => module,
+        Err(error) => {
+            pipeline::report_without_source(
+                &*error,
+                Some(&source_path),
+                options,
+                store,
+            );
+            return false;
+        }
+    };
+    // The artefact may come from somewhere else, we don't trust it.
+    if let Err(error) = check_module(&module) {
+        pipeline::report_without_source(
+            &*error,
+            Some(&source_path),
+            options,
+            store,
+        );
+        return false;
     }
+    pipeline::run_module(&module, Some(&source_path), options, store).is_some()
 }
 
 fn check_idempotence(
@@ -177,7 +366,7 @@ fn format_file(
     _output: Option<PathBuf>,
     options: &GlobalOptions,
     store: Rc<RefCell<Store>>,
-) -> () {
+) {
     match parse_file(source_path, store.clone()) {
         Ok(top) => match check_idempotence(&top, options, store.clone()) {
             Ok(doc) => {
@@ -204,64 +393,319 @@ fn format_file(
                 String::from("OctizysCommandLine"),
                 options.column_width,
             );
-            let report = create_error_report(&request);
-            let report_str = render_with(&report, store, options);
+            let report_str = render_report(&request, store, options);
             eprintln!("We failed to format!");
             eprintln!("{}", report_str)
         }
     }
 }
 
-fn repl(
-    prompt: String,
+/// Evaluates the main of the file or of a compiled artefact, returns
+/// if it succeeded.
+fn run_file(
+    source_path: PathBuf,
     options: &GlobalOptions,
     store: Rc<RefCell<Store>>,
-) -> () {
-    // TODO:  Add option to choose color
-    // TODO: Add commands in repl (maybe use the larlpop parser for that!);
-    let prompt_document = foreground(MODERATE_GREEN, external_text(&prompt));
-    let rendered_prompt = render_with(&prompt_document, store.clone(), options);
-    loop {
-        let mut buffer = String::new();
-        print!("{}", rendered_prompt);
-        let _ = io::stdout().flush();
-        match io::stdin().read_line(&mut buffer) {
-            Ok(_) => match parse_string(&buffer, None, store.clone()) {
-                Ok(top) => {
-                    let doc = top.to_document(&options.pretty_configuration);
-                    let string = render_with(&doc, store.clone(), options);
-                    println!("{}", string);
-                }
-                Err(e) => {
-                    let request = e.build_report_request(
-                        options.target,
-                        String::from("OctizysCommandLine"),
-                        options.column_width,
-                    );
-                    let report = create_error_report(&request);
-                    let report_str =
-                        render_with(&report, store.clone(), options);
-                    eprintln!("{}", report_str)
-                }
-            },
-            Err(e) => {
-                let octizys_error =
-                    IOError::REPlCantReadLine { error: e.kind() };
-                let request = octizys_error.build_report_request(
-                    options.target,
-                    String::from("OctizysREPL"),
-                    options.column_width,
+) -> bool {
+    if source_path.extension().and_then(|e| e.to_str())
+        == Some(ARTEFACT_EXTENSION)
+    {
+        return run_artefact(source_path, options, store);
+    }
+    let mut source_map = SourceMap::new();
+    let Some((graph, order)) = pipeline::load_program(
+        &source_path,
+        &mut source_map,
+        options,
+        store.clone(),
+    ) else {
+        return false;
+    };
+    let Some(program) = pipeline::check_program(
+        &graph,
+        &order,
+        &source_map,
+        options,
+        store.clone(),
+    ) else {
+        return false;
+    };
+    let Some(module) =
+        pipeline::lower_program(&program, &source_map, options, store.clone())
+    else {
+        return false;
+    };
+    pipeline::run_module(&module, Some(&source_path), options, store).is_some()
+}
+
+/// Every fix may uncover a new error, but a fix may also
+/// introduce the same error again, this bounds the loop.
+const MAX_FIX_ITERATIONS: usize = 64;
+
+/// Applies the fixes the compiler is sure about, returns if the
+/// result parses.
+fn fix_file(
+    source_path: PathBuf,
+    output: Option<PathBuf>,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    let original = match std::fs::read_to_string(&source_path) {
+        Ok(content) => content,
+        Err(_) => {
+            let error = IOError::FileLoadError {
+                path: source_path.clone(),
+            };
+            let request = error.build_report_request(
+                options.target,
+                String::from("OctizysCommandLine"),
+                options.column_width,
+            );
+            eprintln!("{}", render_report(&request, store, options));
+            return false;
+        }
+    };
+    let mut content = original.clone();
+    let mut total_applied = 0;
+    let mut rounds = 0;
+    let parses = loop {
+        let Err(e) =
+            parse_string(&content, Some(source_path.clone()), store.clone())
+        else {
+            break true;
+        };
+        let suggestions: Vec<Suggestion> = e
+            .get_suggestions()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.applicability == Applicability::MachineApplicable)
+            .collect();
+        let (new_content, applied) = apply_suggestions(&content, &suggestions);
+        if applied == 0 || rounds == MAX_FIX_ITERATIONS {
+            let request = e.build_report_request(
+                options.target,
+                String::from("OctizysCommandLine"),
+                options.column_width,
+            );
+            if applied == 0 {
+                eprintln!("We can't fix the following automatically:");
+            } else {
+                eprintln!(
+                    "The fixes didn't converge after {} rounds, we stopped at:",
+                    MAX_FIX_ITERATIONS
                 );
-                let report = create_error_report(&request);
-                let report_str = render_with(&report, store.clone(), options);
-                eprintln!("{}", report_str)
             }
+            eprintln!("{}", render_report(&request, store.clone(), options));
+            break false;
         }
+        rounds += 1;
+        total_applied += applied;
+        content = new_content;
+    };
+    eprintln!("Applied {} fix(es).", total_applied);
+    if content == original && output.is_none() {
+        return parses;
+    }
+    let destination = output.unwrap_or(source_path);
+    if std::fs::write(&destination, content).is_err() {
+        let error = IOError::FileLoadError { path: destination };
+        let request = error.build_report_request(
+            options.target,
+            String::from("OctizysCommandLine"),
+            options.column_width,
+        );
+        eprintln!("{}", render_report(&request, store, options));
+        return false;
     }
+    parses
+}
+
+/// Writes the documentation of the program that starts at the file,
+/// returns if it succeeded.
+fn document_file(
+    source_path: PathBuf,
+    output: PathBuf,
+    format: DocumentationFormatKind,
+    private: bool,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    let mut source_map = SourceMap::new();
+    let Some((graph, _)) = pipeline::load_program(
+        &source_path,
+        &mut source_map,
+        options,
+        store.clone(),
+    ) else {
+        return false;
+    };
+    let formats = match format {
+        DocumentationFormatKind::Html => vec![DocumentationFormat::Html],
+        DocumentationFormatKind::Markdown => {
+            vec![DocumentationFormat::Markdown]
+        }
+        DocumentationFormatKind::All => {
+            vec![DocumentationFormat::Html, DocumentationFormat::Markdown]
+        }
+    };
+    let configuration = DocumentationConfiguration {
+        output_folder: output.clone(),
+        formats,
+        include_private: private,
+    };
+    let result =
+        generate_documentation(&graph, &configuration, &store.borrow());
+    match result {
+        Ok(written) => {
+            eprintln!(
+                "Wrote {} page(s) to {}.",
+                written.len(),
+                output.display()
+            );
+            true
+        }
+        Err(error) => {
+            pipeline::report_without_source(
+                &error,
+                Some(&output),
+                options,
+                store,
+            );
+            false
+        }
+    }
+}
+
+/// Checks a example on top of the program and compares the value of
+/// its main with the expected output, returns if it passed.
+fn run_example(
+    example: &Example,
+    file: FileId,
+    session: &Sast,
+    source_map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    let Some(source) = source_map.get(file).map(|f| f.content()) else {
+        return false;
+    };
+    let overlay = example.overlay(source);
+    let top = match parse_overlay(source_map, file, &overlay, store.clone()) {
+        Ok(top) => top,
+        Err(error) => {
+            pipeline::report_parser_error(&error, options, store);
+            return false;
+        }
+    };
+    // The program may have a main too, only the one of the example counts.
+    let has_main = interface(&top, &store.borrow())
+        .values
+        .iter()
+        .any(|(_, name)| name.as_str() == "main");
+    let Some((_, module)) =
+        pipeline::lower_top(&top, session, source_map, options, store.clone())
+    else {
+        return false;
+    };
+    let name = source_map.get(file).and_then(|f| f.path());
+    let Some(expected) = &example.expected else {
+        return !has_main
+            || pipeline::evaluate_module(&module, name, options, store)
+                .is_some();
+    };
+    if !has_main {
+        let error = DoctestError::MissingMain { span: example.span };
+        pipeline::report_error(&error, source_map, options, store);
+        return false;
+    }
+    let Some(found) =
+        pipeline::evaluate_module(&module, name, options, store.clone())
+            .map(|value| value.to_string())
+    else {
+        return false;
+    };
+    if found.trim() == expected.trim() {
+        return true;
+    }
+    let error = DoctestError::WrongOutput {
+        span: example.span,
+        expected: expected.clone(),
+        found,
+    };
+    pipeline::report_error(&error, source_map, options, store);
+    false
+}
+
+/// Runs the examples of the documentation of the program that starts
+/// at the file, returns if all of them passed.
+/// Having nothing to test counts as a failure.
+fn test_file(
+    source_path: PathBuf,
+    doc: bool,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    if !doc {
+        eprintln!(
+            "Nothing to test, use --doc to run the examples of the documentation."
+        );
+        return false;
+    }
+    let mut source_map = SourceMap::new();
+    let Some((graph, order)) = pipeline::load_program(
+        &source_path,
+        &mut source_map,
+        options,
+        store.clone(),
+    ) else {
+        return false;
+    };
+    let Some(program) = pipeline::check_program(
+        &graph,
+        &order,
+        &source_map,
+        options,
+        store.clone(),
+    ) else {
+        return false;
+    };
+    let mut session = Sast::prelude();
+    session.extend(program);
+    let documentation = extract_documentation(&graph, true, &store.borrow());
+    let mut total = 0;
+    let mut failed = 0;
+    for (index, module) in documentation.iter().enumerate() {
+        let file = graph.get(index).file;
+        for example in module_examples(module) {
+            total += 1;
+            if !run_example(
+                &example,
+                file,
+                &session,
+                &source_map,
+                options,
+                store.clone(),
+            ) {
+                failed += 1;
+            }
+        }
+    }
+    if total == 0 {
+        eprintln!("Nothing to test, the documentation has no examples.");
+        return false;
+    }
+    eprintln!("Ran {} example(s), {} failed.", total, failed);
+    failed == 0
+}
+
+/// The whole session runs on a single thread with a stack for the
+/// default depth of the interpreter, instead of spawning one for every
+/// evaluation.
+fn main() -> ExitCode {
+    with_stack(DEFAULT_MAX_DEPTH, session)
 }
 
-// TODO: Set the exit code error for the shell
-fn main() {
+fn session() -> ExitCode {
     let arguments = crate::arguments::Arguments::parse();
     let debug_level = match arguments.debug_level {
         arguments::DebugLevel::Error => simplelog::LevelFilter::Error,
@@ -275,21 +719,236 @@ fn main() {
         simplelog::TerminalMode::Mixed,
         simplelog::ColorChoice::Auto,
     );
+    set_tab_width(arguments.formatter_configuration.tab_width);
     let real_store = Store::default();
     let store = Rc::new(RefCell::new(real_store));
     if arguments.show_arguments {
         println!("{:#?}", arguments);
-        return;
+        return ExitCode::SUCCESS;
     }
     let options = GlobalOptions::from(arguments.formatter_configuration);
 
     match arguments.command {
-        arguments::Commands::Compile { path, output } => {
-            compile_file(path, output, &options, store)
+        arguments::Commands::Compile { path, output, emit } => {
+            if !compile_file(path, output, emit, &options, store) {
+                return ExitCode::FAILURE;
+            }
+        }
+        arguments::Commands::Run { path } => {
+            if !run_file(path, &options, store) {
+                return ExitCode::FAILURE;
+            }
         }
         arguments::Commands::Format { path, output } => {
             format_file(path, output, &options, store)
         }
-        arguments::Commands::REPL { prompt } => repl(prompt, &options, store),
+        arguments::Commands::Fix { path, output } => {
+            if !fix_file(path, output, &options, store) {
+                return ExitCode::FAILURE;
+            }
+        }
+        arguments::Commands::Doc {
+            path,
+            output,
+            format,
+            private,
+        } => {
+            if !document_file(path, output, format, private, &options, store) {
+                return ExitCode::FAILURE;
+            }
+        }
+        arguments::Commands::Test { path, doc } => {
+            if !test_file(path, doc, &options, store) {
+                return ExitCode::FAILURE;
+            }
+        }
+        arguments::Commands::REPL {
+            prompt,
+            history,
+            history_size,
+            no_history,
+        } => {
+            let history_path = if no_history {
+                None
+            } else {
+                Some(history.unwrap_or_else(repl::default_history_path))
+            };
+            let configuration = repl::ReplConfiguration {
+                prompt,
+                history_path,
+                history_size,
+            };
+            repl::repl(configuration, &options, store)
+        }
     };
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod main_test {
+    use super::*;
+    use crate::arguments::Arguments;
+    use std::fs;
+
+    /// Writes the source in a new directory and runs the command on
+    /// it, the directory is removed after it.
+    fn with_source<T>(
+        test: &str,
+        source: &str,
+        command: impl FnOnce(PathBuf, &GlobalOptions, Rc<RefCell<Store>>) -> T,
+    ) -> T {
+        let directory = std::env::temp_dir().join(format!(
+            "octizys_{}_{}",
+            test,
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("main.oct");
+        fs::write(&path, source).unwrap();
+        let arguments = Arguments::parse_from(["octizys", "repl"]);
+        let options = GlobalOptions::from(arguments.formatter_configuration);
+        let store = Rc::new(RefCell::new(Store::default()));
+        let result = command(path, &options, store);
+        fs::remove_dir_all(&directory).unwrap();
+        result
+    }
+
+    /// Writes the source in a new directory and tests its examples.
+    fn test_source(test: &str, source: &str, doc: bool) -> bool {
+        with_source(test, source, |path, options, store| {
+            test_file(path, doc, options, store)
+        })
+    }
+
+    #[test]
+    fn doctests_exit_status() {
+        let example = |expected: &str| {
+            format!(
+                "-- | ```\n-- | main = inc 1;\n-- | -- => {}\n-- | ```\n\
+                 inc : U64 -> U64;\ninc x = x + 1;",
+                expected
+            )
+        };
+        assert!(test_source("doctest_pass", &example("2"), true));
+        assert!(!test_source("doctest_fail", &example("3"), true));
+        assert!(!test_source("doctest_no_doc", &example("2"), false));
+        assert!(!test_source(
+            "doctest_empty",
+            "inc : U64 -> U64;\ninc x = x;",
+            true
+        ));
+        let division =
+            "-- | ```\n-- | main = 1 / 0;\n-- | ```\nf : U64;\nf = 1;";
+        assert!(!test_source("doctest_runtime", division, true));
+    }
+
+    /// Fixes the source and returns if the fix succeeded and the
+    /// fixed source.
+    fn fix_source(test: &str, source: &str) -> (bool, String) {
+        with_source(test, source, |path, options, store| {
+            let fixed = fix_file(path.clone(), None, options, store);
+            (fixed, fs::read_to_string(&path).unwrap())
+        })
+    }
+
+    #[test]
+    fn fix_closes_nested_comments() {
+        let (fixed, content) = fix_source("fix_nested", "main = 1; {- x {-- y");
+        assert!(fixed);
+        assert_eq!(content, "main = 1; {- x {-- y--}-}");
+        let (fixed, content) = fix_source("fix_unfixable", "main = ;");
+        assert!(!fixed);
+        assert_eq!(content, "main = ;");
+    }
+
+    #[test]
+    fn compile_exit_status() {
+        let compile = |test: &str, source: &str, emit: EmitKind| {
+            with_source(test, source, |path, options, store| {
+                let output = path.with_extension("out");
+                compile_file(path, Some(output), emit, options, store)
+            })
+        };
+        let valid = "main : U64;\nmain = 1;";
+        assert!(compile("compile_valid", valid, EmitKind::Artefact));
+        assert!(compile("compile_cst", valid, EmitKind::Cst));
+        assert!(!compile("compile_parse", "main = ;", EmitKind::Cst));
+        let mismatch = "main : U64;\nmain = \"a\";";
+        assert!(!compile("compile_type", mismatch, EmitKind::Core));
+    }
+
+    #[test]
+    fn corrupt_artefacts_fail() {
+        let run = |test: &str, bytes: &[u8]| {
+            with_source(test, "", |path, options, store| {
+                let path = path.with_extension(ARTEFACT_EXTENSION);
+                fs::write(&path, bytes).unwrap();
+                run_artefact(path, options, store)
+            })
+        };
+        assert!(!run("artefact_corrupt", b"OCTC\xff"));
+        assert!(!run("artefact_empty", b""));
+    }
+
+    #[test]
+    fn run_exit_status() {
+        let run = |test: &str, source: &str| {
+            with_source(test, source, |path, options, store| {
+                run_file(path, options, store)
+            })
+        };
+        assert!(run("run_valid", "main : U64;\nmain = 1;"));
+        assert!(!run("run_parse", "main = ;"));
+        assert!(!run("run_type", "main : U64;\nmain = \"a\";"));
+        assert!(!run("run_runtime", "main : U64;\nmain = 1 / 0;"));
+    }
+
+    #[test]
+    fn doc_exit_status() {
+        let document = |test: &str, source: &str| {
+            with_source(test, source, |path, options, store| {
+                let output = path.with_extension("doc");
+                document_file(
+                    path,
+                    output,
+                    DocumentationFormatKind::Markdown,
+                    false,
+                    options,
+                    store,
+                )
+            })
+        };
+        assert!(document("doc_valid", "-- | One.\nmain : U64;\nmain = 1;"));
+        assert!(!document("doc_parse", "main = ;"));
+    }
+
+    /// Formats the source without highlight.
+    fn format_source(source: &str) -> String {
+        let arguments = Arguments::parse_from(["octizys", "repl"]);
+        let options = GlobalOptions {
+            highlight: EmptyRender::render_highlight,
+            ..GlobalOptions::from(arguments.formatter_configuration)
+        };
+        let store = Rc::new(RefCell::new(Store::default()));
+        let top = parse_string(source, None, store.clone()).unwrap();
+        let document = check_idempotence(&top, &options, store.clone())
+            .unwrap_or_else(|_| panic!("Can't format {:?}", source));
+        render_with(&document, store, &options)
+    }
+
+    #[test]
+    fn format_keeps_trailing_comments() {
+        for (source, comment) in [
+            (
+                "main = 1; {- trailing {- nested -} -}",
+                "{- trailing {- nested -} -}",
+            ),
+            ("main = 1; -- trailing", "--trailing"),
+        ] {
+            let formatted = format_source(source);
+            assert!(formatted.starts_with("main = 1; "), "{:?}", formatted);
+            assert!(formatted.contains(comment), "{:?}", formatted);
+            assert_eq!(format_source(&formatted), formatted);
+        }
+    }
 }
@@ -0,0 +1,548 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use octizys_common::report::{
+    ReportFormat, ReportKind, ReportRequest, ReportSourceContext,
+};
+use octizys_common::source_map::SourceMap;
+use octizys_core::checker;
+use octizys_core::common::Identifier;
+use octizys_core::core::Module;
+use octizys_core::evaluation::{Interpreter, Value};
+use octizys_cst::top::Top;
+use octizys_parser::parser::{parse_source, OctizysParserError};
+use octizys_sast::exhaustiveness::{check_module, PatternReport};
+use octizys_sast::from_cst::translate_module;
+use octizys_sast::inference::infer_module;
+use octizys_sast::kinds::check_module_kinds;
+use octizys_sast::lowering::lower_sast;
+use octizys_sast::modules::{
+    imports, interface, unused_items, ImportScope, ModuleError, ModuleGraph,
+    ModuleName, ModuleNode, SourceRoots,
+};
+use octizys_sast::top::Sast;
+use octizys_text_store::store::Store;
+
+use crate::{render_report, GlobalOptions};
+
+/// Shows a report located in some file of the map.
+pub fn report<R: ReportFormat>(
+    report: &R,
+    kind: ReportKind,
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) {
+    let request = ReportRequest::from_source_map(
+        report,
+        map,
+        options.target,
+        kind,
+        options.column_width,
+    );
+    eprintln!("{}", render_report(&request, store, options));
+}
+
+/// Shows a report of a error located in some file of the map.
+pub fn report_error<R: ReportFormat>(
+    error: &R,
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) {
+    report(error, ReportKind::Error, map, options, store)
+}
+
+/// Shows a report that doesn't point to a place in a source, the
+/// name is the file the report is about.
+pub fn report_without_source<R: ReportFormat>(
+    report: &R,
+    name: Option<&Path>,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) {
+    let mut source_context: ReportSourceContext = Default::default();
+    if let Some(name) = name {
+        source_context.src_name = name.display().to_string();
+    }
+    let request = ReportRequest {
+        report,
+        source_context,
+        target: options.target,
+        kind: ReportKind::Error,
+    };
+    eprintln!("{}", render_report(&request, store, options));
+}
+
+/// Shows a error of the lexer or the parser, they carry their source.
+pub fn report_parser_error(
+    error: &OctizysParserError,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) {
+    let request = error.build_report_request(
+        options.target,
+        String::from("OctizysCommandLine"),
+        options.column_width,
+    );
+    eprintln!("{}", render_report(&request, store, options));
+}
+
+/// Shows the reports of the pattern checks, returns false if
+/// some of them is an error.
+pub fn report_patterns(
+    reports: &[PatternReport],
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> bool {
+    let mut ok = true;
+    for pattern_report in reports {
+        let kind = pattern_report.kind();
+        ok = ok && !matches!(kind, ReportKind::Error);
+        report(pattern_report, kind, map, options, store.clone());
+    }
+    ok
+}
+
+/// Translates the CST of a file to a type checked [`Sast`], the file
+/// can use the items of the session that are in the scope of its imports.
+/// Returns the names of the items used by the file too.
+pub fn check_top(
+    top: &Top,
+    session: &Sast,
+    imports: &ImportScope,
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<(Sast, HashSet<Identifier>)> {
+    let kinds = check_module_kinds(top, session, imports, &store.borrow());
+    if let Err(error) = kinds {
+        report_error(&*error, map, options, store);
+        return None;
+    }
+    let translated = translate_module(top, session, imports, &store.borrow());
+    let (mut sast, used) = match translated {
+        Ok(translated) => translated,
+        Err(error) => {
+            report_error(&*error, map, options, store);
+            return None;
+        }
+    };
+    if let Err(error) = infer_module(&mut sast, session) {
+        report_error(&*error, map, options, store);
+        return None;
+    }
+    let reports = check_module(&sast, session);
+    if !report_patterns(&reports, map, options, store.clone()) {
+        return None;
+    }
+    Some((sast, used))
+}
+
+/// Translates the CST of a file to the core language, the file can
+/// use the items of the session.
+/// Returns the session extended with the items of the file and the
+/// core module of all of them.
+pub fn lower_top(
+    top: &Top,
+    session: &Sast,
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<(Sast, Module)> {
+    let imports = ImportScope::default();
+    let (sast, _) =
+        check_top(top, session, &imports, map, options, store.clone())?;
+    let mut full = session.clone();
+    full.extend(sast);
+    let module = lower_full(&full, map, options, store)?;
+    Some((full, module))
+}
+
+/// Lowers a [`Sast`] that doesn't depend on other items and checks
+/// the result.
+fn lower_full(
+    full: &Sast,
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<Module> {
+    let module = match lower_sast(full) {
+        Ok(module) => module,
+        Err(error) => {
+            report_error(&*error, map, options, store);
+            return None;
+        }
+    };
+    match checker::check_module(&module) {
+        Ok(()) => Some(module),
+        Err(error) => {
+            report_error(&*error, map, options, store);
+            None
+        }
+    }
+}
+
+/// The name of the entry file of a program, the modules that import
+/// it must use this name.
+fn entry_name(path: &Path) -> ModuleName {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    ModuleName::from_file(parent, path).unwrap_or_else(|| {
+        // Safe: main is a valid identifier.
+        ModuleName::new(vec![Identifier::make(String::from("main")).unwrap()])
+    })
+}
+
+/// Parses a file and the modules it imports. The modules are searched
+/// in the source roots of the options and then in the directory of
+/// the file.
+/// The graph is checked, the modules can be translated in the
+/// returned order.
+pub fn load_program(
+    path: &Path,
+    map: &mut SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<(ModuleGraph, Vec<usize>)> {
+    let mut roots = options.source_roots.clone();
+    roots.push(path.parent().map(PathBuf::from).unwrap_or_default());
+    let roots = SourceRoots::new(roots);
+    let mut graph = ModuleGraph::new();
+    let mut pending = vec![(entry_name(path), path.to_path_buf())];
+    while let Some((name, path)) = pending.pop() {
+        if graph.find(&name).is_some() {
+            continue;
+        }
+        let file = match map.load(&path) {
+            Ok(file) => file,
+            Err(error) => {
+                let request = error.build_report_request(
+                    options.target,
+                    String::from("OctizysCommandLine"),
+                    options.column_width,
+                );
+                eprintln!("{}", render_report(&request, store, options));
+                return None;
+            }
+        };
+        let top = match parse_source(map, file, store.clone()) {
+            Ok(top) => top,
+            Err(error) => {
+                report_parser_error(&error, options, store);
+                return None;
+            }
+        };
+        let (imports, interface) = {
+            let store = store.borrow();
+            (imports(&top, &store), interface(&top, &store))
+        };
+        for import in imports.iter().rev() {
+            if graph.find(&import.module).is_some() {
+                continue;
+            }
+            match roots.locate(&import.module) {
+                Some(path) => pending.push((import.module.clone(), path)),
+                None => {
+                    let error = ModuleError::ModuleNotFound {
+                        span: import.module_span,
+                        module: import.module.clone(),
+                        searched: roots.candidates(&import.module),
+                    };
+                    report_error(&error, map, options, store);
+                    return None;
+                }
+            }
+        }
+        graph.add(ModuleNode {
+            name,
+            file,
+            top,
+            imports,
+            interface,
+        });
+    }
+    let checked = graph.order().and_then(|order| {
+        graph.check()?;
+        Ok(order)
+    });
+    match checked {
+        Ok(order) => Some((graph, order)),
+        Err(error) => {
+            report_error(&*error, map, options, store);
+            None
+        }
+    }
+}
+
+/// Translates and checks the modules of a program in order, every
+/// module can use the prelude and the modules it imports.
+/// The items whose name is defined by other module are renamed to
+/// their linked name, so the modules can share private names.
+/// The private items that aren't used are reported as warnings.
+/// Returns the items of all the modules, without the prelude.
+pub fn check_program(
+    graph: &ModuleGraph,
+    order: &[usize],
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<Sast> {
+    let mut full = Sast::prelude();
+    let mut program = Sast::default();
+    for &index in order {
+        let imports = graph.scope(index);
+        let module = graph.get(index);
+        let (mut sast, used) = check_top(
+            &module.top,
+            &full,
+            &imports,
+            map,
+            options,
+            store.clone(),
+        )?;
+        // The entry file is the first module.
+        for unused in unused_items(&module.interface, &used, index == 0) {
+            report(&unused, ReportKind::Warning, map, options, store.clone());
+        }
+        // The other modules refer to the items by their linked names.
+        sast.rename(&graph.renames(index));
+        full.extend(sast.clone());
+        program.extend(sast);
+    }
+    Some(program)
+}
+
+/// Lowers the items of a program together with the prelude to a
+/// single core module.
+pub fn lower_program(
+    program: &Sast,
+    map: &SourceMap,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<Module> {
+    let mut full = Sast::prelude();
+    full.extend(program.clone());
+    lower_full(&full, map, options, store)
+}
+
+/// Evaluates the main of the module, the errors are reported with
+/// the name of the file.
+pub fn evaluate_module(
+    module: &Module,
+    name: Option<&Path>,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<Value> {
+    match Interpreter::new(module).run_main() {
+        Ok(value) => Some(value),
+        Err(error) => {
+            report_without_source(&error, name, options, store);
+            None
+        }
+    }
+}
+
+/// Like [`evaluate_module`] but the result is printed.
+pub fn run_module(
+    module: &Module,
+    name: Option<&Path>,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) -> Option<Value> {
+    let value = evaluate_module(module, name, options, store)?;
+    println!("{}", value);
+    Some(value)
+}
+
+#[cfg(test)]
+mod pipeline_test {
+    use super::*;
+    use crate::arguments::Arguments;
+    use clap::Parser;
+    use std::fs;
+
+    /// Writes the files in a new directory and runs the first one.
+    fn run_files(test: &str, files: &[(&str, &str)]) -> Option<String> {
+        let directory = std::env::temp_dir().join(format!(
+            "octizys_{}_{}",
+            test,
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        for (name, source) in files {
+            fs::write(directory.join(name), source).unwrap();
+        }
+        let arguments = Arguments::parse_from(["octizys", "repl"]);
+        let options = GlobalOptions::from(arguments.formatter_configuration);
+        let store = Rc::new(RefCell::new(Store::default()));
+        let mut map = SourceMap::new();
+        let entry = directory.join(files[0].0);
+        let result = load_program(&entry, &mut map, &options, store.clone())
+            .and_then(|(graph, order)| {
+                check_program(&graph, &order, &map, &options, store.clone())
+            })
+            .and_then(|program| {
+                lower_program(&program, &map, &options, store.clone())
+            })
+            .and_then(|module| {
+                run_module(&module, Some(&entry), &options, store.clone())
+            });
+        fs::remove_dir_all(&directory).unwrap();
+        result.map(|value| value.to_string())
+    }
+
+    #[test]
+    fn modules_share_private_names() {
+        let result = run_files(
+            "private_names",
+            &[
+                (
+                    "main.oct",
+                    "import unqualified a::(inc);\n\
+                     import b::;\n\
+                     helper : U64 -> U64;\n\
+                     helper x = x * 10;\n\
+                     main : U64;\n\
+                     main = helper (b::dec (inc 5));",
+                ),
+                (
+                    "a.oct",
+                    "helper : U64 -> U64;\n\
+                     helper x = x + 1;\n\
+                     inc : U64 -> U64;\n\
+                     public inc x = helper x;",
+                ),
+                (
+                    "b.oct",
+                    "helper : U64 -> U64;\n\
+                     helper x = x - 2;\n\
+                     dec : U64 -> U64;\n\
+                     public dec x = helper x;",
+                ),
+            ],
+        );
+        assert_eq!(result.as_deref(), Some("40"));
+    }
+
+    #[test]
+    fn negation_of_expressions() {
+        let result = run_files(
+            "negation",
+            &[(
+                "main.oct",
+                "neg : I64 -> I64;\n\
+                 neg x = -x;\n\
+                 twice x = -(neg x);\n\
+                 main : (I64, I64, I64);\n\
+                 main = (neg (-3), -(5), twice (-7));",
+            )],
+        );
+        assert_eq!(result.as_deref(), Some("(3, -5, -7)"));
+        let result = run_files(
+            "negation_unsigned",
+            &[("main.oct", "bad : U64 -> U64;\nbad x = -x;\nmain = bad 1;")],
+        );
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn interpolation_strings() {
+        let result = run_files(
+            "interpolation",
+            &[(
+                "main.oct",
+                "greet : String -> U64 -> I64 -> String;\n\
+                 greet name age delta = \
+                 f#\"Hello {name}, {age} ({delta}) {{ok}}\";\n\
+                 echo x = f#\"<{x}>\";\n\
+                 main : (String, String, String);\n\
+                 main = (greet \"Ana\" 30 (-2), echo \"!\", f#\"\");",
+            )],
+        );
+        assert_eq!(
+            result.as_deref(),
+            Some(r#"("Hello Ana, 30 (-2) {ok}", "<!>", "")"#)
+        );
+        let result = run_files(
+            "interpolation_bool",
+            &[("main.oct", "b : Bool;\nb = True;\nmain = f#\"{b}\";")],
+        );
+        assert_eq!(result, None);
+    }
+
+    /// Runs `main = f#"<hole>";` with the values `n = 255`, `m = -42`
+    /// and `s = "ab"` in scope.
+    fn run_hole(test: &str, hole: &str) -> Option<String> {
+        let source = format!(
+            "n : U64;\nn = 255;\nm : I64;\nm = -42;\n\
+             s : String;\ns = \"ab\";\nmain = f#\"{}\";",
+            hole
+        );
+        run_files(test, &[("main.oct", &source)])
+    }
+
+    #[test]
+    fn interpolation_width() {
+        let result = run_hole("format_width", "[{n:5}|{m:5}|{s:5}]");
+        assert_eq!(result.as_deref(), Some(r#""[  255|  -42|ab   ]""#));
+    }
+
+    #[test]
+    fn interpolation_alignment() {
+        let result = run_hole("format_alignment", "[{s:>4}|{n:<4}|{s:*^7}]");
+        assert_eq!(result.as_deref(), Some(r#""[  ab|255 |**ab***]""#));
+    }
+
+    #[test]
+    fn interpolation_precision() {
+        let result = run_hole("format_precision", "[{s:.1}|{s:>3.1}|{n:.1}]");
+        assert_eq!(result.as_deref(), Some(r#""[a|  a|255]""#));
+    }
+
+    #[test]
+    fn interpolation_zero() {
+        let result = run_hole("format_zero", "[{n:05}|{m:05}|{m:<05}]");
+        assert_eq!(result.as_deref(), Some(r#""[00255|-0042|-0042]""#));
+    }
+
+    #[test]
+    fn interpolation_hex() {
+        let result = run_hole("format_hex", "[{n:x}|{n:X}|{n:06x}|{m:x}]");
+        assert_eq!(
+            result.as_deref(),
+            Some(r#""[ff|FF|0000ff|ffffffffffffffd6]""#)
+        );
+        let result = run_hole("format_hex_string", "{s:x}");
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn interpolation_binary_and_octal() {
+        let result = run_hole("format_binary", "[{n:b}|{n:o}|{m:>4o}]");
+        assert_eq!(
+            result.as_deref(),
+            Some(r#""[11111111|377|1777777777777777777726]""#)
+        );
+    }
+
+    #[test]
+    fn interpolation_debug() {
+        let result = run_hole("format_debug", "[{s:?}|{n:?}|{s:>6?}]");
+        assert_eq!(result.as_deref(), Some(r#""[\"ab\"|255|  \"ab\"]""#));
+    }
+
+    #[test]
+    fn interpolation_chars() {
+        let result = run_files(
+            "interpolation_chars",
+            &[(
+                "main.oct",
+                "c : Char;\nc = '\\n';\nmain = f#\"[{'a'}|{'a':?}|{c:?}|{'b':>3}]\";",
+            )],
+        );
+        assert_eq!(result.as_deref(), Some(r#""[a|'a'|'\\n'|  b]""#));
+    }
+}
@@ -0,0 +1,881 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use clap::ValueEnum;
+use octizys_common::report::{IOError, ReportFormat};
+use octizys_common::source_map::{FileId, SourceMap};
+use octizys_common::span::Location;
+use octizys_core::checker::check_term;
+use octizys_core::core::Module;
+use octizys_core::evaluation::{Environment, Interpreter};
+use octizys_cst::expressions::Expression;
+use octizys_formatter::to_document::ToDocument;
+use octizys_parser::parser::{
+    parse_expression_source, parse_source, parse_string, OctizysParserError,
+};
+use octizys_pretty::{
+    combinators::{external_text, foreground},
+    highlight::base_colors::MODERATE_GREEN,
+};
+use octizys_sast::_type::TypeScheme;
+use octizys_sast::exhaustiveness::check_expression;
+use octizys_sast::from_cst::translate_expression;
+use octizys_sast::inference::infer_expression;
+use octizys_sast::lowering::{lower_expression, lower_sast};
+use octizys_sast::top::Sast;
+use octizys_text_store::{store::Store, width::set_tab_width};
+use rustyline::{
+    error::ReadlineError,
+    highlight::{CmdKind, Highlighter, MatchingBracketHighlighter},
+    history::DefaultHistory,
+    validate::{ValidationContext, ValidationResult, Validator},
+    Completer, Config, Editor, Helper, Hinter,
+};
+
+use crate::arguments::AvailableRenderers;
+use crate::pipeline::{lower_top, report_error, report_patterns};
+use crate::{render_report, render_with, renderer_highlight, GlobalOptions};
+
+/// The meta commands start with this character, everything else
+/// is octizys code.
+const META_COMMAND_START: char = ':';
+
+const HELP: &str = "Available commands:
+  :load <path>        Load a file and remember it for :reload,
+                      the definitions of the file replace the
+                      previous ones.
+  :reload             Load again the last loaded file.
+  :type <expression>  Show the type of an expression.
+  :format <code>      Format the code and show it.
+  :set <option> <value>
+                      Change an option, available options:
+                        prompt, column-width, indentation,
+                        tab-width, renderer, json.
+  :help               Show this message.
+  :quit               Exit the REPL (Ctrl-D also works).
+Any other input is read as octizys code, expressions are evaluated
+and definitions are added to the session.
+Input can span multiple lines while a bracket or a block comment is open.";
+
+/// The amount of hyphens that the lexer takes after a `{` to open a
+/// block comment that isn't nested.
+const MAX_COMMENT_HYPHENS: usize = 4;
+
+/// The amount of `-` that start at `start`.
+fn count_hyphens(chars: &[char], start: usize) -> usize {
+    chars[start.min(chars.len())..]
+        .iter()
+        .take_while(|c| **c == '-')
+        .count()
+}
+
+/// The amount of chars of the character literal that starts at `start`
+/// (a `'`), `None` if there isn't one, like for the ownership literals
+/// `'0` and variables `'a`. It follows the rules of the lexer.
+fn char_literal_length(chars: &[char], start: usize) -> Option<usize> {
+    let at = |k: usize| chars.get(start + k).copied();
+    match at(1)? {
+        '\\' => {
+            if at(2) == Some('u') && at(3) == Some('{') {
+                let mut k = 4;
+                while let Some(c) = at(k) {
+                    match c {
+                        '}' => {
+                            return (at(k + 1) == Some('\'')).then_some(k + 2)
+                        }
+                        '\'' | '\n' => return None,
+                        _ => k += 1,
+                    }
+                }
+                None
+            } else {
+                let escaped = at(2)?;
+                (!escaped.is_whitespace() && at(3) == Some('\'')).then_some(4)
+            }
+        }
+        c if c != '\'' && c != '\n' && at(2) == Some('\'') => Some(3),
+        _ => {
+            let mut k = 1;
+            while let Some(c) = at(k) {
+                if c == '\'' {
+                    return Some(k + 1);
+                }
+                if c == '\\' || c.is_whitespace() || ",;()[]{}".contains(c) {
+                    return None;
+                }
+                k += 1;
+            }
+            None
+        }
+    }
+}
+
+/// Tells if a input is ready to be processed, that is, if all the
+/// brackets and block comments are closed, every `let` has its `in`
+/// and every `case` has the `{` after its `of`.
+/// Unbalanced closing brackets are left to the parser to report.
+pub fn is_input_complete(input: &str) -> bool {
+    let chars: Vec<char> = input.chars().collect();
+    let mut brackets: Vec<char> = vec![];
+    // The hyphens of the open block comments, like in the lexer a
+    // nested comment is closed with its own amount of hyphens.
+    let mut comments: Vec<usize> = vec![];
+    let mut open_lets: usize = 0;
+    let mut open_cases: usize = 0;
+    // The `of` that are waiting for the `{` of the alternatives.
+    let mut open_ofs: usize = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let current = chars[i];
+        let next = chars.get(i + 1).copied();
+        if let Some(expected) = comments.last().copied() {
+            let hyphens = count_hyphens(&chars, i + 1);
+            if current == '{' && hyphens > 0 {
+                comments.push(hyphens);
+                i += 1 + hyphens;
+            } else if count_hyphens(&chars, i) >= expected
+                && chars.get(i + expected) == Some(&'}')
+            {
+                comments.pop();
+                i += expected + 1;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        match (current, next) {
+            ('{', Some('-')) => {
+                let hyphens =
+                    count_hyphens(&chars, i + 1).min(MAX_COMMENT_HYPHENS);
+                comments.push(hyphens);
+                i += 1 + hyphens;
+            }
+            ('\'', _) => {
+                i += char_literal_length(&chars, i).unwrap_or(1);
+            }
+            ('-', Some('-')) | ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('r', Some('#')) | ('r', Some('"')) => {
+                let mut hashes = 0;
+                let mut j = i + 1;
+                while chars.get(j) == Some(&'#') {
+                    hashes += 1;
+                    j += 1;
+                }
+                if chars.get(j) != Some(&'"') {
+                    i += 1;
+                    continue;
+                }
+                j += 1;
+                loop {
+                    match chars.get(j) {
+                        None => return false,
+                        Some('"')
+                            if (1..=hashes)
+                                .all(|k| chars.get(j + k) == Some(&'#')) =>
+                        {
+                            i = j + 1 + hashes;
+                            break;
+                        }
+                        _ => j += 1,
+                    }
+                }
+            }
+            ('"', _) => {
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return false,
+                        Some('\\') => j += 2,
+                        Some('"') => {
+                            i = j + 1;
+                            break;
+                        }
+                        _ => j += 1,
+                    }
+                }
+            }
+            ('(', _) | ('[', _) | ('{', _) => {
+                if current == '{' {
+                    open_ofs = open_ofs.saturating_sub(1);
+                }
+                brackets.push(current);
+                i += 1;
+            }
+            (')', _) | (']', _) | ('}', _) => {
+                brackets.pop();
+                i += 1;
+            }
+            _ if current.is_alphabetic() || current == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                match word.as_str() {
+                    "let" => open_lets += 1,
+                    "in" => open_lets = open_lets.saturating_sub(1),
+                    "case" => open_cases += 1,
+                    "of" if open_cases > 0 => {
+                        open_cases -= 1;
+                        open_ofs += 1;
+                    }
+                    _ => (),
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    brackets.is_empty()
+        && comments.is_empty()
+        && open_lets == 0
+        && open_cases == 0
+        && open_ofs == 0
+}
+
+#[derive(Helper, Completer, Hinter)]
+struct ReplHelper {
+    colored_prompt: String,
+    brackets: MatchingBracketHighlighter,
+}
+
+impl Validator for ReplHelper {
+    fn validate(
+        &self,
+        ctx: &mut ValidationContext,
+    ) -> rustyline::Result<ValidationResult> {
+        let input = ctx.input();
+        if input.trim_start().starts_with(META_COMMAND_START)
+            || is_input_complete(input)
+        {
+            Ok(ValidationResult::Valid(None))
+        } else {
+            Ok(ValidationResult::Incomplete)
+        }
+    }
+}
+
+impl Highlighter for ReplHelper {
+    fn highlight_prompt<'b, 's: 'b, 'p: 'b>(
+        &'s self,
+        prompt: &'p str,
+        default: bool,
+    ) -> Cow<'b, str> {
+        if default {
+            Cow::Borrowed(&self.colored_prompt)
+        } else {
+            Cow::Borrowed(prompt)
+        }
+    }
+
+    fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+        self.brackets.highlight(line, pos)
+    }
+
+    fn highlight_char(&self, line: &str, pos: usize, kind: CmdKind) -> bool {
+        self.brackets.highlight_char(line, pos, kind)
+    }
+}
+
+/// Where to store the history if the user didn't provide a path.
+pub fn default_history_path() -> PathBuf {
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(".octizys_history"),
+        None => PathBuf::from(".octizys_history"),
+    }
+}
+
+pub struct ReplConfiguration {
+    pub prompt: String,
+    /// If None, the history isn't persisted.
+    pub history_path: Option<PathBuf>,
+    pub history_size: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Continue {
+    Yes,
+    No,
+}
+
+/// A input that starts with [`META_COMMAND_START`].
+#[derive(Debug, PartialEq, Eq)]
+enum MetaCommand {
+    Quit,
+    Help,
+    Load(PathBuf),
+    Reload,
+    Type(String),
+    Format(String),
+    Set {
+        option: String,
+        value: String,
+    },
+    /// The command is known but the arguments are wrong.
+    Usage(&'static str),
+    Unknown(String),
+}
+
+impl MetaCommand {
+    fn parse(input: &str) -> MetaCommand {
+        let input = input.trim();
+        let (command, argument) = match input.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (input, ""),
+        };
+        match command {
+            ":quit" | ":q" => MetaCommand::Quit,
+            ":help" | ":h" => MetaCommand::Help,
+            ":load" | ":l" if argument.is_empty() => {
+                MetaCommand::Usage(":load <path>")
+            }
+            ":load" | ":l" => MetaCommand::Load(PathBuf::from(argument)),
+            ":reload" | ":r" => MetaCommand::Reload,
+            ":type" | ":t" if argument.is_empty() => {
+                MetaCommand::Usage(":type <expression>")
+            }
+            ":type" | ":t" => MetaCommand::Type(String::from(argument)),
+            ":format" | ":f" => MetaCommand::Format(String::from(argument)),
+            ":set" | ":s" => match argument.split_once(char::is_whitespace) {
+                Some((option, value)) => MetaCommand::Set {
+                    option: String::from(option),
+                    value: String::from(value.trim()),
+                },
+                None => MetaCommand::Usage(":set <option> <value>"),
+            },
+            _ => MetaCommand::Unknown(String::from(command)),
+        }
+    }
+}
+
+struct Repl {
+    prompt: String,
+    options: GlobalOptions,
+    store: Rc<RefCell<Store>>,
+    /// The last file loaded with `:load`.
+    loaded: Option<PathBuf>,
+    /// Every input is stored as a file to report errors on it.
+    source_map: SourceMap,
+    /// The definitions available to the inputs.
+    session: Sast,
+    /// The core of the session, used to evaluate expressions.
+    module: Module,
+}
+
+/// Where the parser found the error.
+fn error_index(error: &OctizysParserError) -> usize {
+    match error.get_location_maybe() {
+        Some(Location::Span(span)) => span.start.source_index,
+        Some(Location::Position(position)) => position.source_index,
+        None => 0,
+    }
+}
+
+impl Repl {
+    fn new(
+        prompt: String,
+        options: &GlobalOptions,
+        store: Rc<RefCell<Store>>,
+    ) -> Self {
+        Repl {
+            prompt,
+            options: options.clone(),
+            store,
+            loaded: None,
+            source_map: SourceMap::new(),
+            session: Sast::prelude(),
+            // The prelude only has data types without parameters.
+            module: lower_sast(&Sast::prelude()).unwrap_or_default(),
+        }
+    }
+
+    fn colored_prompt(&self) -> String {
+        let prompt_document =
+            foreground(MODERATE_GREEN, external_text(&self.prompt));
+        render_with(&prompt_document, self.store.clone(), &self.options)
+    }
+
+    fn report_io_error(&self, error: IOError) {
+        let request = error.build_report_request(
+            self.options.target,
+            String::from("OctizysREPL"),
+            self.options.column_width,
+        );
+        eprintln!(
+            "{}",
+            render_report(&request, self.store.clone(), &self.options)
+        );
+    }
+
+    /// Parses the code and shows it formatted.
+    fn format(&self, code: &str, source_name: Option<PathBuf>) {
+        match parse_string(code, source_name, self.store.clone()) {
+            Ok(top) => {
+                let doc = top.to_document(&self.options.pretty_configuration);
+                println!(
+                    "{}",
+                    render_with(&doc, self.store.clone(), &self.options)
+                );
+            }
+            Err(e) => {
+                let request = e.build_report_request(
+                    self.options.target,
+                    String::from("OctizysREPL"),
+                    self.options.column_width,
+                );
+                eprintln!(
+                    "{}",
+                    render_report(&request, self.store.clone(), &self.options)
+                );
+            }
+        }
+    }
+
+    fn report_parser_error(&self, error: OctizysParserError) {
+        let request = error.build_report_request(
+            self.options.target,
+            String::from("OctizysREPL"),
+            self.options.column_width,
+        );
+        eprintln!(
+            "{}",
+            render_report(&request, self.store.clone(), &self.options)
+        );
+    }
+
+    fn add_input(&mut self, code: &str) -> FileId {
+        let name = format!("<repl:{}>", self.source_map.iter().count());
+        self.source_map.add(name, None, String::from(code))
+    }
+
+    /// Translates the definitions of the file on top of the session.
+    fn define(&mut self, file: FileId, session: Sast) -> bool {
+        let top = match parse_source(&self.source_map, file, self.store.clone())
+        {
+            Ok(top) => top,
+            Err(e) => {
+                self.report_parser_error(e);
+                return false;
+            }
+        };
+        match lower_top(
+            &top,
+            &session,
+            &self.source_map,
+            &self.options,
+            self.store.clone(),
+        ) {
+            Some((session, module)) => {
+                self.session = session;
+                self.module = module;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn load(&mut self, path: PathBuf) {
+        let file = match self.source_map.find_by_path(&path) {
+            Some(file) => match std::fs::read_to_string(&path) {
+                Ok(content) => {
+                    self.source_map.update(file, content);
+                    Ok(file)
+                }
+                Err(_) => Err(IOError::FileLoadError { path: path.clone() }),
+            },
+            None => self.source_map.load(&path),
+        };
+        match file {
+            Ok(file) => {
+                if self.define(file, Sast::prelude()) {
+                    println!("Loaded {}", path.display());
+                    self.loaded = Some(path);
+                }
+            }
+            Err(e) => self.report_io_error(e),
+        }
+    }
+
+    /// Translates and infers the type of an expression.
+    fn check_expression(
+        &self,
+        expression: &Expression,
+    ) -> Option<(octizys_sast::expression::Expression, TypeScheme)> {
+        let translated = translate_expression(
+            expression,
+            &self.session,
+            &self.store.borrow(),
+        );
+        let mut expression = match translated {
+            Ok(expression) => expression,
+            Err(error) => {
+                self.report(&*error);
+                return None;
+            }
+        };
+        match infer_expression(&mut expression, &self.session) {
+            Ok(scheme) => {
+                let reports = check_expression(&expression, &self.session);
+                report_patterns(
+                    &reports,
+                    &self.source_map,
+                    &self.options,
+                    self.store.clone(),
+                )
+                .then_some((expression, scheme))
+            }
+            Err(error) => {
+                self.report(&*error);
+                None
+            }
+        }
+    }
+
+    fn report<R: ReportFormat>(&self, error: &R) {
+        report_error(error, &self.source_map, &self.options, self.store.clone())
+    }
+
+    /// The type of the expression, the errors are reported.
+    fn expression_type(&mut self, code: &str) -> Option<TypeScheme> {
+        let file = self.add_input(code);
+        match parse_expression_source(
+            &self.source_map,
+            file,
+            self.store.clone(),
+        ) {
+            Ok(expression) => self
+                .check_expression(&expression)
+                .map(|(_, scheme)| scheme.normalize()),
+            Err(e) => {
+                self.report_parser_error(e);
+                None
+            }
+        }
+    }
+
+    fn type_of(&mut self, code: &str) {
+        if let Some(scheme) = self.expression_type(code) {
+            println!("{}", scheme)
+        }
+    }
+
+    fn evaluate(&mut self, expression: &Expression) {
+        let Some((expression, scheme)) = self.check_expression(expression)
+        else {
+            return;
+        };
+        let term = match lower_expression(&expression, &scheme, &self.session) {
+            Ok(term) => term,
+            Err(error) => return self.report(&*error),
+        };
+        if let Err(error) = check_term(&self.module, &term) {
+            return self.report(&*error);
+        }
+        match Interpreter::new(&self.module).eval(&term, &Environment::empty())
+        {
+            Ok(value) => println!("{} : {}", value, scheme.normalize()),
+            Err(error) => self.report(&error),
+        }
+    }
+
+    /// Evaluates the input if it is a expression, otherwise it is
+    /// read as definitions.
+    fn code(&mut self, code: &str) {
+        let file = self.add_input(code);
+        let as_expression =
+            parse_expression_source(&self.source_map, file, self.store.clone());
+        let expression_error = match as_expression {
+            Ok(expression) => return self.evaluate(&expression),
+            Err(e) => e,
+        };
+        let mut definitions = String::from(code.trim_end());
+        if !definitions.ends_with(';') {
+            definitions.push(';');
+        }
+        let file = self.add_input(&definitions);
+        match parse_source(&self.source_map, file, self.store.clone()) {
+            Ok(_) => {
+                let session = self.session.clone();
+                self.define(file, session);
+            }
+            // We report the error of the parser that read more input.
+            Err(e) => {
+                if error_index(&expression_error) >= error_index(&e) {
+                    self.report_parser_error(expression_error)
+                } else {
+                    self.report_parser_error(e)
+                }
+            }
+        }
+    }
+
+    fn set(&mut self, option: &str, value: &str) -> Result<(), String> {
+        let parse_number = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("Expected a number, got: {}", value))
+        };
+        match option {
+            "prompt" => {
+                if value.contains('\n') {
+                    return Err(String::from(
+                        "The prompt can't contain line breaks",
+                    ));
+                }
+                self.prompt = String::from(value);
+            }
+            "column-width" => self.options.column_width = parse_number(value)?,
+            "indentation" => {
+                let deep = u16::try_from(parse_number(value)?)
+                    .map_err(|_| String::from("Indentation is too big"))?;
+                self.options.pretty_configuration.indentation_deep = deep
+            }
+            "tab-width" => set_tab_width(parse_number(value)?),
+            "renderer" => {
+                let renderer = AvailableRenderers::from_str(value, true)?;
+                self.options.highlight = renderer_highlight(renderer);
+            }
+            "json" => {
+                self.options.json_diagnostics =
+                    value.parse::<bool>().map_err(|_| {
+                        format!("Expected true or false, got: {}", value)
+                    })?
+            }
+            _ => return Err(format!("Unknown option: {}", option)),
+        }
+        Ok(())
+    }
+
+    fn meta_command(&mut self, input: &str) -> Continue {
+        match MetaCommand::parse(input) {
+            MetaCommand::Quit => return Continue::No,
+            MetaCommand::Help => println!("{}", HELP),
+            MetaCommand::Load(path) => self.load(path),
+            MetaCommand::Reload => match self.loaded.clone() {
+                Some(path) => self.load(path),
+                None => {
+                    eprintln!("There isn't a loaded file, use :load first.")
+                }
+            },
+            MetaCommand::Type(code) => self.type_of(&code),
+            MetaCommand::Format(code) => self.format(&code, None),
+            MetaCommand::Set { option, value } => {
+                if let Err(message) = self.set(&option, &value) {
+                    eprintln!("{}", message);
+                }
+            }
+            MetaCommand::Usage(usage) => eprintln!("Usage: {}", usage),
+            MetaCommand::Unknown(command) => eprintln!(
+                "Unknown command: {}, use :help to see the available commands.",
+                command
+            ),
+        }
+        Continue::Yes
+    }
+
+    fn input(&mut self, input: &str) -> Continue {
+        if input.trim().is_empty() {
+            Continue::Yes
+        } else if input.trim_start().starts_with(META_COMMAND_START) {
+            self.meta_command(input)
+        } else {
+            self.code(input);
+            Continue::Yes
+        }
+    }
+}
+
+pub fn repl(
+    configuration: ReplConfiguration,
+    options: &GlobalOptions,
+    store: Rc<RefCell<Store>>,
+) {
+    let mut state = Repl::new(configuration.prompt, options, store);
+    let editor_config = Config::builder()
+        .max_history_size(configuration.history_size)
+        .and_then(|c| c.history_ignore_dups(true))
+        .map(|c| c.auto_add_history(false).build());
+    let mut editor: Editor<ReplHelper, DefaultHistory> =
+        match editor_config.and_then(Editor::with_config) {
+            Ok(editor) => editor,
+            Err(e) => {
+                let error = match e {
+                    ReadlineError::Io(e) => e.kind(),
+                    _ => std::io::ErrorKind::Other,
+                };
+                state.report_io_error(IOError::REPlCantReadLine { error });
+                return;
+            }
+        };
+    editor.set_helper(Some(ReplHelper {
+        colored_prompt: state.colored_prompt(),
+        brackets: MatchingBracketHighlighter::new(),
+    }));
+    if let Some(path) = &configuration.history_path {
+        // A missing history file is expected the first time.
+        let _ = editor.load_history(path);
+    }
+    loop {
+        if let Some(helper) = editor.helper_mut() {
+            helper.colored_prompt = state.colored_prompt();
+        }
+        let prompt = state.prompt.clone();
+        match editor.readline(&prompt) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = editor.add_history_entry(line.as_str());
+                }
+                match state.input(&line) {
+                    Continue::Yes => (),
+                    Continue::No => break,
+                }
+            }
+            // Ctrl-C discards the current input.
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                let error = match e {
+                    ReadlineError::Io(e) => e.kind(),
+                    _ => std::io::ErrorKind::Other,
+                };
+                state.report_io_error(IOError::REPlCantReadLine { error });
+                break;
+            }
+        }
+    }
+    if let Some(path) = &configuration.history_path {
+        if editor.save_history(path).is_err() {
+            state
+                .report_io_error(IOError::FileLoadError { path: path.clone() });
+        }
+    }
+}
+
+#[cfg(test)]
+mod repl_test {
+    use super::*;
+    use crate::arguments::Arguments;
+    use clap::Parser;
+
+    fn repl() -> Repl {
+        let arguments = Arguments::parse_from(["octizys", "repl"]);
+        let options = GlobalOptions::from(arguments.formatter_configuration);
+        Repl::new(
+            String::from("> "),
+            &options,
+            Rc::new(RefCell::new(Store::default())),
+        )
+    }
+
+    #[test]
+    fn brackets_and_comments_keep_the_input_open() {
+        assert!(is_input_complete("f (a, [b])"));
+        assert!(!is_input_complete("f (a, [b]"));
+        assert!(!is_input_complete("{ a = 1"));
+        assert!(is_input_complete("1 {- a {- b -} -}"));
+        assert!(!is_input_complete("1 {- a {- b -}"));
+        // Brackets in comments and strings don't count.
+        assert!(is_input_complete("1 -- (\n"));
+        assert!(is_input_complete("\"(\""));
+        assert!(!is_input_complete("\"abc"));
+        // Like in the lexer a comment closes with its own hyphens.
+        assert!(is_input_complete("1 {-- a --}"));
+        assert!(!is_input_complete("1 {-- a -}"));
+        assert!(is_input_complete("1 {- a {-- b --} -}"));
+        assert!(!is_input_complete("1 {- a {-- b -} -}"));
+    }
+
+    #[test]
+    fn char_literals_dont_open_anything() {
+        assert!(is_input_complete("'('"));
+        assert!(is_input_complete("'\"'"));
+        assert!(is_input_complete("['{', '[']"));
+        assert!(is_input_complete("'\\''"));
+        assert!(is_input_complete("'\\u{28}'"));
+        assert!(!is_input_complete("f '('  ("));
+        // The ownership literals and variables aren't characters.
+        assert!(is_input_complete("f '0 (a) 'b"));
+        assert!(!is_input_complete("f '0 ("));
+        assert!(!is_input_complete("f 'inf (a, '1"));
+    }
+
+    #[test]
+    fn let_and_case_keep_the_input_open() {
+        assert!(!is_input_complete("let a = 1;"));
+        assert!(is_input_complete("let a = 1; in a"));
+        assert!(!is_input_complete("let a = let b = 1; in b;"));
+        assert!(!is_input_complete("case x"));
+        assert!(!is_input_complete("case x of"));
+        assert!(!is_input_complete("case x of { True -> 1"));
+        assert!(is_input_complete("case x of { True -> 1, False -> 0 }"));
+        // Only whole words are keywords.
+        assert!(is_input_complete("letter + cases + inner"));
+    }
+
+    #[test]
+    fn meta_commands_dispatch() {
+        assert_eq!(MetaCommand::parse(":quit"), MetaCommand::Quit);
+        assert_eq!(MetaCommand::parse("  :q  "), MetaCommand::Quit);
+        assert_eq!(
+            MetaCommand::parse(":type 1 + 2"),
+            MetaCommand::Type(String::from("1 + 2"))
+        );
+        assert_eq!(
+            MetaCommand::parse(":t"),
+            MetaCommand::Usage(":type <expression>")
+        );
+        assert_eq!(
+            MetaCommand::parse(":set column-width  40"),
+            MetaCommand::Set {
+                option: String::from("column-width"),
+                value: String::from("40")
+            }
+        );
+        assert_eq!(
+            MetaCommand::parse(":frobnicate x"),
+            MetaCommand::Unknown(String::from(":frobnicate"))
+        );
+        let mut repl = repl();
+        assert_eq!(repl.input(":quit"), Continue::No);
+        assert_eq!(repl.input(":frobnicate"), Continue::Yes);
+        assert_eq!(repl.input(":type True"), Continue::Yes);
+    }
+
+    #[test]
+    fn type_of_expressions() {
+        let mut repl = repl();
+        let scheme = repl.expression_type("\\x -> x");
+        assert_eq!(
+            scheme.map(|s| s.to_string()).as_deref(),
+            Some("forall a . a -> a")
+        );
+        assert!(repl.expression_type("True").is_some());
+        assert!(repl.expression_type("1 +").is_none());
+    }
+
+    #[test]
+    fn only_defined_files_are_loaded() {
+        let directory = std::env::temp_dir()
+            .join(format!("octizys_repl_load_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let good = directory.join("good.oct");
+        let bad = directory.join("bad.oct");
+        std::fs::write(&good, "main = 1;").unwrap();
+        std::fs::write(&bad, "main = 1 +;").unwrap();
+        let mut repl = repl();
+        repl.load(bad.clone());
+        assert_eq!(repl.loaded, None);
+        repl.load(good.clone());
+        assert_eq!(repl.loaded.as_ref(), Some(&good));
+        repl.load(bad);
+        assert_eq!(repl.loaded, Some(good));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+use octizys_pretty::combinators::{
+    external_text, foreground, group, intersperse, nest, soft_break, static_str,
+};
+use octizys_pretty::document::Document;
+use octizys_pretty::highlight::{base_colors, Color};
+use octizys_text_store::store::NonLineBreakStr;
+
+use crate::identifier::Identifier;
+use crate::span::Span;
+
+/// The color of the name of the nodes in a dump.
+pub const NODE_COLOR: Color = base_colors::CYAN;
+
+/// The color of the literals in a dump.
+pub const LITERAL_COLOR: Color = base_colors::GREEN;
+
+/// A representation of the internal trees of the compiler as
+/// S-expressions, it is meant for the bug reports, not for the
+/// users of the language.
+/// Unlike [`crate::equivalence::Equivalence::represent`] it keeps
+/// all the information, including the positions.
+pub trait Dump {
+    fn dump(&self) -> Document;
+}
+
+/// A node `(name child1 child2 ...)`, the children stay in the line
+/// of the name if they fit, otherwise every child goes in its own line.
+pub fn node<I: IntoIterator<Item = Document>>(
+    name: &'static str,
+    children: I,
+) -> Document {
+    const OPEN: NonLineBreakStr = NonLineBreakStr::new("(");
+    const CLOSE: NonLineBreakStr = NonLineBreakStr::new(")");
+    let name = foreground(NODE_COLOR, static_str(NonLineBreakStr::new(name)));
+    let children: Vec<Document> = children.into_iter().collect();
+    if children.is_empty() {
+        return static_str(OPEN) + name + static_str(CLOSE);
+    }
+    group(
+        static_str(OPEN)
+            + name
+            + nest(2, soft_break() + intersperse(children, soft_break()))
+            + static_str(CLOSE),
+    )
+}
+
+/// A leaf of the tree, the value is shown with its [`std::fmt::Display`].
+pub fn atom<T: std::fmt::Display>(value: T) -> Document {
+    external_text(&value.to_string())
+}
+
+/// A literal of the source, strings and chars are escaped to keep
+/// the dump in a single line.
+pub fn literal<T: std::fmt::Debug>(value: T) -> Document {
+    foreground(LITERAL_COLOR, external_text(&format!("{:?}", value)))
+}
+
+/// Lines and columns start at 1 like in the reports.
+impl Dump for Span {
+    fn dump(&self) -> Document {
+        atom(format!(
+            "{}:{}-{}:{}",
+            self.start.line + 1,
+            self.start.column + 1,
+            self.end.line + 1,
+            self.end.column + 1
+        ))
+    }
+}
+
+/// The text of the identifier is resolved at rendering time.
+impl Dump for Identifier {
+    fn dump(&self) -> Document {
+        let (symbol, len) = self.as_tuple();
+        Document::from_symbol_and_len(symbol, len)
+    }
+}
+
+impl<T: Dump> Dump for Box<T> {
+    fn dump(&self) -> Document {
+        self.as_ref().dump()
+    }
+}
+
+impl<T: Dump> Dump for Option<T> {
+    fn dump(&self) -> Document {
+        match self {
+            Some(value) => value.dump(),
+            None => node("None", []),
+        }
+    }
+}
+
+impl<T: Dump> Dump for Vec<T> {
+    fn dump(&self) -> Document {
+        node("list", self.iter().map(Dump::dump))
+    }
+}
+
+#[cfg(test)]
+mod dump_test {
+    use super::*;
+    use octizys_pretty::highlight::{EmptyRender, HighlightRenderer};
+    use octizys_pretty::store::Store;
+
+    fn render(document: &Document, width: usize) -> String {
+        let store = Store::default();
+        document.render_to_string(width, EmptyRender::render_highlight, &store)
+    }
+
+    #[test]
+    fn nodes_break_when_they_dont_fit() {
+        let tree = node(
+            "Application",
+            [node("Variable", [atom(0)]), literal("a \"b\"\n")],
+        );
+        assert_eq!(
+            render(&tree, 80),
+            "(Application (Variable 0) \"a \\\"b\\\"\\n\")"
+        );
+        assert_eq!(
+            render(&tree, 20),
+            "(Application\n  (Variable 0)\n  \"a \\\"b\\\"\\n\")"
+        );
+        assert_eq!(render(&node("Unit", []), 2), "(Unit)");
+    }
+}
@@ -1,5 +1,6 @@
 use octizys_pretty::combinators::{
-    self, background, between_static, foreground, hard_break, intersperse, nest,
+    self, background, between_static, empty_break, foreground, group,
+    hard_break, intersperse, nest,
 };
 use octizys_pretty::document::Document;
 
@@ -24,12 +25,13 @@ pub const ERROR_BACKGROUND_COLOR: Color = Color {
     color4: Color4Bits::Magenta,
 };
 
+/// The closing parenthesis stays in the line of the node if it fits.
 pub fn parens<Doc: Into<Document>>(inner: Doc) -> Document {
-    between_static(
+    group(between_static(
         NonLineBreakStr::new("("),
-        nest(2, inner.into()) + hard_break(),
+        nest(2, inner.into()) + empty_break(),
         NonLineBreakStr::new(")"),
-    )
+    ))
 }
 
 pub trait Equivalence {
@@ -141,7 +143,7 @@ impl<T: Equivalence> Equivalence for Option<T> {
     fn represent(&self) -> Document {
         match self {
             Some(x) => {
-                combinators::static_str(NonLineBreakStr::new("Some "))
+                combinators::static_str(NonLineBreakStr::new("Some"))
                     + nest(2, hard_break() + parens(x.represent()))
             }
             None => combinators::static_str(NonLineBreakStr::new("None")),
@@ -306,6 +308,9 @@ where
 
     fn represent(&self) -> Document {
         const VECT: NonLineBreakStr = NonLineBreakStr::new("Vec");
+        if self.is_empty() {
+            return combinators::static_str(VECT);
+        }
         let remain = self.iter().map(|x| parens(x.represent()));
         combinators::static_str(VECT)
             + nest(2, hard_break() + intersperse(remain, hard_break()))
@@ -318,7 +323,7 @@ mod equivalence_test {
         HighlightRenderer, TerminalRender24, TerminalRender4, TerminalRender8,
     };
 
-    use super::{assert_equivalent, Equivalence};
+    use super::{assert_equivalent, Document, Equivalence};
 
     #[test]
     fn symmetry() {
@@ -345,4 +350,22 @@ mod equivalence_test {
             TerminalRender24::render_highlight,
         );
     }
+
+    #[test]
+    fn represent_names_and_empty_nodes() {
+        use crate::identifier::Identifier;
+        use octizys_pretty::highlight::EmptyRender;
+        use octizys_pretty::store::Store;
+        let mut store = Store::default();
+        let name = Identifier::make("count", &mut store).unwrap();
+        let render = |document: Document| {
+            document.render_to_string(80, EmptyRender::render_highlight, &store)
+        };
+        assert_eq!(
+            render(Some(name).represent()),
+            "Some\n  (Identifier count)"
+        );
+        assert_eq!(render(vec![None::<u8>].represent()), "Vec\n  (None)");
+        assert_eq!(render(Vec::<u8>::new().represent()), "Vec");
+    }
 }
@@ -1,5 +1,7 @@
-use octizys_pretty::{combinators::static_str, store::NonLineBreakStr};
-use octizys_text_store::store::{approximate_string_width, Store};
+use octizys_pretty::{
+    combinators::static_str, document::Document, store::NonLineBreakStr,
+};
+use octizys_text_store::{store::Store, width::display_width};
 
 use regex::Regex;
 use std::{fmt::Display, sync::LazyLock};
@@ -22,9 +24,11 @@ impl Equivalence for Identifier {
         true
     }
 
+    /// The text of the identifier is resolved at rendering time.
     fn represent(&self) -> octizys_pretty::document::Document {
-        const IDENT: NonLineBreakStr = NonLineBreakStr::new("Identifier");
-        static_str(IDENT)
+        const IDENT: NonLineBreakStr = NonLineBreakStr::new("Identifier ");
+        let (symbol, len) = self.as_tuple();
+        static_str(IDENT) + Document::from_symbol_and_len(symbol, len)
     }
 
     fn equivalence_or_diff(
@@ -98,7 +102,7 @@ impl<'a> Identifier {
         }
         if IDENTIFIER_LAZY_REGEX.is_match(&s) {
             let symbol = store.regular.unsafe_add(s);
-            let len = approximate_string_width(s);
+            let len = display_width(s);
             Ok(Identifier { symbol, len })
         } else {
             return Err(IdentifierError::ContainsInvalidCodePoint(
@@ -1,6 +1,8 @@
 pub mod identifier;
 pub mod logic_path;
 pub mod report;
+pub mod source_map;
 pub mod span;
 
+pub mod dump;
 pub mod equivalence;
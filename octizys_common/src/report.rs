@@ -5,7 +5,8 @@ use crate::span::{HasLocation, Position};
 #[cfg(feature = "lalrpop")]
 use lalrpop_util::ParseError;
 
-use crate::span::Location;
+use crate::source_map::SourceMap;
+use crate::span::{Location, Span};
 use octizys_pretty::{
     combinators::{
         self, concat, emphasis, empty, empty_break, external_text, foreground,
@@ -16,10 +17,11 @@ use octizys_pretty::{
         base_colors::{CYAN, MAGENTA, MODERATE_GREEN, RED},
         Color, Emphasis,
     },
+    highlight::{EmptyRender, HighlightRenderer},
     static_text,
-    store::NonLineBreakStr,
+    store::{NonLineBreakStr, Store},
 };
-use octizys_text_store::store::approximate_string_width;
+use octizys_text_store::width::{display_width, display_width_at, expand_tabs};
 
 #[derive(Debug)]
 pub struct ReportSourceContext<'source> {
@@ -28,6 +30,28 @@ pub struct ReportSourceContext<'source> {
     pub max_line_width: usize,
 }
 
+impl<'source> ReportSourceContext<'source> {
+    /// Builds the context using the file of the location, if there's
+    /// no location or the file isn't in the map we use an empty source.
+    pub fn from_source_map(
+        map: &'source SourceMap,
+        location: Option<Location>,
+        max_line_width: usize,
+    ) -> Self {
+        match location.and_then(|l| map.get(l.file())) {
+            Some(file) => ReportSourceContext {
+                src: file.content(),
+                src_name: String::from(file.name()),
+                max_line_width,
+            },
+            None => ReportSourceContext {
+                max_line_width,
+                ..Default::default()
+            },
+        }
+    }
+}
+
 impl<'a> Default for ReportSourceContext<'a> {
     fn default() -> Self {
         ReportSourceContext {
@@ -92,12 +116,83 @@ impl ReportKind {
     }
 }
 
+/// How confident we are that applying a [`Suggestion`] produces the
+/// code the user meant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user meant, tools
+    /// (like `octizys fix`) can apply it without asking.
+    MachineApplicable,
+    /// The suggestion may be what the user meant, but we are not sure.
+    MaybeIncorrect,
+    /// The replacement contains placeholders the user must fill.
+    HasPlaceholders,
+    /// We don't know anything about the suggestion.
+    Unspecified,
+}
+
+impl Applicability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Applicability::MachineApplicable => "MachineApplicable",
+            Applicability::MaybeIncorrect => "MaybeIncorrect",
+            Applicability::HasPlaceholders => "HasPlaceholders",
+            Applicability::Unspecified => "Unspecified",
+        }
+    }
+}
+
+/// A suggested edit to fix a report.
+/// The text in [`Suggestion::span`] is replaced by
+/// [`Suggestion::replacement`], an empty span means an insertion and an
+/// empty replacement means a deletion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub description: NonLineBreakStr,
+    pub span: Span,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+impl Suggestion {
+    pub fn insert_at(
+        description: NonLineBreakStr,
+        position: crate::span::Position,
+        text: String,
+        applicability: Applicability,
+    ) -> Self {
+        Suggestion {
+            description,
+            span: Span::from((position, position)),
+            replacement: text,
+            applicability,
+        }
+    }
+
+    pub fn remove(
+        description: NonLineBreakStr,
+        span: Span,
+        applicability: Applicability,
+    ) -> Self {
+        Suggestion {
+            description,
+            span,
+            replacement: String::new(),
+            applicability,
+        }
+    }
+}
+
 pub trait ReportFormat {
     fn get_report_name(&self) -> NonLineBreakStr;
     fn get_short_description(&self) -> NonLineBreakStr;
     fn get_long_description(&self, target: &ReportTarget) -> Option<Document>;
     fn get_expected(&self) -> Option<Vec<String>>;
     fn get_location_maybe(&self) -> Option<Location>;
+    /// The fixes to offer, most reports don't have any.
+    fn get_suggestions(&self) -> Option<Vec<Suggestion>> {
+        None
+    }
 }
 
 pub struct ReportRequest<'source, T>
@@ -112,6 +207,33 @@ where
     pub kind: ReportKind,
 }
 
+impl<'source, T> ReportRequest<'source, T>
+where
+    T: ReportFormat,
+{
+    /// Every report looks for the source of its location in the map,
+    /// this way a single [`SourceMap`] can be used for reports of
+    /// multiple files.
+    pub fn from_source_map(
+        report: &'source T,
+        map: &'source SourceMap,
+        target: ReportTarget,
+        kind: ReportKind,
+        max_line_width: usize,
+    ) -> Self {
+        ReportRequest {
+            report,
+            source_context: ReportSourceContext::from_source_map(
+                map,
+                report.get_location_maybe(),
+                max_line_width,
+            ),
+            target,
+            kind,
+        }
+    }
+}
+
 pub fn make_report_kind<R: ReportFormat>(
     report: &R,
     report_kind: &ReportKind,
@@ -165,14 +287,7 @@ pub fn make_report_info_start<R: ReportFormat>(
     let location = request.report.get_location_maybe();
     let kind = make_report_kind(request.report, &request.kind, &request.target);
     let short_description = request.report.get_short_description();
-    let location_doc = concat(vec![
-        foreground(CYAN, external_text("-->")),
-        foreground(
-            MODERATE_GREEN,
-            external_text(&request.source_context.src_name),
-        ),
-        location.map_or_else(combinators::empty, |x| Location::to_document(&x)),
-    ]);
+    let location_doc = location_to_document(&request.source_context, location);
     concat(vec![
         kind,
         static_str(short_description),
@@ -180,6 +295,20 @@ pub fn make_report_info_start<R: ReportFormat>(
     ])
 }
 
+/// The `-->SourcePath Location` reference to a place in the source.
+fn location_to_document(
+    source_context: &ReportSourceContext,
+    location: Option<Location>,
+) -> Document {
+    concat(vec![
+        foreground(CYAN, external_text("-->")),
+        foreground(MODERATE_GREEN, external_text(&source_context.src_name)),
+        location.map_or_else(combinators::empty, |x| {
+            x.to_document(source_context.src)
+        }),
+    ])
+}
+
 fn expected_to_document(
     expected: Option<Vec<String>>,
     target: &ReportTarget,
@@ -243,20 +372,26 @@ fn make_source_error<R: ReportFormat>(
                 let remain_width = request
                     .source_context
                     .max_line_width
-                    .saturating_sub(approximate_string_width(content));
+                    .saturating_sub(display_width(content));
                 let (before, _, after) = span.get_text_at(
                     request.source_context.src,
                     Some(usize::from(remain_width)),
                 );
-                let pre_spaces = approximate_string_width(before);
-                let pointer = "^".repeat(approximate_string_width(content));
+                // The tabs are expanded, otherwise the terminal would
+                // choose their width and the pointer would be misplaced.
+                let before = expand_tabs(before, 0);
+                let pre_spaces = display_width(&before);
+                let content = expand_tabs(content, pre_spaces);
+                let content_width = display_width_at(&content, pre_spaces);
+                let after = expand_tabs(after, pre_spaces + content_width);
+                let pointer = "^".repeat(content_width);
                 concat(vec![
-                    external_text(before),
+                    external_text(&before),
                     emphasis(
                         Emphasis::Underline(RED),
-                        foreground(MAGENTA, external_text(content)),
+                        foreground(MAGENTA, external_text(&content)),
                     ),
-                    external_text(after),
+                    external_text(&after),
                     nest(
                         // This may be safe as [`before`] has to be smaller
                         // than [`remain_width`].
@@ -296,8 +431,10 @@ fn make_source_error<R: ReportFormat>(
         Location::Position(position) => {
             let (before, after) =
                 position.get_text_at(request.source_context.src, Some(80));
-            let pre_spaces = approximate_string_width(before);
+            let before = expand_tabs(before, 0);
+            let pre_spaces = display_width(&before);
             let spaces = " ".repeat(pre_spaces) + "^";
+            let after = expand_tabs(after, pre_spaces);
             let mut after_iter = after.chars();
             let after_doc = match after_iter.next() {
                 Some(c) => {
@@ -312,7 +449,7 @@ fn make_source_error<R: ReportFormat>(
                 None => empty(),
             };
             concat(vec![
-                external_text(before),
+                external_text(&before),
                 after_doc,
                 hard_break(),
                 foreground(RED, external_text(&spaces)),
@@ -328,6 +465,69 @@ fn make_source_error<R: ReportFormat>(
     }
 }
 
+fn suggestion_to_document(
+    suggestion: &Suggestion,
+    source_context: &ReportSourceContext,
+) -> Document {
+    let action = if suggestion.span.start == suggestion.span.end {
+        static_text!("insert ")
+    } else if suggestion.replacement.is_empty() {
+        static_text!("remove ")
+    } else {
+        static_text!("replace with ")
+    };
+    let replacement = if suggestion.replacement.is_empty() {
+        empty()
+    } else {
+        emphasis(
+            Emphasis::Bold,
+            foreground(
+                CYAN,
+                external_text(&format!(
+                    "\"{}\" ",
+                    suggestion.replacement.escape_debug()
+                )),
+            ),
+        )
+    };
+    let location = if suggestion.span.start == suggestion.span.end {
+        Location::Position(suggestion.span.start)
+    } else {
+        Location::Span(suggestion.span)
+    };
+    concat(vec![
+        foreground(MODERATE_GREEN, static_text!("Suggestion: ")),
+        static_str(suggestion.description),
+        nest(
+            2,
+            hard_break()
+                + action
+                + replacement
+                + location_to_document(source_context, Some(location)),
+        ),
+    ])
+}
+
+fn suggestions_to_document(
+    suggestions: Option<Vec<Suggestion>>,
+    source_context: &ReportSourceContext,
+) -> Document {
+    match suggestions {
+        None => empty(),
+        Some(v) => concat(
+            v.iter()
+                .map(|x| {
+                    nest(
+                        2,
+                        hard_break()
+                            + suggestion_to_document(x, source_context),
+                    )
+                })
+                .collect(),
+        ),
+    }
+}
+
 /// Use it to render a report request.
 pub fn create_error_report<R: ReportFormat>(
     request: &ReportRequest<R>,
@@ -350,9 +550,159 @@ pub fn create_error_report<R: ReportFormat>(
             combinators::empty()
         },
         nest(2, hard_break() + long_description),
+        suggestions_to_document(
+            request.report.get_suggestions(),
+            &request.source_context,
+        ),
     ])
 }
 
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_position(p: &crate::span::Position, src: &str) -> String {
+    format!(
+        "{{\"index\":{},\"line\":{},\"column\":{}}}",
+        p.source_index,
+        p.line + 1,
+        p.display_column(src) + 1
+    )
+}
+
+fn json_span(s: &Span, src: &str) -> String {
+    format!(
+        "{{\"start\":{},\"end\":{}}}",
+        json_position(&s.start, src),
+        json_position(&s.end, src)
+    )
+}
+
+fn json_location(l: &Location, src: &str) -> String {
+    match l {
+        Location::Span(s) => json_span(s, src),
+        Location::Position(p) => json_span(&Span::from((*p, *p)), src),
+    }
+}
+
+fn json_suggestion(s: &Suggestion, src: &str) -> String {
+    format!(
+        "{{\"description\":{},\"span\":{},\"replacement\":{},\"applicability\":{}}}",
+        json_string(s.description.as_str()),
+        json_span(&s.span, src),
+        json_string(&s.replacement),
+        json_string(s.applicability.as_str())
+    )
+}
+
+/// Renders a report request as a single line JSON object.
+///
+/// The long description is a [`Document`] and it is rendered without
+/// highlight using the [`Store`] passed.
+///
+/// # Examples
+///
+/// ```text
+/// {"kind":"Error","name":"ExtraToken","source":"a.oct",
+///  "short_description":"...","long_description":"...",
+///  "location":{"start":{"index":3,"line":1,"column":4},"end":...},
+///  "expected":null,
+///  "suggestions":[{"description":"...","span":...,
+///                  "replacement":"","applicability":"MaybeIncorrect"}]}
+/// ```
+pub fn create_json_report<R: ReportFormat>(
+    request: &ReportRequest<R>,
+    store: &Store,
+) -> String {
+    let report = request.report;
+    let long_description = report
+        .get_long_description(&request.target)
+        .map(|d| {
+            d.render_to_string(
+                request.source_context.max_line_width,
+                EmptyRender::render_highlight,
+                store,
+            )
+        })
+        .map_or_else(|| String::from("null"), |d| json_string(&d));
+    let location = report.get_location_maybe().map_or_else(
+        || String::from("null"),
+        |l| json_location(&l, request.source_context.src),
+    );
+    let expected = report.get_expected().map_or_else(
+        || String::from("null"),
+        |v| {
+            let items: Vec<String> = v.iter().map(|x| json_string(x)).collect();
+            format!("[{}]", items.join(","))
+        },
+    );
+    let suggestions: Vec<String> = report
+        .get_suggestions()
+        .unwrap_or_default()
+        .iter()
+        .map(|s| json_suggestion(s, request.source_context.src))
+        .collect();
+    format!(
+        "{{\"kind\":{},\"name\":{},\"source\":{},\"short_description\":{},\"long_description\":{},\"location\":{},\"expected\":{},\"suggestions\":[{}]}}",
+        json_string(request.kind.as_str().as_str()),
+        json_string(report.get_report_name().as_str()),
+        json_string(&request.source_context.src_name),
+        json_string(report.get_short_description().as_str()),
+        long_description,
+        location,
+        expected,
+        suggestions.join(",")
+    )
+}
+
+/// Applies the suggestions to the source, the suggestions that overlap
+/// with a previous one (in source order) are skipped.
+/// Returns the new source and the number of suggestions applied.
+pub fn apply_suggestions(
+    src: &str,
+    suggestions: &[Suggestion],
+) -> (String, usize) {
+    let mut sorted: Vec<&Suggestion> = suggestions.iter().collect();
+    sorted
+        .sort_by_key(|s| (s.span.start.source_index, s.span.end.source_index));
+    let mut out = String::with_capacity(src.len());
+    let mut last = 0;
+    let mut applied = 0;
+    for suggestion in sorted {
+        let start = suggestion.span.start.source_index;
+        let end = suggestion.span.end.source_index;
+        if start < last
+            || end > src.len()
+            || !src.is_char_boundary(start)
+            || !src.is_char_boundary(end)
+        {
+            continue;
+        }
+        out.push_str(&src[last..start]);
+        out.push_str(&suggestion.replacement);
+        last = end;
+        applied += 1;
+    }
+    out.push_str(&src[last..]);
+    (out, applied)
+}
+
 #[cfg(feature = "lalrpop")]
 impl<T, E> ReportFormat for ParseError<Position, T, E>
 where
@@ -425,6 +775,58 @@ where
             ParseError::User { error } => error.get_location_maybe(),
         }
     }
+    fn get_suggestions(&self) -> Option<Vec<Suggestion>> {
+        match self {
+            ParseError::InvalidToken { .. } => None,
+            ParseError::UnrecognizedEof { location, expected } => {
+                single_literal_expected(expected).map(|literal| {
+                    vec![Suggestion::insert_at(
+                        NonLineBreakStr::new("Add the missing code"),
+                        *location,
+                        literal,
+                        Applicability::MaybeIncorrect,
+                    )]
+                })
+            }
+            ParseError::UnrecognizedToken {
+                token: (start, _, _),
+                expected,
+            } => single_literal_expected(expected).map(|literal| {
+                vec![Suggestion::insert_at(
+                    NonLineBreakStr::new("Add the missing code"),
+                    *start,
+                    literal + " ",
+                    Applicability::MaybeIncorrect,
+                )]
+            }),
+            ParseError::ExtraToken {
+                token: (start, _, end),
+            } => Some(vec![Suggestion::remove(
+                NonLineBreakStr::new("Remove the extra code"),
+                Span::from((*start, *end)),
+                Applicability::MaybeIncorrect,
+            )]),
+            ParseError::User { error } => error.get_suggestions(),
+        }
+    }
+}
+
+/// The parser generator reports the expected terminals as they
+/// appear in the grammar, the literal ones are quoted.
+/// We only suggest something when there is a unique literal candidate.
+#[cfg(feature = "lalrpop")]
+fn single_literal_expected(expected: &[String]) -> Option<String> {
+    match expected {
+        [only] => {
+            let inner = only.strip_prefix('"')?.strip_suffix('"')?;
+            if inner.is_empty() {
+                None
+            } else {
+                Some(inner.replace("\\\"", "\"").replace("\\\\", "\\"))
+            }
+        }
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -515,3 +917,94 @@ impl ReportFormat for IOError {
         }
     }
 }
+
+#[cfg(test)]
+mod report_test {
+    use super::{
+        apply_suggestions, json_position, suggestion_to_document,
+        Applicability, ReportSourceContext, Suggestion,
+    };
+    use crate::span::{Position, Span};
+    use octizys_pretty::highlight::{EmptyRender, HighlightRenderer};
+    use octizys_pretty::store::{NonLineBreakStr, Store};
+
+    fn position(i: usize) -> Position {
+        Position::from((i, 0, i))
+    }
+
+    #[test]
+    fn apply_insert_and_remove() {
+        let suggestions = vec![
+            Suggestion::insert_at(
+                NonLineBreakStr::new("insert"),
+                position(3),
+                String::from("-}"),
+                Applicability::MachineApplicable,
+            ),
+            Suggestion::remove(
+                NonLineBreakStr::new("remove"),
+                Span::from((position(0), position(1))),
+                Applicability::MachineApplicable,
+            ),
+        ];
+        let (result, applied) = apply_suggestions("a{-b", &suggestions);
+        assert_eq!(applied, 2);
+        assert_eq!(result, "{--}b");
+    }
+
+    #[test]
+    fn apply_skips_overlapping() {
+        let suggestions = vec![
+            Suggestion::remove(
+                NonLineBreakStr::new("first"),
+                Span::from((position(0), position(3))),
+                Applicability::MachineApplicable,
+            ),
+            Suggestion::remove(
+                NonLineBreakStr::new("second"),
+                Span::from((position(1), position(2))),
+                Applicability::MachineApplicable,
+            ),
+        ];
+        let (result, applied) = apply_suggestions("abcd", &suggestions);
+        assert_eq!(applied, 1);
+        assert_eq!(result, "d");
+    }
+
+    #[test]
+    fn suggestion_names_the_position_like_the_header() {
+        let suggestion = Suggestion::insert_at(
+            NonLineBreakStr::new("Add the missing code"),
+            Position::from((8, 0, 8)),
+            String::from(":: "),
+            Applicability::MaybeIncorrect,
+        );
+        let source_context = ReportSourceContext {
+            src: "main = 1",
+            src_name: String::from("a.oct"),
+            max_line_width: 80,
+        };
+        let rendered = suggestion_to_document(&suggestion, &source_context)
+            .render_to_string(
+                80,
+                EmptyRender::render_highlight,
+                &Store::default(),
+            );
+        assert_eq!(
+            rendered,
+            "Suggestion: Add the missing code\n  insert \":: \" -->a.oct::At::Line{1}::Column{9}"
+        );
+    }
+
+    #[test]
+    fn json_column_is_the_display_column() {
+        // The tab goes to the fourth column and the wide character
+        // uses two columns, while they use four bytes.
+        let src = "a\n\t日x";
+        let p = Position::from((6, 1, 4));
+        assert_eq!(
+            json_position(&p, src),
+            "{\"index\":6,\"line\":2,\"column\":7}"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+
+use crate::{report::IOError, span::Position};
+
+/// Identifies a file inside a [`SourceMap`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// The file of the sources that don't come from a map, no map
+    /// has it.
+    pub const DETACHED: FileId = FileId(u32::MAX);
+
+    pub fn as_usize(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A source file and the index of the start of every line.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    name: String,
+    path: Option<PathBuf>,
+    content: String,
+    /// The byte index of the beginning of every line, the first
+    /// element is always 0.
+    line_starts: Vec<usize>,
+}
+
+fn compute_line_starts(content: &str) -> Vec<usize> {
+    let mut line_starts = vec![0];
+    line_starts.extend(
+        content
+            .bytes()
+            .enumerate()
+            .filter(|(_, b)| *b == b'\n')
+            .map(|(i, _)| i + 1),
+    );
+    line_starts
+}
+
+impl SourceFile {
+    pub fn new(name: String, path: Option<PathBuf>, content: String) -> Self {
+        let line_starts = compute_line_starts(&content);
+        SourceFile {
+            name,
+            path,
+            content,
+            line_starts,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the line (starting at 0) that contains the byte index.
+    /// Indexes after the end of the content belong to the last line.
+    pub fn line_of(&self, source_index: usize) -> usize {
+        match self.line_starts.binary_search(&source_index) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        }
+    }
+
+    /// The line and column (both starting at 0) of the byte index.
+    /// The column is in bytes like in [`Position`].
+    pub fn line_column(&self, source_index: usize) -> (usize, usize) {
+        let source_index = source_index.min(self.content.len());
+        let line = self.line_of(source_index);
+        (line, source_index - self.line_starts[line])
+    }
+
+    /// The text of the line without the line break, a `\r` before
+    /// the `\n` is part of the line break.
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.content.len(), |next| next - 1);
+        let text = &self.content[start..end];
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+}
+
+/// The set of sources involved in a compilation.
+/// Every [`Position`] refers to a file in the map trough its
+/// [`FileId`], this allows us to report errors that involve
+/// multiple files.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn add(
+        &mut self,
+        name: String,
+        path: Option<PathBuf>,
+        content: String,
+    ) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        self.files.push(SourceFile::new(name, path, content));
+        id
+    }
+
+    /// Reads a file and adds it to the map, if the file is already in
+    /// the map we return the previous [`FileId`] without reading it.
+    pub fn load(&mut self, path: &Path) -> Result<FileId, IOError> {
+        if let Some(id) = self.find_by_path(path) {
+            return Ok(id);
+        }
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                let name = path.to_string_lossy().into_owned();
+                Ok(self.add(name, Some(path.to_path_buf()), content))
+            }
+            Err(_) => Err(IOError::FileLoadError {
+                path: path.to_path_buf(),
+            }),
+        }
+    }
+
+    /// Replaces the content of a file, the [`FileId`] remains valid.
+    pub fn update(&mut self, file: FileId, content: String) {
+        if let Some(source) = self.files.get_mut(file.as_usize()) {
+            source.line_starts = compute_line_starts(&content);
+            source.content = content;
+        }
+    }
+
+    pub fn find_by_path(&self, path: &Path) -> Option<FileId> {
+        self.files
+            .iter()
+            .position(|f| f.path() == Some(path))
+            .map(|i| FileId(i as u32))
+    }
+
+    pub fn get(&self, file: FileId) -> Option<&SourceFile> {
+        self.files.get(file.as_usize())
+    }
+
+    /// Builds the full [`Position`] of a byte index inside a file.
+    /// Indexes after the end of the content become the end.
+    pub fn position(
+        &self,
+        file: FileId,
+        source_index: usize,
+    ) -> Option<Position> {
+        let source = self.get(file)?;
+        let source_index = source_index.min(source.content().len());
+        let (line, column) = source.line_column(source_index);
+        Some(Position {
+            source_index,
+            line,
+            column,
+            file,
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (FileId(i as u32), f))
+    }
+}
+
+#[cfg(test)]
+mod source_map_test {
+    use super::{FileId, SourceMap};
+
+    #[test]
+    fn line_column_lookup() {
+        let mut map = SourceMap::new();
+        let file =
+            map.add(String::from("a"), None, String::from("ab\ncd\n\ne"));
+        let source = map.get(file).unwrap();
+        assert_eq!(source.line_count(), 4);
+        assert_eq!(source.line_column(0), (0, 0));
+        assert_eq!(source.line_column(2), (0, 2));
+        assert_eq!(source.line_column(3), (1, 0));
+        assert_eq!(source.line_column(6), (2, 0));
+        assert_eq!(source.line_column(7), (3, 0));
+        assert_eq!(source.line_column(100), (3, 1));
+        assert_eq!(source.line_text(1), Some("cd"));
+        assert_eq!(source.line_text(2), Some(""));
+        assert_eq!(source.line_text(3), Some("e"));
+        assert_eq!(source.line_text(4), None);
+        let file = map.add(String::from("b"), None, String::from("ab\r\ncd"));
+        let source = map.get(file).unwrap();
+        assert_eq!(source.line_text(0), Some("ab"));
+        assert_eq!(source.line_text(1), Some("cd"));
+    }
+
+    #[test]
+    fn multiple_files() {
+        let mut map = SourceMap::new();
+        let first = map.add(String::from("a"), None, String::from("x"));
+        let second = map.add(String::from("b"), None, String::from("\ny"));
+        assert_ne!(first, second);
+        let position = map.position(second, 1).unwrap();
+        assert_eq!(position.line, 1);
+        assert_eq!(position.column, 0);
+        assert_eq!(position.file, second);
+        assert_eq!(map.get(first).unwrap().name(), "a");
+        let end = map.position(second, 100).unwrap();
+        assert_eq!((end.source_index, end.line, end.column), (2, 1, 1));
+        assert_eq!(map.get(FileId::DETACHED).map(|f| f.name()), None);
+    }
+}
@@ -1,5 +1,9 @@
 use std::{fmt::Display, ops::Add};
 
+use crate::source_map::FileId;
+
+use octizys_text_store::width::display_width;
+
 use octizys_pretty::{
     combinators::{concat, external_text},
     document::Document,
@@ -8,12 +12,16 @@ use octizys_pretty::{
 
 /// Represents a Position on a file.
 /// The Eq and Ord instances are based only on the index, is
-/// user responsibility to be congruent with this.
+/// user responsibility to be congruent with this (don't compare
+/// positions of different files).
 #[derive(Debug, Copy, Clone, Hash)]
 pub struct Position {
     pub source_index: usize,
     pub line: usize,
     pub column: usize,
+    /// The file in the [`crate::source_map::SourceMap`] this position
+    /// belongs to.
+    pub file: FileId,
 }
 
 impl PartialEq for Position {
@@ -43,6 +51,7 @@ impl Default for Position {
             source_index: 0,
             line: 0,
             column: 0,
+            file: FileId::DETACHED,
         }
     }
 }
@@ -112,6 +121,20 @@ impl Position {
         let after = after_pre.split("\n").collect::<Vec<&str>>()[0];
         (before, after)
     }
+
+    /// The column (starting at 0) where the position is shown in a
+    /// terminal, the [`Position::column`] counts bytes instead.
+    /// If the position isn't inside the source we use the
+    /// [`Position::column`].
+    pub fn display_column(&self, src: &str) -> usize {
+        match src.get(..self.source_index) {
+            Some(before) => {
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                display_width(&before[line_start..])
+            }
+            None => self.column,
+        }
+    }
 }
 
 impl From<(usize, usize, usize)> for Position {
@@ -121,6 +144,7 @@ impl From<(usize, usize, usize)> for Position {
             source_index,
             line,
             column,
+            file: FileId::DETACHED,
         }
     }
 }
@@ -168,6 +192,10 @@ impl Add for Span {
 }
 
 impl Span {
+    pub fn file(&self) -> FileId {
+        self.start.file
+    }
+
     /// Returns the span content including line breaks plus some surrounding text. Always return
     /// all the span content.
     /// The max_len is the maximal length of the surrounding text.
@@ -230,11 +258,19 @@ pub trait HasLocation {
 }
 
 impl Location {
-    pub fn to_document(&self) -> Document {
+    pub fn file(&self) -> FileId {
+        match self {
+            Location::Span(s) => s.file(),
+            Location::Position(p) => p.file,
+        }
+    }
+
+    /// The columns are the ones shown in a terminal for the `src`.
+    pub fn to_document(&self, src: &str) -> Document {
         match self {
             Location::Span(s) => {
-                let start = position_to_document(&s.start);
-                let end = position_to_document(&s.end);
+                let start = position_to_document(&s.start, src);
+                let end = position_to_document(&s.end, src);
                 concat(vec![
                     static_text!("::From::"),
                     start,
@@ -243,20 +279,20 @@ impl Location {
                 ])
             }
             Location::Position(p) => {
-                let pos = position_to_document(p);
+                let pos = position_to_document(p, src);
                 static_text!("::At::") + pos
             }
         }
     }
 }
 
-fn position_to_document(p: &Position) -> Document {
+fn position_to_document(p: &Position, src: &str) -> Document {
     concat(vec![
         static_text!("Line{"),
         external_text((1 + p.line).to_string().as_str()),
         external_text("}"),
         static_text!("::Column{"),
-        external_text((1 + p.column).to_string().as_str()),
+        external_text((1 + p.display_column(src)).to_string().as_str()),
         static_text!("}"),
     ])
 }
@@ -0,0 +1,808 @@
+//! The binary representation of a compiled [`Module`].
+//!
+//! An artefact starts with [`MAGIC`] and the [`ARTEFACT_VERSION`] as
+//! a little endian `u32`, followed by the types and definitions of
+//! the module. Numbers are little endian, texts and lists are
+//! prefixed by their length as a `u64` and every variant of
+//! [`Type`] and [`Term`] starts with a tag byte.
+//! Any change of the encoding must increase the version.
+
+use std::sync::Arc;
+
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::Location;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+
+use crate::common::{Identifier, Label, Record, Variable};
+use crate::core::{Definition, Module, TypeDefinition};
+use crate::evaluation::{with_stack, DEFAULT_MAX_DEPTH};
+use crate::primitives::Primitive;
+use crate::terms::{CaseAlternative, Term};
+use crate::types::Type;
+
+/// The extension of the files with artefacts.
+pub const ARTEFACT_EXTENSION: &str = "octc";
+pub const MAGIC: &[u8; 4] = b"OCTC";
+pub const ARTEFACT_VERSION: u32 = 1;
+
+/// The maximum amount of nested types or terms, a damaged artefact
+/// can't overflow the stack of the decoder. The interpreter can't
+/// evaluate deeper terms either.
+const MAX_NESTING: usize = DEFAULT_MAX_DEPTH;
+
+/// The position of a primitive is its tag.
+const PRIMITIVES: [Primitive; 28] = [
+    Primitive::AddUint,
+    Primitive::SubtractUint,
+    Primitive::MultiplyUint,
+    Primitive::DivideUint,
+    Primitive::RemainderUint,
+    Primitive::EqualUint,
+    Primitive::NotEqualUint,
+    Primitive::LessUint,
+    Primitive::LessOrEqualUint,
+    Primitive::MoreUint,
+    Primitive::MoreOrEqualUint,
+    Primitive::SubtractInt,
+    Primitive::ShowUint,
+    Primitive::ShowInt,
+    Primitive::ConcatString,
+    Primitive::ShowUintRadix,
+    Primitive::ShowIntRadix,
+    Primitive::DebugString,
+    Primitive::UppercaseString,
+    Primitive::TruncateString,
+    Primitive::AlignLeft,
+    Primitive::AlignRight,
+    Primitive::AlignCenter,
+    Primitive::PadZeros,
+    Primitive::EqualChar,
+    Primitive::ShowChar,
+    Primitive::DebugChar,
+    Primitive::EqualInt,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArtefactError {
+    /// The bytes don't start with [`MAGIC`].
+    NotAnArtefact,
+    UnsupportedVersion {
+        found: u32,
+    },
+    /// The bytes ended in the middle of a item.
+    Truncated,
+    InvalidTag {
+        item: &'static str,
+        tag: u8,
+    },
+    InvalidText,
+    /// The types or terms are nested more than [`MAX_NESTING`] times.
+    TooNested,
+    /// There are bytes after the module.
+    TrailingBytes {
+        amount: usize,
+    },
+}
+
+impl ReportFormat for ArtefactError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            ArtefactError::NotAnArtefact => {
+                NonLineBreakStr::new("NotAnArtefact")
+            }
+            ArtefactError::UnsupportedVersion { .. } => {
+                NonLineBreakStr::new("UnsupportedArtefactVersion")
+            }
+            ArtefactError::Truncated
+            | ArtefactError::InvalidTag { .. }
+            | ArtefactError::InvalidText
+            | ArtefactError::TooNested
+            | ArtefactError::TrailingBytes { .. } => {
+                NonLineBreakStr::new("CorruptedArtefact")
+            }
+        }
+    }
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            ArtefactError::NotAnArtefact => {
+                NonLineBreakStr::new("The file isn't a compiled module.")
+            }
+            ArtefactError::UnsupportedVersion { .. } => NonLineBreakStr::new(
+                "The module was compiled by another version of octizys.",
+            ),
+            _ => NonLineBreakStr::new("The compiled module is damaged."),
+        }
+    }
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self {
+            ArtefactError::NotAnArtefact => format!(
+                "Compiled modules start with {}, you can create them with the compile command.",
+                String::from_utf8_lossy(MAGIC)
+            ),
+            ArtefactError::UnsupportedVersion { found } => format!(
+                "The artefact has version {}, but we can only load version {}.\nCompile the module again.",
+                found, ARTEFACT_VERSION
+            ),
+            ArtefactError::Truncated => {
+                String::from("The file ended before the end of the module.")
+            }
+            ArtefactError::InvalidTag { item, tag } => {
+                format!("The tag {} doesn't belong to a {}.", tag, item)
+            }
+            ArtefactError::InvalidText => {
+                String::from("A text of the module isn't valid UTF-8.")
+            }
+            ArtefactError::TooNested => format!(
+                "The module has more than {} nested types or terms.",
+                MAX_NESTING
+            ),
+            ArtefactError::TrailingBytes { amount } => format!(
+                "There are {} bytes after the end of the module.",
+                amount
+            ),
+        };
+        Some(external_text(&description))
+    }
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+    fn get_location_maybe(&self) -> Option<Location> {
+        None
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<ArtefactError>>;
+
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn tag(&mut self, tag: u8) {
+        self.bytes.push(tag)
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.bytes.extend_from_slice(&n.to_le_bytes())
+    }
+
+    fn u64(&mut self, n: u64) {
+        self.bytes.extend_from_slice(&n.to_le_bytes())
+    }
+
+    fn length(&mut self, n: usize) {
+        self.u64(n as u64)
+    }
+
+    fn text(&mut self, s: &str) {
+        self.length(s.len());
+        self.bytes.extend_from_slice(s.as_bytes())
+    }
+
+    fn type_(&mut self, type_: &Type) {
+        match type_ {
+            Type::Unit => self.tag(0),
+            Type::Uint => self.tag(1),
+            Type::Int => self.tag(2),
+            Type::String => self.tag(3),
+            Type::Variable(Variable::Named(name)) => {
+                self.tag(4);
+                self.text(name.as_str())
+            }
+            Type::Variable(Variable::Free(index)) => {
+                self.tag(5);
+                self.u32(*index)
+            }
+            Type::Forall(binders, t) => {
+                self.tag(6);
+                self.u64(*binders);
+                self.type_(t)
+            }
+            Type::Arrow(t1, t2) => {
+                self.tag(7);
+                self.type_(t1);
+                self.type_(t2)
+            }
+            Type::Record(record) => {
+                self.tag(8);
+                self.length(record.len());
+                for (label, t) in record.iter() {
+                    self.text(label.as_str());
+                    self.type_(t)
+                }
+            }
+            Type::Recursive(binders, t) => {
+                self.tag(9);
+                self.u64(*binders);
+                self.type_(t)
+            }
+            Type::Application(t1, t2) => {
+                self.tag(10);
+                self.type_(t1);
+                self.type_(t2)
+            }
+            Type::Tuple(items) => {
+                self.tag(11);
+                self.types(items)
+            }
+            Type::Sum(name, items) => {
+                self.tag(12);
+                self.text(name.as_str());
+                self.types(items)
+            }
+            Type::NewType(name, t) => {
+                self.tag(13);
+                self.text(name.as_str());
+                self.type_(t)
+            }
+            Type::Alias(name, t) => {
+                self.tag(14);
+                self.text(name.as_str());
+                self.type_(t)
+            }
+            Type::Char => self.tag(15),
+        }
+    }
+
+    fn types(&mut self, types: &[Type]) {
+        self.length(types.len());
+        for t in types {
+            self.type_(t)
+        }
+    }
+
+    fn terms(&mut self, terms: &[Term]) {
+        self.length(terms.len());
+        for t in terms {
+            self.term(t)
+        }
+    }
+
+    fn term(&mut self, term: &Term) {
+        match term {
+            Term::Unit => self.tag(0),
+            Term::Uint(n) => {
+                self.tag(1);
+                self.u64(*n)
+            }
+            Term::Int(n) => {
+                self.tag(2);
+                self.bytes.extend_from_slice(&n.to_le_bytes())
+            }
+            Term::String(s) => {
+                self.tag(3);
+                self.text(s)
+            }
+            Term::Variable(index, t) => {
+                self.tag(4);
+                self.u64(*index);
+                self.type_(t)
+            }
+            Term::NamedVariable(name) => {
+                self.tag(5);
+                self.text(name.as_str())
+            }
+            Term::GlobalVariable(name, t) => {
+                self.tag(6);
+                self.text(name.as_str());
+                self.type_(t)
+            }
+            Term::Function(body, t) => {
+                self.tag(7);
+                self.term(body);
+                self.type_(t)
+            }
+            Term::Application(function, argument, t) => {
+                self.tag(8);
+                self.term(function);
+                self.term(argument);
+                self.type_(t)
+            }
+            Term::TypeFunction(body, t) => {
+                self.tag(9);
+                self.term(body);
+                self.type_(t)
+            }
+            Term::TypeApplication(term, argument, t) => {
+                self.tag(10);
+                self.term(term);
+                self.type_(argument);
+                self.type_(t)
+            }
+            Term::Constructor(name, index, arguments, t) => {
+                self.tag(11);
+                self.text(name.as_str());
+                self.u64(*index);
+                self.terms(arguments);
+                self.type_(t)
+            }
+            Term::Record(record, t) => {
+                self.tag(12);
+                self.length(record.len());
+                for (label, term) in record.iter() {
+                    self.text(label.as_str());
+                    self.term(term)
+                }
+                self.type_(t)
+            }
+            Term::Tuple(items, t) => {
+                self.tag(13);
+                self.terms(items);
+                self.type_(t)
+            }
+            Term::TupleProjection(term, index, t) => {
+                self.tag(14);
+                self.term(term);
+                self.u64(*index);
+                self.type_(t)
+            }
+            Term::RecordProjection(term, label, t) => {
+                self.tag(15);
+                self.term(term);
+                self.text(label.as_str());
+                self.type_(t)
+            }
+            Term::Case(scrutinee, alternatives, t) => {
+                self.tag(16);
+                self.term(scrutinee);
+                self.length(alternatives.len());
+                for alternative in alternatives {
+                    self.u64(alternative.constructor);
+                    self.u64(alternative.arguments);
+                    self.term(&alternative.value)
+                }
+                self.type_(t)
+            }
+            Term::Primitive(primitive, arguments, t) => {
+                self.tag(17);
+                // Every primitive is in the table.
+                let tag =
+                    PRIMITIVES.iter().position(|p| p == primitive).unwrap();
+                self.tag(tag as u8);
+                self.terms(arguments);
+                self.type_(t)
+            }
+            Term::Char(c) => {
+                self.tag(18);
+                self.u32(u32::from(*c))
+            }
+        }
+    }
+}
+
+/// Serializes the module with the current [`ARTEFACT_VERSION`].
+pub fn encode_module(module: &Module) -> Vec<u8> {
+    let mut encoder = Encoder { bytes: vec![] };
+    encoder.bytes.extend_from_slice(MAGIC);
+    encoder.u32(ARTEFACT_VERSION);
+    encoder.length(module.types.len());
+    for definition in &module.types {
+        encoder.text(definition.name.as_str());
+        encoder.type_(&definition.type_);
+    }
+    encoder.length(module.definitions.len());
+    for definition in &module.definitions {
+        encoder.text(definition.name.as_str());
+        encoder.type_(&definition.type_);
+        encoder.term(&definition.value);
+    }
+    encoder.bytes
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /// The amount of types and terms that we are decoding.
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, amount: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(amount)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(ArtefactError::Truncated)?;
+        let out = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(out)
+    }
+
+    fn tag(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        // The slice has the right length.
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// The length of a list, every item uses at least a byte, this
+    /// avoids big allocations for damaged lengths.
+    fn length(&mut self) -> Result<usize> {
+        let length = self.u64()?;
+        if length > (self.bytes.len() - self.position) as u64 {
+            return Err(Box::new(ArtefactError::Truncated));
+        }
+        Ok(length as usize)
+    }
+
+    fn text(&mut self) -> Result<String> {
+        let length = self.length()?;
+        let bytes = self.take(length)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| Box::new(ArtefactError::InvalidText))
+    }
+
+    fn identifier(&mut self) -> Result<Identifier> {
+        Identifier::make(self.text()?)
+            .map_err(|_| Box::new(ArtefactError::InvalidText))
+    }
+
+    fn label(&mut self) -> Result<Label> {
+        Label::make(self.text()?)
+            .map_err(|_| Box::new(ArtefactError::InvalidText))
+    }
+
+    fn record<T>(&mut self, fields: Vec<(Label, T)>) -> Result<Record<T>> {
+        Record::make(fields).map_err(|_| Box::new(ArtefactError::InvalidText))
+    }
+
+    /// Decodes a nested item, unless it is nested too deep.
+    fn nested<T>(
+        &mut self,
+        decode: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        if self.depth >= MAX_NESTING {
+            return Err(Box::new(ArtefactError::TooNested));
+        }
+        self.depth += 1;
+        let out = decode(self);
+        self.depth -= 1;
+        out
+    }
+
+    fn boxed_type(&mut self) -> Result<Box<Type>> {
+        Ok(Box::new(self.type_()?))
+    }
+
+    fn type_(&mut self) -> Result<Type> {
+        self.nested(Self::type_inner)
+    }
+
+    fn type_inner(&mut self) -> Result<Type> {
+        let tag = self.tag()?;
+        let out = match tag {
+            0 => Type::Unit,
+            1 => Type::Uint,
+            2 => Type::Int,
+            3 => Type::String,
+            4 => Type::Variable(Variable::Named(self.identifier()?)),
+            5 => Type::Variable(Variable::Free(self.u32()?)),
+            6 => Type::Forall(self.u64()?, self.boxed_type()?),
+            7 => Type::Arrow(self.boxed_type()?, self.boxed_type()?),
+            8 => {
+                let length = self.length()?;
+                let mut fields = Vec::with_capacity(length);
+                for _ in 0..length {
+                    fields.push((self.label()?, self.type_()?));
+                }
+                Type::Record(self.record(fields)?)
+            }
+            9 => Type::Recursive(self.u64()?, self.boxed_type()?),
+            10 => Type::Application(self.boxed_type()?, self.boxed_type()?),
+            11 => Type::Tuple(self.types()?),
+            12 => Type::Sum(self.identifier()?, self.types()?),
+            13 => Type::NewType(self.identifier()?, self.boxed_type()?),
+            14 => Type::Alias(self.identifier()?, self.boxed_type()?),
+            15 => Type::Char,
+            _ => {
+                return Err(Box::new(ArtefactError::InvalidTag {
+                    item: "type",
+                    tag,
+                }))
+            }
+        };
+        Ok(out)
+    }
+
+    fn types(&mut self) -> Result<Vec<Type>> {
+        let length = self.length()?;
+        let mut out = Vec::with_capacity(length);
+        for _ in 0..length {
+            out.push(self.type_()?);
+        }
+        Ok(out)
+    }
+
+    fn terms(&mut self) -> Result<Vec<Term>> {
+        let length = self.length()?;
+        let mut out = Vec::with_capacity(length);
+        for _ in 0..length {
+            out.push(self.term()?);
+        }
+        Ok(out)
+    }
+
+    fn boxed_term(&mut self) -> Result<Box<Term>> {
+        Ok(Box::new(self.term()?))
+    }
+
+    fn term(&mut self) -> Result<Term> {
+        self.nested(Self::term_inner)
+    }
+
+    fn term_inner(&mut self) -> Result<Term> {
+        let tag = self.tag()?;
+        let out = match tag {
+            0 => Term::Unit,
+            1 => Term::Uint(self.u64()?),
+            2 => Term::Int(self.i64()?),
+            3 => Term::String(self.text()?),
+            4 => Term::Variable(self.u64()?, self.type_()?),
+            5 => Term::NamedVariable(self.identifier()?),
+            6 => Term::GlobalVariable(self.identifier()?, self.type_()?),
+            7 => Term::Function(Arc::new(self.term()?), self.type_()?),
+            8 => Term::Application(
+                self.boxed_term()?,
+                self.boxed_term()?,
+                self.type_()?,
+            ),
+            9 => Term::TypeFunction(self.boxed_term()?, self.type_()?),
+            10 => Term::TypeApplication(
+                self.boxed_term()?,
+                self.type_()?,
+                self.type_()?,
+            ),
+            11 => Term::Constructor(
+                self.identifier()?,
+                self.u64()?,
+                self.terms()?,
+                self.type_()?,
+            ),
+            12 => {
+                let length = self.length()?;
+                let mut fields = Vec::with_capacity(length);
+                for _ in 0..length {
+                    fields.push((self.label()?, self.term()?));
+                }
+                Term::Record(self.record(fields)?, self.type_()?)
+            }
+            13 => Term::Tuple(self.terms()?, self.type_()?),
+            14 => Term::TupleProjection(
+                self.boxed_term()?,
+                self.u64()?,
+                self.type_()?,
+            ),
+            15 => Term::RecordProjection(
+                self.boxed_term()?,
+                self.label()?,
+                self.type_()?,
+            ),
+            16 => {
+                let scrutinee = self.boxed_term()?;
+                let length = self.length()?;
+                let mut alternatives = Vec::with_capacity(length);
+                for _ in 0..length {
+                    alternatives.push(CaseAlternative {
+                        constructor: self.u64()?,
+                        arguments: self.u64()?,
+                        value: self.term()?,
+                    });
+                }
+                Term::Case(scrutinee, alternatives, self.type_()?)
+            }
+            17 => {
+                let tag = self.tag()?;
+                let primitive = match PRIMITIVES.get(tag as usize) {
+                    Some(primitive) => *primitive,
+                    None => {
+                        return Err(Box::new(ArtefactError::InvalidTag {
+                            item: "primitive",
+                            tag,
+                        }))
+                    }
+                };
+                Term::Primitive(primitive, self.terms()?, self.type_()?)
+            }
+            18 => Term::Char(
+                char::from_u32(self.u32()?)
+                    .ok_or_else(|| Box::new(ArtefactError::InvalidText))?,
+            ),
+            _ => {
+                return Err(Box::new(ArtefactError::InvalidTag {
+                    item: "term",
+                    tag,
+                }))
+            }
+        };
+        Ok(out)
+    }
+}
+
+/// Reads a module written by [`encode_module`], the module isn't
+/// checked. Like the evaluation it needs a stack for [`MAX_NESTING`]
+/// nested items.
+pub fn decode_module(bytes: &[u8]) -> Result<Module> {
+    with_stack(MAX_NESTING, || decode(bytes))
+}
+
+fn decode(bytes: &[u8]) -> Result<Module> {
+    if !bytes.starts_with(MAGIC) {
+        return Err(Box::new(ArtefactError::NotAnArtefact));
+    }
+    let mut decoder = Decoder {
+        bytes,
+        position: MAGIC.len(),
+        depth: 0,
+    };
+    let version = decoder.u32()?;
+    if version != ARTEFACT_VERSION {
+        return Err(Box::new(ArtefactError::UnsupportedVersion {
+            found: version,
+        }));
+    }
+    let length = decoder.length()?;
+    let mut types = Vec::with_capacity(length);
+    for _ in 0..length {
+        types.push(TypeDefinition {
+            name: decoder.identifier()?,
+            type_: decoder.type_()?,
+        });
+    }
+    let length = decoder.length()?;
+    let mut definitions = Vec::with_capacity(length);
+    for _ in 0..length {
+        definitions.push(Definition {
+            name: decoder.identifier()?,
+            type_: decoder.type_()?,
+            value: decoder.term()?,
+        });
+    }
+    let remaining = bytes.len() - decoder.position;
+    if remaining > 0 {
+        return Err(Box::new(ArtefactError::TrailingBytes {
+            amount: remaining,
+        }));
+    }
+    Ok(Module { types, definitions })
+}
+
+#[cfg(test)]
+mod artefact_test {
+    use super::{
+        decode_module, encode_module, ArtefactError, ARTEFACT_VERSION, MAGIC,
+    };
+    use crate::common::{Identifier, Label, Record, Variable};
+    use crate::core::{Definition, Module, TypeDefinition};
+    use crate::primitives::Primitive;
+    use crate::terms::{CaseAlternative, Term};
+    use crate::types::Type;
+
+    fn identifier(name: &str) -> Identifier {
+        Identifier::make(String::from(name)).unwrap()
+    }
+
+    fn module() -> Module {
+        let maybe = Type::Application(
+            Box::new(Type::Variable(Variable::Named(identifier("Maybe")))),
+            Box::new(Type::Uint),
+        );
+        let record = Record::make(vec![(
+            Label::make(String::from("a")).unwrap(),
+            Term::Int(-3),
+        )])
+        .unwrap();
+        Module {
+            types: vec![TypeDefinition {
+                name: identifier("Maybe"),
+                type_: Type::Forall(
+                    1,
+                    Box::new(Type::Sum(
+                        identifier("Maybe"),
+                        vec![
+                            Type::Tuple(vec![]),
+                            Type::Tuple(vec![Type::Variable(Variable::Free(
+                                0,
+                            ))]),
+                        ],
+                    )),
+                ),
+            }],
+            definitions: vec![Definition {
+                name: identifier("main"),
+                value: Term::Tuple(
+                    vec![
+                        Term::Case(
+                            Box::new(Term::Constructor(
+                                identifier("Just"),
+                                1,
+                                vec![Term::Uint(2)],
+                                maybe,
+                            )),
+                            vec![CaseAlternative {
+                                constructor: 1,
+                                arguments: 1,
+                                value: Term::Primitive(
+                                    Primitive::MoreOrEqualUint,
+                                    vec![
+                                        Term::Variable(0, Type::Uint),
+                                        Term::Uint(1),
+                                    ],
+                                    Type::Uint,
+                                ),
+                            }],
+                            Type::Uint,
+                        ),
+                        Term::String(String::from("λ text")),
+                        Term::Char('λ'),
+                        Term::Record(record, Type::Unit),
+                    ],
+                    Type::Unit,
+                ),
+                type_: Type::Unit,
+            }],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let module = module();
+        let bytes = encode_module(&module);
+        assert!(bytes.starts_with(MAGIC));
+        assert_eq!(decode_module(&bytes).unwrap(), module);
+    }
+
+    #[test]
+    fn damaged_artefacts() {
+        let bytes = encode_module(&module());
+        let error = |bytes: &[u8]| *decode_module(bytes).unwrap_err();
+        assert_eq!(error(b"hello"), ArtefactError::NotAnArtefact);
+        let mut newer = bytes.clone();
+        newer[4] = 99;
+        assert_eq!(
+            error(&newer),
+            ArtefactError::UnsupportedVersion { found: 99 }
+        );
+        assert_eq!(error(&bytes[..bytes.len() - 1]), ArtefactError::Truncated);
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert_eq!(error(&longer), ArtefactError::TrailingBytes { amount: 1 });
+    }
+
+    /// An artefact with a definition `main` that has the bytes as its
+    /// type and term.
+    fn definition(item: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(ARTEFACT_VERSION.to_le_bytes());
+        bytes.extend(0u64.to_le_bytes());
+        bytes.extend(1u64.to_le_bytes());
+        bytes.extend(4u64.to_le_bytes());
+        bytes.extend(b"main");
+        bytes.extend(item);
+        bytes
+    }
+
+    #[test]
+    fn hostile_nesting() {
+        let error = |bytes: &[u8]| *decode_module(bytes).unwrap_err();
+        // Arrows of arrows and applications of applications.
+        let types = definition(&vec![7; 1_000_000]);
+        assert_eq!(error(&types), ArtefactError::TooNested);
+        let mut terms = definition(&[0]);
+        terms.extend(vec![8; 1_000_000]);
+        assert_eq!(error(&terms), ArtefactError::TooNested);
+        // A shallow nesting is only truncated.
+        assert_eq!(error(&definition(&[7; 100])), ArtefactError::Truncated);
+    }
+}
@@ -0,0 +1,1027 @@
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::Location;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+
+use crate::common::{Identifier, Label, Variable};
+use crate::core::{Definition, Module};
+use crate::primitives::Primitive;
+use crate::terms::{CaseAlternative, Term};
+use crate::types::Type;
+
+/// The ways a core term can be inconsistent, every one of them is a
+/// bug of the lowering or of a pass over the core.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckErrorKind {
+    UnboundVariable {
+        index: u64,
+        scope_size: u64,
+    },
+    /// The names must be resolved before the checking.
+    UnresolvedName(Identifier),
+    UnknownGlobal(Identifier),
+    UnknownType(Identifier),
+    /// The types must be equivalent.
+    TypeMismatch {
+        expected: Type,
+        found: Type,
+    },
+    /// The type of a use of a polymorphic variable must be an
+    /// instance of the type of the binder.
+    NotAnInstance {
+        scheme: Type,
+        found: Type,
+    },
+    NotAFunction(Type),
+    NotAForall(Type),
+    /// The type of a constructor or of the scrutinee of a case
+    /// isn't a [`Type::Sum`].
+    NotASum(Type),
+    ConstructorIndex {
+        type_: Type,
+        index: u64,
+    },
+    ConstructorArity {
+        index: u64,
+        expected: u64,
+        found: u64,
+    },
+    MissingAlternative {
+        type_: Type,
+        index: u64,
+    },
+    MissingItem {
+        type_: Type,
+        index: u64,
+    },
+    MissingField {
+        type_: Type,
+        label: Label,
+    },
+    DuplicatedAlternative {
+        index: u64,
+    },
+    PrimitiveArity {
+        primitive: Primitive,
+        found: usize,
+    },
+}
+
+/// A inconsistency found by [`check_module`] or [`check_term`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckError {
+    /// The definition that has the error.
+    pub definition: Option<Identifier>,
+    /// The places where the error is, from the innermost to the
+    /// outermost.
+    pub context: Vec<String>,
+    pub kind: CheckErrorKind,
+}
+
+impl CheckError {
+    fn new(kind: CheckErrorKind) -> Box<CheckError> {
+        Box::new(CheckError {
+            definition: None,
+            context: vec![],
+            kind,
+        })
+    }
+
+    fn inside(mut self: Box<Self>, place: String) -> Box<CheckError> {
+        self.context.push(place);
+        self
+    }
+}
+
+impl ReportFormat for CheckError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self.kind {
+            CheckErrorKind::UnboundVariable { .. } => {
+                NonLineBreakStr::new("Internal:UnboundVariable")
+            }
+            CheckErrorKind::UnresolvedName(_) => {
+                NonLineBreakStr::new("Internal:UnresolvedName")
+            }
+            CheckErrorKind::UnknownGlobal(_) => {
+                NonLineBreakStr::new("Internal:UnknownGlobal")
+            }
+            CheckErrorKind::UnknownType(_) => {
+                NonLineBreakStr::new("Internal:UnknownType")
+            }
+            CheckErrorKind::TypeMismatch { .. } => {
+                NonLineBreakStr::new("Internal:TypeMismatch")
+            }
+            CheckErrorKind::NotAnInstance { .. } => {
+                NonLineBreakStr::new("Internal:NotAnInstance")
+            }
+            CheckErrorKind::NotAFunction(_) => {
+                NonLineBreakStr::new("Internal:NotAFunction")
+            }
+            CheckErrorKind::NotAForall(_) => {
+                NonLineBreakStr::new("Internal:NotAForall")
+            }
+            CheckErrorKind::NotASum(_) => {
+                NonLineBreakStr::new("Internal:NotASum")
+            }
+            CheckErrorKind::ConstructorIndex { .. } => {
+                NonLineBreakStr::new("Internal:ConstructorIndex")
+            }
+            CheckErrorKind::ConstructorArity { .. } => {
+                NonLineBreakStr::new("Internal:ConstructorArity")
+            }
+            CheckErrorKind::MissingAlternative { .. } => {
+                NonLineBreakStr::new("Internal:MissingAlternative")
+            }
+            CheckErrorKind::MissingItem { .. } => {
+                NonLineBreakStr::new("Internal:MissingItem")
+            }
+            CheckErrorKind::MissingField { .. } => {
+                NonLineBreakStr::new("Internal:MissingField")
+            }
+            CheckErrorKind::DuplicatedAlternative { .. } => {
+                NonLineBreakStr::new("Internal:DuplicatedAlternative")
+            }
+            CheckErrorKind::PrimitiveArity { .. } => {
+                NonLineBreakStr::new("Internal:PrimitiveArity")
+            }
+        }
+    }
+    fn get_short_description(&self) -> NonLineBreakStr {
+        NonLineBreakStr::new("This is a bug in octizys, please report it!")
+    }
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match &self.kind {
+            CheckErrorKind::UnboundVariable { index, scope_size } => format!(
+                "The variable with index {} was used with {} variables in scope.",
+                index, scope_size
+            ),
+            CheckErrorKind::UnresolvedName(name) => {
+                format!("The name {} wasn't resolved in the core.", name)
+            }
+            CheckErrorKind::UnknownGlobal(name) => {
+                format!("The definition {} doesn't exist in the module.", name)
+            }
+            CheckErrorKind::UnknownType(name) => {
+                format!("The type {} doesn't exist in the module.", name)
+            }
+            CheckErrorKind::TypeMismatch { expected, found } => format!(
+                "A term of type {} was expected, but it has type {}.",
+                expected, found
+            ),
+            CheckErrorKind::NotAnInstance { scheme, found } => format!(
+                "The variable has type {} that isn't an instance of {}.",
+                found, scheme
+            ),
+            CheckErrorKind::NotAFunction(type_) => {
+                format!("The type {} isn't a function.", type_)
+            }
+            CheckErrorKind::NotAForall(type_) => {
+                format!("The type {} doesn't have type parameters.", type_)
+            }
+            CheckErrorKind::NotASum(type_) => {
+                format!("The type {} doesn't have constructors.", type_)
+            }
+            CheckErrorKind::ConstructorIndex { type_, index } => format!(
+                "The type {} doesn't have a constructor with index {}.",
+                type_, index
+            ),
+            CheckErrorKind::ConstructorArity {
+                index,
+                expected,
+                found,
+            } => format!(
+                "The constructor with index {} has {} arguments, but it got {}.",
+                index, expected, found
+            ),
+            CheckErrorKind::MissingAlternative { type_, index } => format!(
+                "The case over {} doesn't have an alternative for the constructor with index {}.",
+                type_, index
+            ),
+            CheckErrorKind::MissingItem { type_, index } => format!(
+                "The type {} isn't a tuple with an item {}.",
+                type_, index
+            ),
+            CheckErrorKind::MissingField { type_, label } => format!(
+                "The type {} isn't a record with a field {}.",
+                type_,
+                label.as_str()
+            ),
+            CheckErrorKind::DuplicatedAlternative { index } => format!(
+                "The case has more than one alternative for the constructor with index {}.",
+                index
+            ),
+            CheckErrorKind::PrimitiveArity { primitive, found } => format!(
+                "The primitive {} expects {} arguments but got {}.",
+                primitive,
+                primitive.arity(),
+                found
+            ),
+        };
+        let definition = match &self.definition {
+            Some(name) => format!("\nIn the definition: {}", name),
+            None => String::new(),
+        };
+        let context = if self.context.is_empty() {
+            String::new()
+        } else {
+            let places: Vec<&str> =
+                self.context.iter().rev().map(|x| x.as_str()).collect();
+            format!("\nInside: {}", places.join(" -> "))
+        };
+        Some(external_text(&(description + &definition + &context)))
+    }
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+    fn get_location_maybe(&self) -> Option<Location> {
+        None
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<CheckError>>;
+
+/// Removes the aliases and the empty binders around the type without
+/// touching the rest of it.
+fn head(type_: &Type) -> &Type {
+    match type_ {
+        Type::Alias(_, t) | Type::Forall(0, t) | Type::Recursive(0, t) => {
+            head(t)
+        }
+        _ => type_,
+    }
+}
+
+fn expect(expected: &Type, found: &Type) -> Result<()> {
+    if expected.equivalent(found) {
+        Ok(())
+    } else {
+        Err(CheckError::new(CheckErrorKind::TypeMismatch {
+            expected: expected.clone(),
+            found: found.clone(),
+        }))
+    }
+}
+
+/// Finds the types that replace the first `binders` free variables of
+/// `pattern` to get `found`. The result only makes sense if the
+/// instantiation is equivalent to `found`.
+fn collect_instance(
+    pattern: &Type,
+    found: &Type,
+    binders: u32,
+    solutions: &mut [Option<Type>],
+) {
+    match (pattern, found) {
+        (Type::Variable(Variable::Free(index)), _) if *index < binders => {
+            let position = (binders - 1 - index) as usize;
+            if solutions[position].is_none() {
+                solutions[position] = Some(found.clone());
+            }
+        }
+        (Type::Arrow(p1, p2), Type::Arrow(f1, f2))
+        | (Type::Application(p1, p2), Type::Application(f1, f2)) => {
+            collect_instance(p1, f1, binders, solutions);
+            collect_instance(p2, f2, binders, solutions);
+        }
+        (Type::Tuple(p), Type::Tuple(f))
+        | (Type::Sum(_, p), Type::Sum(_, f)) => {
+            for (p, f) in p.iter().zip(f.iter()) {
+                collect_instance(p, f, binders, solutions);
+            }
+        }
+        (Type::Record(p), Type::Record(f)) => {
+            for (label, p) in p.iter() {
+                if let Some(f) = f.get(label) {
+                    collect_instance(p, f, binders, solutions);
+                }
+            }
+        }
+        (Type::NewType(_, p), Type::NewType(_, f)) => {
+            collect_instance(p, f, binders, solutions)
+        }
+        // The types of the language don't have binders inside, we
+        // don't look for instances under them.
+        _ => (),
+    }
+}
+
+/// Tells if `found` is `scheme` with its variables replaced.
+fn is_instance(binders: u64, body: &Type, found: &Type) -> bool {
+    let body = body.normalize();
+    let found = found.normalize();
+    let mut solutions = vec![None; binders as usize];
+    collect_instance(&body, &found, binders as u32, &mut solutions);
+    let mut instance = body;
+    for (position, solution) in solutions.into_iter().enumerate() {
+        // Variables that aren't used can be anything.
+        let solution = solution.unwrap_or(Type::Unit);
+        let index = binders as u32 - 1 - position as u32;
+        instance = instance.instantiate(index, &solution);
+    }
+    instance.equivalent(&found)
+}
+
+/// The arguments of a constructor in the item of a [`Type::Sum`].
+fn constructor_arguments(item: &Type) -> Vec<Type> {
+    match head(item) {
+        Type::Tuple(arguments) => arguments.clone(),
+        other => vec![other.clone()],
+    }
+}
+
+/// The maximum number of unfoldings while looking for a sum, the
+/// types of a module never need more than a few.
+const MAX_UNFOLDINGS: usize = 64;
+
+/// A term variable and the number of type binders in scope at the
+/// place of its binder.
+struct Local {
+    type_: Type,
+    depth: u32,
+}
+
+struct Checker<'module> {
+    module: &'module Module,
+    locals: Vec<Local>,
+    /// The number of type binders in scope.
+    depth: u32,
+}
+
+impl<'module> Checker<'module> {
+    fn new(module: &'module Module) -> Self {
+        Checker {
+            module,
+            locals: vec![],
+            depth: 0,
+        }
+    }
+
+    /// The type of a variable must be the one of the binder or an
+    /// instance of it.
+    fn variable(&self, binder: &Type, found: &Type) -> Result<()> {
+        if binder.equivalent(found) {
+            return Ok(());
+        }
+        match head(binder) {
+            Type::Forall(binders, body) => {
+                if is_instance(*binders, body, found) {
+                    Ok(())
+                } else {
+                    Err(CheckError::new(CheckErrorKind::NotAnInstance {
+                        scheme: binder.clone(),
+                        found: found.clone(),
+                    }))
+                }
+            }
+            _ => expect(binder, found),
+        }
+    }
+
+    /// Expands the type until we find the [`Type::Sum`] behind it,
+    /// the named types are replaced by their definitions.
+    fn sum(&self, type_: &Type) -> Result<Vec<Type>> {
+        let not_a_sum =
+            || CheckError::new(CheckErrorKind::NotASum(type_.clone()));
+        let mut current = type_.normalize();
+        for _ in 0..MAX_UNFOLDINGS {
+            current = match current {
+                Type::Sum(_, items) => return Ok(items),
+                Type::NewType(_, inner) => inner.normalize(),
+                Type::Recursive(_, _) => current.unfold().normalize(),
+                _ => {
+                    let mut arguments = vec![];
+                    let mut name = &current;
+                    while let Type::Application(function, argument) = name {
+                        arguments.push(argument);
+                        name = function;
+                    }
+                    let name = match name {
+                        Type::Variable(Variable::Named(name)) => name,
+                        _ => return Err(not_a_sum()),
+                    };
+                    let definition = self
+                        .module
+                        .find_type(name.as_str())
+                        .ok_or_else(|| {
+                            CheckError::new(CheckErrorKind::UnknownType(
+                                name.clone(),
+                            ))
+                        })?;
+                    let mut out = definition.type_.clone();
+                    for argument in arguments.into_iter().rev() {
+                        out =
+                            Type::Application(Box::new(out), argument.clone());
+                    }
+                    out.normalize()
+                }
+            }
+        }
+        Err(not_a_sum())
+    }
+
+    fn with_locals(&mut self, types: Vec<Type>, term: &Term) -> Result<Type> {
+        let previous = self.locals.len();
+        for type_ in types {
+            self.locals.push(Local {
+                type_,
+                depth: self.depth,
+            });
+        }
+        let result = self.term(term);
+        self.locals.truncate(previous);
+        result
+    }
+
+    fn alternative(
+        &mut self,
+        items: &[Type],
+        scrutinee_type: &Type,
+        alternative: &CaseAlternative,
+        type_: &Type,
+    ) -> Result<()> {
+        let index = alternative.constructor;
+        let arguments = match items.get(index as usize) {
+            Some(item) => constructor_arguments(item),
+            None => {
+                return Err(CheckError::new(CheckErrorKind::ConstructorIndex {
+                    type_: scrutinee_type.clone(),
+                    index,
+                }))
+            }
+        };
+        if arguments.len() as u64 != alternative.arguments {
+            return Err(CheckError::new(CheckErrorKind::ConstructorArity {
+                index,
+                expected: arguments.len() as u64,
+                found: alternative.arguments,
+            }));
+        }
+        let found = self.with_locals(arguments, &alternative.value)?;
+        expect(type_, &found)
+    }
+
+    /// Checks the term and returns its type.
+    fn term(&mut self, term: &Term) -> Result<Type> {
+        match term {
+            Term::Unit => Ok(Type::Unit),
+            Term::Uint(_) => Ok(Type::Uint),
+            Term::Int(_) => Ok(Type::Int),
+            Term::String(_) => Ok(Type::String),
+            Term::Char(_) => Ok(Type::Char),
+            Term::Variable(index, type_) => {
+                let local = self
+                    .locals
+                    .len()
+                    .checked_sub(*index as usize + 1)
+                    .map(|position| &self.locals[position]);
+                let local = match local {
+                    Some(local) => local,
+                    None => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::UnboundVariable {
+                                index: *index,
+                                scope_size: self.locals.len() as u64,
+                            },
+                        ))
+                    }
+                };
+                let binder = local.type_.shift(self.depth - local.depth, 0);
+                self.variable(&binder, type_)?;
+                Ok(type_.clone())
+            }
+            Term::NamedVariable(name) => Err(CheckError::new(
+                CheckErrorKind::UnresolvedName(name.clone()),
+            )),
+            Term::GlobalVariable(name, type_) => {
+                let definition = match self.module.find(name.as_str()) {
+                    Some(definition) => definition,
+                    None => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::UnknownGlobal(name.clone()),
+                        ))
+                    }
+                };
+                self.variable(&definition.type_, type_)?;
+                Ok(type_.clone())
+            }
+            Term::Function(body, type_) => {
+                let (argument, output) = match type_.normalize() {
+                    Type::Arrow(argument, output) => (*argument, *output),
+                    _ => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::NotAFunction(type_.clone()),
+                        ))
+                    }
+                };
+                let context = || String::from("the body of a function");
+                let found = self
+                    .with_locals(vec![argument], body)
+                    .map_err(|e| e.inside(context()))?;
+                expect(&output, &found).map_err(|e| e.inside(context()))?;
+                Ok(type_.clone())
+            }
+            Term::Application(function, argument, type_) => {
+                let function_type = self
+                    .term(function)
+                    .map_err(|e| e.inside(String::from("the function")))?;
+                let (expected, output) = match function_type.normalize() {
+                    Type::Arrow(argument, output) => (*argument, *output),
+                    _ => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::NotAFunction(function_type),
+                        ))
+                    }
+                };
+                let context = || String::from("the argument of a function");
+                let found =
+                    self.term(argument).map_err(|e| e.inside(context()))?;
+                expect(&expected, &found).map_err(|e| e.inside(context()))?;
+                expect(type_, &output)?;
+                Ok(type_.clone())
+            }
+            Term::TypeFunction(body, type_) => {
+                let (binders, inner) = match head(type_) {
+                    Type::Forall(binders, inner) => (*binders as u32, inner),
+                    _ => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::NotAForall(type_.clone()),
+                        ))
+                    }
+                };
+                let context = || String::from("the body of a type function");
+                self.depth += binders;
+                let found = self.term(body);
+                self.depth -= binders;
+                let found = found.map_err(|e| e.inside(context()))?;
+                expect(inner, &found).map_err(|e| e.inside(context()))?;
+                Ok(type_.clone())
+            }
+            Term::TypeApplication(term, argument, type_) => {
+                let found = self.term(term).map_err(|e| {
+                    e.inside(String::from("the term of a type application"))
+                })?;
+                match head(&found) {
+                    Type::Forall(binders, body) => expect(
+                        type_,
+                        &Type::apply_forall(*binders, body, argument),
+                    )?,
+                    _ => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::NotAForall(found.clone()),
+                        ))
+                    }
+                }
+                Ok(type_.clone())
+            }
+            Term::Constructor(name, index, arguments, type_) => {
+                let items = self.sum(type_)?;
+                let expected = match items.get(*index as usize) {
+                    Some(item) => constructor_arguments(item),
+                    None => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::ConstructorIndex {
+                                type_: type_.clone(),
+                                index: *index,
+                            },
+                        ))
+                    }
+                };
+                if expected.len() != arguments.len() {
+                    return Err(CheckError::new(
+                        CheckErrorKind::ConstructorArity {
+                            index: *index,
+                            expected: expected.len() as u64,
+                            found: arguments.len() as u64,
+                        },
+                    ));
+                }
+                for (position, (expected, argument)) in
+                    expected.iter().zip(arguments.iter()).enumerate()
+                {
+                    let context =
+                        || format!("the argument {} of {}", position + 1, name);
+                    let found =
+                        self.term(argument).map_err(|e| e.inside(context()))?;
+                    expect(expected, &found)
+                        .map_err(|e| e.inside(context()))?;
+                }
+                Ok(type_.clone())
+            }
+            Term::Record(fields, type_) => {
+                let found = fields.try_map(|field| self.term(field))?;
+                expect(type_, &Type::Record(found))?;
+                Ok(type_.clone())
+            }
+            Term::Tuple(items, type_) => {
+                let mut found = vec![];
+                for (position, item) in items.iter().enumerate() {
+                    found.push(self.term(item).map_err(|e| {
+                        e.inside(format!(
+                            "the item {} of a tuple",
+                            position + 1
+                        ))
+                    })?);
+                }
+                expect(type_, &Type::Tuple(found))?;
+                Ok(type_.clone())
+            }
+            Term::TupleProjection(term, index, type_) => {
+                let found = self.term(term).map_err(|e| {
+                    e.inside(String::from("the term of a projection"))
+                })?;
+                let item = match found.normalize() {
+                    Type::Tuple(items) => items.get(*index as usize).cloned(),
+                    _ => None,
+                };
+                match item {
+                    Some(item) => expect(type_, &item)?,
+                    None => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::MissingItem {
+                                type_: found,
+                                index: *index,
+                            },
+                        ))
+                    }
+                }
+                Ok(type_.clone())
+            }
+            Term::RecordProjection(term, label, type_) => {
+                let found = self.term(term).map_err(|e| {
+                    e.inside(String::from("the term of a projection"))
+                })?;
+                let field = match found.normalize() {
+                    Type::Record(fields) => fields.get(label).cloned(),
+                    _ => None,
+                };
+                match field {
+                    Some(field) => expect(type_, &field)?,
+                    None => {
+                        return Err(CheckError::new(
+                            CheckErrorKind::MissingField {
+                                type_: found,
+                                label: label.clone(),
+                            },
+                        ))
+                    }
+                }
+                Ok(type_.clone())
+            }
+            Term::Case(scrutinee, alternatives, type_) => {
+                let scrutinee_type = self.term(scrutinee).map_err(|e| {
+                    e.inside(String::from("the scrutinee of a case"))
+                })?;
+                let items = self.sum(&scrutinee_type)?;
+                let mut covered = vec![false; items.len()];
+                for alternative in alternatives {
+                    let index = alternative.constructor;
+                    self.alternative(
+                        &items,
+                        &scrutinee_type,
+                        alternative,
+                        type_,
+                    )
+                    .map_err(|e| {
+                        e.inside(format!("the alternative {} of a case", index))
+                    })?;
+                    if covered[index as usize] {
+                        return Err(CheckError::new(
+                            CheckErrorKind::DuplicatedAlternative { index },
+                        ));
+                    }
+                    covered[index as usize] = true;
+                }
+                if let Some(index) = covered.iter().position(|c| !c) {
+                    return Err(CheckError::new(
+                        CheckErrorKind::MissingAlternative {
+                            type_: scrutinee_type,
+                            index: index as u64,
+                        },
+                    ));
+                }
+                Ok(type_.clone())
+            }
+            Term::Primitive(primitive, arguments, type_) => {
+                let (expected, output) = primitive.signature();
+                if expected.len() != arguments.len() {
+                    return Err(CheckError::new(
+                        CheckErrorKind::PrimitiveArity {
+                            primitive: *primitive,
+                            found: arguments.len(),
+                        },
+                    ));
+                }
+                for (position, (expected, argument)) in
+                    expected.iter().zip(arguments.iter()).enumerate()
+                {
+                    let context = || {
+                        format!(
+                            "the argument {} of {}",
+                            position + 1,
+                            primitive
+                        )
+                    };
+                    let found =
+                        self.term(argument).map_err(|e| e.inside(context()))?;
+                    expect(expected, &found)
+                        .map_err(|e| e.inside(context()))?;
+                }
+                expect(type_, &output)?;
+                Ok(type_.clone())
+            }
+        }
+    }
+
+    /// A definition with a [`Type::Forall`] doesn't need a
+    /// [`Term::TypeFunction`], the variables of the forall are free
+    /// in the value.
+    fn definition(&mut self, definition: &Definition) -> Result<()> {
+        let found = self.term(&definition.value)?;
+        match (head(&definition.type_), &definition.value) {
+            (Type::Forall(_, body), value)
+                if !matches!(value, Term::TypeFunction(_, _)) =>
+            {
+                expect(body, &found)
+            }
+            (type_, _) => expect(type_, &found),
+        }
+    }
+}
+
+/// Checks that every definition of the module has the type it
+/// claims, the first inconsistency is reported.
+pub fn check_module(module: &Module) -> Result<()> {
+    for definition in &module.definitions {
+        Checker::new(module)
+            .definition(definition)
+            .map_err(|mut error| {
+                error.definition = Some(definition.name.clone());
+                error
+            })?;
+    }
+    Ok(())
+}
+
+/// Checks a term that can use the definitions of the module and
+/// returns its type.
+pub fn check_term(module: &Module, term: &Term) -> Result<Type> {
+    Checker::new(module).term(term)
+}
+
+#[cfg(test)]
+mod checker_test {
+    use super::{check_module, check_term, CheckErrorKind};
+    use crate::common::{Identifier, Variable};
+    use crate::core::{Definition, Module, TypeDefinition};
+    use crate::primitives::Primitive;
+    use crate::terms::{CaseAlternative, Term};
+    use crate::types::Type;
+    use std::sync::Arc;
+
+    fn identifier(name: &str) -> Identifier {
+        Identifier::make(String::from(name)).unwrap()
+    }
+
+    fn free(index: u32) -> Type {
+        Type::Variable(Variable::Free(index))
+    }
+
+    fn arrow(t1: Type, t2: Type) -> Type {
+        Type::Arrow(Box::new(t1), Box::new(t2))
+    }
+
+    fn maybe(argument: Type) -> Type {
+        Type::Application(
+            Box::new(Type::Variable(Variable::Named(identifier("Maybe")))),
+            Box::new(argument),
+        )
+    }
+
+    /// data Maybe a = Nothing | Just a
+    /// id = \x -> x
+    fn module() -> Module {
+        Module {
+            types: vec![TypeDefinition {
+                name: identifier("Maybe"),
+                type_: Type::Forall(
+                    1,
+                    Box::new(Type::Sum(
+                        identifier("Maybe"),
+                        vec![Type::Tuple(vec![]), Type::Tuple(vec![free(0)])],
+                    )),
+                ),
+            }],
+            definitions: vec![Definition {
+                name: identifier("id"),
+                value: Term::Function(
+                    Arc::new(Term::Variable(0, free(0))),
+                    arrow(free(0), free(0)),
+                ),
+                type_: Type::Forall(1, Box::new(arrow(free(0), free(0)))),
+            }],
+        }
+    }
+
+    fn kind(module: &Module, term: &Term) -> CheckErrorKind {
+        check_term(module, term).unwrap_err().kind
+    }
+
+    #[test]
+    fn module_and_instances() {
+        let module = module();
+        assert!(check_module(&module).is_ok());
+        let term = Term::Application(
+            Box::new(Term::GlobalVariable(
+                identifier("id"),
+                arrow(Type::Uint, Type::Uint),
+            )),
+            Box::new(Term::Uint(1)),
+            Type::Uint,
+        );
+        assert_eq!(check_term(&module, &term).unwrap(), Type::Uint);
+        let wrong = Term::GlobalVariable(
+            identifier("id"),
+            arrow(Type::Uint, Type::String),
+        );
+        assert!(matches!(
+            kind(&module, &wrong),
+            CheckErrorKind::NotAnInstance { .. }
+        ));
+    }
+
+    #[test]
+    fn applications_and_variables() {
+        let module = module();
+        let term = Term::Application(
+            Box::new(Term::Function(
+                Arc::new(Term::Variable(0, Type::Uint)),
+                arrow(Type::Uint, Type::Uint),
+            )),
+            Box::new(Term::String(String::from("a"))),
+            Type::Uint,
+        );
+        let error = check_term(&module, &term).unwrap_err();
+        assert_eq!(
+            error.kind,
+            CheckErrorKind::TypeMismatch {
+                expected: Type::Uint,
+                found: Type::String
+            }
+        );
+        assert_eq!(error.context, vec!["the argument of a function"]);
+        let unbound = Term::Function(
+            Arc::new(Term::Variable(1, Type::Uint)),
+            arrow(Type::Uint, Type::Uint),
+        );
+        assert!(matches!(
+            kind(&module, &unbound),
+            CheckErrorKind::UnboundVariable { index: 1, .. }
+        ));
+        let not_a_function = Term::Function(Arc::new(Term::Unit), Type::Unit);
+        assert!(matches!(
+            kind(&module, &not_a_function),
+            CheckErrorKind::NotAFunction(_)
+        ));
+    }
+
+    #[test]
+    fn type_functions() {
+        let module = module();
+        // The variable is bound outside of the type function, its type
+        // is shifted inside.
+        let identity = Type::Forall(1, Box::new(arrow(free(0), free(0))));
+        let term = Term::Function(
+            Arc::new(Term::TypeFunction(
+                Box::new(Term::Function(
+                    Arc::new(Term::Variable(1, Type::Uint)),
+                    arrow(free(0), Type::Uint),
+                )),
+                Type::Forall(1, Box::new(arrow(free(0), Type::Uint))),
+            )),
+            arrow(
+                Type::Uint,
+                Type::Forall(1, Box::new(arrow(free(0), Type::Uint))),
+            ),
+        );
+        assert!(check_term(&module, &term).is_ok());
+        let applied = Term::TypeApplication(
+            Box::new(Term::TypeFunction(
+                Box::new(Term::Function(
+                    Arc::new(Term::Variable(0, free(0))),
+                    arrow(free(0), free(0)),
+                )),
+                identity.clone(),
+            )),
+            Type::String,
+            arrow(Type::String, Type::String),
+        );
+        assert!(check_term(&module, &applied).is_ok());
+        let wrong = Term::TypeApplication(
+            Box::new(Term::Uint(0)),
+            Type::String,
+            Type::String,
+        );
+        assert!(matches!(
+            kind(&module, &wrong),
+            CheckErrorKind::NotAForall(Type::Uint)
+        ));
+    }
+
+    #[test]
+    fn constructors_and_cases() {
+        let module = module();
+        let just = Term::Constructor(
+            identifier("Just"),
+            1,
+            vec![Term::Uint(3)],
+            maybe(Type::Uint),
+        );
+        assert!(check_term(&module, &just).is_ok());
+        let alternatives = vec![
+            CaseAlternative {
+                constructor: 0,
+                arguments: 0,
+                value: Term::Uint(0),
+            },
+            CaseAlternative {
+                constructor: 1,
+                arguments: 1,
+                value: Term::Variable(0, Type::Uint),
+            },
+        ];
+        let case = Term::Case(
+            Box::new(just.clone()),
+            alternatives.clone(),
+            Type::Uint,
+        );
+        assert_eq!(check_term(&module, &case).unwrap(), Type::Uint);
+        let missing = Term::Case(
+            Box::new(just.clone()),
+            alternatives[1..].to_vec(),
+            Type::Uint,
+        );
+        assert!(matches!(
+            kind(&module, &missing),
+            CheckErrorKind::MissingAlternative { index: 0, .. }
+        ));
+        let duplicated = Term::Case(
+            Box::new(just.clone()),
+            vec![alternatives[0].clone(), alternatives[0].clone()],
+            Type::Uint,
+        );
+        assert_eq!(
+            kind(&module, &duplicated),
+            CheckErrorKind::DuplicatedAlternative { index: 0 }
+        );
+        let arity = Term::Constructor(
+            identifier("Nothing"),
+            0,
+            vec![Term::Unit],
+            maybe(Type::Uint),
+        );
+        assert_eq!(
+            kind(&module, &arity),
+            CheckErrorKind::ConstructorArity {
+                index: 0,
+                expected: 0,
+                found: 1
+            }
+        );
+        let unknown =
+            Term::Constructor(identifier("Nothing"), 0, vec![], Type::Uint);
+        assert_eq!(
+            kind(&module, &unknown),
+            CheckErrorKind::NotASum(Type::Uint)
+        );
+    }
+
+    #[test]
+    fn records_tuples_and_primitives() {
+        let module = module();
+        let tuple = Term::Tuple(
+            vec![Term::Uint(1), Term::Unit],
+            Type::Tuple(vec![Type::Uint, Type::Uint]),
+        );
+        assert!(matches!(
+            kind(&module, &tuple),
+            CheckErrorKind::TypeMismatch { .. }
+        ));
+        let primitive = Term::Primitive(
+            Primitive::AddUint,
+            vec![Term::Uint(1), Term::Unit],
+            Type::Uint,
+        );
+        let error = check_term(&module, &primitive).unwrap_err();
+        assert_eq!(error.context.len(), 1);
+        let arity = Term::Primitive(
+            Primitive::AddUint,
+            vec![Term::Uint(1)],
+            Type::Uint,
+        );
+        assert!(matches!(
+            kind(&module, &arity),
+            CheckErrorKind::PrimitiveArity { found: 1, .. }
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord, Hash)]
 pub struct Label(String);
 
 #[derive(Debug, PartialEq, Eq)]
@@ -9,11 +9,16 @@ impl Label {
         //TODO: verify the string here
         Ok(Label(s))
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord, Hash)]
 pub struct Identifier(String);
 
+#[derive(Debug)]
 pub struct MakeIdentifierError(String);
 
 impl Identifier {
@@ -21,8 +26,19 @@ impl Identifier {
         //TODO: verify the string here
         Ok(Identifier(s))
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug)]
 pub struct MakeRecordError(String);
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -34,9 +50,37 @@ impl<T> Record<T> {
         //- labels must be unique
         Ok(Record(v))
     }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (Label, T)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, label: &Label) -> Option<&T> {
+        self.0.iter().find(|(l, _)| l == label).map(|(_, t)| t)
+    }
+
+    /// Applies a function that can fail to every field.
+    pub fn try_map<U, E, F>(&self, mut f: F) -> Result<Record<U>, E>
+    where
+        F: FnMut(&T) -> Result<U, E>,
+    {
+        let mut out = Vec::with_capacity(self.0.len());
+        for (label, value) in self.0.iter() {
+            out.push((label.clone(), f(value)?));
+        }
+        Ok(Record(out))
+    }
 }
 
-fn unsafe_make_record<T>(v: Vec<(Label, T)>) -> Record<T> {
+pub(crate) fn unsafe_make_record<T>(v: Vec<(Label, T)>) -> Record<T> {
     Record(v)
 }
 
@@ -1 +1,38 @@
+use crate::common::{Identifier, Variable};
+use crate::terms::Term;
+use crate::types::Type;
 
+/// A top level value definition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Definition {
+    pub name: Identifier,
+    pub value: Term,
+    pub type_: Type,
+}
+
+/// A named type, the types refer to it with [`Variable::Named`].
+/// Data types are a [`Type::Sum`] inside a [`Type::Forall`] for the
+/// parameters.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TypeDefinition {
+    pub name: Identifier,
+    pub type_: Type,
+}
+
+/// The core representation of a octizys module, the definitions
+/// refer to each other using [`Term::GlobalVariable`].
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Module {
+    pub types: Vec<TypeDefinition>,
+    pub definitions: Vec<Definition>,
+}
+
+impl Module {
+    pub fn find(&self, name: &str) -> Option<&Definition> {
+        self.definitions.iter().find(|d| d.name.as_str() == name)
+    }
+
+    pub fn find_type(&self, name: &str) -> Option<&TypeDefinition> {
+        self.types.iter().find(|d| d.name.as_str() == name)
+    }
+}
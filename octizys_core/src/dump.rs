@@ -0,0 +1,163 @@
+//! The [`Dump`] of the core trees, used by `octizys compile --emit core`.
+//! The types are dumped as trees instead of using their
+//! [`std::fmt::Display`] to show the De Bruijn indexes as they are.
+
+use octizys_common::dump::{atom, literal, node, Dump};
+use octizys_pretty::document::Document;
+
+use crate::common::{Identifier, Label, Record, Variable};
+use crate::core::{Definition, Module, TypeDefinition};
+use crate::terms::{CaseAlternative, Term};
+use crate::types::Type;
+
+impl Dump for Identifier {
+    fn dump(&self) -> Document {
+        atom(self)
+    }
+}
+
+impl Dump for Label {
+    fn dump(&self) -> Document {
+        atom(self.as_str())
+    }
+}
+
+impl<T: Dump> Dump for Record<T> {
+    fn dump(&self) -> Document {
+        node(
+            "Record",
+            self.iter().map(|(label, value)| {
+                node("field", [label.dump(), value.dump()])
+            }),
+        )
+    }
+}
+
+impl Dump for Variable {
+    fn dump(&self) -> Document {
+        match self {
+            Variable::Named(name) => node("Named", [name.dump()]),
+            Variable::Free(index) => node("Free", [atom(index)]),
+        }
+    }
+}
+
+impl Dump for Type {
+    fn dump(&self) -> Document {
+        match self {
+            Type::Unit => node("Unit", []),
+            Type::Uint => node("Uint", []),
+            Type::Int => node("Int", []),
+            Type::String => node("String", []),
+            Type::Char => node("Char", []),
+            Type::Variable(variable) => variable.dump(),
+            Type::Forall(amount, t) => node("Forall", [atom(amount), t.dump()]),
+            Type::Arrow(argument, output) => {
+                node("Arrow", [argument.dump(), output.dump()])
+            }
+            Type::Record(record) => record.dump(),
+            Type::Recursive(amount, t) => {
+                node("Recursive", [atom(amount), t.dump()])
+            }
+            Type::Application(function, argument) => {
+                node("Application", [function.dump(), argument.dump()])
+            }
+            Type::Tuple(items) => node("Tuple", items.iter().map(Dump::dump)),
+            Type::Sum(name, items) => node(
+                "Sum",
+                std::iter::once(name.dump())
+                    .chain(items.iter().map(Dump::dump)),
+            ),
+            Type::NewType(name, t) => node("NewType", [name.dump(), t.dump()]),
+            Type::Alias(name, t) => node("Alias", [name.dump(), t.dump()]),
+        }
+    }
+}
+
+impl Dump for CaseAlternative {
+    fn dump(&self) -> Document {
+        node(
+            "alternative",
+            [
+                atom(self.constructor),
+                atom(self.arguments),
+                self.value.dump(),
+            ],
+        )
+    }
+}
+
+impl Dump for Term {
+    fn dump(&self) -> Document {
+        match self {
+            Term::Unit => node("Unit", []),
+            Term::Uint(value) => node("Uint", [literal(value)]),
+            Term::Int(value) => node("Int", [literal(value)]),
+            Term::String(value) => node("String", [literal(value)]),
+            Term::Char(value) => node("Char", [literal(value)]),
+            Term::Variable(index, t) => {
+                node("Variable", [atom(index), t.dump()])
+            }
+            Term::NamedVariable(name) => node("NamedVariable", [name.dump()]),
+            Term::GlobalVariable(name, t) => {
+                node("GlobalVariable", [name.dump(), t.dump()])
+            }
+            Term::Function(body, t) => {
+                node("Function", [body.dump(), t.dump()])
+            }
+            Term::Application(function, argument, t) => node(
+                "Application",
+                [function.dump(), argument.dump(), t.dump()],
+            ),
+            Term::TypeFunction(body, t) => {
+                node("TypeFunction", [body.dump(), t.dump()])
+            }
+            Term::TypeApplication(function, argument, t) => node(
+                "TypeApplication",
+                [function.dump(), argument.dump(), t.dump()],
+            ),
+            Term::Constructor(name, index, arguments, t) => node(
+                "Constructor",
+                [name.dump(), atom(index), arguments.dump(), t.dump()],
+            ),
+            Term::Record(record, t) => {
+                node("RecordTerm", [record.dump(), t.dump()])
+            }
+            Term::Tuple(items, t) => node("Tuple", [items.dump(), t.dump()]),
+            Term::TupleProjection(tuple, index, t) => {
+                node("TupleProjection", [tuple.dump(), atom(index), t.dump()])
+            }
+            Term::RecordProjection(record, label, t) => node(
+                "RecordProjection",
+                [record.dump(), label.dump(), t.dump()],
+            ),
+            Term::Case(value, alternatives, t) => {
+                node("Case", [value.dump(), alternatives.dump(), t.dump()])
+            }
+            Term::Primitive(primitive, arguments, t) => {
+                node("Primitive", [atom(primitive), arguments.dump(), t.dump()])
+            }
+        }
+    }
+}
+
+impl Dump for TypeDefinition {
+    fn dump(&self) -> Document {
+        node("TypeDefinition", [self.name.dump(), self.type_.dump()])
+    }
+}
+
+impl Dump for Definition {
+    fn dump(&self) -> Document {
+        node(
+            "Definition",
+            [self.name.dump(), self.type_.dump(), self.value.dump()],
+        )
+    }
+}
+
+impl Dump for Module {
+    fn dump(&self) -> Document {
+        node("Module", [self.types.dump(), self.definitions.dump()])
+    }
+}
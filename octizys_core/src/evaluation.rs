@@ -0,0 +1,925 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::Location;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+
+use crate::common::{Identifier, Record};
+use crate::core::Module;
+use crate::primitives::Primitive;
+use crate::terms::Term;
+
+/// The name of the definition evaluated by [`Interpreter::run_main`].
+pub const MAIN_NAME: &str = "main";
+
+/// The maximum amount of nested evaluations before we give up, this
+/// avoids the overflow of the rust stack on infinite recursion.
+pub const DEFAULT_MAX_DEPTH: usize = 10_000;
+
+/// An upper bound of the stack used by a nested evaluation in a debug
+/// build, the evaluation runs on a thread with a stack big enough to
+/// reach the maximum depth.
+const STACK_BYTES_PER_DEPTH: usize = 16 * 1024;
+
+/// The stack for the frames around the evaluation.
+const BASE_STACK_BYTES: usize = 1024 * 1024;
+
+thread_local! {
+    /// The amount of nested evaluations that the stack of the current
+    /// thread can hold, only the threads of [`with_stack`] know it.
+    static STACK_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Runs the function on a thread with a stack that can hold
+/// `max_depth` nested evaluations, a new one unless the current thread
+/// already can. The interpreters that run inside it with at most that
+/// depth don't need their own thread, so a session can spawn it once
+/// instead of once per evaluation.
+pub fn with_stack<T: Send>(
+    max_depth: usize,
+    f: impl FnOnce() -> T + Send,
+) -> T {
+    if STACK_DEPTH.get() >= max_depth {
+        return f();
+    }
+    let stack_size = max_depth
+        .saturating_mul(STACK_BYTES_PER_DEPTH)
+        .saturating_add(BASE_STACK_BYTES);
+    let mut f = Some(f);
+    let spawned = std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(stack_size)
+            .spawn_scoped(scope, || {
+                STACK_DEPTH.set(max_depth);
+                // The closure only runs if the thread was spawned.
+                (f.take().unwrap())()
+            })
+            .map(|handle| handle.join())
+    });
+    match spawned {
+        Ok(Ok(result)) => result,
+        Ok(Err(panic)) => std::panic::resume_unwind(panic),
+        // Without a thread we can still evaluate shallow terms.
+        Err(_) => (f.take().unwrap())(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Closure {
+    pub environment: Environment,
+    pub body: Arc<Term>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Unit,
+    Uint(u64),
+    Int(i64),
+    String(Arc<str>),
+    Char(char),
+    Closure(Arc<Closure>),
+    Constructor(Identifier, u64, Vec<Value>),
+    Record(Record<Value>),
+    Tuple(Vec<Value>),
+}
+
+/// A summary of a [`Value`] used in the errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Unit,
+    Uint,
+    Int,
+    String,
+    Char,
+    Function,
+    Constructor,
+    Record,
+    Tuple,
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ValueKind::Unit => "unit",
+            ValueKind::Uint => "unsigned integer",
+            ValueKind::Int => "integer",
+            ValueKind::String => "string",
+            ValueKind::Char => "character",
+            ValueKind::Function => "function",
+            ValueKind::Constructor => "constructor",
+            ValueKind::Record => "record",
+            ValueKind::Tuple => "tuple",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Value {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Unit => ValueKind::Unit,
+            Value::Uint(_) => ValueKind::Uint,
+            Value::Int(_) => ValueKind::Int,
+            Value::String(_) => ValueKind::String,
+            Value::Char(_) => ValueKind::Char,
+            Value::Closure(_) => ValueKind::Function,
+            Value::Constructor(..) => ValueKind::Constructor,
+            Value::Record(_) => ValueKind::Record,
+            Value::Tuple(_) => ValueKind::Tuple,
+        }
+    }
+
+    fn is_atomic(&self) -> bool {
+        match self {
+            Value::Constructor(_, _, arguments) => arguments.is_empty(),
+            _ => true,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Uint(n) => write!(f, "{}", n),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{:?}", s),
+            Value::Char(c) => write!(f, "{:?}", c),
+            Value::Closure(_) => write!(f, "<function>"),
+            Value::Constructor(name, _, arguments) => {
+                write!(f, "{}", name)?;
+                for argument in arguments {
+                    if argument.is_atomic() {
+                        write!(f, " {}", argument)?;
+                    } else {
+                        write!(f, " ({})", argument)?;
+                    }
+                }
+                Ok(())
+            }
+            Value::Record(record) => {
+                write!(f, "{{")?;
+                for (i, (label, value)) in record.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} = {}", label.as_str(), value)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Tuple(values) => {
+                write!(f, "(")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EnvironmentNode {
+    value: Value,
+    next: Environment,
+}
+
+/// The values of the variables in scope, the variable with
+/// De Bruijn index 0 is the head.
+#[derive(Debug, Clone, Default)]
+pub struct Environment(Option<Arc<EnvironmentNode>>);
+
+impl Environment {
+    pub fn empty() -> Self {
+        Environment(None)
+    }
+
+    pub fn push(&self, value: Value) -> Self {
+        Environment(Some(Arc::new(EnvironmentNode {
+            value,
+            next: self.clone(),
+        })))
+    }
+
+    pub fn lookup(&self, index: u64) -> Option<&Value> {
+        let mut current = self;
+        for _ in 0..index {
+            current = &current.0.as_ref()?.next;
+        }
+        current.0.as_ref().map(|node| &node.value)
+    }
+
+    pub fn len(&self) -> u64 {
+        let mut current = self;
+        let mut len = 0;
+        while let Some(node) = &current.0 {
+            len += 1;
+            current = &node.next;
+        }
+        len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RuntimeError {
+    /// The term refers to a variable that isn't in the environment,
+    /// the lowering or the checker are wrong.
+    UnboundVariable {
+        index: u64,
+        environment_size: u64,
+    },
+    /// The names must be resolved before the evaluation.
+    UnresolvedName(Identifier),
+    UnknownGlobal(Identifier),
+    /// The evaluation of a global needs its own value.
+    RecursiveGlobal(Identifier),
+    NotAFunction(ValueKind),
+    NotAConstructor(ValueKind),
+    /// The value of a projection doesn't have the item or field.
+    InvalidProjection(ValueKind),
+    NoMatchingAlternative {
+        constructor: Identifier,
+        index: u64,
+    },
+    StackOverflow {
+        max_depth: usize,
+    },
+    MissingMain,
+    DivisionByZero,
+    /// The operands are wide enough for the signed and unsigned
+    /// primitives.
+    ArithmeticOverflow {
+        primitive: Primitive,
+        left: i128,
+        right: i128,
+    },
+    /// The primitive got a value of the wrong kind, the
+    /// type checker must prevent this.
+    PrimitiveArgument {
+        primitive: Primitive,
+        found: ValueKind,
+    },
+    PrimitiveArity {
+        primitive: Primitive,
+        found: usize,
+    },
+    /// Adds the name of the global that was being evaluated.
+    InGlobal {
+        name: Identifier,
+        error: Box<RuntimeError>,
+    },
+}
+
+impl RuntimeError {
+    /// The error without the [`RuntimeError::InGlobal`] context.
+    pub fn root(&self) -> &RuntimeError {
+        match self {
+            RuntimeError::InGlobal { error, .. } => error.root(),
+            _ => self,
+        }
+    }
+
+    fn context(&self) -> Vec<&Identifier> {
+        match self {
+            RuntimeError::InGlobal { name, error } => {
+                let mut context = vec![name];
+                context.extend(error.context());
+                context
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl ReportFormat for RuntimeError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self.root() {
+            RuntimeError::UnboundVariable { .. } => {
+                NonLineBreakStr::new("Internal:UnboundVariable")
+            }
+            RuntimeError::UnresolvedName(_) => {
+                NonLineBreakStr::new("Internal:UnresolvedName")
+            }
+            RuntimeError::UnknownGlobal(_) => {
+                NonLineBreakStr::new("UnknownGlobal")
+            }
+            RuntimeError::RecursiveGlobal(_) => {
+                NonLineBreakStr::new("RecursiveGlobal")
+            }
+            RuntimeError::NotAFunction(_) => {
+                NonLineBreakStr::new("Internal:NotAFunction")
+            }
+            RuntimeError::NotAConstructor(_) => {
+                NonLineBreakStr::new("Internal:NotAConstructor")
+            }
+            RuntimeError::InvalidProjection(_) => {
+                NonLineBreakStr::new("Internal:InvalidProjection")
+            }
+            RuntimeError::NoMatchingAlternative { .. } => {
+                NonLineBreakStr::new("NoMatchingAlternative")
+            }
+            RuntimeError::StackOverflow { .. } => {
+                NonLineBreakStr::new("StackOverflow")
+            }
+            RuntimeError::MissingMain => NonLineBreakStr::new("MissingMain"),
+            RuntimeError::DivisionByZero => {
+                NonLineBreakStr::new("DivisionByZero")
+            }
+            RuntimeError::ArithmeticOverflow { .. } => {
+                NonLineBreakStr::new("ArithmeticOverflow")
+            }
+            RuntimeError::PrimitiveArgument { .. } => {
+                NonLineBreakStr::new("Internal:PrimitiveArgument")
+            }
+            RuntimeError::PrimitiveArity { .. } => {
+                NonLineBreakStr::new("Internal:PrimitiveArity")
+            }
+            RuntimeError::InGlobal { .. } => {
+                NonLineBreakStr::new("Internal:InGlobal")
+            }
+        }
+    }
+    fn get_short_description(&self) -> NonLineBreakStr {
+        let common =
+            NonLineBreakStr::new("This is a bug in octizys, please report it!");
+        match self.root() {
+            RuntimeError::UnboundVariable { .. } => common,
+            RuntimeError::UnresolvedName(_) => common,
+            RuntimeError::UnknownGlobal(_) => {
+                NonLineBreakStr::new("We couldn't find a definition.")
+            }
+            RuntimeError::RecursiveGlobal(_) => NonLineBreakStr::new(
+                "A definition needs its own value to be computed.",
+            ),
+            RuntimeError::NotAFunction(_) => common,
+            RuntimeError::NotAConstructor(_) => common,
+            RuntimeError::InvalidProjection(_) => common,
+            RuntimeError::NoMatchingAlternative { .. } => NonLineBreakStr::new(
+                "A case expression doesn't handle a value.",
+            ),
+            RuntimeError::StackOverflow { .. } => {
+                NonLineBreakStr::new("The program used too many nested calls.")
+            }
+            RuntimeError::MissingMain => NonLineBreakStr::new(
+                "The module doesn't have a main definition.",
+            ),
+            RuntimeError::DivisionByZero => {
+                NonLineBreakStr::new("A number was divided by zero.")
+            }
+            RuntimeError::ArithmeticOverflow { .. } => NonLineBreakStr::new(
+                "The result of an operation doesn't fit in its type.",
+            ),
+            RuntimeError::PrimitiveArgument { .. } => common,
+            RuntimeError::PrimitiveArity { .. } => common,
+            RuntimeError::InGlobal { .. } => common,
+        }
+    }
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self.root() {
+            RuntimeError::UnboundVariable {
+                index,
+                environment_size,
+            } => format!(
+                "The variable with index {} was used in an environment with {} variables.",
+                index, environment_size
+            ),
+            RuntimeError::UnresolvedName(name) => {
+                format!("The name {} wasn't resolved before running.", name)
+            }
+            RuntimeError::UnknownGlobal(name) => {
+                format!("The definition {} doesn't exist in the module.", name)
+            }
+            RuntimeError::RecursiveGlobal(name) => format!(
+                "While computing {} we needed the value of {} again.\nOnly functions can refer to themselves.",
+                name, name
+            ),
+            RuntimeError::NotAFunction(kind) => {
+                format!("We tried to call a {} as a function.", kind)
+            }
+            RuntimeError::NotAConstructor(kind) => {
+                format!("We tried to inspect a {} in a case.", kind)
+            }
+            RuntimeError::InvalidProjection(kind) => {
+                format!("We tried to take a part of a {}.", kind)
+            }
+            RuntimeError::NoMatchingAlternative { constructor, .. } => format!(
+                "The value was built with {}, but the case doesn't have an alternative for it.",
+                constructor
+            ),
+            RuntimeError::StackOverflow { max_depth } => format!(
+                "The limit of {} nested evaluations was reached, maybe the program doesn't terminate?",
+                max_depth
+            ),
+            RuntimeError::MissingMain => format!(
+                "To run a module it needs a definition named {}.",
+                MAIN_NAME
+            ),
+            RuntimeError::DivisionByZero => String::from(
+                "The division and the remainder aren't defined when the second argument is zero.",
+            ),
+            RuntimeError::ArithmeticOverflow {
+                primitive,
+                left,
+                right,
+            } => format!(
+                "The result of {} {} {} is out of the range of the type.",
+                left,
+                primitive.name(),
+                right
+            ),
+            RuntimeError::PrimitiveArgument { primitive, found } => format!(
+                "The primitive {} was applied to a {}.",
+                primitive, found
+            ),
+            RuntimeError::PrimitiveArity { primitive, found } => format!(
+                "The primitive {} expects {} arguments but got {}.",
+                primitive,
+                primitive.arity(),
+                found
+            ),
+            RuntimeError::InGlobal { .. } => String::new(),
+        };
+        let context = self.context();
+        let context_text = if context.is_empty() {
+            String::new()
+        } else {
+            let names: Vec<String> =
+                context.iter().map(|x| x.to_string()).collect();
+            format!("\nWhile evaluating: {}", names.join(" -> "))
+        };
+        Some(external_text(&(description + &context_text)))
+    }
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+    fn get_location_maybe(&self) -> Option<Location> {
+        None
+    }
+}
+
+enum GlobalState {
+    /// We are computing the value of the global.
+    InProgress,
+    Done(Value),
+}
+
+/// Evaluates [`Term`]s using the call by value strategy.
+pub struct Interpreter<'module> {
+    module: &'module Module,
+    globals: HashMap<Identifier, GlobalState>,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<'module> Interpreter<'module> {
+    pub fn new(module: &'module Module) -> Self {
+        Interpreter {
+            module,
+            globals: HashMap::new(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Evaluates the `main` definition of the module.
+    pub fn run_main(&mut self) -> Result<Value, RuntimeError> {
+        match self.module.find(MAIN_NAME) {
+            Some(definition) => self.global(&definition.name.clone()),
+            None => Err(RuntimeError::MissingMain),
+        }
+    }
+
+    /// Returns the value of a global, they are evaluated the first
+    /// time they are needed and then cached.
+    pub fn global(&mut self, name: &Identifier) -> Result<Value, RuntimeError> {
+        match self.globals.get(name) {
+            Some(GlobalState::Done(value)) => return Ok(value.clone()),
+            Some(GlobalState::InProgress) => {
+                return Err(RuntimeError::RecursiveGlobal(name.clone()))
+            }
+            None => (),
+        }
+        let module = self.module;
+        let definition = match module.find(name.as_str()) {
+            Some(definition) => definition,
+            None => return Err(RuntimeError::UnknownGlobal(name.clone())),
+        };
+        self.globals.insert(name.clone(), GlobalState::InProgress);
+        match self.eval(&definition.value, &Environment::empty()) {
+            Ok(value) => {
+                self.globals
+                    .insert(name.clone(), GlobalState::Done(value.clone()));
+                Ok(value)
+            }
+            Err(error) => {
+                self.globals.remove(name);
+                Err(RuntimeError::InGlobal {
+                    name: name.clone(),
+                    error: Box::new(error),
+                })
+            }
+        }
+    }
+
+    /// Evaluates the term. Unless the current thread comes from a
+    /// [`with_stack`] deep enough, the outermost call runs on a new
+    /// thread with a stack that can hold
+    /// [`Interpreter::with_max_depth`] nested evaluations.
+    pub fn eval(
+        &mut self,
+        term: &Term,
+        environment: &Environment,
+    ) -> Result<Value, RuntimeError> {
+        if self.depth > 0 {
+            return self.eval_nested(term, environment);
+        }
+        let max_depth = self.max_depth;
+        with_stack(max_depth, || self.eval_nested(term, environment))
+    }
+
+    fn eval_nested(
+        &mut self,
+        term: &Term,
+        environment: &Environment,
+    ) -> Result<Value, RuntimeError> {
+        if self.depth >= self.max_depth {
+            return Err(RuntimeError::StackOverflow {
+                max_depth: self.max_depth,
+            });
+        }
+        self.depth += 1;
+        let result = self.eval_inner(term, environment);
+        self.depth -= 1;
+        result
+    }
+
+    fn eval_inner(
+        &mut self,
+        term: &Term,
+        environment: &Environment,
+    ) -> Result<Value, RuntimeError> {
+        match term {
+            Term::Unit => Ok(Value::Unit),
+            Term::Uint(n) => Ok(Value::Uint(*n)),
+            Term::Int(n) => Ok(Value::Int(*n)),
+            Term::String(s) => Ok(Value::String(Arc::from(s.as_str()))),
+            Term::Char(c) => Ok(Value::Char(*c)),
+            Term::Variable(index, _) => match environment.lookup(*index) {
+                Some(value) => Ok(value.clone()),
+                None => Err(RuntimeError::UnboundVariable {
+                    index: *index,
+                    environment_size: environment.len(),
+                }),
+            },
+            Term::NamedVariable(name) => {
+                Err(RuntimeError::UnresolvedName(name.clone()))
+            }
+            Term::GlobalVariable(name, _) => self.global(name),
+            Term::Function(body, _) => Ok(Value::Closure(Arc::new(Closure {
+                environment: environment.clone(),
+                body: body.clone(),
+            }))),
+            Term::Application(function, argument, _) => {
+                let function = self.eval(function, environment)?;
+                let argument = self.eval(argument, environment)?;
+                self.apply(function, argument)
+            }
+            // Types don't exist at run time.
+            Term::TypeFunction(body, _) => self.eval(body, environment),
+            Term::TypeApplication(term, _, _) => self.eval(term, environment),
+            Term::Constructor(name, index, arguments, _) => {
+                let mut values = Vec::with_capacity(arguments.len());
+                for argument in arguments {
+                    values.push(self.eval(argument, environment)?);
+                }
+                Ok(Value::Constructor(name.clone(), *index, values))
+            }
+            Term::Record(record, _) => Ok(Value::Record(
+                record.try_map(|t| self.eval(t, environment))?,
+            )),
+            Term::Tuple(terms, _) => {
+                let mut values = Vec::with_capacity(terms.len());
+                for t in terms {
+                    values.push(self.eval(t, environment)?);
+                }
+                Ok(Value::Tuple(values))
+            }
+            Term::TupleProjection(term, index, _) => {
+                match self.eval(term, environment)? {
+                    Value::Tuple(mut values)
+                        if (*index as usize) < values.len() =>
+                    {
+                        Ok(values.swap_remove(*index as usize))
+                    }
+                    other => Err(RuntimeError::InvalidProjection(other.kind())),
+                }
+            }
+            Term::RecordProjection(term, label, _) => {
+                match self.eval(term, environment)? {
+                    Value::Record(record) => match record.get(label) {
+                        Some(value) => Ok(value.clone()),
+                        None => Err(RuntimeError::InvalidProjection(
+                            ValueKind::Record,
+                        )),
+                    },
+                    other => Err(RuntimeError::InvalidProjection(other.kind())),
+                }
+            }
+            Term::Case(scrutinee, alternatives, _) => {
+                let (name, index, arguments) = match self
+                    .eval(scrutinee, environment)?
+                {
+                    Value::Constructor(name, index, arguments) => {
+                        (name, index, arguments)
+                    }
+                    other => {
+                        return Err(RuntimeError::NotAConstructor(other.kind()))
+                    }
+                };
+                let alternative = alternatives
+                    .iter()
+                    .find(|alternative| alternative.constructor == index);
+                match alternative {
+                    Some(alternative) => {
+                        let mut new_environment = environment.clone();
+                        for argument in arguments
+                            .into_iter()
+                            .take(alternative.arguments as usize)
+                        {
+                            new_environment = new_environment.push(argument);
+                        }
+                        self.eval(&alternative.value, &new_environment)
+                    }
+                    None => Err(RuntimeError::NoMatchingAlternative {
+                        constructor: name,
+                        index,
+                    }),
+                }
+            }
+            Term::Primitive(primitive, arguments, _) => {
+                let mut values = Vec::with_capacity(arguments.len());
+                for argument in arguments {
+                    values.push(self.eval(argument, environment)?);
+                }
+                primitive.evaluate(&values)
+            }
+        }
+    }
+
+    pub fn apply(
+        &mut self,
+        function: Value,
+        argument: Value,
+    ) -> Result<Value, RuntimeError> {
+        match function {
+            Value::Closure(closure) => {
+                let environment = closure.environment.push(argument);
+                self.eval(&closure.body, &environment)
+            }
+            other => Err(RuntimeError::NotAFunction(other.kind())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod evaluation_test {
+    use super::{
+        with_stack, Environment, Interpreter, RuntimeError, Value,
+        DEFAULT_MAX_DEPTH, STACK_DEPTH,
+    };
+    use crate::common::Identifier;
+    use crate::core::{Definition, Module};
+    use crate::terms::{CaseAlternative, Term};
+    use crate::types::Type;
+    use std::sync::Arc;
+
+    fn identifier(s: &str) -> Identifier {
+        Identifier::make(String::from(s)).unwrap()
+    }
+
+    fn eval(term: Term) -> Result<Value, RuntimeError> {
+        let module = Module::default();
+        Interpreter::new(&module).eval(&term, &Environment::empty())
+    }
+
+    fn constructor(name: &str, index: u64, arguments: Vec<Term>) -> Term {
+        Term::Constructor(identifier(name), index, arguments, Type::Unit)
+    }
+
+    #[test]
+    fn identity_application() {
+        let identity =
+            Term::Function(Arc::new(Term::Variable(0, Type::Uint)), Type::Uint);
+        let term = Term::Application(
+            Box::new(identity),
+            Box::new(Term::Uint(3)),
+            Type::Uint,
+        );
+        assert_eq!(eval(term).unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn sessions_keep_their_stack() {
+        let value = with_stack(DEFAULT_MAX_DEPTH, || {
+            // The interpreters inside don't spawn another thread.
+            assert_eq!(STACK_DEPTH.get(), DEFAULT_MAX_DEPTH);
+            eval(Term::Uint(3)).unwrap().to_string()
+        });
+        assert_eq!(value, "3");
+        assert_eq!(STACK_DEPTH.get(), 0);
+    }
+
+    #[test]
+    fn closures_capture_environment() {
+        // (\x -> \y -> x) 1 2
+        let constant = Term::Function(
+            Arc::new(Term::Function(
+                Arc::new(Term::Variable(1, Type::Uint)),
+                Type::Uint,
+            )),
+            Type::Uint,
+        );
+        let term = Term::Application(
+            Box::new(Term::Application(
+                Box::new(constant),
+                Box::new(Term::Uint(1)),
+                Type::Uint,
+            )),
+            Box::new(Term::Uint(2)),
+            Type::Uint,
+        );
+        assert_eq!(eval(term).unwrap().to_string(), "1");
+    }
+
+    #[test]
+    fn case_binds_arguments() {
+        // case Pair 1 2 of Pair a b -> (b, a)
+        let term = Term::Case(
+            Box::new(constructor(
+                "Pair",
+                0,
+                vec![Term::Uint(1), Term::Uint(2)],
+            )),
+            vec![CaseAlternative {
+                constructor: 0,
+                arguments: 2,
+                value: Term::Tuple(
+                    vec![
+                        Term::Variable(0, Type::Uint),
+                        Term::Variable(1, Type::Uint),
+                    ],
+                    Type::Unit,
+                ),
+            }],
+            Type::Unit,
+        );
+        assert_eq!(eval(term).unwrap().to_string(), "(2, 1)");
+    }
+
+    #[test]
+    fn missing_alternative() {
+        let term = Term::Case(
+            Box::new(constructor("B", 1, vec![])),
+            vec![CaseAlternative {
+                constructor: 0,
+                arguments: 0,
+                value: Term::Unit,
+            }],
+            Type::Unit,
+        );
+        assert!(matches!(
+            eval(term),
+            Err(RuntimeError::NoMatchingAlternative { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn constructor_display() {
+        let term = constructor(
+            "Just",
+            1,
+            vec![constructor(
+                "Just",
+                1,
+                vec![constructor("Nothing", 0, vec![])],
+            )],
+        );
+        assert_eq!(eval(term).unwrap().to_string(), "Just (Just Nothing)");
+    }
+
+    #[test]
+    fn run_main_with_globals() {
+        let module = Module {
+            types: vec![],
+            definitions: vec![
+                Definition {
+                    name: identifier("one"),
+                    value: Term::Uint(1),
+                    type_: Type::Uint,
+                },
+                Definition {
+                    name: identifier("main"),
+                    value: Term::Tuple(
+                        vec![
+                            Term::GlobalVariable(identifier("one"), Type::Uint),
+                            Term::String(String::from("a")),
+                        ],
+                        Type::Unit,
+                    ),
+                    type_: Type::Unit,
+                },
+            ],
+        };
+        let value = Interpreter::new(&module).run_main().unwrap();
+        assert_eq!(value.to_string(), "(1, \"a\")");
+    }
+
+    #[test]
+    fn recursive_global_and_missing_main() {
+        let module = Module {
+            types: vec![],
+            definitions: vec![Definition {
+                name: identifier("loop"),
+                value: Term::GlobalVariable(identifier("loop"), Type::Unit),
+                type_: Type::Unit,
+            }],
+        };
+        let mut interpreter = Interpreter::new(&module);
+        assert!(matches!(
+            interpreter.global(&identifier("loop")).unwrap_err().root(),
+            RuntimeError::RecursiveGlobal(_)
+        ));
+        assert!(matches!(
+            interpreter.run_main(),
+            Err(RuntimeError::MissingMain)
+        ));
+    }
+
+    /// The module with `f = \x -> f x` and the term `f ()`.
+    fn infinite_recursion() -> (Module, Term) {
+        let module = Module {
+            types: vec![],
+            definitions: vec![Definition {
+                name: identifier("f"),
+                value: Term::Function(
+                    Arc::new(Term::Application(
+                        Box::new(Term::GlobalVariable(
+                            identifier("f"),
+                            Type::Unit,
+                        )),
+                        Box::new(Term::Variable(0, Type::Unit)),
+                        Type::Unit,
+                    )),
+                    Type::Unit,
+                ),
+                type_: Type::Unit,
+            }],
+        };
+        let term = Term::Application(
+            Box::new(Term::GlobalVariable(identifier("f"), Type::Unit)),
+            Box::new(Term::Unit),
+            Type::Unit,
+        );
+        (module, term)
+    }
+
+    #[test]
+    fn infinite_recursion_is_reported() {
+        let (module, term) = infinite_recursion();
+        let result = Interpreter::new(&module)
+            .with_max_depth(200)
+            .eval(&term, &Environment::empty());
+        assert!(matches!(result, Err(RuntimeError::StackOverflow { .. })));
+    }
+
+    #[test]
+    fn deep_recursion_stops_at_the_default_depth() {
+        // The rust stack must not overflow before the limit.
+        let (module, term) = infinite_recursion();
+        let result =
+            Interpreter::new(&module).eval(&term, &Environment::empty());
+        match result {
+            Err(RuntimeError::StackOverflow { max_depth }) => {
+                assert_eq!(max_depth, DEFAULT_MAX_DEPTH)
+            }
+            other => panic!("Expected a stack overflow, got {:?}", other),
+        }
+    }
+}
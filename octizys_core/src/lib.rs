@@ -1,5 +1,10 @@
+pub mod artefact;
+pub mod checker;
 pub mod common;
 pub mod core;
+pub mod dump;
+pub mod evaluation;
+pub mod primitives;
 pub mod terms;
 pub mod types;
 
@@ -0,0 +1,514 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use crate::common::{Identifier, Variable};
+use crate::evaluation::{RuntimeError, Value, ValueKind};
+use crate::types::Type;
+
+/// The name of the boolean type, the comparisons return values of it.
+pub const BOOL_NAME: &str = "Bool";
+pub const FALSE_NAME: &str = "False";
+pub const TRUE_NAME: &str = "True";
+/// The index of the constructors in the sum of [`BOOL_NAME`].
+pub const FALSE_INDEX: u64 = 0;
+pub const TRUE_INDEX: u64 = 1;
+
+/// Operations that the interpreter implements directly.
+/// They are always fully applied in a [`crate::terms::Term::Primitive`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Primitive {
+    AddUint,
+    SubtractUint,
+    MultiplyUint,
+    DivideUint,
+    RemainderUint,
+    EqualUint,
+    NotEqualUint,
+    LessUint,
+    LessOrEqualUint,
+    MoreUint,
+    MoreOrEqualUint,
+    /// The negation of the signed integers is a subtraction from zero.
+    SubtractInt,
+    /// The decimal representation of a number, the holes of the
+    /// interpolation strings use them.
+    ShowUint,
+    ShowInt,
+    /// The representation of a number in the radix of the first
+    /// argument with lowercase digits, the signed integers are shown
+    /// in two's complement.
+    ShowUintRadix,
+    ShowIntRadix,
+    ConcatString,
+    /// The string quoted with the escapes of the string literals.
+    DebugString,
+    UppercaseString,
+    /// Keeps the amount of characters of the first argument.
+    TruncateString,
+    /// Fills the string with the first argument until it has the width
+    /// of the second argument, the format specifiers of the holes use
+    /// them.
+    AlignLeft,
+    AlignRight,
+    AlignCenter,
+    /// Fills a number with zeros after its sign until it has the width
+    /// of the first argument.
+    PadZeros,
+    EqualChar,
+    /// The string with only the character.
+    ShowChar,
+    /// The character quoted with the escapes of the character literals.
+    DebugChar,
+    /// The constants of the patterns of the signed integers compare
+    /// with it.
+    EqualInt,
+}
+
+/// The type of the booleans, the module must define it.
+pub fn bool_type() -> Type {
+    Type::Variable(Variable::Named(
+        Identifier::make(String::from(BOOL_NAME)).unwrap(),
+    ))
+}
+
+pub fn make_bool(value: bool) -> Value {
+    if value {
+        Value::Constructor(
+            Identifier::make(String::from(TRUE_NAME)).unwrap(),
+            TRUE_INDEX,
+            vec![],
+        )
+    } else {
+        Value::Constructor(
+            Identifier::make(String::from(FALSE_NAME)).unwrap(),
+            FALSE_INDEX,
+            vec![],
+        )
+    }
+}
+
+impl Primitive {
+    pub fn arity(&self) -> usize {
+        self.signature().0.len()
+    }
+
+    /// The types of the arguments and the type of the result.
+    pub fn signature(&self) -> (Vec<Type>, Type) {
+        let output = match self {
+            Primitive::AddUint
+            | Primitive::SubtractUint
+            | Primitive::MultiplyUint
+            | Primitive::DivideUint
+            | Primitive::RemainderUint => Type::Uint,
+            Primitive::EqualUint
+            | Primitive::NotEqualUint
+            | Primitive::LessUint
+            | Primitive::LessOrEqualUint
+            | Primitive::MoreUint
+            | Primitive::MoreOrEqualUint => bool_type(),
+            Primitive::SubtractInt => {
+                return (vec![Type::Int, Type::Int], Type::Int)
+            }
+            Primitive::ShowUint => return (vec![Type::Uint], Type::String),
+            Primitive::ShowInt => return (vec![Type::Int], Type::String),
+            Primitive::ShowUintRadix => {
+                return (vec![Type::Uint, Type::Uint], Type::String)
+            }
+            Primitive::ShowIntRadix => {
+                return (vec![Type::Uint, Type::Int], Type::String)
+            }
+            Primitive::ConcatString => {
+                return (vec![Type::String, Type::String], Type::String)
+            }
+            Primitive::DebugString | Primitive::UppercaseString => {
+                return (vec![Type::String], Type::String)
+            }
+            Primitive::TruncateString | Primitive::PadZeros => {
+                return (vec![Type::Uint, Type::String], Type::String)
+            }
+            Primitive::EqualChar => {
+                return (vec![Type::Char, Type::Char], bool_type())
+            }
+            Primitive::ShowChar | Primitive::DebugChar => {
+                return (vec![Type::Char], Type::String)
+            }
+            Primitive::EqualInt => {
+                return (vec![Type::Int, Type::Int], bool_type())
+            }
+            Primitive::AlignLeft
+            | Primitive::AlignRight
+            | Primitive::AlignCenter => {
+                return (
+                    vec![Type::String, Type::Uint, Type::String],
+                    Type::String,
+                )
+            }
+        };
+        (vec![Type::Uint, Type::Uint], output)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Primitive::AddUint => "+",
+            Primitive::SubtractUint => "-",
+            Primitive::MultiplyUint => "*",
+            Primitive::DivideUint => "/",
+            Primitive::RemainderUint => "%",
+            Primitive::EqualUint => "==",
+            Primitive::NotEqualUint => "!=",
+            Primitive::LessUint => "<",
+            Primitive::LessOrEqualUint => "<=",
+            Primitive::MoreUint => ">",
+            Primitive::MoreOrEqualUint => ">=",
+            Primitive::SubtractInt => "-",
+            Primitive::ShowUint | Primitive::ShowInt => "show",
+            Primitive::ShowUintRadix | Primitive::ShowIntRadix => "showRadix",
+            Primitive::ConcatString => "++",
+            Primitive::DebugString => "debug",
+            Primitive::UppercaseString => "uppercase",
+            Primitive::TruncateString => "truncate",
+            Primitive::AlignLeft => "alignLeft",
+            Primitive::AlignRight => "alignRight",
+            Primitive::AlignCenter => "alignCenter",
+            Primitive::PadZeros => "padZeros",
+            Primitive::EqualChar => "==",
+            Primitive::ShowChar => "show",
+            Primitive::DebugChar => "debug",
+            Primitive::EqualInt => "==",
+        }
+    }
+
+    /// Computes the result, the amount of arguments must be
+    /// the [`Primitive::arity`].
+    pub fn evaluate(&self, arguments: &[Value]) -> Result<Value, RuntimeError> {
+        // The primitives with arguments of different kinds take them
+        // by parts.
+        if arguments.len() != self.arity() {
+            return Err(RuntimeError::PrimitiveArity {
+                primitive: *self,
+                found: arguments.len(),
+            });
+        }
+        let uint = |value: &Value| match value {
+            Value::Uint(n) => Some(*n),
+            _ => None,
+        };
+        let int = |value: &Value| match value {
+            Value::Int(n) => Some(*n),
+            _ => None,
+        };
+        let string = |value: &Value| match value {
+            Value::String(s) => Some(s.clone()),
+            _ => None,
+        };
+        let make_string = |s: String| Ok(Value::String(Arc::from(s)));
+        let character = |value: &Value| match value {
+            Value::Char(c) => Some(*c),
+            _ => None,
+        };
+        match self {
+            Primitive::SubtractInt => {
+                let [left, right] = self.operands(arguments, int)?;
+                left.checked_sub(right).map(Value::Int).ok_or(
+                    RuntimeError::ArithmeticOverflow {
+                        primitive: *self,
+                        left: left.into(),
+                        right: right.into(),
+                    },
+                )
+            }
+            Primitive::EqualInt => {
+                let [left, right] = self.operands(arguments, int)?;
+                Ok(make_bool(left == right))
+            }
+            Primitive::ShowUint => {
+                let [n] = self.operands(arguments, uint)?;
+                Ok(Value::String(Arc::from(n.to_string())))
+            }
+            Primitive::ShowInt => {
+                let [n] = self.operands(arguments, int)?;
+                Ok(Value::String(Arc::from(n.to_string())))
+            }
+            Primitive::ShowUintRadix => {
+                let [radix, n] = self.operands(arguments, uint)?;
+                self.show_radix(radix, n).and_then(make_string)
+            }
+            Primitive::ShowIntRadix => {
+                let [radix] = self.operands(&arguments[..1], uint)?;
+                let [n] = self.operands(&arguments[1..], int)?;
+                self.show_radix(radix, n as u64).and_then(make_string)
+            }
+            Primitive::ConcatString => {
+                let [left, right] = self.operands(arguments, string)?;
+                make_string(format!("{}{}", left, right))
+            }
+            Primitive::EqualChar => {
+                let [left, right] = self.operands(arguments, character)?;
+                Ok(make_bool(left == right))
+            }
+            Primitive::ShowChar => {
+                let [c] = self.operands(arguments, character)?;
+                make_string(c.to_string())
+            }
+            Primitive::DebugChar => {
+                let [c] = self.operands(arguments, character)?;
+                make_string(format!("{:?}", c))
+            }
+            Primitive::DebugString => {
+                let [s] = self.operands(arguments, string)?;
+                make_string(format!("{:?}", s))
+            }
+            Primitive::UppercaseString => {
+                let [s] = self.operands(arguments, string)?;
+                make_string(s.to_uppercase())
+            }
+            Primitive::TruncateString => {
+                let [width] = self.operands(&arguments[..1], uint)?;
+                let [s] = self.operands(&arguments[1..], string)?;
+                make_string(s.chars().take(width as usize).collect())
+            }
+            Primitive::AlignLeft
+            | Primitive::AlignRight
+            | Primitive::AlignCenter => {
+                let [fill] = self.operands(&arguments[..1], string)?;
+                let [width] = self.operands(&arguments[1..2], uint)?;
+                let [s] = self.operands(&arguments[2..], string)?;
+                let missing =
+                    (width as usize).saturating_sub(s.chars().count());
+                let before = match self {
+                    Primitive::AlignLeft => 0,
+                    Primitive::AlignRight => missing,
+                    _ => missing / 2,
+                };
+                make_string(format!(
+                    "{}{}{}",
+                    fill.repeat(before),
+                    s,
+                    fill.repeat(missing - before)
+                ))
+            }
+            Primitive::PadZeros => {
+                let [width] = self.operands(&arguments[..1], uint)?;
+                let [s] = self.operands(&arguments[1..], string)?;
+                let (sign, digits) = match s.strip_prefix('-') {
+                    Some(digits) => ("-", digits),
+                    None => ("", &*s),
+                };
+                let missing =
+                    (width as usize).saturating_sub(s.chars().count());
+                make_string(format!(
+                    "{}{}{}",
+                    sign,
+                    "0".repeat(missing),
+                    digits
+                ))
+            }
+            _ => {
+                let [left, right] = self.operands(arguments, uint)?;
+                self.evaluate_uint(left, right)
+            }
+        }
+    }
+
+    /// The digits of `n` in the `radix`, it must be between 2 and 36.
+    fn show_radix(&self, radix: u64, n: u64) -> Result<String, RuntimeError> {
+        if !(2..=36).contains(&radix) {
+            return Err(RuntimeError::PrimitiveArgument {
+                primitive: *self,
+                found: ValueKind::Uint,
+            });
+        }
+        let mut digits = vec![];
+        let mut rest = n;
+        loop {
+            // The digit is less than the radix.
+            digits.push(
+                char::from_digit((rest % radix) as u32, radix as u32).unwrap(),
+            );
+            rest /= radix;
+            if rest == 0 {
+                break;
+            }
+        }
+        Ok(digits.into_iter().rev().collect())
+    }
+
+    fn evaluate_uint(
+        &self,
+        left: u64,
+        right: u64,
+    ) -> Result<Value, RuntimeError> {
+        let overflow = RuntimeError::ArithmeticOverflow {
+            primitive: *self,
+            left: left.into(),
+            right: right.into(),
+        };
+        match self {
+            Primitive::AddUint => {
+                left.checked_add(right).map(Value::Uint).ok_or(overflow)
+            }
+            Primitive::SubtractUint => {
+                left.checked_sub(right).map(Value::Uint).ok_or(overflow)
+            }
+            Primitive::MultiplyUint => {
+                left.checked_mul(right).map(Value::Uint).ok_or(overflow)
+            }
+            Primitive::DivideUint => left
+                .checked_div(right)
+                .map(Value::Uint)
+                .ok_or(RuntimeError::DivisionByZero),
+            Primitive::RemainderUint => left
+                .checked_rem(right)
+                .map(Value::Uint)
+                .ok_or(RuntimeError::DivisionByZero),
+            Primitive::EqualUint => Ok(make_bool(left == right)),
+            Primitive::NotEqualUint => Ok(make_bool(left != right)),
+            Primitive::LessUint => Ok(make_bool(left < right)),
+            Primitive::LessOrEqualUint => Ok(make_bool(left <= right)),
+            Primitive::MoreUint => Ok(make_bool(left > right)),
+            Primitive::MoreOrEqualUint => Ok(make_bool(left >= right)),
+            _ => Err(RuntimeError::PrimitiveArgument {
+                primitive: *self,
+                found: ValueKind::Uint,
+            }),
+        }
+    }
+
+    /// The arguments of the primitive, `operand` gives the content
+    /// of a value of the expected kind.
+    fn operands<T, const N: usize>(
+        &self,
+        arguments: &[Value],
+        operand: impl Fn(&Value) -> Option<T>,
+    ) -> Result<[T; N], RuntimeError> {
+        let arguments: &[Value; N] =
+            arguments
+                .try_into()
+                .map_err(|_| RuntimeError::PrimitiveArity {
+                    primitive: *self,
+                    found: arguments.len(),
+                })?;
+        let mut operands = Vec::with_capacity(N);
+        for argument in arguments {
+            match operand(argument) {
+                Some(value) => operands.push(value),
+                None => {
+                    return Err(RuntimeError::PrimitiveArgument {
+                        primitive: *self,
+                        found: argument.kind(),
+                    })
+                }
+            }
+        }
+        // There is a operand for every argument.
+        Ok(operands.try_into().ok().unwrap())
+    }
+}
+
+impl Display for Primitive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({})", self.name())
+    }
+}
+
+#[cfg(test)]
+mod primitives_test {
+    use super::{Primitive, TRUE_INDEX};
+    use crate::evaluation::{RuntimeError, Value};
+
+    #[test]
+    fn arithmetic() {
+        let result = Primitive::AddUint
+            .evaluate(&[Value::Uint(2), Value::Uint(3)])
+            .unwrap();
+        assert!(matches!(result, Value::Uint(5)));
+        let result = Primitive::SubtractInt
+            .evaluate(&[Value::Int(0), Value::Int(5)])
+            .unwrap();
+        assert!(matches!(result, Value::Int(-5)));
+        let result = Primitive::SubtractInt
+            .evaluate(&[Value::Int(0), Value::Int(i64::MIN)]);
+        assert!(matches!(
+            result,
+            Err(RuntimeError::ArithmeticOverflow { .. })
+        ));
+        let result =
+            Primitive::SubtractUint.evaluate(&[Value::Uint(2), Value::Uint(3)]);
+        assert!(matches!(
+            result,
+            Err(RuntimeError::ArithmeticOverflow { .. })
+        ));
+        let result =
+            Primitive::DivideUint.evaluate(&[Value::Uint(2), Value::Uint(0)]);
+        assert!(matches!(result, Err(RuntimeError::DivisionByZero)));
+    }
+
+    #[test]
+    fn comparison_returns_bool() {
+        let result = Primitive::LessUint
+            .evaluate(&[Value::Uint(2), Value::Uint(3)])
+            .unwrap();
+        assert!(matches!(result, Value::Constructor(_, TRUE_INDEX, _)));
+        assert_eq!(result.to_string(), "True");
+    }
+
+    #[test]
+    fn strings() {
+        let shown = Primitive::ShowInt.evaluate(&[Value::Int(-4)]).unwrap();
+        let result = Primitive::ConcatString
+            .evaluate(&[Value::String("n=".into()), shown])
+            .unwrap();
+        assert_eq!(result.to_string(), "\"n=-4\"");
+        let result = Primitive::ShowUint.evaluate(&[Value::Int(4)]);
+        assert!(matches!(
+            result,
+            Err(RuntimeError::PrimitiveArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn formatting() {
+        let show = |primitive: Primitive, arguments: &[Value]| {
+            primitive.evaluate(arguments).unwrap().to_string()
+        };
+        let string = |s: &str| Value::String(s.into());
+        assert_eq!(
+            show(
+                Primitive::ShowUintRadix,
+                &[Value::Uint(16), Value::Uint(255)]
+            ),
+            "\"ff\""
+        );
+        assert_eq!(
+            show(Primitive::ShowIntRadix, &[Value::Uint(2), Value::Int(-1)]),
+            format!("{:?}", "1".repeat(64))
+        );
+        assert_eq!(
+            show(
+                Primitive::AlignCenter,
+                &[string("*"), Value::Uint(7), string("ab")]
+            ),
+            "\"**ab***\""
+        );
+        assert_eq!(
+            show(Primitive::PadZeros, &[Value::Uint(5), string("-42")]),
+            "\"-0042\""
+        );
+        assert_eq!(
+            show(
+                Primitive::TruncateString,
+                &[Value::Uint(2), string("héllo")]
+            ),
+            "\"hé\""
+        );
+        assert_eq!(
+            show(Primitive::DebugChar, &[Value::Char('\n')]),
+            r#""'\\n'""#
+        );
+        let result = Primitive::AlignLeft.evaluate(&[string("ab")]);
+        assert!(matches!(
+            result,
+            Err(RuntimeError::PrimitiveArity { found: 1, .. })
+        ));
+    }
+}
@@ -1,11 +1,17 @@
+use std::sync::Arc;
+
 use crate::common::*;
+use crate::primitives::Primitive;
 use crate::types::Type;
 
+/// A branch of a [`Term::Case`].
+/// The `arguments` of the constructor are in scope in the `value`,
+/// the last argument is the variable 0.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CaseAlternative {
-    constructor: u64,
-    arguments: u64,
-    value: Term,
+    pub constructor: u64,
+    pub arguments: u64,
+    pub value: Term,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -14,19 +20,36 @@ pub enum Term {
     Uint(u64),
     Int(i64),
     String(String),
+    Char(char),
+    /// De Bruijn index, 0 is the variable of the closest binder.
     Variable(u64, Type),
     NamedVariable(Identifier),
     GlobalVariable(Identifier, Type),
-    Function(Box<Term>, Type),
+    /// The argument of the function is the variable 0 in the body.
+    /// The closures share the body instead of copying it.
+    Function(Arc<Term>, Type),
     Application(Box<Term>, Box<Term>, Type),
+    /// Type abstraction, the type variables have their own
+    /// De Bruijn indexes, they don't shift the term variables.
+    TypeFunction(Box<Term>, Type),
+    TypeApplication(Box<Term>, Type, Type),
+    /// A fully applied constructor, the name is only informative,
+    /// the index is the position of the constructor in the
+    /// [`Type::Sum`].
+    Constructor(Identifier, u64, Vec<Term>, Type),
     Record(Record<Term>, Type),
     Tuple(Vec<Term>, Type),
+    /// The item of a tuple, the first item has index 0.
+    TupleProjection(Box<Term>, u64, Type),
+    RecordProjection(Box<Term>, Label, Type),
     Case(Box<Term>, Vec<CaseAlternative>, Type),
+    /// A fully applied [`Primitive`], the type is the one of the result.
+    Primitive(Primitive, Vec<Term>, Type),
 }
 
-trait ContextInterface<contextType, valuesType> {
-    fn find(&self, name: &str) -> &Vec<valuesType>;
-    fn update(&mut self, name: &str, value: valuesType) -> &mut contextType;
+trait ContextInterface<ContextType, ValuesType> {
+    fn find(&self, name: &str) -> &Vec<ValuesType>;
+    fn update(&mut self, name: &str, value: ValuesType) -> &mut ContextType;
 }
 
 pub struct LocalContext();
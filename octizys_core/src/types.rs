@@ -1,6 +1,8 @@
-use std::fmt::format;
+use std::fmt::Display;
 
-use crate::common::{Identifier, NonEmptyVec, Record, Variable};
+use crate::common::{
+    unsafe_make_record, Identifier, NonEmptyVec, Record, Variable,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Type {
@@ -8,6 +10,7 @@ pub enum Type {
     Uint,
     Int,
     String,
+    Char,
     Variable(Variable),
     // forall a b c . a -> (a-> b) -> c
     // Forall(3, Arrow(2,Arrow(Arrow(2,1), 0)))
@@ -21,171 +24,545 @@ pub enum Type {
     Recursive(u64, Box<Type>),
     Application(Box<Type>, Box<Type>),
     Tuple(Vec<Type>),
-    //The constructor name is the index of the type in the sum
+    //The constructor name is the index of the type in the sum,
+    //every item is the tuple of the arguments of the constructor.
     //data w a b c = S1 | S2 a | S3 b a c
-    //NewType(Identifier(w),Forall(3,Sum([(Sum([])),[2],[1,2,0]])))
+    //Forall(3,Sum(w,[Tuple([]),Tuple([2]),Tuple([1,2,0])]))
     Sum(Identifier, Vec<Type>),
     NewType(Identifier, Box<Type>),
     Alias(Identifier, Box<Type>),
 }
 
-/*
 impl Type {
-    fn shift(t:Self,amount:u64, nest_level:u64)->Self{
-        match t {
-            Type::Uint=> t,
-            Type::String=>t,
-            Type::Variable(x)=> if x >= nest_level {Type::Variable(x+amount)} else {t},
-            Type::Forall(new_levels,t2) => shift(),
-            Type::Arrow(Box<Type>, Box<Type>),
-            Type::Record(Record<Type>),
-            Type::RecursiveType(Box<Type>),
-            Type::Application(Box<Type>, Box<Type>),
-            Type::Tuple(Vec<Type>),
-            Type::Sum(u64, Vec<Vec<Type>>),
-        }
+    fn is_atomic(&self) -> bool {
+        !matches!(
+            self,
+            Type::Forall(_, _)
+                | Type::Recursive(_, _)
+                | Type::Arrow(_, _)
+                | Type::Application(_, _)
+        )
     }
 
-
-    fn substitute_type_var(
-        type1: Type,
-        variable_number: u64,
-        type2: Type,
-    ) -> Type {
-        match type1 {
-            Type::Uint => type1,
-            Type::String => type1,
-            Type::Variable(x) => {
-                if x == variable_number {
-                    type2
+    /// The bound variables are named by the number of binders
+    /// around their binder, `t0` is the outermost.
+    fn fmt_at(
+        &self,
+        depth: u32,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        let atom = |t: &Type, f: &mut std::fmt::Formatter<'_>| {
+            if t.is_atomic() {
+                t.fmt_at(depth, f)
+            } else {
+                write!(f, "(")?;
+                t.fmt_at(depth, f)?;
+                write!(f, ")")
+            }
+        };
+        let binders = |keyword: &str,
+                       n: u64,
+                       t: &Type,
+                       f: &mut std::fmt::Formatter<'_>| {
+            write!(f, "{}", keyword)?;
+            for k in 0..n as u32 {
+                write!(f, " t{}", depth + k)?;
+            }
+            write!(f, " . ")?;
+            t.fmt_at(depth + n as u32, f)
+        };
+        let list = |items: &[Type], f: &mut std::fmt::Formatter<'_>| {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                item.fmt_at(depth, f)?;
+            }
+            Ok(())
+        };
+        match self {
+            Type::Unit => write!(f, "()"),
+            Type::Uint => write!(f, "Uint"),
+            Type::Int => write!(f, "Int"),
+            Type::String => write!(f, "String"),
+            Type::Char => write!(f, "Char"),
+            Type::Variable(Variable::Named(name)) => write!(f, "{}", name),
+            Type::Variable(Variable::Free(index)) => {
+                if *index < depth {
+                    write!(f, "t{}", depth - 1 - index)
                 } else {
-                    type1
+                    write!(f, "#{}", index - depth)
                 }
             }
-            Type::Forall(t1) => {
-                Type::substitute_type_var(*t1, variable_number + 1, type2)
+            Type::Forall(n, t) => binders("forall", *n, t, f),
+            Type::Recursive(n, t) => binders("rec", *n, t, f),
+            Type::Arrow(t1, t2) => {
+                atom(t1, f)?;
+                write!(f, " -> ")?;
+                t2.fmt_at(depth, f)
             }
-            Type::Arrow(t1, t2) => Type::Arrow(
-                Box::from(Type::substitute_type_var(
-                    *t1,
-                    variable_number,
-                    type2.clone(),
-                )),
-                Box::from(Type::substitute_type_var(
-                    *t2,
-                    variable_number,
-                    type2,
-                )),
+            Type::Application(t1, t2) => {
+                match **t1 {
+                    Type::Application(_, _) => t1.fmt_at(depth, f)?,
+                    _ => atom(t1, f)?,
+                }
+                write!(f, " ")?;
+                atom(t2, f)
+            }
+            Type::Record(record) => {
+                write!(f, "{{")?;
+                for (i, (label, t)) in record.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} : ", label.as_str())?;
+                    t.fmt_at(depth, f)?;
+                }
+                write!(f, "}}")
+            }
+            Type::Tuple(items) => {
+                write!(f, "(")?;
+                list(items, f)?;
+                write!(f, ")")
+            }
+            Type::Sum(name, items) => {
+                write!(f, "{}[", name)?;
+                list(items, f)?;
+                write!(f, "]")
+            }
+            Type::NewType(name, t) | Type::Alias(name, t) => {
+                write!(f, "{}<", name)?;
+                t.fmt_at(depth, f)?;
+                write!(f, ">")
+            }
+        }
+    }
+
+    /// Rebuilds the type replacing every [`Variable::Free`], the
+    /// function gets the index and the number of binders between the
+    /// variable and the start of the traversal.
+    fn map_free(&self, depth: u32, f: &impl Fn(u32, u32) -> Type) -> Type {
+        let map = |t: &Type| Box::new(t.map_free(depth, f));
+        match self {
+            Type::Unit | Type::Uint | Type::Int | Type::String | Type::Char => {
+                self.clone()
+            }
+            Type::Variable(Variable::Free(index)) => f(*index, depth),
+            Type::Variable(Variable::Named(_)) => self.clone(),
+            Type::Forall(binders, t) => Type::Forall(
+                *binders,
+                Box::new(t.map_free(depth + *binders as u32, f)),
             ),
-            Type::Record(Record(v)) => Type::Record(unsafe_make_record(
-                v.iter()
-                    .map(|(label, t)| {
-                        (
-                            (*label).clone(),
-                            Type::substitute_type_var(
-                                (*t).clone(),
-                                variable_number,
-                                type2.clone(),
-                            ),
-                        )
-                    })
-                    .collect(),
-            )),
-            Type::RecursiveType(t1) => Type::RecursiveType(Box::from(
-                Type::substitute_type_var(*t1, variable_number + 1, type2),
-            )),
-            Type::Application(t1, t2) => Type::Application(
-                Box::from(Type::substitute_type_var(
-                    *t1,
-                    variable_number,
-                    type2.clone(),
-                )),
-                Box::from(Type::substitute_type_var(
-                    *t2,
-                    variable_number,
-                    type2,
-                )),
+            Type::Recursive(binders, t) => Type::Recursive(
+                *binders,
+                Box::new(t.map_free(depth + *binders as u32, f)),
             ),
-            Type::Tuple(v) => Type::Tuple(
-                v.iter()
-                    .map(|t| {
-                        Type::substitute_type_var(
-                            (*t).clone(),
-                            variable_number,
-                            type2.clone(),
-                        )
-                    })
+            Type::Arrow(t1, t2) => Type::Arrow(map(t1), map(t2)),
+            Type::Application(t1, t2) => Type::Application(map(t1), map(t2)),
+            Type::Record(record) => Type::Record(unsafe_make_record(
+                record
+                    .iter()
+                    .map(|(label, t)| (label.clone(), t.map_free(depth, f)))
                     .collect(),
+            )),
+            Type::Tuple(items) => Type::Tuple(
+                items.iter().map(|t| t.map_free(depth, f)).collect(),
             ),
-            Type::Sum(binded_vars, v1) => Type::Sum(
-                binded_vars,
-                v1.iter()
-                    .map(|v2| {
-                        v2.iter()
-                            .map(|t| {
-                                Type::substitute_type_var(
-                                    (*t).clone(),
-                                    variable_number + binded_vars,
-                                    type2.clone(),
-                                )
-                            })
-                            .collect()
-                    })
-                    .collect(),
+            Type::Sum(name, items) => Type::Sum(
+                name.clone(),
+                items.iter().map(|t| t.map_free(depth, f)).collect(),
             ),
+            Type::NewType(name, t) => Type::NewType(name.clone(), map(t)),
+            Type::Alias(name, t) => Type::Alias(name.clone(), map(t)),
         }
     }
 
-    fn reduction(type_: Type) -> Type {
-        match type_ {
-            Type::Uint => type_,
-            Type::String => type_,
-            Type::Variable(_) => type_,
-            Type::Forall(t1) => Type::Forall(Box::from(Type::reduction(*t1))),
-            Type::Arrow(t1, t2) => Type::Arrow(
-                Box::from(Type::reduction(*t1)),
-                Box::from(Type::reduction(*t2)),
-            ),
-            Type::Record(Record(v)) => Type::Record(unsafe_make_record(
-                v.iter()
-                    .map(|(label, t)| {
-                        ((*label).clone(), Type::reduction((*t).clone()))
-                    })
+    /// Adds `amount` to the indexes of the free variables that are
+    /// at least `cutoff`, the bound variables remain the same. It only
+    /// moves types under more binders, so the indexes never become
+    /// negative.
+    pub(crate) fn shift(&self, amount: u32, cutoff: u32) -> Type {
+        self.map_free(0, &|index, depth| {
+            if index >= cutoff + depth {
+                Type::Variable(Variable::Free(index + amount))
+            } else {
+                Type::Variable(Variable::Free(index))
+            }
+        })
+    }
+
+    /// Replaces the free variable `index` by `replacement`, both
+    /// types are in the same context.
+    pub fn substitute(&self, index: u32, replacement: &Type) -> Type {
+        self.map_free(0, &|i, depth| {
+            if i == index + depth {
+                replacement.shift(depth, 0)
+            } else {
+                Type::Variable(Variable::Free(i))
+            }
+        })
+    }
+
+    /// Removes the binder of the variable `index` replacing the
+    /// variable by `replacement`. The `replacement` is in the context
+    /// outside of the binders of the variables `0..=index`, the free
+    /// variables after `index` go down by one.
+    pub fn instantiate(&self, index: u32, replacement: &Type) -> Type {
+        self.map_free(0, &|i, depth| {
+            if i == index + depth {
+                replacement.shift(index + depth, 0)
+            } else if i > index + depth {
+                Type::Variable(Variable::Free(i - 1))
+            } else {
+                Type::Variable(Variable::Free(i))
+            }
+        })
+    }
+
+    /// The result of applying `Forall(binders, body)` to a argument,
+    /// the argument replaces the first variable of the forall.
+    pub fn apply_forall(binders: u64, body: &Type, argument: &Type) -> Type {
+        if binders == 0 {
+            return Type::Application(
+                Box::new(body.clone()),
+                Box::new(argument.clone()),
+            );
+        }
+        let result = body.instantiate(binders as u32 - 1, argument);
+        if binders == 1 {
+            result
+        } else {
+            Type::Forall(binders - 1, Box::new(result))
+        }
+    }
+
+    /// Replaces the variables bound by a [`Type::Recursive`] with the
+    /// type itself, any other type remains the same.
+    pub fn unfold(&self) -> Type {
+        match self {
+            Type::Recursive(binders, body) => {
+                let mut out = (**body).clone();
+                for index in (0..*binders as u32).rev() {
+                    out = out.instantiate(index, self);
+                }
+                out
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Removes the binders without variables and joins the nested
+    /// [`Type::Forall`]s, this doesn't change the meaning of the type.
+    fn canonical(&self) -> Type {
+        match self {
+            Type::Forall(0, t) | Type::Recursive(0, t) => t.canonical(),
+            Type::Forall(binders, t) => match t.canonical() {
+                Type::Forall(inner, t) => Type::Forall(binders + inner, t),
+                t => Type::Forall(*binders, Box::new(t)),
+            },
+            Type::Recursive(binders, t) => {
+                Type::Recursive(*binders, Box::new(t.canonical()))
+            }
+            _ => self.map_children(&Type::canonical),
+        }
+    }
+
+    /// Applies the function to the types directly inside of the
+    /// type, the binders must be handled by the caller.
+    fn map_children(&self, f: &impl Fn(&Type) -> Type) -> Type {
+        match self {
+            Type::Unit
+            | Type::Uint
+            | Type::Int
+            | Type::String
+            | Type::Char
+            | Type::Variable(_) => self.clone(),
+            Type::Forall(binders, t) => Type::Forall(*binders, Box::new(f(t))),
+            Type::Recursive(binders, t) => {
+                Type::Recursive(*binders, Box::new(f(t)))
+            }
+            Type::Arrow(t1, t2) => {
+                Type::Arrow(Box::new(f(t1)), Box::new(f(t2)))
+            }
+            Type::Application(t1, t2) => {
+                Type::Application(Box::new(f(t1)), Box::new(f(t2)))
+            }
+            Type::Record(record) => Type::Record(unsafe_make_record(
+                record
+                    .iter()
+                    .map(|(label, t)| (label.clone(), f(t)))
                     .collect(),
             )),
-            Type::RecursiveType(t) => {
-                Type::RecursiveType(Box::from(Type::reduction(*t)))
-            }
-            Type::Application(t1, t2) => match *t1 {
-                Type::Forall(t3) => Type::substitute_type_var(*t3, 0, *t2),
-                _ => Type::Application(
-                    Box::from(Type::reduction(*t1)),
-                    Box::from(Type::reduction(*t2)),
-                ),
+            Type::Tuple(items) => Type::Tuple(items.iter().map(f).collect()),
+            Type::Sum(name, items) => {
+                Type::Sum(name.clone(), items.iter().map(f).collect())
+            }
+            Type::NewType(name, t) => {
+                Type::NewType(name.clone(), Box::new(f(t)))
+            }
+            Type::Alias(name, t) => Type::Alias(name.clone(), Box::new(f(t))),
+        }
+    }
+
+    /// Expands the aliases and reduces the applications of
+    /// [`Type::Forall`], the result is also in the form of
+    /// [`Type::canonical`]. [`Type::NewType`] and [`Type::Recursive`]
+    /// aren't unfolded.
+    /// The types must be well kinded, otherwise this may not end.
+    pub fn normalize(&self) -> Type {
+        match self {
+            Type::Alias(_, t) => t.normalize(),
+            Type::Forall(0, t) | Type::Recursive(0, t) => t.normalize(),
+            Type::Forall(binders, t) => match t.normalize() {
+                Type::Forall(inner, t) => Type::Forall(binders + inner, t),
+                t => Type::Forall(*binders, Box::new(t)),
             },
-            Type::Tuple(v) => Type::Tuple(
-                v.iter().map(|t| Type::reduction((*t).clone())).collect(),
-            ),
-            Type::Sum(binded_vars, v1) => Type::Sum(
-                binded_vars,
-                v1.iter()
-                    .map(|v2| {
-                        v2.iter()
-                            .map(|t| Type::reduction((*t).clone()))
-                            .collect()
-                    })
-                    .collect(),
-            ),
+            Type::Application(head, argument) => {
+                let argument = argument.normalize();
+                match head.normalize() {
+                    Type::Forall(binders, body) => {
+                        Type::apply_forall(binders, &body, &argument)
+                            .normalize()
+                    }
+                    head => {
+                        Type::Application(Box::new(head), Box::new(argument))
+                    }
+                }
+            }
+            _ => self.map_children(&Type::normalize),
         }
     }
-    fn unify(type1: Type, type2: Type) -> Result<(), String> {
-        let simplified1 = Type::reduction(type1.clone());
-        let simplified2 = Type::reduction(type2.clone());
-        if simplified1 == simplified2 {
-            Ok(())
-        } else {
-            Err(format!("Can't unify types {:?} and {:?}", type1, type2))
+
+    /// Tells if the types are the same up to the names of the bound
+    /// variables, the De Bruijn indexes make this almost a syntactic
+    /// equality.
+    pub fn alpha_equivalent(&self, other: &Type) -> bool {
+        self.canonical() == other.canonical()
+    }
+
+    /// Tells if the types are the same after the expansion of the
+    /// aliases and the reduction of the applications.
+    pub fn equivalent(&self, other: &Type) -> bool {
+        self.normalize() == other.normalize()
+    }
+}
+
+/// The free variables are shown as `#index`.
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_at(0, f)
+    }
+}
+
+#[cfg(test)]
+mod types_test {
+    use super::Type;
+    use crate::common::{Identifier, Label, Record, Variable};
+    use proptest::prelude::*;
+
+    fn free(index: u32) -> Type {
+        Type::Variable(Variable::Free(index))
+    }
+
+    fn named(name: &str) -> Type {
+        Type::Variable(Variable::Named(
+            Identifier::make(String::from(name)).unwrap(),
+        ))
+    }
+
+    fn arrow(t1: Type, t2: Type) -> Type {
+        Type::Arrow(Box::new(t1), Box::new(t2))
+    }
+
+    fn application(t1: Type, t2: Type) -> Type {
+        Type::Application(Box::new(t1), Box::new(t2))
+    }
+
+    fn identifier(name: &str) -> Identifier {
+        Identifier::make(String::from(name)).unwrap()
+    }
+
+    /// The inverse of [`Type::shift`], the indexes must be at least
+    /// `amount`.
+    fn unshift(t: &Type, amount: u32, cutoff: u32) -> Type {
+        t.map_free(0, &|index, depth| {
+            if index >= cutoff + depth {
+                Type::Variable(Variable::Free(index - amount))
+            } else {
+                Type::Variable(Variable::Free(index))
+            }
+        })
+    }
+
+    /// Types where the head of a application is never a variable,
+    /// this way the normalization always ends.
+    fn arbitrary_type() -> impl Strategy<Value = Type> {
+        let leaf = prop_oneof![
+            Just(Type::Unit),
+            Just(Type::Uint),
+            Just(Type::String),
+            (0..6u32).prop_map(free),
+            Just(named("T")),
+        ];
+        leaf.prop_recursive(4, 48, 3, |inner| {
+            let head = prop_oneof![
+                Just(named("F")),
+                (1..3u64, inner.clone())
+                    .prop_map(|(n, t)| Type::Forall(n, Box::new(t))),
+                (1..3u64, inner.clone()).prop_map(|(n, t)| Type::Alias(
+                    identifier("A"),
+                    Box::new(Type::Forall(n, Box::new(t)))
+                )),
+            ];
+            prop_oneof![
+                (0..3u64, inner.clone())
+                    .prop_map(|(n, t)| Type::Forall(n, Box::new(t))),
+                (0..2u64, inner.clone())
+                    .prop_map(|(n, t)| Type::Recursive(n, Box::new(t))),
+                (inner.clone(), inner.clone())
+                    .prop_map(|(t1, t2)| arrow(t1, t2)),
+                (head, inner.clone()).prop_map(|(t1, t2)| application(t1, t2)),
+                prop::collection::vec(inner.clone(), 0..3)
+                    .prop_map(Type::Tuple),
+                prop::collection::vec(inner.clone(), 0..3)
+                    .prop_map(|items| Type::Sum(identifier("S"), items)),
+                (inner.clone(), inner.clone()).prop_map(|(t1, t2)| {
+                    Type::Record(
+                        Record::make(vec![
+                            (Label::make(String::from("a")).unwrap(), t1),
+                            (Label::make(String::from("b")).unwrap(), t2),
+                        ])
+                        .unwrap(),
+                    )
+                }),
+                inner
+                    .clone()
+                    .prop_map(|t| Type::Alias(identifier("A"), Box::new(t))),
+                inner.prop_map(|t| Type::NewType(identifier("N"), Box::new(t))),
+            ]
+        })
+    }
+
+    #[test]
+    fn shift_skips_bound_variables() {
+        // forall a . a -> b
+        let t = Type::Forall(1, Box::new(arrow(free(0), free(1))));
+        let expected = Type::Forall(1, Box::new(arrow(free(0), free(3))));
+        assert_eq!(t.shift(2, 0), expected);
+    }
+
+    #[test]
+    fn substitute_avoids_capture() {
+        // (forall a . a -> b)[b := a]
+        let t = Type::Forall(1, Box::new(arrow(free(0), free(1))));
+        let expected = Type::Forall(1, Box::new(arrow(free(0), free(1))));
+        assert_eq!(t.substitute(0, &free(0)), expected);
+    }
+
+    #[test]
+    fn apply_forall_instantiates_the_first_variable() {
+        // (forall a b . a -> b) Uint = forall b . Uint -> b
+        let t = application(
+            Type::Forall(2, Box::new(arrow(free(1), free(0)))),
+            Type::Uint,
+        );
+        let expected = Type::Forall(1, Box::new(arrow(Type::Uint, free(0))));
+        assert_eq!(t.normalize(), expected);
+    }
+
+    #[test]
+    fn normalize_expands_aliases() {
+        // alias Pair a = (a, a)
+        let pair = Type::Alias(
+            identifier("Pair"),
+            Box::new(Type::Forall(
+                1,
+                Box::new(Type::Tuple(vec![free(0), free(0)])),
+            )),
+        );
+        let t = application(pair, free(3));
+        assert_eq!(t.normalize(), Type::Tuple(vec![free(3), free(3)]));
+    }
+
+    #[test]
+    fn unfold_recursive() {
+        // data List = (Uint, List)
+        let list = Type::Recursive(
+            1,
+            Box::new(Type::Tuple(vec![Type::Uint, free(0)])),
+        );
+        assert_eq!(list.unfold(), Type::Tuple(vec![Type::Uint, list.clone()]));
+    }
+
+    #[test]
+    fn nested_foralls_are_alpha_equivalent() {
+        let t1 = Type::Forall(
+            1,
+            Box::new(Type::Forall(1, Box::new(arrow(free(1), free(0))))),
+        );
+        let t2 = Type::Forall(2, Box::new(arrow(free(1), free(0))));
+        assert!(t1.alpha_equivalent(&t2));
+        assert!(!t1.alpha_equivalent(&Type::Forall(
+            2,
+            Box::new(arrow(free(0), free(1)))
+        )));
+    }
+
+    proptest! {
+        #[test]
+        fn shift_by_zero_is_identity(t in arbitrary_type(), cutoff in 0..4u32) {
+            prop_assert_eq!(t.shift(0, cutoff), t);
+        }
+
+        #[test]
+        fn shift_is_invertible(
+            t in arbitrary_type(),
+            amount in 0..4u32,
+            cutoff in 0..4u32,
+        ) {
+            prop_assert_eq!(unshift(&t.shift(amount, cutoff), amount, cutoff), t);
+        }
+
+        #[test]
+        fn instantiate_of_unused_variable(
+            t in arbitrary_type(),
+            replacement in arbitrary_type(),
+        ) {
+            prop_assert_eq!(t.shift(1, 0).instantiate(0, &replacement), t);
+        }
+
+        #[test]
+        fn substitute_of_unused_variable(
+            t in arbitrary_type(),
+            replacement in arbitrary_type(),
+        ) {
+            prop_assert_eq!(t.shift(1, 0).substitute(0, &replacement), t.shift(1, 0));
+        }
+
+        #[test]
+        fn normalize_is_idempotent(t in arbitrary_type()) {
+            let normal = t.normalize();
+            prop_assert_eq!(normal.normalize(), normal);
+        }
+
+        #[test]
+        fn normalize_commutes_with_shift(
+            t in arbitrary_type(),
+            amount in 0..4u32,
+            cutoff in 0..4u32,
+        ) {
+            prop_assert_eq!(
+                t.shift(amount, cutoff).normalize(),
+                t.normalize().shift(amount, cutoff)
+            );
+        }
+
+        #[test]
+        fn equivalence_is_reflexive(t in arbitrary_type()) {
+            let wrapped = Type::Forall(0, Box::new(t.clone()));
+            prop_assert!(t.alpha_equivalent(&wrapped));
+            prop_assert!(t.equivalent(&wrapped));
+            prop_assert!(t.equivalent(&t.normalize()));
         }
     }
 }
-*/
@@ -303,6 +303,16 @@ where
     pub _enclosure_phantom: PhantomData<Enclosure>,
 }
 
+impl<T, Enclosure> Between<T, Enclosure>
+where
+    Enclosure: Delimiters,
+{
+    /// The region from the opening delimiter to the closing one.
+    pub fn span(&self) -> Span {
+        self.left.span + self.right.span
+    }
+}
+
 /// A item on a list of items separated by some separator like `,` or `|`.
 /// This item contains the separation comma between itself and the
 /// previous item.
@@ -347,6 +357,16 @@ where
     pub trailing_sep: Option<TokenInfo>,
 }
 
+impl<T, SeparatorPhantom> TrailingList<T, SeparatorPhantom>
+where
+    SeparatorPhantom: Separator,
+{
+    /// Iterates over the items without the separators.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        std::iter::once(&self.first).chain(self.items.iter().map(|x| &x.item))
+    }
+}
+
 impl<T, ToInfo, SeparatorPhantom> From<(T, Vec<(ToInfo, T)>, Option<ToInfo>)>
     for TrailingList<T, SeparatorPhantom>
 where
@@ -70,29 +70,15 @@ pub enum CommentKind {
 /// finish with the same amount of `-` followed
 /// by a `}`.
 ///
-/// <div class="warning">
-///
-/// This means that a comment like:
+/// Block comments can be nested at any depth, every `{-` inside
+/// a comment opens a nested comment that must be closed with the
+/// same amount of `-` before the outer comment ends:
 ///
 /// ```txt
-/// {-- some comment {--- inner comment ---} remain --}
+/// {-- some comment {--- inner {- innermost -} comment ---} remain --}
 /// ```
 ///
-/// Would be processed as
-///
-/// ```txt
-/// {-- some comment {--- inner comment ---}
-/// ```
-///
-/// Causing a syntax error at `--}`.
-/// To nest comments is recommended to begin using a singe hyphen
-/// and continue incrementing them as needed.
-///
-/// </div>
-///
-/// We acknowledge the need for nested block comments but at
-/// the same time we believe that a finite amount of them
-/// is enough for most uses if not all of them.
+/// The nested comments are part of the content of the outer one.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Equivalence)]
 pub enum CommentBraceKind {
     // "{- asdf -}"
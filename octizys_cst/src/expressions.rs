@@ -3,14 +3,16 @@ use crate::base::{
     TokenInfo, TrailingList,
 };
 use crate::literals::{
-    InterpolationString, StringLiteral, UFloatingPointLiteral, UintLiteral,
+    CharLiteral, InterpolationString, StringLiteral, UFloatingPointLiteral,
+    UintLiteral,
 };
 use crate::patterns::PatternMatch;
 use crate::types::Type;
 use octizys_common::identifier::Identifier;
+use octizys_common::span::Span;
 use octizys_macros::Equivalence;
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct LetBinding {
     pub pattern: PatternMatch,
     #[equivalence(ignore)]
@@ -20,7 +22,7 @@ pub struct LetBinding {
     pub semicolon: TokenInfo,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct Let {
     #[equivalence(ignore)]
     pub let_: TokenInfo,
@@ -30,7 +32,7 @@ pub struct Let {
     pub expression: Box<Expression>,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct CaseItem {
     pub pattern: PatternMatch,
     #[equivalence(ignore)]
@@ -38,7 +40,7 @@ pub struct CaseItem {
     pub expression: Box<Expression>,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct Case {
     #[equivalence(ignore)]
     pub case: TokenInfo,
@@ -48,26 +50,30 @@ pub struct Case {
     pub cases: Between<TrailingList<CaseItem, Comma>, Braces>,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct BinaryOperator {
     pub left: Box<Expression>,
     pub right: Box<Expression>,
     pub name: Token<OperatorName>,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct LambdaExpression {
+    #[equivalence(ignore)]
+    pub start: TokenInfo,
     pub variable: Token<Identifier>,
+    #[equivalence(ignore)]
+    pub arrow: TokenInfo,
     pub expression: Box<Expression>,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct ApplicationExpression {
     pub start: Box<Expression>,
     pub remain: Vec<Expression>,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub enum ExpressionRecordItem {
     SingleVariable {
         variable: Token<Identifier>,
@@ -80,18 +86,28 @@ pub enum ExpressionRecordItem {
     },
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct ExpressionSelector {
     pub expression: Box<Expression>,
     pub accessor: Token<Identifier>,
 }
 
-#[derive(Debug, Equivalence)]
+// The grammar builds the variants by value, boxing the big ones
+// would change all of its rules.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub enum Expression {
     String(Token<StringLiteral>),
     InterpolationString(Token<InterpolationString>),
     Uint(Token<UintLiteral>),
     UFloat(Token<UFloatingPointLiteral>),
+    Char(Token<CharLiteral>),
+    /// The prefix `-`, applied to a literal it makes a negative literal.
+    Negation {
+        #[equivalence(ignore)]
+        minus: TokenInfo,
+        expression: Box<Expression>,
+    },
     LocalVariable(Token<Identifier>),
     ImportedVariable(Token<ImportedVariable>),
     NamedHole(Token<u64>),
@@ -135,12 +151,49 @@ impl Expression {
         }
     }
 
+    /// The region of the source that the expression covers.
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::String(t) => t.info.span,
+            Expression::InterpolationString(t) => t.info.span,
+            Expression::Uint(t) => t.info.span,
+            Expression::UFloat(t) => t.info.span,
+            Expression::Char(t) => t.info.span,
+            Expression::Negation { minus, expression } => {
+                minus.span + expression.span()
+            }
+            Expression::LocalVariable(t) => t.info.span,
+            Expression::ImportedVariable(t) => t.info.span,
+            Expression::NamedHole(t) => t.info.span,
+            Expression::Tuple(t) => t.span(),
+            Expression::Record(r) => r.span(),
+            Expression::Case(c) => c.case.span + c.cases.span(),
+            Expression::Parens(p) => p.span(),
+            Expression::Selector(s) => {
+                s.expression.span() + s.accessor.info.span
+            }
+            Expression::Interrogation { expression, symbol } => {
+                expression.span() + symbol.span
+            }
+            Expression::TypeArgument { at, type_ } => at.span + type_.span(),
+            Expression::Let(l) => l.let_.span + l.expression.span(),
+            Expression::BinaryOperator(b) => b.left.span() + b.right.span(),
+            Expression::Lambda(l) => l.start.span + l.expression.span(),
+            Expression::Application(a) => match a.remain.last() {
+                Some(last) => a.start.span() + last.span(),
+                None => a.start.span(),
+            },
+        }
+    }
+
     pub fn need_parens(&self) -> bool {
         match self {
             Expression::String(_) => false,
             Expression::InterpolationString(_) => false,
             Expression::Uint(_) => false,
             Expression::UFloat(_) => false,
+            Expression::Char(_) => false,
+            Expression::Negation { .. } => true,
             Expression::LocalVariable(_) => false,
             Expression::ImportedVariable(_) => false,
             Expression::NamedHole(_) => false,
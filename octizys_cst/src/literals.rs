@@ -1,4 +1,5 @@
-use octizys_common::{identifier::Identifier, span::Span};
+use crate::expressions::Expression;
+use octizys_common::span::Span;
 use octizys_macros::Equivalence;
 
 #[derive(
@@ -20,12 +21,58 @@ pub enum StringKind {
     Raw3, //r####
     #[default]
     Normal, //"
+    /// A `"""` block, the value doesn't have the first and last lines
+    /// if they are blank, neither the common indentation of the lines.
+    TextBlock,
+}
+
+impl StringKind {
+    /// The amount of `#` around a raw string.
+    pub fn hashes(&self) -> Option<usize> {
+        match self {
+            StringKind::Raw0 => Some(1),
+            StringKind::Raw1 => Some(2),
+            StringKind::Raw2 => Some(3),
+            StringKind::Raw3 => Some(4),
+            StringKind::Normal | StringKind::TextBlock => None,
+        }
+    }
+
+    pub fn start(&self) -> String {
+        match (self, self.hashes()) {
+            (StringKind::TextBlock, _) => String::from("\"\"\""),
+            (_, Some(hashes)) => format!("r{}\"", "#".repeat(hashes)),
+            (_, None) => String::from("\""),
+        }
+    }
+
+    pub fn end(&self) -> String {
+        match (self, self.hashes()) {
+            (StringKind::TextBlock, _) => String::from("\"\"\""),
+            (_, Some(hashes)) => format!("\"{}", "#".repeat(hashes)),
+            (_, None) => String::from("\""),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Equivalence)]
 pub enum StringComponent {
     RegularString(String),
-    Scaped(String),
+    /// A escape sequence like `\n`, the spelling includes the `\`.
+    Scaped {
+        spelling: String,
+        value: char,
+    },
+}
+
+impl StringComponent {
+    /// The text of the component as it was written.
+    pub fn spelling(&self) -> &str {
+        match self {
+            StringComponent::RegularString(s) => s,
+            StringComponent::Scaped { spelling, .. } => spelling,
+        }
+    }
 }
 
 impl Default for StringComponent {
@@ -40,31 +87,221 @@ pub struct StringLiteral {
     pub value: Vec<StringComponent>,
 }
 
+impl StringLiteral {
+    /// The value of the string, with the escapes replaced by their
+    /// characters.
+    pub fn decoded(&self) -> String {
+        let mut out = String::new();
+        for component in &self.value {
+            match component {
+                StringComponent::RegularString(s) => out.push_str(s),
+                StringComponent::Scaped { value, .. } => out.push(*value),
+            }
+        }
+        out
+    }
+
+    /// The lines of a text block as they must be written, without
+    /// the common indentation.
+    pub fn text_block_lines(&self) -> Vec<String> {
+        let content: String = self.value.iter().map(|c| c.spelling()).collect();
+        content.split('\n').map(String::from).collect()
+    }
+}
+
+/// The literal as it was written in the source, the text blocks are
+/// written without indentation.
+impl std::fmt::Display for StringLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind.start())?;
+        if self.kind == StringKind::TextBlock {
+            for line in self.text_block_lines() {
+                write!(f, "\n{}", line)?;
+            }
+            return write!(f, "\n{}", self.kind.end());
+        }
+        for component in &self.value {
+            write!(f, "{}", component.spelling())?;
+        }
+        write!(f, "{}", self.kind.end())
+    }
+}
+
 #[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Equivalence,
+)]
+pub enum FormatAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl FormatAlign {
+    pub fn symbol(&self) -> char {
+        match self {
+            FormatAlign::Left => '<',
+            FormatAlign::Center => '^',
+            FormatAlign::Right => '>',
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '<' => Some(FormatAlign::Left),
+            '^' => Some(FormatAlign::Center),
+            '>' => Some(FormatAlign::Right),
+            _ => None,
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Equivalence,
+)]
+pub enum FormatKind {
+    /// `x`
+    LowerHex,
+    /// `X`
+    UpperHex,
+    /// `b`
+    Binary,
+    /// `o`
+    Octal,
+    /// `?`
     Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Default,
-    Equivalence,
+}
+
+impl FormatKind {
+    pub fn symbol(&self) -> char {
+        match self {
+            FormatKind::LowerHex => 'x',
+            FormatKind::UpperHex => 'X',
+            FormatKind::Binary => 'b',
+            FormatKind::Octal => 'o',
+            FormatKind::Debug => '?',
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            'x' => Some(FormatKind::LowerHex),
+            'X' => Some(FormatKind::UpperHex),
+            'b' => Some(FormatKind::Binary),
+            'o' => Some(FormatKind::Octal),
+            '?' => Some(FormatKind::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// The format specifier of a interpolation hole, the part after the
+/// `:` in `{value:>8.2x}`. It follows the shape
+/// `[[fill]align][0][width][.precision][kind]`.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Equivalence,
 )]
-pub struct InterpolationOptions {
-    pub is_debug: bool,
+pub struct FormatSpec {
+    /// Only present if there is a alignment.
+    pub fill: Option<char>,
+    pub align: Option<FormatAlign>,
+    /// Pad numbers with zeros instead of the fill.
+    pub zero: bool,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+    pub kind: Option<FormatKind>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Equivalence)]
+impl FormatSpec {
+    /// Parses the text after the `:` of a hole, `None` if it doesn't
+    /// follow the shape of a specifier.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut spec = FormatSpec::default();
+        let mut rest = text;
+        let mut chars = rest.chars();
+        let first = chars.next();
+        let second = chars.next();
+        if let (Some(fill), Some(align)) =
+            (first, second.and_then(FormatAlign::from_symbol))
+        {
+            spec.fill = Some(fill);
+            spec.align = Some(align);
+            rest = &rest[fill.len_utf8() + 1..];
+        } else if let Some(align) = first.and_then(FormatAlign::from_symbol) {
+            spec.align = Some(align);
+            rest = &rest[1..];
+        }
+        if let Some(after_zero) = rest.strip_prefix('0') {
+            spec.zero = true;
+            rest = after_zero;
+        }
+        let (width, after_width) = split_digits(rest);
+        spec.width = width;
+        rest = after_width;
+        if let Some(after_point) = rest.strip_prefix('.') {
+            let (precision, after_precision) = split_digits(after_point);
+            spec.precision = Some(precision?);
+            rest = after_precision;
+        }
+        let mut chars = rest.chars();
+        if let Some(kind) = chars.next() {
+            spec.kind = Some(FormatKind::from_symbol(kind)?);
+        }
+        if chars.next().is_some() {
+            return None;
+        }
+        Some(spec)
+    }
+}
+
+/// Splits the leading decimal digits of the text, `None` if there
+/// aren't any or they are too big.
+fn split_digits(text: &str) -> (Option<usize>, &str) {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    (text[..end].parse().ok(), &text[end..])
+}
+
+/// The specifier as it is written after the `:`.
+impl std::fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(fill) = self.fill {
+            write!(f, "{}", fill)?;
+        }
+        if let Some(align) = self.align {
+            write!(f, "{}", align.symbol())?;
+        }
+        if self.zero {
+            write!(f, "0")?;
+        }
+        if let Some(width) = self.width {
+            write!(f, "{}", width)?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{}", precision)?;
+        }
+        if let Some(kind) = self.kind {
+            write!(f, "{}", kind.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub enum InterpolationComponent {
     RegularString(String),
-    Scaped(char),
+    /// A escape sequence, including the braces `{{` and `}}`.
+    Scaped {
+        spelling: String,
+        value: char,
+    },
+    /// A hole like `{x + 1:>8}`.
     Interpolation {
-        variable: Identifier,
+        expression: Box<Expression>,
+        /// The span of the hole, including the braces.
         #[equivalence(ignore)]
         span: Span,
-        options: InterpolationOptions,
+        format: Option<FormatSpec>,
     },
 }
 
@@ -73,6 +310,23 @@ pub struct InterpolationString {
     pub value: Vec<InterpolationComponent>,
 }
 
+/// A character literal like `'a'` or `'\u{1F600}'`.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Equivalence,
+)]
+pub struct CharLiteral {
+    /// The text between the quotes as it was written.
+    pub spelling: String,
+    pub value: char,
+}
+
+/// The literal as it was written in the source.
+impl std::fmt::Display for CharLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}'", self.spelling)
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -85,35 +339,118 @@ pub struct InterpolationString {
     Default,
     Equivalence,
 )]
-pub enum UintKind {
-    Hex,
-    Octal,
+pub enum UintBase {
     Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hex,
+}
+
+impl UintBase {
+    pub fn radix(&self) -> u32 {
+        match self {
+            UintBase::Binary => 2,
+            UintBase::Octal => 8,
+            UintBase::Decimal => 10,
+            UintBase::Hex => 16,
+        }
+    }
+
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            UintBase::Binary => "0b",
+            UintBase::Octal => "0o",
+            UintBase::Decimal => "",
+            UintBase::Hex => "0x",
+        }
+    }
+}
+
+/// The type of a integer literal given by its suffix, like `255u8` or
+/// `5i8`. The literals are always written without sign, a negative
+/// literal is a `-` followed by a literal: `-128i8`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Equivalence,
+)]
+pub enum UintKind {
     U8,
     U16,
     U32,
     U64,
-    /// This means a regular literal (not hex or octal)
-    /// that has no suffix for the type
-    #[default]
-    Unspecified,
+    I8,
+    I16,
+    I32,
+    I64,
+}
+
+impl UintKind {
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            UintKind::U8 => "u8",
+            UintKind::U16 => "u16",
+            UintKind::U32 => "u32",
+            UintKind::U64 => "u64",
+            UintKind::I8 => "i8",
+            UintKind::I16 => "i16",
+            UintKind::I32 => "i32",
+            UintKind::I64 => "i64",
+        }
+    }
+
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix {
+            "u8" => Some(UintKind::U8),
+            "u16" => Some(UintKind::U16),
+            "u32" => Some(UintKind::U32),
+            "u64" => Some(UintKind::U64),
+            "i8" => Some(UintKind::I8),
+            "i16" => Some(UintKind::I16),
+            "i32" => Some(UintKind::I32),
+            "i64" => Some(UintKind::I64),
+            _ => None,
+        }
+    }
 }
 
+/// A integer literal like `0xff_ffu16`, it keeps the spelling of the
+/// source. The digits can represent numbers of any size, the range
+/// is checked against the type of the literal after the parsing.
 #[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Default,
-    Equivalence,
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Equivalence,
 )]
 pub struct UintLiteral {
-    pub kind: UintKind,
-    pub value: u64,
+    pub base: UintBase,
+    /// The digits without the prefix of the base, with their underscores.
+    pub digits: String,
+    /// Without a suffix the type is inferred.
+    pub kind: Option<UintKind>,
+}
+
+impl UintLiteral {
+    /// The value of the literal, `None` if it doesn't fit in a `u128`.
+    pub fn value(&self) -> Option<u128> {
+        let radix = self.base.radix();
+        self.digits
+            .chars()
+            .filter(|c| *c != '_')
+            .try_fold(0u128, |acc, c| {
+                let digit = c.to_digit(radix)?;
+                acc.checked_mul(u128::from(radix))?
+                    .checked_add(u128::from(digit))
+            })
+    }
+}
+
+/// The literal as it was written in the source.
+impl std::fmt::Display for UintLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.base.prefix(), self.digits)?;
+        if let Some(kind) = self.kind {
+            write!(f, "{}", kind.suffix())?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(
@@ -135,19 +472,265 @@ pub enum UFloatingPointKind {
 }
 
 #[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Default,
-    Equivalence,
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Equivalence,
+)]
+pub enum ExponentSign {
+    Plus,
+    Minus,
+}
+
+/// The exponent of a floating point literal as it was written.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Equivalence,
+)]
+pub struct FloatExponent {
+    /// The exponent was written with `E` instead of `e`.
+    pub uppercase: bool,
+    pub sign: Option<ExponentSign>,
+    /// The digits with their underscores.
+    pub digits: String,
+}
+
+/// A floating point literal like `1_000.25e-3f32`, it keeps the
+/// spelling of the source, the digits keep their underscores.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Equivalence,
 )]
 pub struct UFloatingPointLiteral {
-    pub kind: UFloatingPointKind,
-    pub integral_part: u64,
-    pub exponent: u8,
+    pub integral_part: String,
+    /// The digits after the point, if there is one.
+    pub fractional_part: Option<String>,
+    pub exponent: Option<FloatExponent>,
+    /// Without a suffix the literal is a [`UFloatingPointKind::F64`].
+    pub suffix: Option<UFloatingPointKind>,
+}
+
+impl UFloatingPointKind {
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            UFloatingPointKind::F32 => "f32",
+            UFloatingPointKind::F64 => "f64",
+        }
+    }
+}
+
+impl UFloatingPointLiteral {
+    pub fn kind(&self) -> UFloatingPointKind {
+        self.suffix.unwrap_or_default()
+    }
+
+    /// The literal as the standard library parses it, without
+    /// underscores and suffix.
+    fn normalized(&self) -> String {
+        let mut out = self.integral_part.replace('_', "");
+        if let Some(fraction) = &self.fractional_part {
+            out.push('.');
+            out.push_str(&fraction.replace('_', ""));
+        }
+        if let Some(exponent) = &self.exponent {
+            out.push('e');
+            if exponent.sign == Some(ExponentSign::Minus) {
+                out.push('-');
+            }
+            out.push_str(&exponent.digits.replace('_', ""));
+        }
+        out
+    }
+
+    /// Tells if all the digits of the mantissa are zero.
+    fn is_zero(&self) -> bool {
+        self.integral_part
+            .chars()
+            .chain(self.fractional_part.iter().flat_map(|f| f.chars()))
+            .all(|c| c == '0' || c == '_')
+    }
+
+    /// The value of the literal, `None` if it is too big or too small
+    /// to be represented in a `f64`.
+    pub fn to_f64(&self) -> Option<f64> {
+        let value: f64 = self.normalized().parse().ok()?;
+        if value.is_infinite() || (value == 0.0 && !self.is_zero()) {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// The value of the literal, `None` if it is too big or too small
+    /// to be represented in a `f32`.
+    pub fn to_f32(&self) -> Option<f32> {
+        let value: f32 = self.normalized().parse().ok()?;
+        if value.is_infinite() || (value == 0.0 && !self.is_zero()) {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+/// The literal as it was written in the source.
+impl std::fmt::Display for UFloatingPointLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.integral_part)?;
+        if let Some(fraction) = &self.fractional_part {
+            write!(f, ".{}", fraction)?;
+        }
+        if let Some(exponent) = &self.exponent {
+            write!(f, "{}", if exponent.uppercase { 'E' } else { 'e' })?;
+            match exponent.sign {
+                Some(ExponentSign::Plus) => write!(f, "+")?,
+                Some(ExponentSign::Minus) => write!(f, "-")?,
+                None => (),
+            }
+            write!(f, "{}", exponent.digits)?;
+        }
+        if let Some(suffix) = self.suffix {
+            write!(f, "{}", suffix.suffix())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod literals_test {
+    use super::*;
+
+    fn float(
+        integral_part: &str,
+        fractional_part: Option<&str>,
+        exponent: Option<(bool, Option<ExponentSign>, &str)>,
+        suffix: Option<UFloatingPointKind>,
+    ) -> UFloatingPointLiteral {
+        UFloatingPointLiteral {
+            integral_part: String::from(integral_part),
+            fractional_part: fractional_part.map(String::from),
+            exponent: exponent.map(|(uppercase, sign, digits)| FloatExponent {
+                uppercase,
+                sign,
+                digits: String::from(digits),
+            }),
+            suffix,
+        }
+    }
+
+    #[test]
+    fn string_spelling_and_value() {
+        let literal = StringLiteral {
+            kind: StringKind::Normal,
+            value: vec![
+                StringComponent::RegularString(String::from("a")),
+                StringComponent::Scaped {
+                    spelling: String::from("\\u{1F600}"),
+                    value: '😀',
+                },
+                StringComponent::Scaped {
+                    spelling: String::from("\\n"),
+                    value: '\n',
+                },
+            ],
+        };
+        assert_eq!(literal.to_string(), "\"a\\u{1F600}\\n\"");
+        assert_eq!(literal.decoded(), "a😀\n");
+        let raw = StringLiteral {
+            kind: StringKind::Raw1,
+            value: vec![StringComponent::RegularString(String::from("\\n"))],
+        };
+        assert_eq!(raw.to_string(), "r##\"\\n\"##");
+        assert_eq!(raw.decoded(), "\\n");
+    }
+
+    #[test]
+    fn text_block_spelling() {
+        let literal = StringLiteral {
+            kind: StringKind::TextBlock,
+            value: vec![
+                StringComponent::RegularString(String::from("  a\n\nb")),
+                StringComponent::Scaped {
+                    spelling: String::from("\\t"),
+                    value: '\t',
+                },
+            ],
+        };
+        assert_eq!(literal.text_block_lines(), vec!["  a", "", "b\\t"]);
+        assert_eq!(literal.to_string(), "\"\"\"\n  a\n\nb\\t\n\"\"\"");
+        assert_eq!(literal.decoded(), "  a\n\nb\t");
+    }
+
+    #[test]
+    fn uint_spelling_and_values() {
+        let literal = UintLiteral {
+            base: UintBase::Hex,
+            digits: String::from("fF_ff"),
+            kind: Some(UintKind::U16),
+        };
+        assert_eq!(literal.to_string(), "0xfF_ffu16");
+        assert_eq!(literal.value(), Some(0xffff));
+        let big = UintLiteral {
+            base: UintBase::Decimal,
+            digits: String::from("18_446_744_073_709_551_616"),
+            kind: None,
+        };
+        assert_eq!(big.value(), Some(u128::from(u64::MAX) + 1));
+        let huge = UintLiteral {
+            base: UintBase::Binary,
+            digits: "1".repeat(129),
+            kind: None,
+        };
+        assert_eq!(huge.value(), None);
+    }
+
+    #[test]
+    fn float_spelling_and_values() {
+        let literal = float(
+            "1_000",
+            Some("2_5"),
+            Some((true, Some(ExponentSign::Minus), "0_3")),
+            Some(UFloatingPointKind::F32),
+        );
+        assert_eq!(literal.to_string(), "1_000.2_5E-0_3f32");
+        assert_eq!(literal.to_f32(), Some(1.000_25));
+        assert_eq!(literal.kind(), UFloatingPointKind::F32);
+        let plain = float("3", Some("5"), None, None);
+        assert_eq!(plain.to_string(), "3.5");
+        assert_eq!(plain.to_f64(), Some(3.5));
+        assert_eq!(plain.kind(), UFloatingPointKind::F64);
+    }
+
+    #[test]
+    fn float_out_of_range() {
+        let big = float("1", None, Some((false, None, "39")), None);
+        assert_eq!(big.to_f64(), Some(1e39));
+        assert_eq!(big.to_f32(), None);
+        let small = float(
+            "1",
+            None,
+            Some((false, Some(ExponentSign::Minus), "400")),
+            None,
+        );
+        assert_eq!(small.to_f64(), None);
+        let zero = float(
+            "0",
+            Some("0"),
+            Some((false, Some(ExponentSign::Plus), "9999")),
+            None,
+        );
+        assert_eq!(zero.to_f64(), Some(0.0));
+    }
+
+    #[test]
+    fn format_spec_round_trip() {
+        for text in ["", "x", ">8", "*^10.3", "08X", ".2", "<<5?", "b"] {
+            let spec = FormatSpec::parse(text);
+            assert_eq!(spec.map(|s| s.to_string()), Some(String::from(text)));
+        }
+        let spec = FormatSpec::parse("-<4.1o").unwrap();
+        assert_eq!(spec.fill, Some('-'));
+        assert_eq!(spec.align, Some(FormatAlign::Left));
+        assert_eq!(spec.width, Some(4));
+        assert_eq!(spec.precision, Some(1));
+        assert_eq!(spec.kind, Some(FormatKind::Octal));
+        for text in ["8.", "xx", "8z", "abc"] {
+            assert_eq!(FormatSpec::parse(text), None);
+        }
+    }
 }
@@ -3,12 +3,15 @@ use crate::{
         Between, Braces, Comma, ImportedVariable, Parens, Token, TokenInfo,
         TrailingList,
     },
-    literals::{StringLiteral, UFloatingPointLiteral, UintLiteral},
+    literals::{
+        CharLiteral, StringLiteral, UFloatingPointLiteral, UintLiteral,
+    },
 };
 use octizys_common::identifier::Identifier;
+use octizys_common::span::Span;
 use octizys_macros::Equivalence;
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub enum PatternMatchRecordItem {
     OnlyVariable {
         variable: Token<Identifier>,
@@ -21,7 +24,7 @@ pub enum PatternMatchRecordItem {
     },
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct PatternMatchBind {
     pub variable: Token<Identifier>,
     #[equivalence(ignore)]
@@ -29,13 +32,22 @@ pub struct PatternMatchBind {
     pub pattern: Box<PatternMatch>,
 }
 
-#[derive(Debug, Equivalence)]
+// Like the expressions, the grammar builds the variants by value.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub enum PatternMatch {
     LocalVariable(Token<Identifier>),
     ImportedVariable(Token<ImportedVariable>),
     String(Token<StringLiteral>),
     Uint(Token<UintLiteral>),
+    /// A negative integer like `-5`.
+    Negative {
+        #[equivalence(ignore)]
+        minus: TokenInfo,
+        literal: Token<UintLiteral>,
+    },
     UFloat(Token<UFloatingPointLiteral>),
+    Char(Token<CharLiteral>),
     AnonHole(#[equivalence(ignore)] TokenInfo),
     Tuple(Between<TrailingList<Box<PatternMatch>, Comma>, Parens>),
     Record(Between<TrailingList<PatternMatchRecordItem, Comma>, Braces>),
@@ -47,3 +59,33 @@ pub enum PatternMatch {
     },
     Parens(Between<Box<PatternMatch>, Parens>),
 }
+
+impl PatternMatch {
+    /// The region of the source that the pattern covers.
+    pub fn span(&self) -> Span {
+        match self {
+            PatternMatch::LocalVariable(t) => t.info.span,
+            PatternMatch::ImportedVariable(t) => t.info.span,
+            PatternMatch::String(t) => t.info.span,
+            PatternMatch::Uint(t) => t.info.span,
+            PatternMatch::Negative { minus, literal } => {
+                minus.span + literal.info.span
+            }
+            PatternMatch::UFloat(t) => t.info.span,
+            PatternMatch::Char(t) => t.info.span,
+            PatternMatch::AnonHole(info) => info.span,
+            PatternMatch::Tuple(t) => t.span(),
+            PatternMatch::Record(r) => r.span(),
+            PatternMatch::Bind(b) => b.variable.info.span + b.pattern.span(),
+            PatternMatch::Application {
+                start,
+                second,
+                remain,
+            } => match remain.last() {
+                Some(last) => start.span() + last.span(),
+                None => start.span() + second.span(),
+            },
+            PatternMatch::Parens(p) => p.span(),
+        }
+    }
+}
@@ -8,12 +8,16 @@ use crate::{
         TrailingList,
     },
     comments::Comment,
+    expressions::Expression,
     imports::Import,
     types::Type,
 };
 
+/// A constructor of a data type or newtype, a `public` constructor
+/// can be used by other modules.
 #[derive(Debug, Equivalence)]
 pub struct Constructor {
+    pub public: Option<TokenInfoWithPhantom<PublicKeyword>>,
     pub name: Token<Identifier>,
     pub type_: Option<Type>,
 }
@@ -76,16 +80,54 @@ pub struct NewType {
     pub constructor: Constructor,
 }
 
+/// The type signature of a value, the value is public if its
+/// signature or its definition are marked as public.
+///
+/// ```txt
+/// public? name : type
+/// ```
+#[derive(Debug, Equivalence)]
+pub struct Declaration {
+    pub public: Option<TokenInfoWithPhantom<PublicKeyword>>,
+    pub name: Token<Identifier>,
+    #[equivalence(ignore)]
+    pub colon: TokenInfo,
+    pub type_: Type,
+}
+
+/// The definition of a value, the arguments are sugar for
+/// a lambda expression.
+///
+/// ```txt
+/// public? name argument1 argument2 = expression
+/// ```
+#[derive(Debug, Equivalence)]
+pub struct Definition {
+    pub public: Option<TokenInfoWithPhantom<PublicKeyword>>,
+    pub name: Token<Identifier>,
+    pub arguments: Vec<Token<Identifier>>,
+    #[equivalence(ignore)]
+    pub eq: TokenInfo,
+    pub value: Expression,
+}
+
+// There is one item per definition, their size doesn't matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Equivalence)]
 pub enum TopItem {
     Data(Data),
     Alias(Alias),
     NewType(NewType),
+    Declaration(Declaration),
+    Definition(Definition),
 }
 
 #[derive(Debug, Equivalence)]
 pub struct Top {
     pub imports: Option<TrailingList<Import, SemiColon>>,
     pub items: Option<TrailingList<TopItem, SemiColon>>,
-    pub last_comment: Option<Comment>,
+    /// The comments after the last item, like the comments of the
+    /// tokens they don't take part in the equivalence.
+    #[equivalence(ignore)]
+    pub last_comments: Vec<Comment>,
 }
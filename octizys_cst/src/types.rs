@@ -3,6 +3,7 @@ use crate::base::{
     TokenInfo, TrailingList, TrailingListItem,
 };
 use octizys_common::identifier::Identifier;
+use octizys_common::span::Span;
 use octizys_macros::Equivalence;
 
 #[derive(
@@ -29,7 +30,7 @@ pub struct OwnershipVariable {
     pub variable: Identifier,
 }
 
-#[derive(Debug, Equivalence)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub struct TypeRecordItem {
     pub variable: Token<Identifier>,
     #[equivalence(ignore)]
@@ -40,7 +41,9 @@ pub struct TypeRecordItem {
     pub expression: Box<Type>,
 }
 
-#[derive(Debug, Equivalence)]
+// Like the expressions, the grammar builds the variants by value.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq, Equivalence)]
 pub enum Type {
     LocalVariable(Token<Identifier>),
     ImportedVariable(Token<ImportedVariable>),
@@ -68,6 +71,32 @@ pub enum Type {
 }
 
 impl Type {
+    /// The region of the source that the type covers.
+    pub fn span(&self) -> Span {
+        match self {
+            Type::LocalVariable(t) => t.info.span,
+            Type::ImportedVariable(t) => t.info.span,
+            Type::Tuple(t) => t.span(),
+            Type::Record(r) => r.span(),
+            Type::Parens(p) => p.span(),
+            Type::Application {
+                start,
+                second,
+                remain,
+            } => match remain.last() {
+                Some(last) => start.span() + last.span(),
+                None => start.span() + second.span(),
+            },
+            Type::Arrow { first, remain } => match remain.last() {
+                Some(last) => first.span() + last.item.span(),
+                None => first.span(),
+            },
+            Type::Scheme {
+                forall, expression, ..
+            } => forall.span + expression.span(),
+        }
+    }
+
     ///This function tell the pretty printer if the type needs to be
     ///surrounded by parentheses if the type is a argument in a
     ///application.
@@ -1,9 +1,27 @@
-use crate::common_configuration::CommonConfiguration;
-
 use std::path::PathBuf;
 
-#[derive(Debug)]
+/// The formats of the generated pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentationFormat {
+    Html,
+    Markdown,
+}
+
+impl DocumentationFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            DocumentationFormat::Html => "html",
+            DocumentationFormat::Markdown => "md",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct DocumentationConfiguration {
-    pub common: CommonConfiguration,
+    /// The folder where we put the pages, it is created if
+    /// it doesn't exists.
     pub output_folder: PathBuf,
+    pub formats: Vec<DocumentationFormat>,
+    /// Document the items that other modules can't use too.
+    pub include_private: bool,
 }
@@ -0,0 +1,276 @@
+use crate::extraction::{
+    Documentation, DocumentationLine, ItemDocumentation, ModuleDocumentation,
+};
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::{Location, Position, Span};
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+
+/// A line of a example that starts with this mark is part of the
+/// expected output of the `main` of the example.
+/// The mark is a regular comment, the example remains valid code.
+///
+/// ```txt
+/// main = 1 + 2;
+/// -- => 3
+/// ```
+pub const EXPECTED_OUTPUT_MARK: &str = "-- =>";
+
+/// The language of the fenced code blocks that are examples, a
+/// block without language is a example too.
+pub const EXAMPLE_LANGUAGE: &str = "octizys";
+
+/// A example that is only shown, it isn't compiled.
+pub const IGNORE_ATTRIBUTE: &str = "ignore";
+
+/// A fenced code block of a documentation comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// The lines between the fences.
+    pub code: Vec<DocumentationLine>,
+    /// The text after the [`EXPECTED_OUTPUT_MARK`] of every line
+    /// that has it.
+    pub expected: Option<String>,
+    /// From the opening fence to the end of the closing one.
+    pub span: Span,
+}
+
+fn end_of(line: &DocumentationLine) -> Position {
+    Position {
+        source_index: line.start.source_index + line.text.len(),
+        column: line.start.column + line.text.len(),
+        ..line.start
+    }
+}
+
+/// The amount of backticks of a fence, and the text after them.
+fn fence(line: &str) -> Option<(usize, &str)> {
+    let ticks = line.len() - line.trim_start_matches('`').len();
+    (ticks >= 3).then(|| (ticks, line[ticks..].trim()))
+}
+
+/// Tells if the info string of a fence (the text after the
+/// backticks) belongs to a example.
+fn is_example(info: &str) -> bool {
+    let mut words = info.split(|c: char| c == ',' || c.is_whitespace());
+    let language = words.next().unwrap_or_default();
+    (language.is_empty() || language == EXAMPLE_LANGUAGE)
+        && !words.any(|word| word == IGNORE_ATTRIBUTE)
+}
+
+/// The examples of a documentation, a fence without end finishes at
+/// the end of the documentation, as in Markdown.
+pub fn examples(documentation: &Documentation) -> Vec<Example> {
+    let mut result = vec![];
+    let mut lines = documentation.0.iter();
+    while let Some((opening, (ticks, info))) =
+        lines.by_ref().find_map(|l| fence(&l.text).map(|f| (l, f)))
+    {
+        let mut code = vec![];
+        let mut end = end_of(opening);
+        for line in lines.by_ref() {
+            end = end_of(line);
+            match fence(&line.text) {
+                Some((closing, "")) if closing >= ticks => break,
+                _ => code.push(line.clone()),
+            }
+        }
+        if !is_example(info) {
+            continue;
+        }
+        let expected: Vec<&str> = code
+            .iter()
+            .filter_map(|l| {
+                l.text.trim_start().strip_prefix(EXPECTED_OUTPUT_MARK)
+            })
+            .map(|text| text.strip_prefix(' ').unwrap_or(text))
+            .collect();
+        let expected = (!expected.is_empty()).then(|| expected.join("\n"));
+        result.push(Example {
+            code,
+            expected,
+            span: Span {
+                start: opening.start,
+                end,
+            },
+        });
+    }
+    result
+}
+
+fn item_examples(item: &ItemDocumentation, result: &mut Vec<Example>) {
+    result.extend(examples(&item.documentation));
+    for constructor in &item.constructors {
+        item_examples(constructor, result);
+    }
+}
+
+/// The examples of the imports, items and constructors of a module
+/// in order of appearance.
+pub fn module_examples(module: &ModuleDocumentation) -> Vec<Example> {
+    let mut result = vec![];
+    for import in &module.imports {
+        result.extend(examples(&import.documentation));
+    }
+    for item in &module.items {
+        item_examples(item, &mut result);
+    }
+    result
+}
+
+impl Example {
+    /// The source of the file of the example with everything but the
+    /// code replaced by spaces. Parsing it gives positions that point
+    /// to the example inside the original file.
+    pub fn overlay(&self, source: &str) -> String {
+        let mut bytes: Vec<u8> = source
+            .bytes()
+            .map(|b| if b == b'\n' || b == b'\r' { b } else { b' ' })
+            .collect();
+        for line in &self.code {
+            let start = line.start.source_index;
+            if let Some(target) = bytes.get_mut(start..start + line.text.len())
+            {
+                target.copy_from_slice(line.text.as_bytes());
+            }
+        }
+        // Only ASCII bytes replaced whole characters.
+        String::from_utf8(bytes).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DoctestError {
+    /// The example has a expected output but nothing to run.
+    MissingMain { span: Span },
+    WrongOutput {
+        span: Span,
+        expected: String,
+        found: String,
+    },
+}
+
+impl DoctestError {
+    pub fn span(&self) -> Span {
+        match self {
+            DoctestError::MissingMain { span }
+            | DoctestError::WrongOutput { span, .. } => *span,
+        }
+    }
+}
+
+impl ReportFormat for DoctestError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            DoctestError::MissingMain { .. } => {
+                NonLineBreakStr::new("DoctestMissingMain")
+            }
+            DoctestError::WrongOutput { .. } => {
+                NonLineBreakStr::new("DoctestWrongOutput")
+            }
+        }
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            DoctestError::MissingMain { .. } => NonLineBreakStr::new(
+                "The example has a expected output but no main.",
+            ),
+            DoctestError::WrongOutput { .. } => NonLineBreakStr::new(
+                "The example doesn't produce the expected output.",
+            ),
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        match self {
+            DoctestError::MissingMain { .. } => Some(external_text(&format!(
+                "The output of a example is the value of its main, define it or remove the lines that start with {}.",
+                EXPECTED_OUTPUT_MARK
+            ))),
+            DoctestError::WrongOutput {
+                expected, found, ..
+            } => Some(external_text(&format!(
+                "Expected:\n{}\nFound:\n{}",
+                expected, found
+            ))),
+        }
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span()))
+    }
+}
+
+#[cfg(test)]
+mod doctest_test {
+    use super::*;
+    use crate::extraction::extract_documentation;
+    use octizys_common::source_map::FileId;
+    use octizys_parser::parser::parse_string;
+    use octizys_sast::modules::{
+        imports, interface, ModuleGraph, ModuleName, ModuleNode,
+    };
+    use octizys_text_store::store::Store;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn examples_of(source: &str) -> Vec<Example> {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let top = parse_string(source, None, store.clone()).unwrap();
+        let store = store.borrow();
+        let mut graph = ModuleGraph::new();
+        graph.add(ModuleNode {
+            name: ModuleName::from_file(
+                std::path::Path::new(""),
+                std::path::Path::new("main"),
+            )
+            .unwrap(),
+            file: FileId::DETACHED,
+            imports: imports(&top, &store),
+            interface: interface(&top, &store),
+            top,
+        });
+        let documentation = extract_documentation(&graph, true, &store);
+        module_examples(&documentation[0])
+    }
+
+    #[test]
+    fn fences_and_expected_output() {
+        let examples = examples_of(
+            "-- | Text.\n-- | ```octizys\n-- | main = 1;\n-- | -- => 1\n-- | ```\n-- | ```txt\n-- | not code\n-- | ```\n-- | ```octizys ignore\n-- | not code\n-- | ```\n-- | ````\n-- | f = 2;\n-- | ```\n-- | ````\nf = 1;",
+        );
+        let code: Vec<Vec<&str>> = examples
+            .iter()
+            .map(|e| e.code.iter().map(|l| l.text.as_str()).collect())
+            .collect();
+        assert_eq!(code, [vec!["main = 1;", "-- => 1"], vec!["f = 2;", "```"]]);
+        assert_eq!(examples[0].expected.as_deref(), Some("1"));
+        assert_eq!(examples[1].expected, None);
+    }
+
+    #[test]
+    fn overlay_keeps_the_positions() {
+        let source = "-- | ```\n-- | main = 1;\n-- | ```\nf = 1;\n{- |\n   ```\n   main = 2;\n   -- => 2\n   ```\n-}\ng = 2;";
+        let examples = examples_of(source);
+        assert_eq!(examples.len(), 2);
+        for example in &examples {
+            let overlay = example.overlay(source);
+            assert_eq!(overlay.len(), source.len());
+            assert_eq!(overlay.lines().count(), source.lines().count());
+            for line in &example.code {
+                let start = line.start.source_index;
+                let range = start..start + line.text.len();
+                assert_eq!(&source[range.clone()], line.text);
+                assert_eq!(&overlay[range], line.text);
+            }
+        }
+        assert_eq!(examples[1].span.start.line, 5);
+        assert_eq!(examples[1].span.start.column, 3);
+    }
+}
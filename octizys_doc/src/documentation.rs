@@ -1,17 +1,144 @@
-use crate::documentation::configuration::DocumentationConfiguration;
-use crate::error::Error;
+use std::path::PathBuf;
 
-pub enum DocumentationError {}
+use crate::configuration::{DocumentationConfiguration, DocumentationFormat};
+use crate::extraction::{extract_documentation, ModuleDocumentation};
+use crate::{html, markdown};
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::Location;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+use octizys_sast::modules::{ModuleGraph, ModuleName};
+use octizys_text_store::store::Store;
 
-impl Into<Error> for DocumentationError {
-    fn into(self) -> Error {
-        todo!()
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentationError {
+    CantCreateFolder { path: PathBuf },
+    CantWriteFile { path: PathBuf },
+}
+
+impl ReportFormat for DocumentationError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        NonLineBreakStr::new("OctizysDocumentation")
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            DocumentationError::CantCreateFolder { .. } => {
+                NonLineBreakStr::new("Can't create the output folder.")
+            }
+            DocumentationError::CantWriteFile { .. } => {
+                NonLineBreakStr::new("Can't write a documentation page.")
+            }
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        match self {
+            DocumentationError::CantCreateFolder { path } => Some(
+                external_text(&format!(
+                    "We couldn't create the folder {}, check that the path is right and that you can write on it.",
+                    path.display()
+                )),
+            ),
+            DocumentationError::CantWriteFile { path } => {
+                Some(external_text(&format!(
+                    "We couldn't write the file {}.",
+                    path.display()
+                )))
+            }
+        }
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        None
     }
 }
 
+/// The file of the page of a module, the pages of the modules start
+/// with `module.` so they never collide with the index.
+pub fn module_file(name: &ModuleName, format: DocumentationFormat) -> String {
+    format!(
+        "module.{}.{}",
+        name.to_string().replace("::", "."),
+        format.extension()
+    )
+}
+
+/// The file of the page with the list of modules.
+pub fn index_file(format: DocumentationFormat) -> String {
+    format!("index.{}", format.extension())
+}
+
+/// Writes a page per module and an index in every format of the
+/// configuration. Returns the written files.
 pub fn generate_documentation(
-    config: DocumentationConfiguration,
-) -> Result<(), DocumentationError> {
-    println!("{:?}", config);
-    todo!("generating documentation");
+    graph: &ModuleGraph,
+    configuration: &DocumentationConfiguration,
+    store: &Store,
+) -> Result<Vec<PathBuf>, DocumentationError> {
+    let folder = &configuration.output_folder;
+    std::fs::create_dir_all(folder).map_err(|_| {
+        DocumentationError::CantCreateFolder {
+            path: folder.clone(),
+        }
+    })?;
+    let modules =
+        extract_documentation(graph, configuration.include_private, store);
+    let mut written = vec![];
+    for &format in &configuration.formats {
+        for (name, content) in render(&modules, format) {
+            let path = folder.join(name);
+            std::fs::write(&path, content).map_err(|_| {
+                DocumentationError::CantWriteFile { path: path.clone() }
+            })?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+/// The file and content of every page in the given format.
+fn render(
+    modules: &[ModuleDocumentation],
+    format: DocumentationFormat,
+) -> Vec<(String, String)> {
+    let mut pages: Vec<(String, String)> = modules
+        .iter()
+        .map(|module| {
+            let content = match format {
+                DocumentationFormat::Html => html::module_page(module),
+                DocumentationFormat::Markdown => markdown::module_page(module),
+            };
+            (module_file(&module.name, format), content)
+        })
+        .collect();
+    let index = match format {
+        DocumentationFormat::Html => html::index_page(modules),
+        DocumentationFormat::Markdown => markdown::index_page(modules),
+    };
+    pages.push((index_file(format), index));
+    pages
+}
+
+#[cfg(test)]
+mod documentation_test {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn pages_never_collide_with_the_index() {
+        let name =
+            ModuleName::from_file(Path::new(""), Path::new("index")).unwrap();
+        let nested =
+            ModuleName::from_file(Path::new(""), Path::new("a/b")).unwrap();
+        let format = DocumentationFormat::Html;
+        assert_eq!(module_file(&name, format), "module.index.html");
+        assert_eq!(module_file(&nested, format), "module.a.b.html");
+        assert_eq!(index_file(DocumentationFormat::Markdown), "index.md");
+    }
 }
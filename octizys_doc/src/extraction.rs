@@ -0,0 +1,911 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use octizys_common::identifier::Identifier as CstIdentifier;
+use octizys_common::logic_path::LogicPath;
+use octizys_common::span::Position;
+use octizys_cst::base::{
+    AliasKeyword, DataKeyword, NewTypeKeyword, ShowableToken, TokenInfo,
+    UnqualifiedKeyword,
+};
+use octizys_cst::comments::{
+    Comment, CommentBlock, CommentKind, CommentLine, CommentLineContent,
+};
+use octizys_cst::top::{Constructor, Declaration, TopItem};
+use octizys_cst::types::Type;
+use octizys_formatter::keywords;
+use octizys_sast::modules::{
+    ImportDeclaration, ItemKind, ModuleGraph, ModuleInterface, ModuleName,
+};
+use octizys_text_store::store::{Store, StoreSymbol};
+
+/// The place where a item is documented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub module: ModuleName,
+    pub kind: ItemKind,
+    pub name: String,
+}
+
+impl Target {
+    /// The identifier of the item inside the page of its module.
+    /// Types and values live in different namespaces, a type and a
+    /// constructor can share the name.
+    pub fn anchor(&self) -> String {
+        anchor(self.kind, &self.name)
+    }
+}
+
+fn anchor(kind: ItemKind, name: &str) -> String {
+    match kind {
+        ItemKind::Type => format!("type.{}", name),
+        ItemKind::Constructor | ItemKind::Value => format!("value.{}", name),
+    }
+}
+
+/// A piece of a [`Signature`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignaturePart {
+    Text(String),
+    /// A name that refers to a documented item.
+    Item {
+        text: String,
+        target: Target,
+    },
+    /// The name of a documented module.
+    Module {
+        text: String,
+        module: ModuleName,
+    },
+}
+
+/// The text of a item as it is written in the source, without
+/// comments and with the references to other items resolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Signature(pub Vec<SignaturePart>);
+
+impl Signature {
+    fn text(&mut self, text: &str) {
+        match self.0.last_mut() {
+            Some(SignaturePart::Text(last)) => last.push_str(text),
+            _ => self.0.push(SignaturePart::Text(String::from(text))),
+        }
+    }
+
+    fn item(&mut self, text: String, target: Option<Target>) {
+        match target {
+            Some(target) => self.0.push(SignaturePart::Item { text, target }),
+            None => self.text(&text),
+        }
+    }
+
+    /// The items of other pages or of the same page used by the
+    /// signature, without repetitions.
+    pub fn references(&self) -> Vec<(&str, &Target)> {
+        let mut result: Vec<(&str, &Target)> = vec![];
+        for part in &self.0 {
+            if let SignaturePart::Item { text, target } = part {
+                if !result.iter().any(|(_, t)| *t == target) {
+                    result.push((text, target))
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for part in &self.0 {
+            match part {
+                SignaturePart::Text(text)
+                | SignaturePart::Item { text, .. }
+                | SignaturePart::Module { text, .. } => write!(f, "{}", text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A line of a documentation comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentationLine {
+    pub text: String,
+    /// The place of the first character of the text in the source.
+    pub start: Position,
+}
+
+/// The documentation comments of a item in Markdown, without the
+/// comment delimiters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Documentation(pub Vec<DocumentationLine>);
+
+impl Documentation {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Puts the other lines after a blank line.
+    fn append(&mut self, mut other: Documentation) {
+        if let (Some(last), Some(_)) = (self.0.last(), other.0.first()) {
+            let blank = DocumentationLine {
+                text: String::new(),
+                start: last.start,
+            };
+            self.0.push(blank);
+        }
+        self.0.append(&mut other.0);
+    }
+}
+
+impl Display for Documentation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, line) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", line.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// A item of a module together with its documentation comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemDocumentation {
+    pub kind: ItemKind,
+    pub name: String,
+    pub public: bool,
+    pub signature: Signature,
+    pub documentation: Documentation,
+    /// The constructors of a data type or newtype.
+    pub constructors: Vec<ItemDocumentation>,
+}
+
+impl ItemDocumentation {
+    pub fn anchor(&self) -> String {
+        anchor(self.kind, &self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDocumentation {
+    pub module: ModuleName,
+    pub signature: Signature,
+    pub documentation: Documentation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleDocumentation {
+    pub name: ModuleName,
+    pub imports: Vec<ImportDocumentation>,
+    pub items: Vec<ItemDocumentation>,
+}
+
+/// The names of the items of a module that appear in the
+/// documentation.
+#[derive(Debug, Default)]
+struct Documented {
+    types: HashSet<String>,
+    values: HashSet<String>,
+}
+
+impl Documented {
+    fn new(interface: &ModuleInterface, include_private: bool) -> Self {
+        let is_private = |name: &str, type_namespace: bool| {
+            interface.private.iter().any(|p| {
+                p.name.as_str() == name
+                    && (p.kind == ItemKind::Type) == type_namespace
+            })
+        };
+        let types = interface.types.iter().map(|(_, name)| name.as_str());
+        let values = interface.values.iter().map(|(_, name)| name.as_str());
+        Documented {
+            types: types
+                .filter(|name| include_private || !is_private(name, true))
+                .map(String::from)
+                .collect(),
+            values: values
+                .filter(|name| include_private || !is_private(name, false))
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+/// Collects the documentation comments of every module of the
+/// graph, the modules keep the order of the graph.
+/// A documentation comment belongs to the item, constructor or
+/// import that follows it. Only the public items are collected
+/// unless `include_private` is set.
+pub fn extract_documentation(
+    graph: &ModuleGraph,
+    include_private: bool,
+    store: &Store,
+) -> Vec<ModuleDocumentation> {
+    let documented: Vec<Documented> = (0..graph.len())
+        .map(|i| Documented::new(&graph.get(i).interface, include_private))
+        .collect();
+    (0..graph.len())
+        .map(|index| {
+            let extractor = Extractor {
+                graph,
+                index,
+                documented: &documented,
+                store,
+                bound: vec![],
+            };
+            extractor.module(include_private)
+        })
+        .collect()
+}
+
+struct Extractor<'a> {
+    graph: &'a ModuleGraph,
+    index: usize,
+    documented: &'a [Documented],
+    store: &'a Store,
+    /// The type variables in scope, they never refer to items.
+    bound: Vec<String>,
+}
+
+impl Extractor<'_> {
+    fn module(mut self, include_private: bool) -> ModuleDocumentation {
+        let module = self.graph.get(self.index);
+        let imports = match &module.top.imports {
+            Some(imports) => imports
+                .iter()
+                .zip(module.imports.iter())
+                .map(|(import, declaration)| ImportDocumentation {
+                    module: declaration.module.clone(),
+                    signature: self.import(declaration),
+                    documentation: documentation(&import.import, self.store),
+                })
+                .collect(),
+            None => vec![],
+        };
+        let mut declarations: HashMap<String, &Declaration> = HashMap::new();
+        let mut items = vec![];
+        for item in module.top.items.iter().flat_map(|items| items.iter()) {
+            if let TopItem::Declaration(declaration) = item {
+                declarations
+                    .insert(self.name(&declaration.name.value), declaration);
+            }
+        }
+        for item in module.top.items.iter().flat_map(|items| items.iter()) {
+            self.bound.clear();
+            if let Some(mut item) = self.item(item, &declarations) {
+                if include_private || item.public {
+                    item.constructors.retain(|c| include_private || c.public);
+                    items.push(item);
+                }
+            }
+        }
+        ModuleDocumentation {
+            name: module.name.clone(),
+            imports,
+            items,
+        }
+    }
+
+    fn name(&self, identifier: &CstIdentifier) -> String {
+        self.store
+            .regular
+            .resolve(StoreSymbol::from(*identifier))
+            .map(String::from)
+            .unwrap_or_default()
+    }
+
+    fn item(
+        &mut self,
+        item: &TopItem,
+        declarations: &HashMap<String, &Declaration>,
+    ) -> Option<ItemDocumentation> {
+        match item {
+            TopItem::Data(data) => {
+                let left = &data.left_part;
+                let first = left
+                    .public
+                    .as_ref()
+                    .map_or(&left.statement_keyword.info, |p| &p.info);
+                let (name, signature) =
+                    self.type_left(DataKeyword::show().as_str(), left);
+                let public = left.public.is_some();
+                let constructors = match &data.constructors {
+                    Some(constructors) => {
+                        let list = &constructors.constructors;
+                        let separators = std::iter::once(&constructors.eq)
+                            .chain(list.items.iter().map(|i| &i.separator));
+                        let all: Vec<&Constructor> = list.iter().collect();
+                        let marked = all.iter().any(|c| c.public.is_some());
+                        separators
+                            .zip(all)
+                            .map(|(separator, c)| {
+                                self.constructor(separator, c, public, marked)
+                            })
+                            .collect()
+                    }
+                    None => vec![],
+                };
+                Some(ItemDocumentation {
+                    kind: ItemKind::Type,
+                    name,
+                    public,
+                    signature,
+                    documentation: documentation(first, self.store),
+                    constructors,
+                })
+            }
+            TopItem::Alias(alias) => {
+                let left = &alias.left_part;
+                let first = left
+                    .public
+                    .as_ref()
+                    .map_or(&left.statement_keyword.info, |p| &p.info);
+                let (name, mut signature) =
+                    self.type_left(AliasKeyword::show().as_str(), left);
+                signature.text(&format!(" {} ", keywords::ASIGNATION.as_str()));
+                self.type_(&mut signature, &alias.type_);
+                Some(ItemDocumentation {
+                    kind: ItemKind::Type,
+                    name,
+                    public: left.public.is_some(),
+                    signature,
+                    documentation: documentation(first, self.store),
+                    constructors: vec![],
+                })
+            }
+            TopItem::NewType(new_type) => {
+                let left = &new_type.left_part;
+                let first = left
+                    .public
+                    .as_ref()
+                    .map_or(&left.statement_keyword.info, |p| &p.info);
+                let (name, signature) =
+                    self.type_left(NewTypeKeyword::show().as_str(), left);
+                let public = left.public.is_some();
+                let constructor = self.constructor(
+                    &new_type.eq,
+                    &new_type.constructor,
+                    public,
+                    new_type.constructor.public.is_some(),
+                );
+                Some(ItemDocumentation {
+                    kind: ItemKind::Type,
+                    name,
+                    public,
+                    signature,
+                    documentation: documentation(first, self.store),
+                    constructors: vec![constructor],
+                })
+            }
+            // They are documented together with their definition.
+            TopItem::Declaration(_) => None,
+            TopItem::Definition(definition) => {
+                let name = self.name(&definition.name.value);
+                let first = definition
+                    .public
+                    .as_ref()
+                    .map_or(&definition.name.info, |p| &p.info);
+                let mut signature = Signature::default();
+                signature.text(&name);
+                let mut comments = Documentation::default();
+                let mut public = definition.public.is_some();
+                match declarations.get(&name) {
+                    Some(declaration) => {
+                        signature
+                            .text(&format!(" {} ", keywords::COLON.as_str()));
+                        self.type_(&mut signature, &declaration.type_);
+                        let first = declaration
+                            .public
+                            .as_ref()
+                            .map_or(&declaration.name.info, |p| &p.info);
+                        comments.append(documentation(first, self.store));
+                        public = public || declaration.public.is_some();
+                    }
+                    None => {
+                        for argument in &definition.arguments {
+                            signature.text(" ");
+                            signature.text(&self.name(&argument.value));
+                        }
+                    }
+                }
+                comments.append(documentation(first, self.store));
+                Some(ItemDocumentation {
+                    kind: ItemKind::Value,
+                    name,
+                    public,
+                    signature,
+                    documentation: comments,
+                    constructors: vec![],
+                })
+            }
+        }
+    }
+
+    /// The signature of the left part of a type definition, the
+    /// variables of the type become bound.
+    fn type_left<Keyword>(
+        &mut self,
+        keyword: &str,
+        left: &octizys_cst::top::TopTypeDefinitionLeft<Keyword>,
+    ) -> (String, Signature)
+    where
+        Keyword: octizys_cst::top::TopTypeName,
+    {
+        let name = self.name(&left.name.value);
+        let mut signature = Signature::default();
+        signature.text(keyword);
+        signature.text(" ");
+        signature.text(&name);
+        for variable in &left.variables {
+            let variable = self.name(&variable.value);
+            signature.text(" ");
+            signature.text(&variable);
+            self.bound.push(variable);
+        }
+        (name, signature)
+    }
+
+    /// A constructor is public if it is marked as public or if none
+    /// of the constructors of a public type is marked.
+    /// The documentation can be around the separator too.
+    fn constructor(
+        &self,
+        separator: &TokenInfo,
+        constructor: &Constructor,
+        owner_public: bool,
+        marked: bool,
+    ) -> ItemDocumentation {
+        let name = self.name(&constructor.name.value);
+        let first = constructor
+            .public
+            .as_ref()
+            .map_or(&constructor.name.info, |p| &p.info);
+        let mut signature = Signature::default();
+        signature.text(&name);
+        if let Some(type_) = &constructor.type_ {
+            signature.text(" ");
+            self.type_(&mut signature, type_);
+        }
+        let separator = &separator.comments;
+        let mut comments = comments_documentation(
+            separator.before.iter().chain(&separator.after),
+            self.store,
+        );
+        comments.append(documentation(first, self.store));
+        ItemDocumentation {
+            kind: ItemKind::Constructor,
+            name,
+            public: constructor.public.is_some() || (owner_public && !marked),
+            signature,
+            documentation: comments,
+            constructors: vec![],
+        }
+    }
+
+    fn import(&self, import: &ImportDeclaration) -> Signature {
+        let separator = keywords::MODULE_SEPARATOR.as_str();
+        let mut signature = Signature::default();
+        signature.text(keywords::IMPORT.as_str());
+        signature.text(" ");
+        if import.unqualified {
+            signature.text(UnqualifiedKeyword::show().as_str());
+            signature.text(" ");
+        }
+        let text = format!("{}{}", import.module, separator);
+        let imported = self.graph.find(&import.module);
+        match imported {
+            Some(_) => signature.0.push(SignaturePart::Module {
+                text,
+                module: import.module.clone(),
+            }),
+            None => signature.text(&text),
+        }
+        if let Some(names) = &import.names {
+            signature.text(keywords::LPAREN.as_str());
+            for (i, (_, name)) in names.iter().enumerate() {
+                if i > 0 {
+                    signature.text(&format!("{} ", keywords::COMMA.as_str()));
+                }
+                let name = name.as_str();
+                let target = imported.and_then(|m| {
+                    let documented = &self.documented[m];
+                    let kind = if documented.types.contains(name) {
+                        ItemKind::Type
+                    } else if documented.values.contains(name) {
+                        ItemKind::Value
+                    } else {
+                        return None;
+                    };
+                    Some(Target {
+                        module: import.module.clone(),
+                        kind,
+                        name: String::from(name),
+                    })
+                });
+                signature.item(String::from(name), target);
+            }
+            signature.text(keywords::RPAREN.as_str());
+        }
+        if let Some(alias) = &import.alias {
+            signature.text(&format!(
+                " {} {}{}",
+                keywords::AS.as_str(),
+                alias,
+                separator
+            ));
+        }
+        signature
+    }
+
+    /// The documented module that defines the type, if it is visible
+    /// without prefix.
+    fn local_type(&self, name: &str) -> Option<Target> {
+        if self.bound.iter().any(|b| b == name) {
+            return None;
+        }
+        let target = |index: usize| Target {
+            module: self.graph.get(index).name.clone(),
+            kind: ItemKind::Type,
+            name: String::from(name),
+        };
+        if self.documented[self.index].types.contains(name) {
+            return Some(target(self.index));
+        }
+        self.graph
+            .get(self.index)
+            .imports
+            .iter()
+            .filter(|import| import.unqualified)
+            .filter(|import| match &import.names {
+                Some(names) => names.iter().any(|(_, n)| n.as_str() == name),
+                None => true,
+            })
+            .filter_map(|import| self.graph.find(&import.module))
+            .find(|&index| self.documented[index].types.contains(name))
+            .map(target)
+    }
+
+    fn qualified_type(&self, path: &LogicPath, name: &str) -> Option<Target> {
+        let qualifier = ModuleName::from_logic_path(path, self.store);
+        self.graph
+            .get(self.index)
+            .imports
+            .iter()
+            .filter(|import| import.qualifier() == &qualifier)
+            .filter_map(|import| self.graph.find(&import.module))
+            .find(|&index| self.documented[index].types.contains(name))
+            .map(|index| Target {
+                module: self.graph.get(index).name.clone(),
+                kind: ItemKind::Type,
+                name: String::from(name),
+            })
+    }
+
+    fn type_(&self, signature: &mut Signature, type_: &Type) {
+        match type_ {
+            Type::LocalVariable(variable) => {
+                let name = self.name(&variable.value);
+                let target = self.local_type(&name);
+                signature.item(name, target)
+            }
+            Type::ImportedVariable(variable) => {
+                let name = self.name(&variable.value.name);
+                let target = self.qualified_type(&variable.value.path, &name);
+                let parts: &Vec<CstIdentifier> = (&variable.value.path).into();
+                let mut text = String::new();
+                for part in parts {
+                    text.push_str(&self.name(part));
+                    text.push_str(keywords::MODULE_SEPARATOR.as_str());
+                }
+                text.push_str(&name);
+                signature.item(text, target)
+            }
+            Type::Tuple(tuple) => {
+                signature.text(keywords::LPAREN.as_str());
+                for (i, item) in tuple.value.iter().enumerate() {
+                    if i > 0 {
+                        signature
+                            .text(&format!("{} ", keywords::COMMA.as_str()));
+                    }
+                    self.type_(signature, item);
+                }
+                signature.text(keywords::RPAREN.as_str());
+            }
+            Type::Record(record) => {
+                signature.text(keywords::LBRACE.as_str());
+                for (i, item) in record.value.iter().enumerate() {
+                    if i > 0 {
+                        signature
+                            .text(&format!("{} ", keywords::COMMA.as_str()));
+                    }
+                    signature.text(&format!(
+                        "{} {} ",
+                        self.name(&item.variable.value),
+                        keywords::COLON.as_str()
+                    ));
+                    self.type_(signature, &item.expression);
+                }
+                signature.text(keywords::RBRACE.as_str());
+            }
+            Type::Parens(parens) => {
+                signature.text(keywords::LPAREN.as_str());
+                self.type_(signature, &parens.value);
+                signature.text(keywords::RPAREN.as_str());
+            }
+            Type::Application {
+                start,
+                second,
+                remain,
+            } => {
+                self.type_(signature, start);
+                signature.text(" ");
+                self.type_(signature, second);
+                for argument in remain {
+                    signature.text(" ");
+                    self.type_(signature, argument);
+                }
+            }
+            Type::Arrow { first, remain } => {
+                self.type_(signature, first);
+                for item in remain {
+                    signature
+                        .text(&format!(" {} ", keywords::RIGHT_ARROW.as_str()));
+                    self.type_(signature, &item.item);
+                }
+            }
+            Type::Scheme {
+                first_variable,
+                remain_variables,
+                expression,
+                ..
+            } => {
+                let mut inner = Extractor {
+                    bound: self.bound.clone(),
+                    ..*self
+                };
+                signature.text(keywords::FORALL.as_str());
+                for variable in
+                    std::iter::once(first_variable).chain(remain_variables)
+                {
+                    let variable = self.name(&variable.value);
+                    signature.text(" ");
+                    signature.text(&variable);
+                    inner.bound.push(variable);
+                }
+                signature.text(&format!(" {} ", keywords::DOT.as_str()));
+                inner.type_(signature, expression);
+            }
+        }
+    }
+}
+
+/// The text of a comment line.
+fn line(content: &CommentLineContent, store: &Store) -> String {
+    store
+        .comments
+        .resolve(content.get_index())
+        .map(|line| String::from(<&str>::from(line)))
+        .unwrap_or_default()
+}
+
+/// The position some bytes after another in the same line.
+fn shifted(position: Position, bytes: usize) -> Position {
+    Position {
+        source_index: position.source_index + bytes,
+        column: position.column + bytes,
+        ..position
+    }
+}
+
+/// The content of a line comment is at the end of its span, the span
+/// includes the line break if there is one.
+fn line_comment_line(
+    comment: &CommentLine,
+    store: &Store,
+) -> DocumentationLine {
+    let text = line(&comment.content, store);
+    let span = comment.span;
+    let end = if span.end.line > span.start.line {
+        span.end.source_index - 1
+    } else {
+        span.end.source_index
+    };
+    let bytes = end.saturating_sub(text.len() + span.start.source_index);
+    DocumentationLine {
+        start: shifted(span.start, bytes),
+        text,
+    }
+}
+
+/// The content of a block comment ends right before the closing
+/// delimiter, it has the same length as the opening one.
+/// The lines after the first one start at the beginning of a line.
+fn block_comment_lines(
+    block: &CommentBlock,
+    store: &Store,
+) -> Vec<DocumentationLine> {
+    let texts: Vec<String> =
+        block.content.iter().map(|c| line(c, store)).collect();
+    let length = texts.iter().map(|t| t.len() + 1).sum::<usize>();
+    let end = block.span.end.source_index - block.brace.len();
+    let mut index = (end + 1).saturating_sub(length);
+    let mut start = shifted(
+        block.span.start,
+        index.saturating_sub(block.span.start.source_index),
+    );
+    let mut result = vec![];
+    for text in texts {
+        index += text.len() + 1;
+        result.push(DocumentationLine { text, start });
+        start = Position {
+            source_index: index,
+            line: start.line + 1,
+            column: 0,
+            ..start
+        };
+    }
+    result
+}
+
+/// Removes the indentation shared by all the lines that aren't blank.
+fn dedent(lines: &mut [DocumentationLine]) {
+    let indentation =
+        |l: &DocumentationLine| l.text.len() - l.text.trim_start().len();
+    let common = lines
+        .iter()
+        .filter(|l| !l.text.trim().is_empty())
+        .map(indentation)
+        .min()
+        .unwrap_or(0);
+    for l in lines.iter_mut() {
+        let cut = common.min(indentation(l));
+        l.text = String::from(l.text[cut..].trim_end());
+        l.start = shifted(l.start, cut);
+    }
+}
+
+/// The documentation comments before a token.
+/// Consecutive line comments form a block, the lines of every
+/// block lose their shared indentation.
+/// In block comments the first line follows the `|`, so it
+/// doesn't count for the indentation.
+pub fn documentation(info: &TokenInfo, store: &Store) -> Documentation {
+    comments_documentation(&info.comments.before, store)
+}
+
+fn comments_documentation<'a>(
+    comments: impl IntoIterator<Item = &'a Comment>,
+    store: &Store,
+) -> Documentation {
+    let mut result: Vec<DocumentationLine> = vec![];
+    let mut lines: Vec<DocumentationLine> = vec![];
+    for comment in comments {
+        match comment {
+            Comment::Line(comment)
+                if comment.kind == CommentKind::Documentation =>
+            {
+                lines.push(line_comment_line(comment, store))
+            }
+            Comment::Block(block)
+                if block.kind == CommentKind::Documentation =>
+            {
+                dedent(&mut lines);
+                result.append(&mut lines);
+                let mut block_lines = block_comment_lines(block, store);
+                if let Some(first) = block_lines.first_mut() {
+                    dedent(std::slice::from_mut(first));
+                }
+                if block_lines.len() > 1 {
+                    dedent(&mut block_lines[1..]);
+                }
+                result.append(&mut block_lines);
+            }
+            _ => (),
+        }
+    }
+    dedent(&mut lines);
+    result.append(&mut lines);
+    let start = result.iter().position(|l| !l.text.is_empty());
+    let end = result.iter().rposition(|l| !l.text.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => {
+            Documentation(result.drain(start..=end).collect())
+        }
+        _ => Documentation::default(),
+    }
+}
+
+#[cfg(test)]
+mod extraction_test {
+    use super::*;
+    use octizys_common::source_map::FileId;
+    use octizys_parser::parser::parse_string;
+    use octizys_sast::modules::{imports, interface, ModuleNode};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn documentation_of(
+        files: &[(&str, &str)],
+        include_private: bool,
+    ) -> Vec<ModuleDocumentation> {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let mut graph = ModuleGraph::new();
+        for (name, source) in files {
+            let top = parse_string(source, None, store.clone()).unwrap();
+            let store = store.borrow();
+            let name = ModuleName::from_file(
+                std::path::Path::new(""),
+                std::path::Path::new(&name.replace("::", "/")),
+            )
+            .unwrap();
+            graph.add(ModuleNode {
+                name,
+                file: FileId::DETACHED,
+                imports: imports(&top, &store),
+                interface: interface(&top, &store),
+                top,
+            });
+        }
+        let store = store.borrow();
+        extract_documentation(&graph, include_private, &store)
+    }
+
+    #[test]
+    fn comments_attach_to_the_next_item() {
+        let modules = documentation_of(
+            &[(
+                "main",
+                "-- | A maybe.\n-- |   Indented.\npublic data Maybe a =\n  -- | Empty.\n  Nothing\n  | {- | Full\n     value. -} Just a;\n-- | Private.\nhidden = 1;",
+            )],
+            false,
+        );
+        let items = &modules[0].items;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].documentation.to_string(), "A maybe.\n  Indented.");
+        assert_eq!(items[0].signature.to_string(), "data Maybe a");
+        let constructors: Vec<(&str, String)> = items[0]
+            .constructors
+            .iter()
+            .map(|c| (c.name.as_str(), c.documentation.to_string()))
+            .collect();
+        assert_eq!(
+            constructors,
+            [
+                ("Nothing", String::from("Empty.")),
+                ("Just", String::from("Full\nvalue."))
+            ]
+        );
+    }
+
+    #[test]
+    fn signatures_link_other_modules() {
+        let modules = documentation_of(
+            &[
+                (
+                    "main",
+                    "-- | Base.\nimport a::b:: as c::;\nimport unqualified a::b::(T);\nf : forall x . T -> c::T -> x -> U;\npublic f = f;\ndata U = U;",
+                ),
+                ("a::b", "public data T = T;"),
+            ],
+            true,
+        );
+        let main = &modules[0];
+        assert_eq!(main.imports[0].documentation.to_string(), "Base.");
+        assert_eq!(
+            main.items[0].signature.to_string(),
+            "f : forall x . T -> c::T -> x -> U"
+        );
+        let references: Vec<(&str, String)> = main.items[0]
+            .signature
+            .references()
+            .into_iter()
+            .map(|(text, target)| (text, target.module.to_string()))
+            .collect();
+        assert_eq!(
+            references,
+            [("T", String::from("a::b")), ("U", String::from("main"))]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt::Write;
+
+use pulldown_cmark::{html::push_html, Parser};
+
+use crate::configuration::DocumentationFormat;
+use crate::documentation::{index_file, module_file};
+use crate::extraction::{
+    ItemDocumentation, ModuleDocumentation, Signature, SignaturePart, Target,
+};
+use octizys_sast::modules::ModuleName;
+
+const FORMAT: DocumentationFormat = DocumentationFormat::Html;
+
+const STYLE: &str =
+    "body{font-family:sans-serif;max-width:60em;margin:auto;padding:1em}\
+pre{background:#f4f4f4;padding:.5em;overflow-x:auto}\
+.private{color:#777;font-size:small}";
+
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// The link to a item from the page of `current`.
+fn item_link(current: &ModuleName, target: &Target) -> String {
+    if &target.module == current {
+        format!("#{}", target.anchor())
+    } else {
+        format!(
+            "{}#{}",
+            module_file(&target.module, FORMAT),
+            target.anchor()
+        )
+    }
+}
+
+fn signature(current: &ModuleName, signature: &Signature) -> String {
+    let mut result = String::new();
+    for part in &signature.0 {
+        match part {
+            SignaturePart::Text(text) => result.push_str(&escape(text)),
+            SignaturePart::Item { text, target } => {
+                let _ = write!(
+                    result,
+                    "<a href=\"{}\">{}</a>",
+                    escape(&item_link(current, target)),
+                    escape(text)
+                );
+            }
+            SignaturePart::Module { text, module } => {
+                let _ = write!(
+                    result,
+                    "<a href=\"{}\">{}</a>",
+                    escape(&module_file(module, FORMAT)),
+                    escape(text)
+                );
+            }
+        }
+    }
+    result
+}
+
+fn markdown(text: &str) -> String {
+    let mut result = String::new();
+    push_html(&mut result, Parser::new(text));
+    result
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn item(
+    result: &mut String,
+    current: &ModuleName,
+    item: &ItemDocumentation,
+    level: usize,
+) {
+    let _ = writeln!(
+        result,
+        "<section id=\"{}\">\n<h{level}><code>{}</code></h{level}>",
+        escape(&item.anchor()),
+        escape(&item.name),
+    );
+    if !item.public {
+        result.push_str("<p class=\"private\">private</p>\n");
+    }
+    let _ = writeln!(
+        result,
+        "<pre><code>{}</code></pre>",
+        signature(current, &item.signature)
+    );
+    result.push_str(&markdown(&item.documentation.to_string()));
+    if !item.constructors.is_empty() {
+        let _ =
+            writeln!(result, "<h{}>Constructors</h{}>", level + 1, level + 1);
+        for constructor in &item.constructors {
+            self::item(result, current, constructor, level + 2);
+        }
+    }
+    result.push_str("</section>\n");
+}
+
+pub fn module_page(module: &ModuleDocumentation) -> String {
+    let title = format!("Module {}", module.name);
+    let mut body = String::new();
+    let _ = writeln!(
+        body,
+        "<nav><a href=\"{}\">Modules</a></nav>\n<h1>Module <code>{}</code></h1>",
+        index_file(FORMAT),
+        escape(&module.name.to_string())
+    );
+    if !module.imports.is_empty() {
+        body.push_str("<h2>Imports</h2>\n<ul>\n");
+        for import in &module.imports {
+            let _ = writeln!(
+                body,
+                "<li><code>{}</code>{}</li>",
+                signature(&module.name, &import.signature),
+                markdown(&import.documentation.to_string())
+            );
+        }
+        body.push_str("</ul>\n");
+    }
+    if !module.items.is_empty() {
+        body.push_str("<h2>Items</h2>\n");
+        for i in &module.items {
+            item(&mut body, &module.name, i, 3);
+        }
+    }
+    page(&title, &body)
+}
+
+pub fn index_page(modules: &[ModuleDocumentation]) -> String {
+    let mut body = String::from("<h1>Modules</h1>\n<ul>\n");
+    for module in modules {
+        let _ = writeln!(
+            body,
+            "<li><a href=\"{}\"><code>{}</code></a></li>",
+            escape(&module_file(&module.name, FORMAT)),
+            escape(&module.name.to_string())
+        );
+    }
+    body.push_str("</ul>\n");
+    page("Modules", &body)
+}
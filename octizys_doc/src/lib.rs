@@ -1,17 +1,6 @@
 pub mod configuration;
+pub mod doctest;
 pub mod documentation;
-
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub mod extraction;
+pub mod html;
+pub mod markdown;
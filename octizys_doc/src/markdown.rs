@@ -0,0 +1,122 @@
+use std::fmt::Write;
+
+use crate::configuration::DocumentationFormat;
+use crate::documentation::{index_file, module_file};
+use crate::extraction::{
+    ItemDocumentation, ModuleDocumentation, SignaturePart, Target,
+};
+use octizys_sast::modules::ModuleName;
+
+const FORMAT: DocumentationFormat = DocumentationFormat::Markdown;
+
+/// The link to a item from the page of `current`.
+fn item_link(current: &ModuleName, target: &Target) -> String {
+    if &target.module == current {
+        format!("#{}", target.anchor())
+    } else {
+        format!(
+            "{}#{}",
+            module_file(&target.module, FORMAT),
+            target.anchor()
+        )
+    }
+}
+
+/// The code blocks of Markdown can't have links, the items used by
+/// a signature are listed after it.
+fn references(
+    result: &mut String,
+    current: &ModuleName,
+    item: &ItemDocumentation,
+) {
+    let references = item.signature.references();
+    if references.is_empty() {
+        return;
+    }
+    let links: Vec<String> = references
+        .into_iter()
+        .map(|(text, target)| {
+            format!("[`{}`]({})", text, item_link(current, target))
+        })
+        .collect();
+    let _ = writeln!(result, "Uses: {}\n", links.join(", "));
+}
+
+fn item(
+    result: &mut String,
+    current: &ModuleName,
+    item: &ItemDocumentation,
+    level: usize,
+) {
+    let _ = writeln!(
+        result,
+        "<a id=\"{}\"></a>\n\n{} `{}`\n",
+        item.anchor(),
+        "#".repeat(level),
+        item.name
+    );
+    if !item.public {
+        result.push_str("*private*\n\n");
+    }
+    let _ = writeln!(result, "```octizys\n{}\n```\n", item.signature);
+    references(result, current, item);
+    if !item.documentation.is_empty() {
+        let _ = writeln!(result, "{}\n", item.documentation);
+    }
+    if !item.constructors.is_empty() {
+        let _ = writeln!(result, "{} Constructors\n", "#".repeat(level + 1));
+        for constructor in &item.constructors {
+            self::item(result, current, constructor, level + 2);
+        }
+    }
+}
+
+pub fn module_page(module: &ModuleDocumentation) -> String {
+    let mut result = String::new();
+    let _ = writeln!(
+        result,
+        "# Module `{}`\n\n[Modules]({})\n",
+        module.name,
+        index_file(FORMAT)
+    );
+    if !module.imports.is_empty() {
+        result.push_str("## Imports\n\n");
+        for import in &module.imports {
+            let text = format!("`{}`", import.signature);
+            let line = match import.signature.0.iter().find_map(|p| match p {
+                SignaturePart::Module { module, .. } => Some(module),
+                _ => None,
+            }) {
+                Some(module) => {
+                    format!("[{}]({})", text, module_file(module, FORMAT))
+                }
+                None => text,
+            };
+            let _ = writeln!(result, "- {}", line);
+            for documentation in import.documentation.0.iter() {
+                let _ = writeln!(result, "  {}", documentation.text);
+            }
+        }
+        result.push('\n');
+    }
+    if !module.items.is_empty() {
+        result.push_str("## Items\n\n");
+        for i in &module.items {
+            item(&mut result, &module.name, i, 3);
+        }
+    }
+    result
+}
+
+pub fn index_page(modules: &[ModuleDocumentation]) -> String {
+    let mut result = String::from("# Modules\n\n");
+    for module in modules {
+        let _ = writeln!(
+            result,
+            "- [`{}`]({})",
+            module.name,
+            module_file(&module.name, FORMAT)
+        );
+    }
+    result
+}
@@ -15,13 +15,13 @@ use octizys_cst::{
     },
     imports::{AsPath, Import},
     literals::{
-        InterpolationString, StringLiteral, UFloatingPointLiteral, UintKind,
-        UintLiteral,
+        CharLiteral, InterpolationComponent, InterpolationString, StringKind,
+        StringLiteral, UFloatingPointLiteral, UintLiteral,
     },
     patterns::{PatternMatch, PatternMatchBind, PatternMatchRecordItem},
     top::{
-        Alias, Constructor, Data, DataConstructors, NewType, Top, TopItem,
-        TopTypeDefinitionLeft, TopTypeName,
+        Alias, Constructor, Data, DataConstructors, Declaration, Definition,
+        NewType, Top, TopItem, TopTypeDefinitionLeft, TopTypeName,
     },
     types::{Type, TypeRecordItem},
 };
@@ -29,7 +29,7 @@ use octizys_pretty::{
     combinators::{
         concat, concat_iter, empty, empty_break, external_text, group,
         hard_break, intersperse, nest, no_break_space, parens, repeat,
-        soft_break, static_str,
+        soft_break, static_str, verbatim_text,
     },
     document::Document,
 };
@@ -137,7 +137,6 @@ impl ToDocument<PrettyCSTConfiguration> for CommentBlock {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
         let (brace_start0, brace_end) = comment_brace_to_documents(&self.brace);
         let block_start = brace_start0 + self.kind.to_document(configuration);
-        let block_end = self.kind.to_document(configuration) + brace_end;
         let content_raw = intersperse(
             self.content.iter().map(|x| x.to_document(configuration)),
             hard_break(),
@@ -149,7 +148,9 @@ impl ToDocument<PrettyCSTConfiguration> for CommentBlock {
             content_raw
         };
 
-        concat(vec![block_start, content, block_end, hard_break()])
+        // The content is verbatim, with the spaces of the author and the
+        // nested comments, only the documentation mark is normalized.
+        concat(vec![block_start, content, brace_end, hard_break()])
     }
 }
 
@@ -259,7 +260,7 @@ impl ToDocument<PrettyCSTConfiguration> for OperatorName {
             OperatorName::Appliative => keywords::APPLIATIVE,
             OperatorName::ApplicativeRight => keywords::APPLICATIVE_RIGHT,
             OperatorName::ApplicativeLeft => keywords::APPLICATIVE_LEFT,
-            OperatorName::Equality => keywords::ASIGNATION,
+            OperatorName::Equality => keywords::EQUALITY,
             OperatorName::NotEqual => keywords::NOT_EQUAL,
             OperatorName::LessOrEqual => keywords::LESS_OR_EQUAL,
             OperatorName::MoreOrEqual => keywords::MORE_OR_EQUAL,
@@ -289,27 +290,40 @@ where
     T: ToDocument<PrettyCSTConfiguration>,
 {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
-        let (start_str, end_str) = Enclosure::to_strs();
-        let (start, end) = (
-            Document::static_str(start_str),
-            Document::static_str(end_str),
-        );
-        //TODO: after the refactor, lookup if there are comments
-        //inside the span of this between, if they are,
-        //enforce the ")" to end in a separate line always
-        //otherwise keep this.
-        token_info_to_document(&self.left, configuration, start)
-            + group(concat(vec![
-                nest(
-                    configuration.indentation_deep,
-                    empty_break() + self.value.to_document(configuration),
-                ),
-                empty_break(),
-                token_info_to_document(&self.right, configuration, end),
-            ]))
+        between_to_document(self, configuration, empty_break())
     }
 }
 
+/// The `inner_break` goes between the delimiters and the content.
+fn between_to_document<T, Enclosure>(
+    between: &Between<T, Enclosure>,
+    configuration: &PrettyCSTConfiguration,
+    inner_break: Document,
+) -> Document
+where
+    Enclosure: Delimiters,
+    T: ToDocument<PrettyCSTConfiguration>,
+{
+    let (start_str, end_str) = Enclosure::to_strs();
+    let (start, end) = (
+        Document::static_str(start_str),
+        Document::static_str(end_str),
+    );
+    //TODO: after the refactor, lookup if there are comments
+    //inside the span of this between, if they are,
+    //enforce the ")" to end in a separate line always
+    //otherwise keep this.
+    token_info_to_document(&between.left, configuration, start)
+        + group(concat(vec![
+            nest(
+                configuration.indentation_deep,
+                inner_break.clone() + between.value.to_document(configuration),
+            ),
+            inner_break,
+            token_info_to_document(&between.right, configuration, end),
+        ]))
+}
+
 impl ToDocument<PrettyCSTConfiguration> for ImportedVariable {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
         concat(vec![
@@ -388,30 +402,86 @@ where
 
 impl ToDocument<PrettyCSTConfiguration> for UintLiteral {
     fn to_document(&self, _configuration: &PrettyCSTConfiguration) -> Document {
-        external_text(&match self.kind {
-            UintKind::Hex => format!("{:x}", self.value),
-            UintKind::Octal => format!("{:o}", self.value),
-            UintKind::Binary => format!("{:b}", self.value),
-            _ => format!("{}", self.value),
-        })
+        external_text(&self.to_string())
     }
 }
 
+// The line breaks inside strings are part of their values, they can't
+// get the indentation of the surrounding code. The text blocks are the
+// exception, the lexer removes the common indentation of their lines,
+// so they are written at the current indentation. The empty lines don't
+// get indentation as the trailing spaces would be part of them.
 impl ToDocument<PrettyCSTConfiguration> for StringLiteral {
     fn to_document(&self, _configuration: &PrettyCSTConfiguration) -> Document {
-        todo!("Formatting for strings is not supported yet!")
+        if self.kind != StringKind::TextBlock {
+            return verbatim_text(&self.to_string());
+        }
+        static_str(keywords::TEXT_BLOCK)
+            + concat(
+                self.text_block_lines()
+                    .iter()
+                    .map(|line| {
+                        if line.is_empty() {
+                            verbatim_text("\n")
+                        } else {
+                            hard_break() + external_text(line)
+                        }
+                    })
+                    .collect(),
+            )
+            + hard_break()
+            + static_str(keywords::TEXT_BLOCK)
     }
 }
 
 impl ToDocument<PrettyCSTConfiguration> for InterpolationString {
+    fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
+        concat(vec![
+            static_str(keywords::INTERPOLATION_START),
+            concat(
+                self.value
+                    .iter()
+                    .map(|component| match component {
+                        InterpolationComponent::RegularString(s) => {
+                            verbatim_text(s)
+                        }
+                        InterpolationComponent::Scaped { spelling, .. } => {
+                            external_text(spelling)
+                        }
+                        InterpolationComponent::Interpolation {
+                            expression,
+                            format,
+                            ..
+                        } => {
+                            let format = match format {
+                                Some(format) => {
+                                    static_str(keywords::COLON)
+                                        + external_text(&format.to_string())
+                                }
+                                None => empty(),
+                            };
+                            static_str(keywords::LBRACE)
+                                + expression.to_document(configuration)
+                                + format
+                                + static_str(keywords::RBRACE)
+                        }
+                    })
+                    .collect(),
+            ),
+            static_str(keywords::QUOTE),
+        ])
+    }
+}
+
+impl ToDocument<PrettyCSTConfiguration> for CharLiteral {
     fn to_document(&self, _configuration: &PrettyCSTConfiguration) -> Document {
-        todo!("Formatting for strings is not supported yet!")
+        external_text(&self.to_string())
     }
 }
 
 impl ToDocument<PrettyCSTConfiguration> for UFloatingPointLiteral {
     fn to_document(&self, _configuration: &PrettyCSTConfiguration) -> Document {
-        todo!("Formatting for floating point is not supported yet!")
+        external_text(&self.to_string())
     }
 }
 
@@ -477,11 +547,13 @@ impl ToDocument<PrettyCSTConfiguration> for PatternMatchRecordItem {
                 pattern,
             } => concat(vec![
                 variable.to_document(configuration),
+                no_break_space(),
                 token_info_to_document(
                     separator,
                     configuration,
-                    Document::static_str(keywords::COLON),
+                    Document::static_str(keywords::ASIGNATION),
                 ),
+                no_break_space(),
                 pattern.to_document(configuration),
             ]),
         }
@@ -537,7 +609,15 @@ impl ToDocument<PrettyCSTConfiguration> for PatternMatch {
             }
             PatternMatch::String(tok) => tok.to_document(configuration),
             PatternMatch::UFloat(tok) => tok.to_document(configuration),
+            PatternMatch::Char(tok) => tok.to_document(configuration),
             PatternMatch::Uint(tok) => tok.to_document(configuration),
+            PatternMatch::Negative { minus, literal } => {
+                token_info_to_document(
+                    minus,
+                    configuration,
+                    static_str(keywords::HYPHEN),
+                ) + literal.to_document(configuration)
+            }
             PatternMatch::AnonHole(info) => token_info_to_document(
                 info,
                 configuration,
@@ -550,13 +630,18 @@ impl ToDocument<PrettyCSTConfiguration> for PatternMatch {
                 start,
                 second,
                 remain,
-            } => concat(vec![
-                to_document_pattern_application_argument(start, configuration),
-                to_document_pattern_application_argument(second, configuration),
-                concat_iter(remain.into_iter().map(|x| {
-                    to_document_pattern_application_argument(x, configuration)
-                })),
-            ]),
+            } => intersperse(
+                [start.as_ref(), second.as_ref()]
+                    .into_iter()
+                    .chain(remain.iter())
+                    .map(|x| {
+                        to_document_pattern_application_argument(
+                            x,
+                            configuration,
+                        )
+                    }),
+                no_break_space(),
+            ),
             PatternMatch::Parens(x) => x.to_document(configuration),
         }
     }
@@ -636,7 +721,7 @@ impl ToDocument<PrettyCSTConfiguration> for Type {
                             ),
                             soft_break(),
                             intersperse(
-                                remain.into_iter().map(|x| {
+                                remain.iter().map(|x| {
                                     to_document_type_application_argument(
                                         x,
                                         configuration,
@@ -648,9 +733,8 @@ impl ToDocument<PrettyCSTConfiguration> for Type {
                     )
             }
             Type::Arrow { first, remain } => {
-                let remain_doc = remain
-                    .into_iter()
-                    .map(|arg| arg.to_document(configuration));
+                let remain_doc =
+                    remain.iter().map(|arg| arg.to_document(configuration));
                 to_document_type_arrow_arguments(
                     (*first).as_ref(),
                     configuration,
@@ -676,7 +760,7 @@ impl ToDocument<PrettyCSTConfiguration> for Type {
                         soft_break(),
                         intersperse(
                             remain_variables
-                                .into_iter()
+                                .iter()
                                 .map(|x| x.to_document(configuration)),
                             soft_break(),
                         ),
@@ -728,10 +812,11 @@ impl ToDocument<PrettyCSTConfiguration> for CaseItem {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
         concat(vec![
             self.pattern.to_document(configuration),
+            no_break_space(),
             token_info_to_document(
                 &self.arrow,
                 configuration,
-                Document::static_str(keywords::MORE_OR_EQUAL),
+                Document::static_str(keywords::RIGHT_ARROW),
             ),
             indent(
                 configuration,
@@ -742,19 +827,20 @@ impl ToDocument<PrettyCSTConfiguration> for CaseItem {
 }
 impl ToDocument<PrettyCSTConfiguration> for Let {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
-        concat(vec![
+        group(concat(vec![
             token_info_to_document(
                 &self.let_,
                 configuration,
                 Document::static_str(keywords::LET),
             ),
-            indent(
-                configuration,
+            nest(
+                configuration.indentation_deep,
                 soft_break()
-                    + concat_iter(
+                    + intersperse(
                         self.bindings
                             .iter()
                             .map(|x| x.to_document(configuration)),
+                        soft_break(),
                     ),
             ),
             soft_break(),
@@ -763,78 +849,111 @@ impl ToDocument<PrettyCSTConfiguration> for Let {
                 configuration,
                 Document::static_str(keywords::IN),
             ),
-            indent(
-                configuration,
+            nest(
+                configuration.indentation_deep,
                 soft_break() + self.expression.to_document(configuration),
             ),
-        ])
+        ]))
     }
 }
 
 impl ToDocument<PrettyCSTConfiguration> for Case {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
         concat(vec![
-            token_info_to_document(
-                &self.case,
-                configuration,
-                Document::static_str(keywords::CASE),
-            ),
-            indent(
-                configuration,
-                soft_break() + self.expression.to_document(configuration),
-            ),
-            token_info_to_document(
-                &self.of,
-                configuration,
-                Document::static_str(keywords::OF),
-            ),
+            group(concat(vec![
+                token_info_to_document(
+                    &self.case,
+                    configuration,
+                    Document::static_str(keywords::CASE),
+                ),
+                nest(
+                    configuration.indentation_deep,
+                    soft_break() + self.expression.to_document(configuration),
+                ),
+                soft_break(),
+                token_info_to_document(
+                    &self.of,
+                    configuration,
+                    Document::static_str(keywords::OF),
+                ),
+            ])),
+            no_break_space(),
             //TODO: finish this, we need a cases especific to_document instead of the default for
             //between
-            self.cases.to_document(configuration),
+            // `{-` starts a block comment, a negative pattern can't
+            // follow the brace.
+            match self.cases.value.first.pattern {
+                PatternMatch::Negative { .. } => between_to_document(
+                    &self.cases,
+                    configuration,
+                    soft_break(),
+                ),
+                _ => self.cases.to_document(configuration),
+            },
         ])
     }
 }
 
 impl ToDocument<PrettyCSTConfiguration> for BinaryOperator {
-    fn to_document(&self, _configuration: &PrettyCSTConfiguration) -> Document {
-        todo!("Formatting for BinaryOperator delayed")
+    fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
+        group(
+            self.left.to_document(configuration)
+                + nest(
+                    configuration.indentation_deep,
+                    concat(vec![
+                        soft_break(),
+                        self.name.to_document(configuration),
+                        no_break_space(),
+                        self.right.to_document(configuration),
+                    ]),
+                ),
+        )
     }
 }
 impl ToDocument<PrettyCSTConfiguration> for LambdaExpression {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
-        static_str(keywords::LAMBDA_START)
-            + nest(
-                configuration.indentation_deep,
-                soft_break() + self.variable.to_document(configuration),
-            )
-            + static_str(keywords::RIGHT_ARROW)
-            + soft_break()
-            + nest(
+        group(concat(vec![
+            token_info_to_document(
+                &self.start,
+                configuration,
+                static_str(keywords::LAMBDA_START),
+            ),
+            self.variable.to_document(configuration),
+            no_break_space(),
+            token_info_to_document(
+                &self.arrow,
+                configuration,
+                static_str(keywords::RIGHT_ARROW),
+            ),
+            nest(
                 configuration.indentation_deep,
-                group(self.expression.to_document(configuration)),
-            )
+                soft_break() + self.expression.to_document(configuration),
+            ),
+        ]))
     }
 }
 
 impl ToDocument<PrettyCSTConfiguration> for ApplicationExpression {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
-        self.start.to_document(configuration)
-            + nest(
-                configuration.indentation_deep,
-                soft_break()
-                    + intersperse(
-                        self.remain.iter().map(|x| {
-                            let var = x.to_document(configuration);
-
-                            if x.need_parens() {
-                                parens(var)
-                            } else {
-                                var
-                            }
-                        }),
-                        soft_break(),
-                    ),
-            )
+        group(
+            self.start.to_document(configuration)
+                + nest(
+                    configuration.indentation_deep,
+                    soft_break()
+                        + intersperse(
+                            self.remain.iter().map(|x| {
+                                let var = x.to_document(configuration);
+
+                                if x.need_parens() {
+                                    parens(var)
+                                } else {
+                                    var
+                                }
+                            }),
+                            soft_break(),
+                        ),
+                ),
+        )
     }
 }
 
@@ -854,7 +973,7 @@ impl ToDocument<PrettyCSTConfiguration> for ExpressionRecordItem {
                     + token_info_to_document(
                         equal,
                         configuration,
-                        static_str(keywords::EQUALITY),
+                        static_str(keywords::ASIGNATION),
                     )
                     + nest(
                         configuration.indentation_deep,
@@ -867,14 +986,77 @@ impl ToDocument<PrettyCSTConfiguration> for ExpressionRecordItem {
 }
 
 impl ToDocument<PrettyCSTConfiguration> for ExpressionSelector {
-    fn to_document(&self, _configuration: &PrettyCSTConfiguration) -> Document {
-        todo!()
+    fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
+        self.expression.to_document(configuration)
+            + token_info_to_document(
+                &self.accessor.info,
+                configuration,
+                static_str(keywords::DOT)
+                    + self.accessor.value.to_document(configuration),
+            )
     }
 }
 
 impl ToDocument<PrettyCSTConfiguration> for Expression {
-    fn to_document(&self, _configuration: &PrettyCSTConfiguration) -> Document {
-        todo!()
+    fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
+        match self {
+            Expression::String(token) => token.to_document(configuration),
+            Expression::InterpolationString(token) => {
+                token.to_document(configuration)
+            }
+            Expression::Uint(token) => token.to_document(configuration),
+            Expression::UFloat(token) => token.to_document(configuration),
+            Expression::Char(token) => token.to_document(configuration),
+            Expression::Negation { minus, expression } => {
+                token_info_to_document(
+                    minus,
+                    configuration,
+                    static_str(keywords::HYPHEN),
+                ) + expression.to_document(configuration)
+            }
+            Expression::LocalVariable(token) => {
+                token.to_document(configuration)
+            }
+            Expression::ImportedVariable(token) => {
+                token.to_document(configuration)
+            }
+            Expression::NamedHole(token) => token_info_to_document(
+                &token.info,
+                configuration,
+                static_str(keywords::UNDERSCORE)
+                    + external_text(&token.value.to_string()),
+            ),
+            Expression::Tuple(between) => between.to_document(configuration),
+            Expression::Record(between) => between.to_document(configuration),
+            Expression::Case(case) => case.to_document(configuration),
+            Expression::Parens(between) => between.to_document(configuration),
+            Expression::Selector(selector) => {
+                selector.to_document(configuration)
+            }
+            Expression::Interrogation { expression, symbol } => {
+                expression.to_document(configuration)
+                    + token_info_to_document(
+                        symbol,
+                        configuration,
+                        static_str(keywords::INTERROGATION),
+                    )
+            }
+            Expression::TypeArgument { at, type_ } => {
+                token_info_to_document(
+                    at,
+                    configuration,
+                    static_str(keywords::AT),
+                ) + to_document_type_application_argument(type_, configuration)
+            }
+            Expression::Let(let_) => let_.to_document(configuration),
+            Expression::BinaryOperator(operator) => {
+                operator.to_document(configuration)
+            }
+            Expression::Lambda(lambda) => lambda.to_document(configuration),
+            Expression::Application(application) => {
+                application.to_document(configuration)
+            }
+        }
     }
 }
 
@@ -882,15 +1064,21 @@ impl ToDocument<PrettyCSTConfiguration> for Expression {
 
 impl ToDocument<PrettyCSTConfiguration> for Constructor {
     fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
+        let public = self
+            .public
+            .as_ref()
+            .map(|x| x.to_document(configuration) + no_break_space())
+            .unwrap_or_else(empty);
         match &self.type_ {
             Some(x) => {
-                self.name.to_document(configuration)
+                public
+                    + self.name.to_document(configuration)
                     + nest(
                         configuration.indentation_deep,
                         soft_break() + x.to_document(configuration),
                     )
             }
-            None => self.name.to_document(configuration),
+            None => public + self.name.to_document(configuration),
         }
     }
 }
@@ -1004,6 +1192,62 @@ impl ToDocument<PrettyCSTConfiguration> for NewType {
     }
 }
 
+// --------------------------------- Values --------------------------------
+
+impl ToDocument<PrettyCSTConfiguration> for Declaration {
+    fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
+        let public = self
+            .public
+            .as_ref()
+            .map(|x| x.to_document(configuration) + no_break_space())
+            .unwrap_or_else(empty);
+        concat(vec![
+            public,
+            self.name.to_document(configuration),
+            no_break_space(),
+            token_info_to_document(
+                &self.colon,
+                configuration,
+                static_str(keywords::COLON),
+            ),
+            indent(
+                configuration,
+                soft_break() + self.type_.to_document(configuration),
+            ),
+        ])
+    }
+}
+
+impl ToDocument<PrettyCSTConfiguration> for Definition {
+    fn to_document(&self, configuration: &PrettyCSTConfiguration) -> Document {
+        let arguments = concat_iter(
+            self.arguments
+                .iter()
+                .map(|x| no_break_space() + x.to_document(configuration)),
+        );
+        let public = self
+            .public
+            .as_ref()
+            .map(|x| x.to_document(configuration) + no_break_space())
+            .unwrap_or_else(empty);
+        concat(vec![
+            public,
+            self.name.to_document(configuration),
+            arguments,
+            no_break_space(),
+            token_info_to_document(
+                &self.eq,
+                configuration,
+                static_str(keywords::ASIGNATION),
+            ),
+            indent(
+                configuration,
+                soft_break() + self.value.to_document(configuration),
+            ),
+        ])
+    }
+}
+
 // --------------------------------- Top --------------------------------
 
 impl ToDocument<PrettyCSTConfiguration> for TopItem {
@@ -1012,6 +1256,8 @@ impl ToDocument<PrettyCSTConfiguration> for TopItem {
             TopItem::Data(d) => d.to_document(configuration),
             TopItem::Alias(a) => a.to_document(configuration),
             TopItem::NewType(n) => n.to_document(configuration),
+            TopItem::Declaration(d) => d.to_document(configuration),
+            TopItem::Definition(d) => d.to_document(configuration),
         }
     }
 }
@@ -1036,6 +1282,29 @@ where
         + l.item.to_document(configuration)
 }
 
+/// The comments in the line of the last separator stay after it,
+/// there isn't a next item to move them before.
+fn last_separator_to_document(
+    separator: &TokenInfo,
+    configuration: &PrettyCSTConfiguration,
+) -> Document {
+    let before = CommentsInfo {
+        before: separator.comments.before.clone(),
+        after: vec![],
+    };
+    comments_info_to_document(
+        &before,
+        configuration,
+        static_str(SemiColon::to_str()),
+    ) + concat_iter(
+        separator
+            .comments
+            .after
+            .iter()
+            .map(|x| external_text(" ") + x.to_document(configuration)),
+    )
+}
+
 fn trailing_list_to_document_top<Item>(
     l: &TrailingList<Item, SemiColon>,
     configuration: &PrettyCSTConfiguration,
@@ -1050,7 +1319,12 @@ where
                 .iter()
                 .map(|x| trailing_list_item_to_document_top(x, configuration)),
         ),
-        static_str(SemiColon::to_str()),
+        match &l.trailing_sep {
+            Some(separator) => {
+                last_separator_to_document(separator, configuration)
+            }
+            None => static_str(SemiColon::to_str()),
+        },
     ])
 }
 
@@ -1062,26 +1336,90 @@ impl ToDocument<PrettyCSTConfiguration> for Top {
             .map(|x| trailing_list_to_document_top(x, configuration))
             .unwrap_or_else(empty);
         let separation = gen_top_separation(configuration);
+        let last_comments = intersperse(
+            self.last_comments
+                .iter()
+                .map(|x| x.to_document(configuration)),
+            repeat(hard_break(), usize::from(configuration.separe_comments_by)),
+        );
         match &self.imports {
-            Some(imports) => match &self.last_comment {
-                Some(last_comment) => {
+            Some(imports) => {
+                if self.last_comments.is_empty() {
                     trailing_list_to_document_top(imports, configuration)
                         + separation
                         + items
-                        + last_comment.to_document(configuration)
-                }
-                None => {
+                } else {
                     trailing_list_to_document_top(imports, configuration)
                         + separation
                         + items
+                        + last_comments
                 }
-            },
-            None => match &self.last_comment {
-                Some(last_comment) => {
-                    items + separation + last_comment.to_document(configuration)
+            }
+            None => {
+                if self.last_comments.is_empty() {
+                    items
+                } else {
+                    items + separation + last_comments
                 }
-                None => items,
-            },
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod cst_test {
+    use octizys_common::span::Position;
+    use octizys_cst::comments::{CommentBlock, CommentBraceKind, CommentKind};
+    use octizys_pretty::highlight::{EmptyRender, HighlightRenderer};
+    use octizys_text_store::store::Store;
+
+    use super::PrettyCSTConfiguration;
+    use crate::to_document::ToDocument;
+
+    fn render_block(
+        kind: CommentKind,
+        brace: CommentBraceKind,
+        content: &str,
+        indent_comment_blocks: bool,
+    ) -> String {
+        let mut store = Store::default();
+        let position = Position::default();
+        let block = CommentBlock::make(
+            kind, brace, content, position, position, &mut store,
+        );
+        let configuration = PrettyCSTConfiguration {
+            indent_comment_blocks,
+            ..Default::default()
+        };
+        block.to_document(&configuration).render_to_string(
+            80,
+            EmptyRender::render_highlight,
+            &store,
+        )
+    }
+
+    #[test]
+    fn nested_block_comments() {
+        let result = render_block(
+            CommentKind::NonDocumentation,
+            CommentBraceKind::Brace0,
+            "  outer {- inner -} ",
+            false,
+        );
+        assert_eq!(result, "{-  outer {- inner -} -}\n");
+        let result = render_block(
+            CommentKind::Documentation,
+            CommentBraceKind::Brace1,
+            " doc {- a {--- b {---- c ----} ---} -} ",
+            false,
+        );
+        assert_eq!(result, "{-- | doc {- a {--- b {---- c ----} ---} -} --}\n");
+        let result = render_block(
+            CommentKind::NonDocumentation,
+            CommentBraceKind::Brace2,
+            "a {- b\n  {-- c --} -}",
+            true,
+        );
+        assert_eq!(result, "{---\n  a {- b\n    {-- c --} -}\n---}\n");
+    }
+}
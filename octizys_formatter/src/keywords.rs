@@ -12,7 +12,7 @@ make_keywords!(
     ,PUBLIC :"public"
     ,UNQUALIFIED : "unqualified"
     ,FORALL : "forall"
-    ,CASE:"forall"
+    ,CASE:"case"
     ,OF: "of"
     ,AS : "as"
     ,LET: "let"
@@ -30,6 +30,9 @@ make_keywords!(
     ,INTERROGATION : "?"
     ,EXCLAMATION : "!"
     ,HASH : "#"
+    ,QUOTE : "\""
+    ,INTERPOLATION_START : "f#\""
+    ,TEXT_BLOCK : "\"\"\""
     ,COMMA : ","
     ,COLON : ":"
     ,SEMICOLON : ";"
@@ -53,7 +56,7 @@ make_keywords!(
     ,EQUALITY : "=="
     ,NOT_EQUAL : "!="
     ,LESS_OR_EQUAL : "<="
-    ,MORE_OR_EQUAL : "=>"
+    ,MORE_OR_EQUAL : ">="
     ,LESS_THAN : "<"
     ,MORE_THAN : ">"
     ,AND : "&&"
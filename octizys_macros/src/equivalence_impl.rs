@@ -4,7 +4,7 @@ use syn::DeriveInput;
 
 use crate::equivalence_parser::{
     parse_input, DataFieldName, EquivalenceInput, GeneratorArguments,
-    OtherFieldName, SelfFieldName, StructCaseName,
+    GeneratorField, OtherFieldName, SelfFieldName, StructCaseName,
 };
 
 fn generate_equivalent_body(
@@ -341,7 +341,7 @@ fn generate_equivalence_or_diff(
 }
 
 fn from_generict_param_to_vec(
-    v: &Vec<(SelfFieldName, OtherFieldName, bool)>,
+    v: &Vec<GeneratorField>,
 ) -> Vec<(SelfFieldName, OtherFieldName)> {
     let mut out: Vec<(SelfFieldName, OtherFieldName)> =
         Vec::with_capacity(v.len());
@@ -94,11 +94,14 @@ impl StructCaseName {
     }
 }
 
-/// The last boolean represents if the field is ignored or not.
+/// The names of a field, the boolean represents if the field is
+/// ignored or not.
+pub(crate) type GeneratorField = (SelfFieldName, OtherFieldName, bool);
+
 #[derive(Debug, Clone)]
 pub(crate) enum GeneratorArguments {
-    Struct(Vec<(SelfFieldName, OtherFieldName, bool)>),
-    Enum(Vec<(syn::Ident, Vec<(SelfFieldName, OtherFieldName, bool)>)>),
+    Struct(Vec<GeneratorField>),
+    Enum(Vec<(syn::Ident, Vec<GeneratorField>)>),
 }
 
 pub(crate) struct EquivalenceInput {
@@ -213,7 +216,7 @@ fn parse_type_parameters(input: &DeriveInput) -> Vec<syn::Ident> {
 fn self_and_other_from_fields(
     fields: &syn::Fields,
     is_enum: bool,
-) -> Vec<(SelfFieldName, OtherFieldName, bool)> {
+) -> Vec<GeneratorField> {
     match fields {
         syn::Fields::Named(named_fields) => {
             let local_fields = named_fields.named.clone();
@@ -265,10 +268,7 @@ fn parse_generators_arguments(data: &syn::Data) -> GeneratorArguments {
             self_and_other_from_fields(&s.fields, false),
         ),
         syn::Data::Enum(e) => {
-            let mut acc: Vec<(
-                syn::Ident,
-                Vec<(SelfFieldName, OtherFieldName, bool)>,
-            )> = vec![];
+            let mut acc: Vec<(syn::Ident, Vec<GeneratorField>)> = vec![];
             for variant in e.variants.iter() {
                 acc.push((
                     variant.ident.clone(),
@@ -3,6 +3,7 @@ use octizys_common::{
     identifier::Identifier,
     logic_path::LogicPath,
     report::{ReportFormat, ReportKind, ReportTarget},
+    source_map::FileId,
     span::{HasLocation, Location, Position, Span},
 };
 use octizys_cst::{
@@ -11,10 +12,12 @@ use octizys_cst::{
         Comment, CommentBlock, CommentBraceKind, CommentKind, CommentLine,
         CommentLineContent, CommentsInfo, LineCommentStart,
     },
-    expressions::Let,
+    expressions::{Expression, Let},
     literals::{
-        InterpolationString, StringLiteral, UFloatingPointLiteral, UintKind,
-        UintLiteral,
+        CharLiteral, ExponentSign, FloatExponent, FormatSpec,
+        InterpolationComponent, InterpolationString, StringComponent,
+        StringKind, StringLiteral, UFloatingPointKind, UFloatingPointLiteral,
+        UintBase, UintKind, UintLiteral,
     },
     types::{OwnershipLiteral, OwnershipVariable},
 };
@@ -24,15 +27,14 @@ use octizys_pretty::{
     document::Document,
     store::NonLineBreakStr,
 };
-use octizys_text_store::store::{approximate_string_width, Store};
+use octizys_text_store::store::Store;
 
+use lalrpop_util::ParseError;
 use regex::{Captures, Match, Regex};
-use std::{
-    borrow::BorrowMut, cell::RefCell, num::ParseIntError, rc::Rc,
-    sync::LazyLock,
-};
+use std::{borrow::BorrowMut, cell::RefCell, rc::Rc, sync::LazyLock};
 
 use crate::{
+    grammar::expressionParser,
     report::{LexerReportKind, OctizysParserReport, ParserReport},
     tokens::aux_base_token_to_token,
 };
@@ -80,44 +82,31 @@ fn make_error_report_with_position<T>(
 }
 
 fn make_block_comment(
-    re: &Regex,
     context: &mut BaseLexerContext,
     m: Match,
     brace_kind: CommentBraceKind,
 ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
     let matched = m.as_str();
-    match re.captures(&context.index) {
-        Some(c) => {
-            let kind = match c.name("doc") {
-                Some(_) => CommentKind::Documentation,
-                None => CommentKind::NonDocumentation,
-            };
-            let span =
-                context.advance_with_line_breaks(c.get(0).unwrap().as_str());
-            match c.name("content") {
-                Some(content) => {
-                    let comment = CommentBlock::make(
-                        kind,
-                        brace_kind,
-                        content.as_str(),
-                        span.start,
-                        span.end,
-                        &mut *(*context.store).borrow_mut(),
-                    );
-                    Some(Ok((span, BaseToken::BlockComment(comment))))
-                }
-                //TODO: This is most likely for a non closed comment, we may look for possible
-                //closings of the comment, store that in the info?
-                None => make_error_report_with_span(
-                    LexerReportKind::NonFinishedLineComment,
-                    span,
-                ),
-            }
+    let index = context.index;
+    match scan_block_comment(index, brace_kind.len() - 1) {
+        Ok(scan) => {
+            let span = context.advance_with_line_breaks(&index[..scan.length]);
+            let (from, to) = scan.content;
+            let comment = CommentBlock::make(
+                scan.kind,
+                brace_kind,
+                &index[from..to],
+                span.start,
+                span.end,
+                &mut *(*context.store).borrow_mut(),
+            );
+            Some(Ok((span, BaseToken::BlockComment(comment))))
         }
-        None => {
+        Err(open) => {
+            let end = context.end_position();
             let span = context.advance_non_line_breaks(matched);
             make_error_report_with_span(
-                LexerReportKind::CouldntMatchBlockComment(brace_kind),
+                LexerReportKind::CouldntMatchBlockComment(open, end),
                 span,
             )
         }
@@ -131,7 +120,7 @@ fn make_line_comment(
     line_start: LineCommentStart,
 ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
     let matched = m.as_str();
-    match SLASH_COMMENT.captures(&context.index) {
+    match re.captures(context.index) {
         Some(c) => {
             let kind = match c.name("doc") {
                 Some(_) => CommentKind::Documentation,
@@ -178,17 +167,38 @@ fn make_line_comment(
 }
 
 impl<'store, 'source> BaseLexerContext<'source> {
-    //TODO: make this function capable to start everywhere in the source
     pub fn new(source: &'source str, store: Rc<RefCell<Store>>) -> Self {
+        BaseLexerContext::new_at(source, Position::default(), store)
+    }
+
+    /// Like [`BaseLexerContext::new`] but the source is a piece of a
+    /// bigger source that begins at the given [`Position`].
+    pub fn new_at(
+        source: &'source str,
+        position: Position,
+        store: Rc<RefCell<Store>>,
+    ) -> Self {
         BaseLexerContext {
             source,
             index: source,
-            position: Position::default(),
-            last_line: 0,
+            position,
+            last_line: position.line,
             store,
         }
     }
 
+    /// Like [`BaseLexerContext::new`] but the positions refer to the given
+    /// file of a [`octizys_common::source_map::SourceMap`].
+    pub fn new_in_file(
+        source: &'source str,
+        file: FileId,
+        store: Rc<RefCell<Store>>,
+    ) -> Self {
+        let mut context = BaseLexerContext::new(source, store);
+        context.position.file = file;
+        context
+    }
+
     fn advance_with_line_breaks(&mut self, s: &str) -> Span {
         //println!("Advancing spaces! {s},size={:}", s.len());
         let start = self.position;
@@ -210,6 +220,22 @@ impl<'store, 'source> BaseLexerContext<'source> {
         }
     }
 
+    /// The [`Position`] at the end of the source.
+    fn end_position(&self) -> Position {
+        let mut position = self.position;
+        position.source_index += self.index.len();
+        for c in self.index.chars() {
+            match c {
+                '\n' => {
+                    position.line += 1;
+                    position.column = 0;
+                }
+                _ => position.column += c.len_utf8(),
+            }
+        }
+        position
+    }
+
     fn advance_non_line_breaks(&mut self, s: &str) -> Span {
         //println!("Advancing! {:}", s);
         let start = self.position;
@@ -248,30 +274,10 @@ impl<'store, 'source> BaseLexerContext<'source> {
                 m,
                 octizys_cst::comments::LineCommentStart::DoubleSlash,
             ),
-            "{-" => make_block_comment(
-                &*COMMENT_BLOCK0,
-                self,
-                m,
-                CommentBraceKind::Brace0,
-            ),
-            "{--" => make_block_comment(
-                &*COMMENT_BLOCK1,
-                self,
-                m,
-                CommentBraceKind::Brace1,
-            ),
-            "{---" => make_block_comment(
-                &*COMMENT_BLOCK2,
-                self,
-                m,
-                CommentBraceKind::Brace2,
-            ),
-            "{----" => make_block_comment(
-                &*COMMENT_BLOCK3,
-                self,
-                m,
-                CommentBraceKind::Brace3,
-            ),
+            "{-" => make_block_comment(self, m, CommentBraceKind::Brace0),
+            "{--" => make_block_comment(self, m, CommentBraceKind::Brace1),
+            "{---" => make_block_comment(self, m, CommentBraceKind::Brace2),
+            "{----" => make_block_comment(self, m, CommentBraceKind::Brace3),
             _ => {
                 let span = self.advance_non_line_breaks(matched);
                 make_error_report_with_span(
@@ -376,7 +382,185 @@ impl<'store, 'source> BaseLexerContext<'source> {
         &mut self,
         m: Match,
     ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
-        todo!()
+        let matched = m.as_str();
+        if matched == "f#\"" {
+            return self.interpolation_string(matched);
+        }
+        let kind = match matched {
+            "r#\"" => StringKind::Raw0,
+            "r##\"" => StringKind::Raw1,
+            "r###\"" => StringKind::Raw2,
+            "r####\"" => StringKind::Raw3,
+            "\"\"\"" => StringKind::TextBlock,
+            _ => StringKind::Normal,
+        };
+        let index = self.index;
+        let body = &index[matched.len()..];
+        let Some(scan) = scan_string(body, kind) else {
+            let end = self.end_position();
+            let span = self.advance_non_line_breaks(matched);
+            return make_error_report_with_span(
+                LexerReportKind::UnfinishedString(kind, end),
+                span,
+            );
+        };
+        let start = self.position;
+        self.advance_non_line_breaks(matched);
+        if let Some((from, to)) = scan.invalid_escape {
+            self.advance_with_line_breaks(&body[..from]);
+            let span = self.advance_with_line_breaks(&body[from..to]);
+            self.advance_with_line_breaks(&body[to..scan.length]);
+            return make_error_report_with_span(
+                LexerReportKind::InvalidEscape,
+                span,
+            );
+        }
+        let end = self.advance_with_line_breaks(&body[..scan.length]).end;
+        Some(Ok((
+            Span { start, end },
+            BaseToken::StringLiteral(StringLiteral {
+                kind,
+                value: scan.components,
+            }),
+        )))
+    }
+
+    /// The interpolation strings `f#"..{x:>8}.."`, the holes are parsed
+    /// as expressions with the positions of the source.
+    fn interpolation_string(
+        &mut self,
+        matched: &str,
+    ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
+        let index = self.index;
+        let body = &index[matched.len()..];
+        let Some(scan) = scan_interpolation(body) else {
+            let end = self.end_position();
+            let span = self.advance_non_line_breaks(matched);
+            return make_error_report_with_span(
+                LexerReportKind::UnfinishedString(StringKind::Normal, end),
+                span,
+            );
+        };
+        let start = self.position;
+        self.advance_non_line_breaks(matched);
+        let first_error = [
+            scan.invalid_escape
+                .map(|range| (range, LexerReportKind::InvalidEscape)),
+            scan.unmatched_brace.map(|brace| {
+                (
+                    (brace, brace + 1),
+                    LexerReportKind::UnmatchedInterpolationBrace,
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .min_by_key(|((from, _), _)| *from);
+        if let Some(((from, to), kind)) = first_error {
+            self.advance_with_line_breaks(&body[..from]);
+            let span = self.advance_with_line_breaks(&body[from..to]);
+            self.advance_with_line_breaks(&body[to..scan.length]);
+            return make_error_report_with_span(kind, span);
+        }
+        let mut value = vec![];
+        let mut consumed = 0;
+        for piece in scan.pieces {
+            match piece {
+                InterpolationPiece::Component(
+                    StringComponent::RegularString(s),
+                ) => value.push(InterpolationComponent::RegularString(s)),
+                InterpolationPiece::Component(StringComponent::Scaped {
+                    spelling,
+                    value: c,
+                }) => value.push(InterpolationComponent::Scaped {
+                    spelling,
+                    value: c,
+                }),
+                InterpolationPiece::Hole {
+                    expression: (from, to),
+                    format,
+                } => {
+                    // The `{` is before the expression.
+                    let hole_start = self
+                        .advance_with_line_breaks(&body[consumed..from - 1])
+                        .end;
+                    self.advance_non_line_breaks("{");
+                    let text = &body[from..to];
+                    let text_span = self.advance_with_line_breaks(text);
+                    let expression = match self.hole(text, text_span) {
+                        Ok(expression) => expression,
+                        Err(error) => {
+                            self.advance_with_line_breaks(
+                                &body[to..scan.length],
+                            );
+                            return Some(Err(*error));
+                        }
+                    };
+                    let format = match format {
+                        Some((format_from, format_to)) => {
+                            self.advance_non_line_breaks(":");
+                            let spec = &body[format_from..format_to];
+                            let spec_span = self.advance_with_line_breaks(spec);
+                            match FormatSpec::parse(spec) {
+                                Some(format) => Some(format),
+                                None => {
+                                    self.advance_with_line_breaks(
+                                        &body[format_to..scan.length],
+                                    );
+                                    return make_error_report_with_span(
+                                        LexerReportKind::InvalidFormatSpec,
+                                        spec_span,
+                                    );
+                                }
+                            }
+                        }
+                        None => None,
+                    };
+                    let hole_end = self.advance_non_line_breaks("}").end;
+                    consumed = self.position.source_index
+                        - start.source_index
+                        - matched.len();
+                    value.push(InterpolationComponent::Interpolation {
+                        expression: Box::new(expression),
+                        span: Span {
+                            start: hole_start,
+                            end: hole_end,
+                        },
+                        format,
+                    });
+                }
+            }
+        }
+        let end = self
+            .advance_with_line_breaks(&body[consumed..scan.length])
+            .end;
+        Some(Ok((
+            Span { start, end },
+            BaseToken::StringInterpolation(InterpolationString { value }),
+        )))
+    }
+
+    /// Parses the expression of a hole of a interpolation string, the
+    /// span is the one of the text.
+    fn hole(
+        &self,
+        text: &str,
+        span: Span,
+    ) -> Result<Expression, Box<OctizysParserReport>> {
+        let mut context =
+            BaseLexerContext::new_at(text, span.start, self.store.clone());
+        expressionParser::new()
+            .parse(LexerContext::new(None, &mut context))
+            .map_err(|error| match error {
+                ParseError::User { error } => Box::new(error),
+                _ => Box::new(OctizysParserReport {
+                    kind: ReportKind::Error,
+                    report: ParserReport::Lexer(
+                        LexerReportKind::InvalidInterpolation,
+                    ),
+                    location: Location::Span(span),
+                }),
+            })
     }
 
     fn named_hole(
@@ -508,70 +692,76 @@ impl<'store, 'source> BaseLexerContext<'source> {
         }
     }
 
-    fn octal(
+    fn char_literal(
         &mut self,
         m: Match,
     ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
         let matched = m.as_str();
         let span = self.advance_non_line_breaks(matched);
-        match u64::from_str_radix(&matched[2..], 8) {
-            Ok(value) => Some(Ok((
+        // Safe: the literal starts and ends with `'`.
+        let spelling = &matched[1..matched.len() - 1];
+        let value = match spelling.strip_prefix('\\') {
+            Some(escape) => escaped_char(escape),
+            None => {
+                let mut chars = spelling.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => None,
+                }
+            }
+        };
+        match value {
+            Some(value) => Some(Ok((
                 span,
-                BaseToken::UintLiteral(UintLiteral {
-                    kind: UintKind::Octal,
+                BaseToken::CharLiteral(CharLiteral {
+                    spelling: String::from(spelling),
                     value,
                 }),
             ))),
-            Err(e) => make_error_report_with_span(
-                LexerReportKind::CantParseU64(e),
+            None => make_error_report_with_span(
+                LexerReportKind::InvalidCharLiteral,
                 span,
             ),
         }
     }
 
-    fn hex(
+    /// Integer literals with a base prefix, the digits are kept as they
+    /// are written, the range is checked after the parsing.
+    fn uint_with_base(
         &mut self,
         m: Match,
+        base: UintBase,
     ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
         let matched = m.as_str();
         let span = self.advance_non_line_breaks(matched);
-        match u64::from_str_radix(&matched[2..], 16) {
-            Ok(value) => Some(Ok((
-                span,
-                BaseToken::UintLiteral(UintLiteral {
-                    kind: UintKind::Hex,
-                    value,
-                }),
-            ))),
-            Err(e) => make_error_report_with_span(
-                LexerReportKind::CantParseU64(e),
-                span,
-            ),
-        }
+        // Safe: the prefixes of the bases are ascii.
+        Some(Ok((
+            span,
+            BaseToken::UintLiteral(uint_literal(base, &matched[2..])),
+        )))
+    }
+
+    fn octal(
+        &mut self,
+        m: Match,
+    ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
+        self.uint_with_base(m, UintBase::Octal)
+    }
+
+    fn hex(
+        &mut self,
+        m: Match,
+    ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
+        self.uint_with_base(m, UintBase::Hex)
     }
 
     fn binary(
         &mut self,
         m: Match,
     ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
-        let matched = m.as_str();
-        let span = self.advance_non_line_breaks(matched);
-        match u64::from_str_radix(&matched[2..], 2) {
-            Ok(value) => Some(Ok((
-                span,
-                BaseToken::UintLiteral(UintLiteral {
-                    kind: UintKind::Binary,
-                    value,
-                }),
-            ))),
-            Err(e) => make_error_report_with_span(
-                LexerReportKind::CantParseU64(e),
-                span,
-            ),
-        }
+        self.uint_with_base(m, UintBase::Binary)
     }
 
-    //TODO: add type signatures to uint literals like `27_u8`
     fn numeric(
         &mut self,
         m: Match,
@@ -579,26 +769,462 @@ impl<'store, 'source> BaseLexerContext<'source> {
         let matched = m.as_str();
         let span = self.advance_non_line_breaks(matched);
         let re_parsed = NUMBER.captures(matched).unwrap();
-        match re_parsed.name("decimal_part") {
-            Some(_) => Some(Ok((span, BaseToken::UFloatLiteral(todo!())))),
-            None => match matched.parse::<u64>() {
-                Ok(value) => Some(Ok((
-                    span,
-                    BaseToken::UintLiteral(UintLiteral {
-                        kind: UintKind::Unspecified,
-                        value,
-                    }),
-                ))),
-                Err(e) => make_error_report_with_span(
-                    LexerReportKind::CantParseU64(e),
-                    span,
-                ),
-            },
+        let is_float = re_parsed.name("decimal_part").is_some()
+            || re_parsed.name("exponential_part").is_some()
+            || re_parsed.name("float_suffix").is_some();
+        if is_float {
+            return Some(Ok((
+                span,
+                BaseToken::UFloatLiteral(float_literal(&re_parsed)),
+            )));
         }
+        Some(Ok((
+            span,
+            BaseToken::UintLiteral(uint_literal(UintBase::Decimal, matched)),
+        )))
     }
 }
 
-const MAIN_REGEX_STR: &'static str = r#"^((?<comment_start>//|--|\{----|\{---|\{--|\{-)|(?<punctuation_or_operator>\\|/|#|,|;|\?|\+|\^|%|\.|::|:|->|-|\|\||\|>|\||<\?>|<&>|<<|<\*>|<\*|<\$>|<\$|<-|<=|<\|>|<\||<|\*>|\*|==|=|!=|!|>=|>>|>|&&|&|\$>|\$|@)|(?<bracket_start>\(|\[|\{)|(?<bracket_end>\)|\]|\})|(?<string_start>f#"|r####"|r###"|r##"|r#"|")|(?<named_hole>_[0-9][0-9_]*)|(?<identifier>_*\p{XID_START}\p{XID_CONTINUE}*)|(?<infix_identifier>`_*\p{XID_START}\p{XID_CONTINUE}*`)|(?<anon_hole>_)|(?<ownership_literal>'(0|1|inf))|(?<ownership_variable>'_*\p{XID_START}\p{XID_CONTINUE}*)|(?<octal>0o[0-7][0-7_]*)|(?<hex>0x[0-9a-fA-F][0-9a-fA-F_]*)|(?<binary>0b[01][01_]*)|(?<numeric>[0-9][0-9_]*(?<decimal_part>\.[0-9][0-9_]*(?<exponential_part>(e|E)(?<sign>\+|-)?[0-9][0-9_]*)?)?))"#;
+/// A block comment at the start of a text.
+struct BlockCommentScan {
+    kind: CommentKind,
+    /// The byte range of the content, without the delimiters and the
+    /// documentation mark.
+    content: (usize, usize),
+    /// The bytes of the comment, including the closing delimiter.
+    length: usize,
+}
+
+/// Reads the block comment at the start of the text, its opening
+/// delimiter has the given amount of hyphens. Every `{-` inside the
+/// comment opens a nested comment that must be closed with the same
+/// amount of hyphens before the comment ends, the nested comments are
+/// part of the content. The content is kept verbatim, only the spaces
+/// before the documentation mark are dropped, the formatter decides the
+/// spacing of the delimiters. If the comment isn't closed the error
+/// has the hyphens of the comments still open, the outermost first.
+fn scan_block_comment(
+    text: &str,
+    hyphens: usize,
+) -> Result<BlockCommentScan, Vec<usize>> {
+    let mut position = 1 + hyphens;
+    let spaces =
+        text[position..].len() - text[position..].trim_start_matches(' ').len();
+    let kind = if text[position + spaces..].starts_with('|') {
+        position += spaces + 1;
+        CommentKind::Documentation
+    } else {
+        CommentKind::NonDocumentation
+    };
+    let content_start = position;
+    let mut open = vec![hyphens];
+    while let Some(c) = text[position..].chars().next() {
+        let rest = &text.as_bytes()[position..];
+        let inner = rest[1..].iter().take_while(|b| **b == b'-').count();
+        if c == '{' && inner > 0 {
+            open.push(inner);
+            position += 1 + inner;
+            continue;
+        }
+        // The comment returns when the outermost comment is closed.
+        let expected = open[open.len() - 1];
+        if rest.len() > expected
+            && rest[..expected].iter().all(|b| *b == b'-')
+            && rest[expected] == b'}'
+        {
+            open.pop();
+            if open.is_empty() {
+                return Ok(BlockCommentScan {
+                    kind,
+                    content: (content_start, position),
+                    length: position + expected + 1,
+                });
+            }
+            position += expected + 1;
+            continue;
+        }
+        position += c.len_utf8();
+    }
+    Err(open)
+}
+
+/// The body of a string after its opening delimiter.
+struct StringScan {
+    components: Vec<StringComponent>,
+    /// The bytes of the body, including the closing delimiter.
+    length: usize,
+    /// The byte range of the first invalid escape.
+    invalid_escape: Option<(usize, usize)>,
+}
+
+/// Reads the body of a string until its closing delimiter, `None` if
+/// there isn't one. The raw strings don't have escapes.
+fn scan_string(body: &str, kind: StringKind) -> Option<StringScan> {
+    let delimiter = kind.end();
+    if kind.hashes().is_some() {
+        let length = body.find(&delimiter)?;
+        let components = if length == 0 {
+            vec![]
+        } else {
+            vec![StringComponent::RegularString(String::from(
+                &body[..length],
+            ))]
+        };
+        return Some(StringScan {
+            components,
+            length: length + delimiter.len(),
+            invalid_escape: None,
+        });
+    }
+    let length = find_closing(body, &delimiter)?;
+    let (components, invalid_escape) = if kind == StringKind::TextBlock {
+        // The escapes are reported with the positions of the source.
+        let (_, invalid_escape) = string_components(&body[..length]);
+        let (components, _) =
+            string_components(&text_block_content(&body[..length]));
+        (components, invalid_escape)
+    } else {
+        string_components(&body[..length])
+    };
+    Some(StringScan {
+        components,
+        length: length + delimiter.len(),
+        invalid_escape,
+    })
+}
+
+/// The position of the first `delimiter` that isn't part of a escape.
+fn find_closing(body: &str, delimiter: &str) -> Option<usize> {
+    let mut position = 0;
+    while let Some(c) = body[position..].chars().next() {
+        if body[position..].starts_with(delimiter) {
+            return Some(position);
+        }
+        position += c.len_utf8();
+        if c == '\\' {
+            position += body[position..].chars().next()?.len_utf8();
+        }
+    }
+    None
+}
+
+/// Splits the content of a string in regular text and escapes, with
+/// the byte range of the first invalid escape.
+fn string_components(
+    text: &str,
+) -> (Vec<StringComponent>, Option<(usize, usize)>) {
+    let mut components = vec![];
+    let mut invalid_escape = None;
+    let mut regular_start = 0;
+    while let Some(offset) = text[regular_start..].find('\\') {
+        let position = regular_start + offset;
+        if regular_start < position {
+            components.push(StringComponent::RegularString(String::from(
+                &text[regular_start..position],
+            )))
+        }
+        let (end, value) =
+            scan_escape(text, position).unwrap_or((text.len(), None));
+        match value {
+            Some(value) => components.push(StringComponent::Scaped {
+                spelling: String::from(&text[position..end]),
+                value,
+            }),
+            None => {
+                invalid_escape.get_or_insert((position, end));
+            }
+        }
+        regular_start = end;
+    }
+    if regular_start < text.len() {
+        components.push(StringComponent::RegularString(String::from(
+            &text[regular_start..],
+        )))
+    }
+    (components, invalid_escape)
+}
+
+/// The content of a text block without its first and last lines if
+/// they are blank, and without the common indentation. The line of
+/// the closing delimiter counts for the indentation, and the lines
+/// with only spaces become empty.
+fn text_block_content(raw: &str) -> String {
+    let is_blank = |line: &str| line.trim().is_empty();
+    let indentation =
+        |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let mut lines: Vec<&str> = raw.split('\n').collect();
+    if lines.len() > 1 && is_blank(lines[0]) {
+        lines.remove(0);
+    }
+    let mut common = None;
+    if let Some(last) = lines.last().filter(|l| lines.len() > 1 && is_blank(l))
+    {
+        common = Some(indentation(last));
+        lines.pop();
+    }
+    let common = lines
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| indentation(line))
+        .chain(common)
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| if is_blank(line) { "" } else { &line[common..] })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The end of the escape sequence that starts at `position` with a
+/// `\\` and its character, the character is `None` for invalid
+/// escapes. The `\\u{..}` escapes take all the hexadecimal digits
+/// and the closing brace if there is one.
+fn scan_escape(body: &str, position: usize) -> Option<(usize, Option<char>)> {
+    let first = body[position + 1..].chars().next()?;
+    let mut end = position + 1 + first.len_utf8();
+    if first == 'u' && body[end..].starts_with('{') {
+        end += 1;
+        end += body[end..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(body.len() - end);
+        if body[end..].starts_with('}') {
+            end += 1;
+        }
+    }
+    Some((end, escaped_char(&body[position + 1..end])))
+}
+
+/// A piece of the body of a interpolation string.
+#[derive(Debug, PartialEq, Eq)]
+enum InterpolationPiece {
+    Component(StringComponent),
+    /// The byte range of the expression of a hole, and of its format
+    /// specifier (without the `:`) if there is one.
+    Hole {
+        expression: (usize, usize),
+        format: Option<(usize, usize)>,
+    },
+}
+
+/// The body of a interpolation string after the `f#"`.
+#[derive(Debug, PartialEq, Eq)]
+struct InterpolationScan {
+    pieces: Vec<InterpolationPiece>,
+    /// The bytes of the body, including the closing delimiter.
+    length: usize,
+    /// The byte range of the first invalid escape.
+    invalid_escape: Option<(usize, usize)>,
+    /// The byte position of the first `}` that doesn't close a hole.
+    unmatched_brace: Option<usize>,
+}
+
+/// Like [`scan_string`] but the braces open holes, the `{{` and `}}`
+/// are escapes for the braces.
+fn scan_interpolation(body: &str) -> Option<InterpolationScan> {
+    let mut pieces = vec![];
+    let mut invalid_escape = None;
+    let mut unmatched_brace = None;
+    let mut regular_start = 0;
+    let mut position = 0;
+    let flush = |pieces: &mut Vec<InterpolationPiece>,
+                 from: usize,
+                 to: usize| {
+        if from < to {
+            pieces.push(InterpolationPiece::Component(
+                StringComponent::RegularString(String::from(&body[from..to])),
+            ))
+        }
+    };
+    while let Some(c) = body[position..].chars().next() {
+        let next = body[position + c.len_utf8()..].chars().next();
+        match (c, next) {
+            ('"', _) => {
+                flush(&mut pieces, regular_start, position);
+                return Some(InterpolationScan {
+                    pieces,
+                    length: position + 1,
+                    invalid_escape,
+                    unmatched_brace,
+                });
+            }
+            ('\\', _) => {
+                flush(&mut pieces, regular_start, position);
+                let (end, value) = scan_escape(body, position)?;
+                match value {
+                    Some(value) => pieces.push(InterpolationPiece::Component(
+                        StringComponent::Scaped {
+                            spelling: String::from(&body[position..end]),
+                            value,
+                        },
+                    )),
+                    None => {
+                        invalid_escape.get_or_insert((position, end));
+                    }
+                }
+                position = end;
+            }
+            ('{', Some('{')) | ('}', Some('}')) => {
+                flush(&mut pieces, regular_start, position);
+                pieces.push(InterpolationPiece::Component(
+                    StringComponent::Scaped {
+                        spelling: String::from(&body[position..position + 2]),
+                        value: c,
+                    },
+                ));
+                position += 2;
+            }
+            ('{', _) => {
+                flush(&mut pieces, regular_start, position);
+                let (close, colon) = scan_hole(body, position + 1)?;
+                pieces.push(InterpolationPiece::Hole {
+                    expression: (position + 1, colon.unwrap_or(close)),
+                    format: colon.map(|colon| (colon + 1, close)),
+                });
+                position = close + 1;
+            }
+            ('}', _) => {
+                flush(&mut pieces, regular_start, position);
+                unmatched_brace.get_or_insert(position);
+                position += 1;
+            }
+            _ => {
+                position += c.len_utf8();
+                continue;
+            }
+        }
+        regular_start = position;
+    }
+    None
+}
+
+/// Finds the `}` that closes the hole that starts at `start` and the
+/// last `:` outside of parentheses, brackets and braces, it skips the
+/// nested strings and characters.
+fn scan_hole(body: &str, start: usize) -> Option<(usize, Option<usize>)> {
+    let mut depth: usize = 0;
+    let mut colon = None;
+    let mut position = start;
+    while let Some(c) = body[position..].chars().next() {
+        let rest = &body[position + c.len_utf8()..];
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '}' if depth == 0 => return Some((position, colon)),
+            '}' => depth -= 1,
+            ':' if rest.starts_with(':') => position += 1,
+            ':' if depth == 0 => colon = Some(position),
+            '"' => {
+                let before = &body[start..position];
+                let length = if before.ends_with("f#") {
+                    scan_interpolation(rest)?.length
+                } else if let Some(block) = rest.strip_prefix("\"\"") {
+                    2 + scan_string(block, StringKind::TextBlock)?.length
+                } else {
+                    let hashes =
+                        before.len() - before.trim_end_matches('#').len();
+                    let kind = match hashes {
+                        1 if before.ends_with("r#") => StringKind::Raw0,
+                        2 if before.ends_with("r##") => StringKind::Raw1,
+                        3 if before.ends_with("r###") => StringKind::Raw2,
+                        4 if before.ends_with("r####") => StringKind::Raw3,
+                        _ => StringKind::Normal,
+                    };
+                    scan_string(rest, kind)?.length
+                };
+                position += 1 + length;
+                continue;
+            }
+            '\'' => {
+                // Only the characters literals, the ownership
+                // literals and variables are skipped as usual.
+                let mut chars = rest.char_indices();
+                let literal_end = match chars.next() {
+                    Some((_, '\\')) => rest.find('\''),
+                    Some((_, x)) => match chars.next() {
+                        Some((index, '\'')) if x != '\'' => Some(index),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                if let Some(end) = literal_end {
+                    position += 1 + end + 1;
+                    continue;
+                }
+            }
+            _ => (),
+        }
+        position += c.len_utf8();
+    }
+    None
+}
+
+/// The character of a escape sequence without the `\\`, like `n` or
+/// `u{1F600}`. `None` for unknown escapes and for codes that aren't
+/// unicode scalar values.
+fn escaped_char(escape: &str) -> Option<char> {
+    match escape {
+        "n" => Some('\n'),
+        "t" => Some('\t'),
+        "r" => Some('\r'),
+        "0" => Some('\0'),
+        "\\" => Some('\\'),
+        "'" => Some('\''),
+        "\"" => Some('"'),
+        _ => {
+            let code = escape.strip_prefix("u{")?.strip_suffix('}')?;
+            if code.is_empty() || code.len() > 6 {
+                return None;
+            }
+            char::from_u32(u32::from_str_radix(code, 16).ok()?)
+        }
+    }
+}
+
+/// Splits the digits of a integer literal from its suffix, the suffixes
+/// are the only part of a integer literal that can have a `u` or a `i`.
+fn uint_literal(base: UintBase, text: &str) -> UintLiteral {
+    let (digits, kind) = match text.find(['u', 'i']) {
+        Some(index) => (&text[..index], UintKind::from_suffix(&text[index..])),
+        None => (text, None),
+    };
+    UintLiteral {
+        base,
+        digits: String::from(digits),
+        kind,
+    }
+}
+
+/// Builds a float from the captures of [`NUMBER`], the digits are
+/// kept as they are in the source.
+fn float_literal(captures: &Captures) -> UFloatingPointLiteral {
+    let text =
+        |name: &str| captures.name(name).map(|m| String::from(m.as_str()));
+    let exponent = captures.name("exponent").map(|digits| FloatExponent {
+        uppercase: text("exponent_letter").as_deref() == Some("E"),
+        sign: captures.name("sign").map(|sign| match sign.as_str() {
+            "-" => ExponentSign::Minus,
+            _ => ExponentSign::Plus,
+        }),
+        digits: String::from(digits.as_str()),
+    });
+    let suffix =
+        captures
+            .name("float_suffix")
+            .map(|suffix| match suffix.as_str() {
+                "f32" => UFloatingPointKind::F32,
+                _ => UFloatingPointKind::F64,
+            });
+    UFloatingPointLiteral {
+        // Safe: the regex always matches the integral part.
+        integral_part: text("integral_part").unwrap(),
+        fractional_part: text("decimal_part"),
+        exponent,
+        suffix,
+    }
+}
+
+const MAIN_REGEX_STR: &'static str = r#"^((?<comment_start>//|--|\{----|\{---|\{--|\{-)|(?<punctuation_or_operator>\\|/|#|,|;|\?|\+|\^|%|\.|::|:|->|-|\|\||\|>|\||<\?>|<&>|<<|<\*>|<\*|<\$>|<\$|<-|<=|<\|>|<\||<|\*>|\*|==|=|!=|!|>=|>>|>|&&|&|\$>|\$|@)|(?<bracket_start>\(|\[|\{)|(?<bracket_end>\)|\]|\})|(?<string_start>f#"|r####"|r###"|r##"|r#"|"""|")|(?<named_hole>_[0-9][0-9_]*)|(?<identifier>_*\p{XID_START}\p{XID_CONTINUE}*)|(?<infix_identifier>`_*\p{XID_START}\p{XID_CONTINUE}*`)|(?<anon_hole>_)|(?<char_literal>'(\\u\{[^}'\n]*\}|\\\S|[^'\\\n]|[^'\\\s,;()\[\]{}]*)')|(?<ownership_literal>'(0|1|inf))|(?<ownership_variable>'_*\p{XID_START}\p{XID_CONTINUE}*)|(?<octal>0o[0-7][0-7_]*(u8|u16|u32|u64|i8|i16|i32|i64)?)|(?<hex>0x[0-9a-fA-F][0-9a-fA-F_]*(u8|u16|u32|u64|i8|i16|i32|i64)?)|(?<binary>0b[01][01_]*(u8|u16|u32|u64|i8|i16|i32|i64)?)|(?<numeric>(?<integral_part>[0-9][0-9_]*)((?<uint_suffix>u8|u16|u32|u64|i8|i16|i32|i64)|(\.(?<decimal_part>[0-9][0-9_]*))?(?<exponential_part>(?<exponent_letter>e|E)(?<sign>\+|-)?(?<exponent>[0-9][0-9_]*))?(?<float_suffix>f32|f64)?)))"#;
 
 const MAIN_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(MAIN_REGEX_STR).unwrap());
@@ -616,49 +1242,24 @@ static SLASH_COMMENT: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^// *(?<doc>\|)?(?<content>.*)(\n|$)").unwrap()
 });
 
-// Remember, for the concatenation the complement of
-// `ab`
-// is
-// `[^a]|a[^b]`
-// In blocks we want to find complements of `--}`.
-static COMMENT_BLOCK0: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\{- *(?<doc>\|)?(?<content>([^-]|-[^\}])*)-\}").unwrap()
-});
-static COMMENT_BLOCK1: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"\{-- *(?<doc>\|)?(?<content>([^-]|-([^-]|-[^\}]))*)--\}")
-        .unwrap()
-});
-static COMMENT_BLOCK2: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"\{--- *(?<doc>\|)?(?<content>([^-]|-([^-]|-([^-]|-[^\}])))*)---\}",
-    )
-    .unwrap()
-});
-static COMMENT_BLOCK3: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"\{---- *(?<doc>\|)?(?<content>([^-]|-([^-]|-([^-]|-([^-]|-[^\}]))))*)----\}",
-    )
-    .unwrap()
-});
 static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
-    r"(?<numeric>[0-9][0-9_]*(?<decimal_part>\.[0-9][0-9_]*(?<exponential_part>(e|E)(?<sign>\+|-)?[0-9][0-9_]*)?)?)"
+    r"(?<numeric>(?<integral_part>[0-9][0-9_]*)((?<uint_suffix>u8|u16|u32|u64|i8|i16|i32|i64)|(\.(?<decimal_part>[0-9][0-9_]*))?(?<exponential_part>(?<exponent_letter>e|E)(?<sign>\+|-)?(?<exponent>[0-9][0-9_]*))?(?<float_suffix>f32|f64)?))"
     )
     .unwrap()
 });
-static SIMPLE_STRING: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r#""([^"]|[^\\](\\\\)*\\")*""#).unwrap());
+
+/// Lexes the token of a group of the [`MAIN_REGEX`].
+type GroupLexer<'context, 'source> =
+    fn(
+        &'context mut BaseLexerContext<'source>,
+        m: Match,
+    ) -> Option<Result<(Span, BaseToken), OctizysParserReport>>;
 
 fn find_match_group<'source, 'store, 'context>(
     c: Captures,
     blc: &'context mut BaseLexerContext<'source>,
-    v: Vec<(
-        &'static str,
-        fn(
-            &'context mut BaseLexerContext<'source>,
-            m: Match,
-        ) -> Option<Result<(Span, BaseToken), OctizysParserReport>>,
-    )>,
+    v: Vec<(&'static str, GroupLexer<'context, 'source>)>,
 ) -> Option<Result<(Span, BaseToken), OctizysParserReport>> {
     for (name, f) in v {
         match c.name(name) {
@@ -697,6 +1298,7 @@ impl<'store, 'source> Iterator for BaseLexerContext<'source> {
                     ("identifier", BaseLexerContext::identifier),
                     ("infix_identifier", BaseLexerContext::infix_identifier),
                     ("anon_hole", BaseLexerContext::anon_hole),
+                    ("char_literal", BaseLexerContext::char_literal),
                     ("ownership_literal", BaseLexerContext::ownership_literal),
                     (
                         "ownership_variable",
@@ -722,19 +1324,25 @@ pub enum BaseOrComments {
     Comments(Vec<Comment>),
 }
 
+/// A token or comments of the base lexer, the errors keep the
+/// comments found before them.
+pub type BaseOrCommentsResult =
+    Result<(Span, BaseOrComments), (Vec<Comment>, OctizysParserReport)>;
+
+/// A token of the parser, the errors keep the comments found before
+/// them.
+pub type TokenResult =
+    Result<(Position, Token, Position), (Vec<Comment>, OctizysParserReport)>;
+
 #[derive(Debug)]
 pub struct LexerContext<'src> {
-    previous_token: Option<
-        Result<(Span, BaseOrComments), (Vec<Comment>, OctizysParserReport)>,
-    >,
+    previous_token: Option<BaseOrCommentsResult>,
     lexer: &'src mut BaseLexerContext<'src>,
 }
 
 impl<'src> LexerContext<'src> {
     pub fn new(
-        previous_token: Option<
-            Result<(Span, BaseOrComments), (Vec<Comment>, OctizysParserReport)>,
-        >,
+        previous_token: Option<BaseOrCommentsResult>,
         lexer: &'src mut BaseLexerContext<'src>,
     ) -> Self {
         LexerContext {
@@ -807,9 +1415,7 @@ pub fn complete_token_or_save(
     current_token: BaseToken,
     context: &mut LexerContext,
     mut info: TokenInfo,
-) -> Option<
-    Result<(Position, Token, Position), (Vec<Comment>, OctizysParserReport)>,
-> {
+) -> Option<TokenResult> {
     let mut acc = vec![];
     let next_token = accumulate_comments(context.lexer, &mut acc);
     let (to_attach, remain) = split_comments_by_line(info.span.end.line, acc);
@@ -872,11 +1478,7 @@ fn make_last_comment_token(
 }
 
 /// To call at the beginning of lexing.
-fn parse_token(
-    context: &mut LexerContext,
-) -> Option<
-    Result<(Position, Token, Position), (Vec<Comment>, OctizysParserReport)>,
-> {
+fn parse_token(context: &mut LexerContext) -> Option<TokenResult> {
     let mut acc = vec![];
     let current_value = accumulate_comments(&mut context.lexer, &mut acc);
     match current_value {
@@ -890,13 +1492,15 @@ fn parse_token(
             complete_token_or_save(current_token, context, info)
         }
         //Reached eof in the lexer
-        None => make_last_comment_token(acc).map(|x| Ok(x)),
+        None => make_last_comment_token(acc).map(Ok),
         Some(Err(e)) => Some(Err((vec![], e))),
     }
 }
 
 impl<'store, 'src> Iterator for LexerContext<'src> {
     type Item = Result<(Position, Token, Position), OctizysParserReport>;
+    // The parser of lalrpop takes the errors of the lexer by value.
+    #[allow(clippy::result_large_err)]
     fn next(&mut self) -> Option<Self::Item> {
         //println!("{:?}", self);
         match &self.previous_token {
@@ -912,7 +1516,7 @@ impl<'store, 'src> Iterator for LexerContext<'src> {
                 Ok((span, BaseOrComments::Comments(comments))) => {
                     let comments2 = comments.clone();
                     self.previous_token = None;
-                    make_last_comment_token(comments2).map(|x| Ok(x))
+                    make_last_comment_token(comments2).map(Ok)
                 }
                 // We stored previously a non comment token
                 // and we are going to emit it after looking up
@@ -1027,6 +1631,197 @@ mod test_regex {
         main_regex_with("r####\"", "string_start");
     }
 
+    #[test]
+    fn string_scan() {
+        use super::scan_string;
+        use octizys_cst::literals::{StringComponent, StringKind};
+        let scan =
+            scan_string(r#"a\n\u{1F600}b" rest"#, StringKind::Normal).unwrap();
+        assert_eq!(scan.length, 14);
+        assert_eq!(scan.invalid_escape, None);
+        assert_eq!(
+            scan.components,
+            vec![
+                StringComponent::RegularString(String::from("a")),
+                StringComponent::Scaped {
+                    spelling: String::from(r"\n"),
+                    value: '\n',
+                },
+                StringComponent::Scaped {
+                    spelling: String::from(r"\u{1F600}"),
+                    value: '😀',
+                },
+                StringComponent::RegularString(String::from("b")),
+            ]
+        );
+        let scan = scan_string(r#"ok\q""#, StringKind::Normal).unwrap();
+        assert_eq!(scan.invalid_escape, Some((2, 4)));
+        let scan = scan_string(r#"\u{12"#, StringKind::Normal);
+        assert!(scan.is_none());
+        let scan = scan_string(r#"x\u{12 ""#, StringKind::Normal).unwrap();
+        assert_eq!(scan.invalid_escape, Some((1, 6)));
+        let scan =
+            scan_string(r###"\" "a" \q"##"###, StringKind::Raw1).unwrap();
+        assert_eq!(scan.length, 12);
+        assert_eq!(
+            scan.components,
+            vec![StringComponent::RegularString(String::from(r#"\" "a" \q"#))]
+        );
+        assert!(scan_string(r##"a"#"##, StringKind::Raw1).is_none());
+    }
+
+    #[test]
+    fn block_comment_scan() {
+        use super::scan_block_comment;
+        let text = "{--  a {--- b {- c -} ---} -} d --} rest";
+        let scan = scan_block_comment(text, 2).unwrap();
+        assert_eq!(scan.kind, CommentKind::NonDocumentation);
+        assert_eq!(
+            &text[scan.content.0..scan.content.1],
+            "  a {--- b {- c -} ---} -} d "
+        );
+        assert_eq!(&text[..scan.length], "{--  a {--- b {- c -} ---} -} d --}");
+        let text = "{- | doc --}";
+        let scan = scan_block_comment(text, 1).unwrap();
+        assert_eq!(scan.kind, CommentKind::Documentation);
+        assert_eq!(&text[scan.content.0..scan.content.1], " doc -");
+        assert_eq!(scan.length, text.len());
+        assert_eq!(scan_block_comment("{- a {- b -}", 1).err(), Some(vec![1]));
+        assert_eq!(scan_block_comment("{-- a -}", 2).err(), Some(vec![2]));
+        assert_eq!(
+            scan_block_comment("{- x {-- y {- z -}", 1).err(),
+            Some(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn interpolation_scan() {
+        use super::{scan_interpolation, InterpolationPiece};
+        use octizys_cst::literals::StringComponent;
+        let scan = scan_interpolation(r#"a{{{f "}" '}':>4}}}" rest"#).unwrap();
+        assert_eq!(scan.length, 20);
+        assert_eq!(scan.unmatched_brace, None);
+        assert_eq!(
+            scan.pieces,
+            vec![
+                InterpolationPiece::Component(StringComponent::RegularString(
+                    String::from("a")
+                )),
+                InterpolationPiece::Component(StringComponent::Scaped {
+                    spelling: String::from("{{"),
+                    value: '{',
+                }),
+                InterpolationPiece::Hole {
+                    expression: (4, 13),
+                    format: Some((14, 16)),
+                },
+                InterpolationPiece::Component(StringComponent::Scaped {
+                    spelling: String::from("}}"),
+                    value: '}',
+                }),
+            ]
+        );
+        let scan = scan_interpolation(r##"{(a : b)} {f#"{x}"::y}""##).unwrap();
+        assert_eq!(
+            scan.pieces[0],
+            InterpolationPiece::Hole {
+                expression: (1, 8),
+                format: None,
+            }
+        );
+        assert_eq!(
+            scan.pieces[2],
+            InterpolationPiece::Hole {
+                expression: (11, 21),
+                format: None,
+            }
+        );
+        let scan = scan_interpolation(r#"a}b""#).unwrap();
+        assert_eq!(scan.unmatched_brace, Some(1));
+        assert!(scan_interpolation(r#"{x""#).is_none());
+    }
+
+    #[test]
+    fn text_block() {
+        use super::{scan_string, text_block_content};
+        use octizys_cst::literals::{StringComponent, StringKind};
+        main_regex_with("\"\"\"", "string_start");
+        assert_eq!(
+            text_block_content("\n    a\n\n      b\n  \n    "),
+            "a\n\n  b\n"
+        );
+        assert_eq!(text_block_content("\n    a\n  "), "  a");
+        assert_eq!(text_block_content("a\n  b"), "a\n  b");
+        assert_eq!(text_block_content(""), "");
+        let scan = scan_string(
+            "\n  x \\\"\"\"\n  \\q\n  \"\"\" rest",
+            StringKind::TextBlock,
+        )
+        .unwrap();
+        assert_eq!(scan.length, 20);
+        assert_eq!(scan.invalid_escape, Some((12, 14)));
+        assert_eq!(
+            scan.components[..2],
+            [
+                StringComponent::RegularString(String::from("x ")),
+                StringComponent::Scaped {
+                    spelling: String::from("\\\""),
+                    value: '"',
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn interpolation_hole_spans() {
+        use octizys_cst::literals::InterpolationComponent;
+        let store = Rc::new(RefCell::new(Store::default()));
+        let source = "f#\"a\n {x + 1:08x}\"";
+        let mut context = BaseLexerContext::new(source, store);
+        let Some(Ok((span, BaseToken::StringInterpolation(string)))) =
+            context.next()
+        else {
+            panic!("Expected a interpolation string");
+        };
+        assert_eq!(span.end.source_index, source.len());
+        let InterpolationComponent::Interpolation {
+            expression,
+            span,
+            format,
+        } = &string.value[1]
+        else {
+            panic!("Expected a hole");
+        };
+        assert_eq!((span.start.line, span.start.column), (1, 1));
+        assert_eq!(span.end.source_index, source.len() - 1);
+        let expression_span = expression.span();
+        assert_eq!(expression_span.start.source_index, 7);
+        assert_eq!(expression_span.end.source_index, 12);
+        assert_eq!(format.as_ref().map(|f| f.to_string()), Some("08x".into()));
+    }
+
+    #[test]
+    fn hyphen_documentation_comment() {
+        use octizys_cst::comments::{CommentKind, LineCommentStart};
+        let store = Rc::new(RefCell::new(Store::default()));
+        let mut context = BaseLexerContext::new("-- | doc\nx", store);
+        let Some(Ok((_, BaseToken::LineComment(comment)))) = context.next()
+        else {
+            panic!("Expected a line comment");
+        };
+        assert_eq!(comment.kind, CommentKind::Documentation);
+        assert_eq!(comment.start, LineCommentStart::DoubleHyphen);
+    }
+
+    #[test]
+    fn comments_at_the_end_of_the_file() {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let top =
+            crate::parser::parse_string("x = 1;\n-- a\n{- b -}", None, store)
+                .unwrap();
+        assert_eq!(top.last_comments.len(), 2);
+    }
+
     #[test]
     fn named_hole() {
         main_regex_with("_0", "named_hole");
@@ -1063,6 +1858,32 @@ mod test_regex {
         main_regex_with("_", "anon_hole");
     }
 
+    #[test]
+    fn char_literal() {
+        main_regex_with("'a'", "char_literal");
+        main_regex_with("' '", "char_literal");
+        main_regex_with("'0'", "char_literal");
+        main_regex_with("'\\n'", "char_literal");
+        main_regex_with("'\\''", "char_literal");
+        main_regex_with("'\\u{1F600}'", "char_literal");
+        main_regex_with("'😀'", "char_literal");
+        // Invalid literals are still matched to report them.
+        main_regex_with("'ab'", "char_literal");
+        main_regex_with("''", "char_literal");
+    }
+
+    #[test]
+    fn char_values() {
+        use super::escaped_char;
+        assert_eq!(escaped_char("n"), Some('\n'));
+        assert_eq!(escaped_char("\\"), Some('\\'));
+        assert_eq!(escaped_char("u{1F600}"), Some('😀'));
+        assert_eq!(escaped_char("u{D800}"), None);
+        assert_eq!(escaped_char("u{}"), None);
+        assert_eq!(escaped_char("u{1234567}"), None);
+        assert_eq!(escaped_char("q"), None);
+    }
+
     #[test]
     fn ownership_literal() {
         main_regex_with("'0", "ownership_literal");
@@ -1088,6 +1909,9 @@ mod test_regex {
         main_regex_with("0o1", "octal");
         main_regex_with("0o2_3_4", "octal");
         main_regex_with("0o1234", "octal");
+        main_regex_with("0o17u8", "octal");
+        main_regex_with("0o1_7_u64", "octal");
+        main_regex_with("0o17i8", "octal");
     }
 
     #[test]
@@ -1097,6 +1921,9 @@ mod test_regex {
         main_regex_with("0x1", "hex");
         main_regex_with("0x2_3_4", "hex");
         main_regex_with("0x1234", "hex");
+        main_regex_with("0xFF_u8", "hex");
+        main_regex_with("0xff_ffu16", "hex");
+        main_regex_with("0xFFi64", "hex");
     }
 
     #[test]
@@ -1106,6 +1933,7 @@ mod test_regex {
         main_regex_with("0b1", "binary");
         main_regex_with("0b101", "binary");
         main_regex_with("0b001010_1_0100_001", "binary");
+        main_regex_with("0b1010u32", "binary");
     }
 
     #[test]
@@ -1116,5 +1944,41 @@ mod test_regex {
         main_regex_with("34345.384e32", "numeric");
         main_regex_with("0_89_79.52218E-32", "numeric");
         main_regex_with("839_3479.52788e+32", "numeric");
+        main_regex_with("1e10", "numeric");
+        main_regex_with("2f32", "numeric");
+        main_regex_with("1_0.5E+3f64", "numeric");
+        main_regex_with("255u8", "numeric");
+        main_regex_with("1_000_u16", "numeric");
+        main_regex_with("128i8", "numeric");
+        main_regex_with("99999999999999999999999", "numeric");
+    }
+
+    #[test]
+    fn float_keeps_spelling() {
+        for source in
+            ["1.5", "0_89_79.52218E-32", "1e10", "2f32", "1_0.5E+3f64"]
+        {
+            let captures = super::NUMBER.captures(source).unwrap();
+            let literal = super::float_literal(&captures);
+            assert_eq!(literal.to_string(), source);
+        }
+    }
+
+    #[test]
+    fn uint_keeps_spelling() {
+        use octizys_cst::literals::{UintBase, UintKind};
+        let literal = super::uint_literal(UintBase::Decimal, "1_000_u16");
+        assert_eq!(literal.digits, "1_000_");
+        assert_eq!(literal.kind, Some(UintKind::U16));
+        assert_eq!(literal.value(), Some(1000));
+        let literal = super::uint_literal(UintBase::Hex, "ff_ffu64");
+        assert_eq!(literal.to_string(), "0xff_ffu64");
+        let literal = super::uint_literal(UintBase::Binary, "1010i16");
+        assert_eq!(literal.kind, Some(UintKind::I16));
+        assert_eq!(literal.to_string(), "0b1010i16");
+        let big =
+            super::uint_literal(UintBase::Decimal, "99999999999999999999999");
+        assert_eq!(big.kind, None);
+        assert_eq!(big.value(), Some(99999999999999999999999));
     }
 }
@@ -3,4 +3,5 @@ pub mod parser;
 pub mod report;
 pub mod tokens;
 use lalrpop_util::lalrpop_mod;
-lalrpop_mod!(pub grammar);
+// The code that lalrpop generates doesn't follow the lints.
+lalrpop_mod!(#[allow(clippy::all)] pub grammar);
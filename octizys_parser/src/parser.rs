@@ -5,15 +5,16 @@ use log::{debug, trace};
 use octizys_common::{
     report::{
         IOError, ReportFormat, ReportKind, ReportRequest, ReportSourceContext,
-        ReportTarget,
+        ReportTarget, Suggestion,
     },
+    source_map::{FileId, SourceMap},
     span::Position,
 };
-use octizys_cst::top::Top;
+use octizys_cst::{expressions::Expression, top::Top};
 use octizys_pretty::store::Store;
 
 use crate::{
-    grammar::topParser,
+    grammar::{expressionParser, topParser},
     lexer::{BaseLexerContext, LexerContext, Token},
     report::OctizysParserReport,
 };
@@ -23,7 +24,7 @@ pub enum OctizysParserError {
     AtParsing {
         source: String,
         source_name: Option<PathBuf>,
-        error: ParseError<Position, Token, OctizysParserReport>,
+        error: Box<ParseError<Position, Token, OctizysParserReport>>,
     },
     IO(IOError),
 }
@@ -53,7 +54,7 @@ impl OctizysParserError {
                     src_name: name,
                     max_line_width: line_width,
                 };
-                let kind = match error {
+                let kind = match error.as_ref() {
                     ParseError::User { error } => error.kind,
                     _ => ReportKind::Error,
                 };
@@ -127,6 +128,15 @@ impl ReportFormat for OctizysParserError {
             OctizysParserError::IO(e) => e.get_short_description(),
         }
     }
+
+    fn get_suggestions(&self) -> Option<Vec<Suggestion>> {
+        match self {
+            OctizysParserError::AtParsing { error, .. } => {
+                error.get_suggestions()
+            }
+            OctizysParserError::IO(e) => e.get_suggestions(),
+        }
+    }
 }
 
 pub fn parse_string(
@@ -141,11 +151,105 @@ pub fn parse_string(
         OctizysParserError::AtParsing {
             source: String::from(source),
             source_name,
-            error,
+            error: Box::new(error),
         }
     })
 }
 
+/// Runs a parser over a file of the [`SourceMap`], or over `text`
+/// as if it were the content of the file.
+fn parse_source_with<T>(
+    map: &SourceMap,
+    file: FileId,
+    text: Option<&str>,
+    store: Rc<RefCell<Store>>,
+    parse: impl FnOnce(
+        &mut dyn Iterator<
+            Item = Result<(Position, Token, Position), OctizysParserReport>,
+        >,
+    ) -> Result<
+        T,
+        Box<ParseError<Position, Token, OctizysParserReport>>,
+    >,
+) -> Result<T, OctizysParserError> {
+    let source_file = match map.get(file) {
+        Some(f) => f,
+        None => {
+            return Err(OctizysParserError::IO(IOError::FileLoadError {
+                path: PathBuf::new(),
+            }))
+        }
+    };
+    let source = source_file.content();
+    let mut base_context =
+        BaseLexerContext::new_in_file(text.unwrap_or(source), file, store);
+    let mut iterator = LexerContext::new(None, &mut base_context)
+        .inspect(|result| trace!("{:?}", result));
+    parse(&mut iterator).map_err(|error| OctizysParserError::AtParsing {
+        source: String::from(source),
+        source_name: source_file
+            .path()
+            .map(PathBuf::from)
+            .or_else(|| Some(PathBuf::from(source_file.name()))),
+        error,
+    })
+}
+
+/// Parses a file of the [`SourceMap`], the positions in the result
+/// (and in the errors) refer to the given [`FileId`].
+pub fn parse_source(
+    map: &SourceMap,
+    file: FileId,
+    store: Rc<RefCell<Store>>,
+) -> Result<Top, OctizysParserError> {
+    parse_source_with(map, file, None, store, |iterator| {
+        topParser::new().parse(iterator).map_err(Box::new)
+    })
+}
+
+/// Like [`parse_source`] but parses `overlay` instead of the content
+/// of the file. The overlay must keep the lines and columns of the
+/// parts to parse, the errors are reported over the original file.
+pub fn parse_overlay(
+    map: &SourceMap,
+    file: FileId,
+    overlay: &str,
+    store: Rc<RefCell<Store>>,
+) -> Result<Top, OctizysParserError> {
+    parse_source_with(map, file, Some(overlay), store, |iterator| {
+        topParser::new().parse(iterator).map_err(Box::new)
+    })
+}
+
+/// Runs only the lexer over a file of the [`SourceMap`], the comments
+/// are attached to the tokens as in the parser.
+pub fn lex_source(
+    map: &SourceMap,
+    file: FileId,
+    store: Rc<RefCell<Store>>,
+) -> Result<Vec<Token>, OctizysParserError> {
+    parse_source_with(map, file, None, store, |iterator| {
+        iterator
+            .map(|result| {
+                result
+                    .map(|(_, token, _)| token)
+                    .map_err(|error| Box::new(ParseError::User { error }))
+            })
+            .collect()
+    })
+}
+
+/// Like [`parse_source`] but the file must contain a single expression.
+pub fn parse_expression_source(
+    map: &SourceMap,
+    file: FileId,
+    store: Rc<RefCell<Store>>,
+) -> Result<Expression, OctizysParserError> {
+    parse_source_with(map, file, None, store, |iterator| {
+        expressionParser::new().parse(iterator).map_err(Box::new)
+    })
+}
+
 pub fn parse_file(
     path_name: PathBuf,
     store: Rc<RefCell<Store>>,
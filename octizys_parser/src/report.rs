@@ -1,11 +1,11 @@
-use std::num::ParseIntError;
-
 use lalrpop_util::ParseError;
 use octizys_common::{
-    report::{ReportFormat, ReportKind, ReportTarget},
+    report::{
+        Applicability, ReportFormat, ReportKind, ReportTarget, Suggestion,
+    },
     span::{Location, Position},
 };
-use octizys_cst::comments::CommentBraceKind;
+use octizys_cst::literals::StringKind;
 use octizys_macros::Equivalence;
 use octizys_pretty::{
     combinators::external_text, document::Document, store::NonLineBreakStr,
@@ -36,7 +36,11 @@ pub enum LexerReportKind {
     /// but the input didn't match the regex,
     /// in the user side a unbalanced bracket is what
     /// is expected.
-    CouldntMatchBlockComment(CommentBraceKind),
+    /// The hyphens of the comments still open, the outermost
+    /// first, every nested comment needs its own closing brace.
+    /// The position is the end of the source, where
+    /// the missing closing braces can be inserted.
+    CouldntMatchBlockComment(Vec<usize>, #[equivalence(ignore)] Position),
     Notu64NamedHole,
     /// We expected a identifier and the regex should guarantee it!
     /// but if not...
@@ -52,12 +56,22 @@ pub enum LexerReportKind {
     /// regex was update without updating the handler!
     /// this is a bug.
     UnexpectedOwnershipLiteralMatch,
-    /// Can't parse a u64 but we already know that the string
-    /// is a valid rust u64, this signals a bug!
-    CantParseU64(
-        //TODO: Implement Equivalence for ParseIntError
-        #[equivalence(ignore)] ParseIntError,
-    ),
+    /// A character literal with zero or more than one unicode
+    /// scalar values, or with a unknown escape.
+    InvalidCharLiteral,
+    /// A string without its closing delimiter, the position is the
+    /// end of the source.
+    UnfinishedString(StringKind, #[equivalence(ignore)] Position),
+    /// A unknown escape in a string, or a `\u{..}` that isn't a
+    /// unicode scalar value.
+    InvalidEscape,
+    /// A `}` in a interpolation string that doesn't close a hole.
+    UnmatchedInterpolationBrace,
+    /// The content of a hole in a interpolation string isn't a
+    /// expression.
+    InvalidInterpolation,
+    /// The text after the `:` of a hole isn't a format specifier.
+    InvalidFormatSpec,
 }
 
 impl ReportFormat for LexerReportKind {
@@ -84,7 +98,7 @@ impl ReportFormat for LexerReportKind {
             LexerReportKind::CantCreateCommentLine => {
                 NonLineBreakStr::new("Internal:CantCreateCommentLine")
             }
-            LexerReportKind::CouldntMatchBlockComment(_) => {
+            LexerReportKind::CouldntMatchBlockComment(..) => {
                 NonLineBreakStr::new("CouldntMatchBlockComment")
             }
             LexerReportKind::Notu64NamedHole => {
@@ -99,8 +113,23 @@ impl ReportFormat for LexerReportKind {
             LexerReportKind::UnexpectedOwnershipLiteralMatch => {
                 NonLineBreakStr::new("Internal:UnexpectedOwnershipLiteralMatch")
             }
-            LexerReportKind::CantParseU64(_) => {
-                NonLineBreakStr::new("Internal:CantParseU64")
+            LexerReportKind::InvalidCharLiteral => {
+                NonLineBreakStr::new("InvalidCharLiteral")
+            }
+            LexerReportKind::UnfinishedString(..) => {
+                NonLineBreakStr::new("UnfinishedString")
+            }
+            LexerReportKind::InvalidEscape => {
+                NonLineBreakStr::new("InvalidEscape")
+            }
+            LexerReportKind::UnmatchedInterpolationBrace => {
+                NonLineBreakStr::new("UnmatchedInterpolationBrace")
+            }
+            LexerReportKind::InvalidInterpolation => {
+                NonLineBreakStr::new("InvalidInterpolation")
+            }
+            LexerReportKind::InvalidFormatSpec => {
+                NonLineBreakStr::new("InvalidFormatSpec")
             }
         }
     }
@@ -119,14 +148,31 @@ impl ReportFormat for LexerReportKind {
             LexerReportKind::NonFinishedLineComment => common,
             LexerReportKind::NonContentInLineComment => common,
             LexerReportKind::CantCreateCommentLine => common,
-            LexerReportKind::CouldntMatchBlockComment(_)=> NonLineBreakStr::new("We found the beginning of a block comment but couldn't finished it!"),
+            LexerReportKind::CouldntMatchBlockComment(..)=> NonLineBreakStr::new("We found the beginning of a block comment but couldn't finished it!"),
             LexerReportKind::Notu64NamedHole => {
                 NonLineBreakStr::new("Named holes are limited to u64 integers.")
             }
             LexerReportKind::CantCreateIdentifier => common,
             LexerReportKind::CantTranslateToToken(_) => common,
             LexerReportKind::UnexpectedOwnershipLiteralMatch => common,
-            LexerReportKind::CantParseU64(_)=> common,
+            LexerReportKind::InvalidCharLiteral => {
+                NonLineBreakStr::new("This isn't a valid character.")
+            }
+            LexerReportKind::UnfinishedString(..) => {
+                NonLineBreakStr::new("We found the beginning of a string but not its end!")
+            }
+            LexerReportKind::InvalidEscape => {
+                NonLineBreakStr::new("This isn't a valid escape.")
+            }
+            LexerReportKind::UnmatchedInterpolationBrace => {
+                NonLineBreakStr::new("This brace doesn't close a hole.")
+            }
+            LexerReportKind::InvalidInterpolation => {
+                NonLineBreakStr::new("This hole doesn't contain a expression.")
+            }
+            LexerReportKind::InvalidFormatSpec => {
+                NonLineBreakStr::new("This isn't a valid format specifier.")
+            }
         }
     }
     fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
@@ -137,19 +183,29 @@ impl ReportFormat for LexerReportKind {
             LexerReportKind::NonFinishedLineComment => "We find the start of a comment but not the end for some reason (not unbalanced brackets)",
             LexerReportKind::NonContentInLineComment => "We find a comment but we were unable to retrieve the content",
             LexerReportKind::CantCreateCommentLine => "We got the content of a comment but the internalizer disagree with us that this comment has the right format!",
-            LexerReportKind::CouldntMatchBlockComment(_)=> "We were looking for a matching end for the comment.\nEither we didn't find it, and we consumed all the code looking for it.\nOr something else got wrong in the search (improbable)",
+            LexerReportKind::CouldntMatchBlockComment(..)=> "We were looking for a matching end for the comment.\nWe consumed all the code looking for it.\nThe comments nested inside it, like {- inner -}, must be closed first.",
             LexerReportKind::Notu64NamedHole => "Internally the named holes are stored as u64 integers.\nThe provided value for the hole is out of the bound for this range.\nPlease modify the hole value to something between 0 and 2^64 -1",
             LexerReportKind::CantCreateIdentifier => "Internally we expected something to follow the same rules as an identifier, but it didn't follow those rules",
             LexerReportKind::CantTranslateToToken(_) => "The internal translation between simple Tokens and the CST::Tokens failed!",
             LexerReportKind::UnexpectedOwnershipLiteralMatch => "We find what seems to look like an ownership literal, but something unexpected passed while working with it!",
-            LexerReportKind::CantParseU64(_)=> "We find what seems to look like an u64 literal, but something unexpected passed while working with it!",
+            LexerReportKind::UnfinishedString(..) => "We consumed all the code looking for the end of the string.",
+            LexerReportKind::InvalidEscape => "The escapes in strings are '\\n', '\\t', '\\r', '\\0', '\\\\', '\\'', '\\\"' and '\\u{..}' with the\nhexadecimal code of a unicode scalar value, like '\\u{1F600}'.\nUse a raw string like r#\"..\"# to keep the backslashes.",
+            LexerReportKind::UnmatchedInterpolationBrace => "The braces in interpolation strings open and close holes like f#\"{x}\",\nwrite '}}' for a literal '}' and '{{' for a literal '{'.",
+            LexerReportKind::InvalidInterpolation => "The holes of a interpolation string must contain a single expression,\noptionally followed by ':' and a format specifier like f#\"{x + 1:>8}\".",
+            LexerReportKind::InvalidFormatSpec => "A format specifier has the shape '[[fill]align][0][width][.precision][kind]',\nthe alignments are '<', '^' and '>' and the kinds are 'x', 'X', 'b', 'o' and '?'.\nAs an example f#\"{x:*^10.2}\" and f#\"{x:08X}\".",
+            LexerReportKind::InvalidCharLiteral => "A character literal must have exactly one unicode scalar value like 'a',\nor one of the escapes '\\n', '\\t', '\\r', '\\0', '\\\\', '\\'', '\\\"' or '\\u{1F600}'.",
         }))
     }
     fn get_expected(&self) -> Option<Vec<String>> {
         match self {
-            LexerReportKind::CouldntMatchBlockComment(kind) => {
-                let hyphens = "-".repeat(kind.len() - 1);
-                Some(vec![hyphens + "}"])
+            LexerReportKind::CouldntMatchBlockComment(open, _) => {
+                Some(vec![block_comment_closers(open)])
+            }
+            LexerReportKind::UnfinishedString(kind, _) => {
+                Some(vec![kind.end()])
+            }
+            LexerReportKind::UnmatchedInterpolationBrace => {
+                Some(vec![String::from("}}")])
             }
             _ => None,
         }
@@ -157,6 +213,35 @@ impl ReportFormat for LexerReportKind {
     fn get_location_maybe(&self) -> Option<Location> {
         None
     }
+    fn get_suggestions(&self) -> Option<Vec<Suggestion>> {
+        match self {
+            LexerReportKind::CouldntMatchBlockComment(open, end) => {
+                Some(vec![Suggestion::insert_at(
+                    NonLineBreakStr::new("Close the block comment"),
+                    *end,
+                    block_comment_closers(open),
+                    Applicability::MachineApplicable,
+                )])
+            }
+            LexerReportKind::UnfinishedString(kind, end) => {
+                Some(vec![Suggestion::insert_at(
+                    NonLineBreakStr::new("Close the string"),
+                    *end,
+                    kind.end(),
+                    Applicability::MaybeIncorrect,
+                )])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The closing braces of the open block comments, the innermost first.
+fn block_comment_closers(open: &[usize]) -> String {
+    open.iter()
+        .rev()
+        .map(|hyphens| "-".repeat(*hyphens) + "}")
+        .collect()
 }
 
 #[derive(Debug, Clone, Equivalence)]
@@ -198,6 +283,11 @@ impl ReportFormat for ParserReport {
             ParserReport::Lexer(e) => e.get_short_description(),
         }
     }
+    fn get_suggestions(&self) -> Option<Vec<Suggestion>> {
+        match self {
+            ParserReport::Lexer(e) => e.get_suggestions(),
+        }
+    }
 }
 
 impl ReportFormat for OctizysParserReport {
@@ -216,4 +306,7 @@ impl ReportFormat for OctizysParserReport {
     fn get_short_description(&self) -> NonLineBreakStr {
         self.report.get_short_description()
     }
+    fn get_suggestions(&self) -> Option<Vec<Suggestion>> {
+        self.report.get_suggestions()
+    }
 }
@@ -1,5 +1,6 @@
 use crate::report::{LexerReportKind, OctizysParserReport, ParserReport};
 use lalrpop_util::ParseError;
+use octizys_common::dump::{atom, literal, node, Dump};
 use octizys_common::span::Position;
 use octizys_common::{
     identifier::Identifier,
@@ -10,11 +11,13 @@ use octizys_cst::{
     base::TokenInfo,
     comments::{Comment, CommentBlock, CommentLine},
     literals::{
-        InterpolationString, StringLiteral, UFloatingPointLiteral, UintLiteral,
+        CharLiteral, InterpolationString, StringLiteral, UFloatingPointLiteral,
+        UintLiteral,
     },
     types::{OwnershipLiteral, OwnershipVariable},
 };
 use octizys_macros::Equivalence;
+use octizys_pretty::document::Document;
 use paste::paste;
 
 /// We lex the stream in two phases, the first one retrieve a
@@ -89,6 +92,7 @@ pub enum BaseToken {
     BlockComment(CommentBlock),
     UintLiteral(UintLiteral),
     UFloatLiteral(UFloatingPointLiteral),
+    CharLiteral(CharLiteral),
     StringLiteral(StringLiteral),
     StringInterpolation(InterpolationString),
     Identifier(Identifier),
@@ -183,6 +187,7 @@ pub enum Token {
     StringInterpolation(#[equivalence(ignore)] TokenInfo, InterpolationString),
     UintLiteral(#[equivalence(ignore)] TokenInfo, UintLiteral),
     UFloatLiteral(#[equivalence(ignore)] TokenInfo, UFloatingPointLiteral),
+    CharLiteral(#[equivalence(ignore)] TokenInfo, CharLiteral),
     Identifier(#[equivalence(ignore)] TokenInfo, Identifier),
     InfixIdentifier(#[equivalence(ignore)] TokenInfo, Identifier),
     Selector(#[equivalence(ignore)] TokenInfo, Identifier),
@@ -193,6 +198,153 @@ pub enum Token {
     OwnershipVariable(#[equivalence(ignore)] TokenInfo, OwnershipVariable),
 }
 
+/// The payload of the literals and comments is shown with the
+/// [`std::fmt::Debug`] of the CST.
+impl Dump for Token {
+    fn dump(&self) -> Document {
+        match self {
+            Token::Interrogation(info) => {
+                node("Interrogation", [info.span.dump()])
+            }
+            Token::Exclamation(info) => node("Exclamation", [info.span.dump()]),
+            Token::Hash(info) => node("Hash", [info.span.dump()]),
+            Token::Comma(info) => node("Comma", [info.span.dump()]),
+            Token::Colon(info) => node("Colon", [info.span.dump()]),
+            Token::StatementEnd(info) => {
+                node("StatementEnd", [info.span.dump()])
+            }
+            Token::Dot(info) => node("Dot", [info.span.dump()]),
+            Token::ModuleSeparator(info) => {
+                node("ModuleSeparator", [info.span.dump()])
+            }
+            Token::Minus(info) => node("Minus", [info.span.dump()]),
+            Token::CompositionRight(info) => {
+                node("CompositionRight", [info.span.dump()])
+            }
+            Token::CompositionLeft(info) => {
+                node("CompositionLeft", [info.span.dump()])
+            }
+            Token::Plus(info) => node("Plus", [info.span.dump()]),
+            Token::Power(info) => node("Power", [info.span.dump()]),
+            Token::Star(info) => node("Star", [info.span.dump()]),
+            Token::Div(info) => node("Div", [info.span.dump()]),
+            Token::Module(info) => node("Module", [info.span.dump()]),
+            Token::ShiftLeft(info) => node("ShiftLeft", [info.span.dump()]),
+            Token::ShiftRight(info) => node("ShiftRight", [info.span.dump()]),
+            Token::Map(info) => node("Map", [info.span.dump()]),
+            Token::MapConstRight(info) => {
+                node("MapConstRight", [info.span.dump()])
+            }
+            Token::MapConstLeft(info) => {
+                node("MapConstLeft", [info.span.dump()])
+            }
+            Token::Appliative(info) => node("Appliative", [info.span.dump()]),
+            Token::ApplicativeRight(info) => {
+                node("ApplicativeRight", [info.span.dump()])
+            }
+            Token::ApplicativeLeft(info) => {
+                node("ApplicativeLeft", [info.span.dump()])
+            }
+            Token::Equality(info) => node("Equality", [info.span.dump()]),
+            Token::NotEqual(info) => node("NotEqual", [info.span.dump()]),
+            Token::LessOrEqual(info) => node("LessOrEqual", [info.span.dump()]),
+            Token::MoreOrEqual(info) => node("MoreOrEqual", [info.span.dump()]),
+            Token::LessThan(info) => node("LessThan", [info.span.dump()]),
+            Token::MoreThan(info) => node("MoreThan", [info.span.dump()]),
+            Token::And(info) => node("And", [info.span.dump()]),
+            Token::Or(info) => node("Or", [info.span.dump()]),
+            Token::ReverseApplication(info) => {
+                node("ReverseApplication", [info.span.dump()])
+            }
+            Token::DollarApplication(info) => {
+                node("DollarApplication", [info.span.dump()])
+            }
+            Token::Asignation(info) => node("Asignation", [info.span.dump()]),
+            Token::At(info) => node("At", [info.span.dump()]),
+            Token::Pipe(info) => node("Pipe", [info.span.dump()]),
+            Token::Alternative(info) => node("Alternative", [info.span.dump()]),
+            Token::FlippedMap(info) => node("FlippedMap", [info.span.dump()]),
+            Token::Annotate(info) => node("Annotate", [info.span.dump()]),
+            Token::LParen(info) => node("LParen", [info.span.dump()]),
+            Token::RParen(info) => node("RParen", [info.span.dump()]),
+            Token::LBracket(info) => node("LBracket", [info.span.dump()]),
+            Token::RBracket(info) => node("RBracket", [info.span.dump()]),
+            Token::LBrace(info) => node("LBrace", [info.span.dump()]),
+            Token::RBrace(info) => node("RBrace", [info.span.dump()]),
+            Token::RightArrow(info) => node("RightArrow", [info.span.dump()]),
+            Token::LeftArrow(info) => node("LeftArrow", [info.span.dump()]),
+            Token::LambdaStart(info) => node("LambdaStart", [info.span.dump()]),
+            Token::Let(info) => node("Let", [info.span.dump()]),
+            Token::In(info) => node("In", [info.span.dump()]),
+            Token::Case(info) => node("Case", [info.span.dump()]),
+            Token::Of(info) => node("Of", [info.span.dump()]),
+            Token::Import(info) => node("Import", [info.span.dump()]),
+            Token::Data(info) => node("Data", [info.span.dump()]),
+            Token::Newtype(info) => node("Newtype", [info.span.dump()]),
+            Token::Class(info) => node("Class", [info.span.dump()]),
+            Token::Instance(info) => node("Instance", [info.span.dump()]),
+            Token::Public(info) => node("Public", [info.span.dump()]),
+            Token::Alias(info) => node("Alias", [info.span.dump()]),
+            Token::As(info) => node("As", [info.span.dump()]),
+            Token::Unqualified(info) => node("Unqualified", [info.span.dump()]),
+            Token::Forall(info) => node("Forall", [info.span.dump()]),
+            Token::Type(info) => node("Type", [info.span.dump()]),
+            Token::U8(info) => node("U8", [info.span.dump()]),
+            Token::U16(info) => node("U16", [info.span.dump()]),
+            Token::U32(info) => node("U32", [info.span.dump()]),
+            Token::U64(info) => node("U64", [info.span.dump()]),
+            Token::I8(info) => node("I8", [info.span.dump()]),
+            Token::I16(info) => node("I16", [info.span.dump()]),
+            Token::I32(info) => node("I32", [info.span.dump()]),
+            Token::I64(info) => node("I64", [info.span.dump()]),
+            Token::F32(info) => node("F32", [info.span.dump()]),
+            Token::F64(info) => node("F64", [info.span.dump()]),
+            Token::CharType(info) => node("CharType", [info.span.dump()]),
+            Token::StringType(info) => node("StringType", [info.span.dump()]),
+            Token::Comment(info, value) => {
+                node("Comment", [info.span.dump(), literal(value)])
+            }
+            Token::StringLiteral(info, value) => {
+                node("StringLiteral", [info.span.dump(), literal(value)])
+            }
+            Token::StringInterpolation(info, value) => {
+                node("StringInterpolation", [info.span.dump(), literal(value)])
+            }
+            Token::UintLiteral(info, value) => {
+                node("UintLiteral", [info.span.dump(), atom(value)])
+            }
+            Token::UFloatLiteral(info, value) => {
+                node("UFloatLiteral", [info.span.dump(), atom(value)])
+            }
+            Token::CharLiteral(info, value) => {
+                node("CharLiteral", [info.span.dump(), atom(value)])
+            }
+            Token::Identifier(info, value) => {
+                node("Identifier", [info.span.dump(), value.dump()])
+            }
+            Token::InfixIdentifier(info, value) => {
+                node("InfixIdentifier", [info.span.dump(), value.dump()])
+            }
+            Token::Selector(info, value) => {
+                node("Selector", [info.span.dump(), value.dump()])
+            }
+            Token::AnonHole(info) => node("AnonHole", [info.span.dump()]),
+            Token::NamedHole(info, value) => {
+                node("NamedHole", [info.span.dump(), atom(value)])
+            }
+            Token::LastComments(info, value) => {
+                node("LastComments", [info.span.dump(), literal(value)])
+            }
+            Token::OwnershipLiteral(info, value) => {
+                node("OwnershipLiteral", [info.span.dump(), literal(value)])
+            }
+            Token::OwnershipVariable(info, value) => {
+                node("OwnershipVariable", [info.span.dump(), literal(value)])
+            }
+        }
+    }
+}
+
 impl HasLocation for Token {
     fn get_location(&self) -> Location {
         <&TokenInfo>::from(self).get_location()
@@ -283,6 +435,7 @@ impl From<Token> for TokenInfo {
             Token::StringInterpolation(info, _) => info,
             Token::UintLiteral(info, _) => info,
             Token::UFloatLiteral(info, _) => info,
+            Token::CharLiteral(info, _) => info,
             Token::Identifier(info, _) => info,
             Token::InfixIdentifier(info, _) => info,
             Token::Selector(info, _) => info,
@@ -379,6 +532,7 @@ impl<'a> From<&'a Token> for &'a TokenInfo {
             Token::StringInterpolation(info, _) => info,
             Token::UintLiteral(info, _) => info,
             Token::UFloatLiteral(info, _) => info,
+            Token::CharLiteral(info, _) => info,
             Token::Identifier(info, _) => info,
             Token::InfixIdentifier(info, _) => info,
             Token::Selector(info, _) => info,
@@ -392,8 +546,10 @@ impl<'a> From<&'a Token> for &'a TokenInfo {
 }
 
 macro_rules! make_lexer_token_to_token {
-    ($name:tt, $output_constructor:tt, $output_type:tt) => {
+    ($name:tt, $output_constructor:tt, $output_type:ty) => {
         paste!{
+            // The rules of lalrpop return the errors by value.
+            #[allow(clippy::result_large_err)]
             pub fn [< $name _token_to_token >](t:Token)->Result<octizys_cst::base::Token<$output_type>,ParseError<Position,Token,OctizysParserReport>>{
                 match t {
                     Token::$output_constructor(info,value) => Ok(octizys_cst::base::Token{value,info}),
@@ -421,9 +577,10 @@ make_lexer_token_to_token!(
     InterpolationString
 );
 make_lexer_token_to_token!(ufloat, UFloatLiteral, UFloatingPointLiteral);
+make_lexer_token_to_token!(char, CharLiteral, CharLiteral);
 make_lexer_token_to_token!(selector, Selector, Identifier);
 make_lexer_token_to_token!(named_hole, NamedHole, u64);
-make_lexer_token_to_token!(last_comment, Comment, Comment);
+make_lexer_token_to_token!(last_comments, LastComments, Vec<Comment>);
 
 pub fn aux_base_token_to_token(
     base_token: BaseToken,
@@ -502,6 +659,7 @@ pub fn aux_base_token_to_token(
         }
         BaseToken::UintLiteral(s) => Token::UintLiteral(info, s),
         BaseToken::UFloatLiteral(s) => Token::UFloatLiteral(info, s),
+        BaseToken::CharLiteral(s) => Token::CharLiteral(info, s),
         BaseToken::Identifier(s) => Token::Identifier(info, s),
         BaseToken::InfixIdentifier(s) => Token::InfixIdentifier(info, s),
         BaseToken::Selector(s) => Token::Selector(info, s),
@@ -93,6 +93,11 @@ pub fn static_str(word: NonLineBreakStr) -> Document {
     Document::static_str(word)
 }
 
+/// Text whose line breaks don't get the indentation.
+pub fn verbatim_text(words: &str) -> Document {
+    Document::verbatim_text(words)
+}
+
 /// Stores a given string as a comment in the [`Store`] and returns a
 /// document that can be used to refer to it.
 pub fn comment_line(
@@ -1,9 +1,9 @@
 use std::ops::Add;
 
 use log::{debug, trace};
-use octizys_text_store::store::{
-    approximate_string_width, NonLineBreakStr, NonLineBreakString, Store,
-    StoreSymbol,
+use octizys_text_store::{
+    store::{NonLineBreakStr, NonLineBreakString, Store, StoreSymbol},
+    width::{display_width, display_width_at},
 };
 
 use crate::highlight::{Color, Emphasis, Highlight};
@@ -21,6 +21,8 @@ enum DocumentInternal {
     SoftBreak,
     /// Always translated to \n
     HardBreak,
+    /// Always translated to \n, without the indentation.
+    VerbatimBreak,
     /// Flat mode : empty
     /// Break mode : \n
     EmptyBreak,
@@ -120,6 +122,12 @@ impl Document {
         Document(DocumentInternal::HardBreak)
     }
 
+    /// Always a line break, the next line doesn't get the
+    /// indentation of the nest.
+    pub fn verbatim_break() -> Self {
+        Document(DocumentInternal::VerbatimBreak)
+    }
+
     /// Flat mode : empty doc.
     /// Break mode : a line break.
     pub fn empty_break() -> Self {
@@ -146,7 +154,7 @@ impl Document {
         maybe_words: &str,
     ) -> Option<Self> {
         let symbol = store.regular.try_add(maybe_words)?;
-        let len = approximate_string_width(maybe_words);
+        let len = display_width(maybe_words);
         Some(Document(DocumentInternal::StoredRegularText(symbol, len)))
     }
 
@@ -162,7 +170,7 @@ impl Document {
         store: &mut Store,
         s: NonLineBreakString,
     ) -> Self {
-        let len = approximate_string_width((&s).into());
+        let len = display_width((&s).into());
         let symbol = store.regular.add(s);
         Document(DocumentInternal::StoredRegularText(symbol, len))
     }
@@ -179,7 +187,7 @@ impl Document {
         store: &mut Store,
         s: NonLineBreakStr,
     ) -> Self {
-        let len = approximate_string_width(s.into());
+        let len = display_width(s.into());
         let symbol = store.regular.add_str(s);
         Document(DocumentInternal::StoredRegularText(symbol, len))
     }
@@ -190,7 +198,7 @@ impl Document {
     pub fn external_text(words: &str) -> Self {
         let mut acc = vec![];
         for word in NonLineBreakString::decompose(words) {
-            let len = approximate_string_width((&word).into());
+            let len = display_width((&word).into());
             let doc = DocumentInternal::ExternalText(word, len);
             acc.push(doc);
             acc.push(DocumentInternal::HardBreak);
@@ -205,9 +213,27 @@ impl Document {
         Document(DocumentInternal::Concat(acc))
     }
 
+    /// Like [`Document::external_text`] but the lines are separated by
+    /// [`Document::verbatim_break`], the text is rendered exactly as it is
+    /// regardless of the indentation, as the string literals need.
+    pub fn verbatim_text(words: &str) -> Self {
+        let mut acc = vec![];
+        for word in NonLineBreakString::decompose(words) {
+            let len = display_width((&word).into());
+            acc.push(DocumentInternal::ExternalText(word, len));
+            acc.push(DocumentInternal::VerbatimBreak);
+        }
+        acc.pop();
+        match acc.len() {
+            0 => Document::empty(),
+            1 => Document(acc.pop().unwrap()),
+            _ => Document(DocumentInternal::Concat(acc)),
+        }
+    }
+
     pub fn static_str(word: NonLineBreakStr) -> Document {
         let s = word.as_str();
-        let len = approximate_string_width(s);
+        let len = display_width(s);
         Document(DocumentInternal::StaticText(word, len))
     }
 
@@ -219,7 +245,7 @@ impl Document {
         comments_accumulator.push(String::from(source));
         Document(DocumentInternal::StoredCommentText {
             index,
-            len: approximate_string_width(source),
+            len: display_width(source),
         })
     }
 
@@ -339,17 +365,39 @@ impl<'doc> DocumentIterator<'doc> {
     }
 
     fn advance_width_with(&mut self, new_str: String) -> String {
-        self.consumed_width += approximate_string_width(&new_str);
+        self.consumed_width += display_width_at(&new_str, self.consumed_width);
         new_str
     }
 
     fn gen_line_break(&mut self, indentation: u16) -> String {
         let new_str =
             [String::from("\n"), " ".repeat(usize::from(indentation))].join("");
-        self.consumed_width = approximate_string_width(&new_str) - 1;
+        self.consumed_width = usize::from(indentation);
         new_str
     }
 
+    /// The width of a text node if it starts at `column`, measured
+    /// like [`DocumentIterator::advance_width_with`] does.
+    /// The stored length assumes the text starts at the beginning of
+    /// a line, this only matters for texts with tabs.
+    fn text_width_at(&self, doc: &DocumentInternal, column: usize) -> usize {
+        let (text, len): (Option<&str>, usize) = match doc {
+            DocumentInternal::StoredRegularText(s, len) => {
+                (self.store.regular.resolve(*s), *len)
+            }
+            DocumentInternal::ExternalText(s, len) => (Some(s.into()), *len),
+            DocumentInternal::StaticText(s, len) => (Some(s.as_str()), *len),
+            DocumentInternal::StoredCommentText { index, len } => {
+                (self.store.comments.resolve(*index).map(|x| x.into()), *len)
+            }
+            _ => (None, 0),
+        };
+        match text {
+            Some(text) if text.contains('\t') => display_width_at(text, column),
+            _ => len,
+        }
+    }
+
     fn fits(&self, param: FitsParam) -> bool {
         debug!(
             "Entering fits function with line_width= {}, consumed = {}, param = {param:#?}",
@@ -396,6 +444,7 @@ impl<'doc> DocumentIterator<'doc> {
                     Mode::Break => return true,
                 },
                 DocumentInternal::HardBreak => return true,
+                DocumentInternal::VerbatimBreak => return true,
                 DocumentInternal::EmptyBreak => match current.mode {
                     Mode::Flat => continue,
                     Mode::Break => return true,
@@ -415,34 +464,20 @@ impl<'doc> DocumentIterator<'doc> {
                         stack.push(current.with_document(doc))
                     }
                 }
-                DocumentInternal::StoredRegularText(_, width) => {
-                    if *width <= remain_width {
-                        remain_width -= width;
-                    } else {
-                        return false;
-                    }
-                }
-                DocumentInternal::ExternalText(_, width) => {
-                    if *width <= remain_width {
-                        remain_width -= width;
-                    } else {
-                        return false;
-                    }
-                }
-                DocumentInternal::StaticText(_, width) => {
-                    if *width <= remain_width {
+                DocumentInternal::StoredRegularText(..)
+                | DocumentInternal::ExternalText(..)
+                | DocumentInternal::StaticText(..)
+                | DocumentInternal::StoredCommentText { .. } => {
+                    let width = self.text_width_at(
+                        current.doc,
+                        self.line_width - remain_width,
+                    );
+                    if width <= remain_width {
                         remain_width -= width;
                     } else {
                         return false;
                     }
                 }
-                DocumentInternal::StoredCommentText { len, .. } => {
-                    if *len <= remain_width {
-                        remain_width -= len;
-                    } else {
-                        return false;
-                    }
-                }
                 DocumentInternal::Nest(more_indent, doc) => stack
                     .push(current.with_document(doc).add_ident(*more_indent)),
                 DocumentInternal::Group(doc) => {
@@ -502,6 +537,11 @@ impl<'doc> Iterator for DocumentIterator<'doc> {
                 &self.gen_line_break(current.ident),
                 self.highlight_renderer,
             )),
+            DocumentInternal::VerbatimBreak => Some(add_highlight(
+                &current.highlight,
+                &self.gen_line_break(0),
+                self.highlight_renderer,
+            )),
             DocumentInternal::EmptyBreak => match current.mode {
                 Mode::Flat => Some(String::new()),
                 Mode::Break => Some(add_highlight(
@@ -649,6 +689,16 @@ mod render_tests {
         make_test(&raw, document, 10)
     }
 
+    #[test]
+    fn verbatim_text_ignores_nest() {
+        let document = Document::external_text("a")
+            + Document::nest(
+                2,
+                Document::hard_break() + Document::verbatim_text("b\n c"),
+            );
+        make_test("a\n  b\n c", document, 10)
+    }
+
     #[test]
     fn nest_break_group() {
         let document = Document::group(Document::concat(vec![
@@ -682,4 +732,30 @@ mod render_tests {
         ]));
         make_test("hello world !", document, 20)
     }
+
+    #[test]
+    fn wide_characters_break_group() {
+        // Eight chars (with the space of the break) but eleven columns,
+        // it doesn't fit in ten.
+        let document = Document::group(Document::concat(vec![
+            Document::external_text("日本語"),
+            Document::soft_break(),
+            Document::external_text("abcd"),
+        ]));
+        make_test("日本語\nabcd", document, 10)
+    }
+
+    #[test]
+    fn tabs_are_measured_from_their_column() {
+        // The tab after "ab" only uses two columns, so the group takes
+        // seven columns, it would be nine if the tab were measured
+        // from the beginning of the line.
+        let document = Document::external_text("ab")
+            + Document::group(Document::concat(vec![
+                Document::external_text("\tc"),
+                Document::soft_break(),
+                Document::external_text("d"),
+            ]));
+        make_test("ab\tc d", document, 7)
+    }
 }
@@ -1,11 +1,14 @@
-use octizys_common::{
-    identifier::Identifier, module_logic_path::LogicPath, span::Span,
-};
+use std::collections::HashMap;
+use std::fmt::Display;
 
-#[derive(Debug)]
+use octizys_core::common::Identifier;
+
+/// A variable created by the inference, they are solved by
+/// unification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VariableId(pub u32);
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BasicType {
     U64,
     I64,
@@ -21,74 +24,275 @@ pub enum BasicType {
     Char,
 }
 
-#[derive(Debug)]
-pub struct Function {
-    pub span: Span,
-    pub arguments: Vec<Span>,
-    pub output: Box<Type>,
+impl BasicType {
+    /// The name used in the source to refer to the type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BasicType::U64 => "U64",
+            BasicType::I64 => "I64",
+            BasicType::U32 => "U32",
+            BasicType::I32 => "I32",
+            BasicType::U16 => "U16",
+            BasicType::I16 => "I16",
+            BasicType::U8 => "U8",
+            BasicType::I8 => "I8",
+            BasicType::Float => "F32",
+            BasicType::Double => "F64",
+            BasicType::String => "String",
+            BasicType::Char => "Char",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BasicType> {
+        match name {
+            "U64" => Some(BasicType::U64),
+            "I64" => Some(BasicType::I64),
+            "U32" => Some(BasicType::U32),
+            "I32" => Some(BasicType::I32),
+            "U16" => Some(BasicType::U16),
+            "I16" => Some(BasicType::I16),
+            "U8" => Some(BasicType::U8),
+            "I8" => Some(BasicType::I8),
+            "F32" => Some(BasicType::Float),
+            "F64" => Some(BasicType::Double),
+            "String" => Some(BasicType::String),
+            "Char" => Some(BasicType::Char),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct Forall {
-    pub span: Span,
-    pub arguments: Vec<Span>,
-    pub output: Box<Type>,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    BasicType(BasicType),
+    /// A rigid variable, introduced by a `forall`, a parameter of a
+    /// data type or by the generalization of a definition.
+    Variable(Identifier),
+    InferenceVariable(VariableId),
+    Function(Box<Type>, Box<Type>),
+    Tuple(Vec<Type>),
+    /// The fields are sorted by label.
+    Record(Vec<(Identifier, Type)>),
+    /// A data type or a newtype applied to its arguments.
+    Named(Identifier, Vec<Type>),
 }
 
-#[derive(Debug)]
-pub struct Constructor {
-    pub span: Span,
-    pub prefix: Option<LogicPath>,
-    pub name: (Span, Variable),
-    pub _type: Box<Type>,
+impl Type {
+    pub fn function(argument: Type, output: Type) -> Type {
+        Type::Function(Box::new(argument), Box::new(output))
+    }
+
+    pub fn unit() -> Type {
+        Type::Tuple(vec![])
+    }
+
+    /// Replaces the rigid variables using the map.
+    pub fn substitute(&self, map: &HashMap<Identifier, Type>) -> Type {
+        match self {
+            Type::Variable(name) => match map.get(name) {
+                Some(t) => t.clone(),
+                None => self.clone(),
+            },
+            Type::BasicType(_) | Type::InferenceVariable(_) => self.clone(),
+            Type::Function(argument, output) => {
+                Type::function(argument.substitute(map), output.substitute(map))
+            }
+            Type::Tuple(items) => {
+                Type::Tuple(items.iter().map(|t| t.substitute(map)).collect())
+            }
+            Type::Record(fields) => Type::Record(
+                fields
+                    .iter()
+                    .map(|(label, t)| (label.clone(), t.substitute(map)))
+                    .collect(),
+            ),
+            Type::Named(name, arguments) => Type::Named(
+                name.clone(),
+                arguments.iter().map(|t| t.substitute(map)).collect(),
+            ),
+        }
+    }
+
+    /// Renames the data types using the map.
+    pub fn rename(&mut self, map: &HashMap<Identifier, Identifier>) {
+        match self {
+            Type::BasicType(_)
+            | Type::Variable(_)
+            | Type::InferenceVariable(_) => (),
+            Type::Function(argument, output) => {
+                argument.rename(map);
+                output.rename(map);
+            }
+            Type::Tuple(items) => items.iter_mut().for_each(|t| t.rename(map)),
+            Type::Record(fields) => {
+                fields.iter_mut().for_each(|(_, t)| t.rename(map))
+            }
+            Type::Named(name, arguments) => {
+                if let Some(new) = map.get(name) {
+                    *name = new.clone();
+                }
+                arguments.iter_mut().for_each(|t| t.rename(map))
+            }
+        }
+    }
+
+    /// Adds the rigid variables to the vector in order of appearance.
+    pub fn rigid_variables(&self, acc: &mut Vec<Identifier>) {
+        match self {
+            Type::Variable(name) => {
+                if !acc.contains(name) {
+                    acc.push(name.clone())
+                }
+            }
+            Type::BasicType(_) | Type::InferenceVariable(_) => (),
+            Type::Function(argument, output) => {
+                argument.rigid_variables(acc);
+                output.rigid_variables(acc);
+            }
+            Type::Tuple(items) => {
+                items.iter().for_each(|t| t.rigid_variables(acc))
+            }
+            Type::Record(fields) => {
+                fields.iter().for_each(|(_, t)| t.rigid_variables(acc))
+            }
+            Type::Named(_, arguments) => {
+                arguments.iter().for_each(|t| t.rigid_variables(acc))
+            }
+        }
+    }
+
+    fn is_atomic(&self) -> bool {
+        match self {
+            Type::Function(..) => false,
+            Type::Named(_, arguments) => arguments.is_empty(),
+            _ => true,
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct SumType {
-    pub span: Span,
-    pub prefix: Option<LogicPath>,
-    pub name: (Span, Identifier),
-    pub bound_variables: Vec<(Span, Identifier)>,
-    pub constructors: Vec<Constructor>,
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::BasicType(basic) => write!(f, "{}", basic.name()),
+            Type::Variable(name) => write!(f, "{}", name),
+            Type::InferenceVariable(id) => write!(f, "?{}", id.0),
+            Type::Function(argument, output) => {
+                if let Type::Function(..) = **argument {
+                    write!(f, "({}) -> {}", argument, output)
+                } else {
+                    write!(f, "{} -> {}", argument, output)
+                }
+            }
+            Type::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, t) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", t)?;
+                }
+                if items.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Type::Record(fields) => {
+                write!(f, "{{")?;
+                for (i, (label, t)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} : {}", label, t)?;
+                }
+                write!(f, "}}")
+            }
+            Type::Named(name, arguments) => {
+                write!(f, "{}", name)?;
+                for argument in arguments {
+                    if argument.is_atomic() {
+                        write!(f, " {}", argument)?;
+                    } else {
+                        write!(f, " ({})", argument)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct NewType {
-    pub span: Span,
-    pub prefix: Option<LogicPath>,
-    pub name: (Span, Identifier),
-    pub bound_variables: Vec<(Span, Identifier)>,
-    pub constructor: Constructor,
+/// A type with universally quantified variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeScheme {
+    pub variables: Vec<Identifier>,
+    pub type_: Type,
 }
 
-#[derive(Debug)]
-pub struct Alias {
-    pub span: Span,
-    pub prefix: Option<LogicPath>,
-    pub name: (Span, Identifier),
-    pub bound_variables: Vec<(Span, Identifier)>,
-    pub _type: Box<Type>,
+impl TypeScheme {
+    pub fn rename(&mut self, map: &HashMap<Identifier, Identifier>) {
+        self.type_.rename(map)
+    }
+
+    pub fn monomorphic(type_: Type) -> Self {
+        TypeScheme {
+            variables: vec![],
+            type_,
+        }
+    }
+
+    /// Renames the quantified variables to `a`, `b`, `c`... in order
+    /// of appearance, this is how we show the schemes to the user.
+    pub fn normalize(&self) -> TypeScheme {
+        let mut appearance = vec![];
+        self.type_.rigid_variables(&mut appearance);
+        let bound: Vec<&Identifier> = appearance
+            .iter()
+            .filter(|x| self.variables.contains(x))
+            .collect();
+        let free: Vec<Identifier> = appearance
+            .iter()
+            .filter(|x| !self.variables.contains(x))
+            .cloned()
+            .collect();
+        let mut map = HashMap::new();
+        let mut variables = vec![];
+        let mut names = variable_names().filter(|name| !free.contains(name));
+        for old in bound {
+            // The iterator is infinite.
+            let name = names.next().unwrap();
+            map.insert(old.clone(), Type::Variable(name.clone()));
+            variables.push(name);
+        }
+        TypeScheme {
+            variables,
+            type_: self.type_.substitute(&map),
+        }
+    }
 }
 
-/*
-#[derive(Debug)]
-pub struct Record {
-    span: Span,
-    //TODO: Add row polymorphism
-    record: octizys_common::Record<Type>,
+impl Display for TypeScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.variables.is_empty() {
+            write!(f, "{}", self.type_)
+        } else {
+            write!(f, "forall")?;
+            for variable in &self.variables {
+                write!(f, " {}", variable)?;
+            }
+            write!(f, " . {}", self.type_)
+        }
+    }
 }
-*/
 
-#[derive(Debug)]
-pub enum Type {
-    BasicType { _type: BasicType, span: Span },
-    LocalVariable(VariableId),
-    ExternalVariable(Identifier, Option<LogicPath>),
-    InferenceVariable(VariableId),
-    Function(Function),
-    Forall(Forall),
-    SumType(SumType),
-    NewType(NewType),
-    Alias(Alias),
-    //Record(Record<'prefix, Info>),
+/// The infinite sequence `a`, `b`, ..., `z`, `a1`, `b1`, ...
+pub fn variable_names() -> impl Iterator<Item = Identifier> {
+    (0u64..).map(|i| {
+        let letter = char::from(b'a' + (i % 26) as u8);
+        let name = if i < 26 {
+            letter.to_string()
+        } else {
+            format!("{}{}", letter, i / 26)
+        };
+        Identifier::make(name).unwrap()
+    })
 }
@@ -0,0 +1,236 @@
+//! The [`Dump`] of the SAST, used by `octizys compile --emit sast`.
+//! The types are already resolved, we show them with their
+//! [`std::fmt::Display`] as a node `(: type)`.
+
+use octizys_common::dump::{atom, literal, node, Dump};
+use octizys_pretty::document::Document;
+
+use crate::_type::{Type, TypeScheme};
+use crate::expression::{
+    BasicExpression, CaseCase, ConstructorReference, Expression,
+    ExpressionKind, InterpolationPart, LetBinding, Pattern, PatternKind,
+};
+use crate::top::{
+    Alias, DataConstructor, DataKind, DataType, Sast, VariableDefinition,
+};
+
+impl Dump for Type {
+    fn dump(&self) -> Document {
+        node(":", [atom(self)])
+    }
+}
+
+impl Dump for TypeScheme {
+    fn dump(&self) -> Document {
+        node(":", [atom(self)])
+    }
+}
+
+impl Dump for BasicExpression {
+    fn dump(&self) -> Document {
+        match self {
+            BasicExpression::U64(value) => node("U64", [literal(value)]),
+            BasicExpression::I64(value) => node("I64", [literal(value)]),
+            BasicExpression::U32(value) => node("U32", [literal(value)]),
+            BasicExpression::I32(value) => node("I32", [literal(value)]),
+            BasicExpression::U16(value) => node("U16", [literal(value)]),
+            BasicExpression::I16(value) => node("I16", [literal(value)]),
+            BasicExpression::U8(value) => node("U8", [literal(value)]),
+            BasicExpression::I8(value) => node("I8", [literal(value)]),
+            BasicExpression::Float(value) => node("F32", [literal(value)]),
+            BasicExpression::Double(value) => node("F64", [literal(value)]),
+            BasicExpression::String(value) => node("String", [literal(value)]),
+            BasicExpression::Char(value) => node("Char", [literal(value)]),
+        }
+    }
+}
+
+impl Dump for ConstructorReference {
+    fn dump(&self) -> Document {
+        node(
+            "ConstructorReference",
+            [atom(&self.data), atom(&self.name), atom(self.index)],
+        )
+    }
+}
+
+impl Dump for Pattern {
+    fn dump(&self) -> Document {
+        let kind = match &self.kind {
+            PatternKind::Constant(value) => node("Constant", [value.dump()]),
+            PatternKind::Variable(name) => node("Variable", [atom(name)]),
+            PatternKind::Constructor {
+                constructor,
+                argument,
+            } => node("Constructor", [constructor.dump(), argument.dump()]),
+            PatternKind::Tuple(items) => {
+                node("Tuple", items.iter().map(Dump::dump))
+            }
+            PatternKind::Record(fields) => node(
+                "Record",
+                fields
+                    .iter()
+                    .map(|(label, p)| node("field", [atom(label), p.dump()])),
+            ),
+            PatternKind::Bind { name, pattern } => {
+                node("Bind", [atom(name), pattern.dump()])
+            }
+            PatternKind::Discard => node("Discard", []),
+        };
+        node("Pattern", [self.span.dump(), self.type_.dump(), kind])
+    }
+}
+
+impl Dump for LetBinding {
+    fn dump(&self) -> Document {
+        node("binding", [self.pattern.dump(), self.value.dump()])
+    }
+}
+
+impl Dump for CaseCase {
+    fn dump(&self) -> Document {
+        node(
+            "case",
+            [
+                self.span.dump(),
+                self.pattern.dump(),
+                self.expression.dump(),
+            ],
+        )
+    }
+}
+
+impl Dump for Expression {
+    fn dump(&self) -> Document {
+        let kind = match &self.kind {
+            ExpressionKind::BasicTerm(value) => value.dump(),
+            ExpressionKind::LocalVariable(name) => {
+                node("LocalVariable", [atom(name)])
+            }
+            ExpressionKind::GlobalVariable(name) => {
+                node("GlobalVariable", [atom(name)])
+            }
+            ExpressionKind::Constructor(constructor) => {
+                node("Constructor", [constructor.dump()])
+            }
+            ExpressionKind::Function { argument, body } => node(
+                "Function",
+                [argument.0.dump(), atom(&argument.1), body.dump()],
+            ),
+            ExpressionKind::Let { bindings, output } => {
+                node("Let", [bindings.dump(), output.dump()])
+            }
+            ExpressionKind::Case { expression, cases } => {
+                node("Case", [expression.dump(), cases.dump()])
+            }
+            ExpressionKind::Application { head, arguments } => {
+                node("Application", [head.dump(), arguments.dump()])
+            }
+            ExpressionKind::Tuple(items) => {
+                node("Tuple", items.iter().map(Dump::dump))
+            }
+            ExpressionKind::Record(fields) => node(
+                "Record",
+                fields
+                    .iter()
+                    .map(|(label, e)| node("field", [atom(label), e.dump()])),
+            ),
+            ExpressionKind::BinaryOperation {
+                operator,
+                left,
+                right,
+            } => node(
+                "BinaryOperation",
+                [
+                    operator.0.dump(),
+                    atom(operator.1.symbol()),
+                    left.dump(),
+                    right.dump(),
+                ],
+            ),
+            ExpressionKind::Negation(expression) => {
+                node("Negation", [expression.dump()])
+            }
+            ExpressionKind::Interpolation(parts) => node(
+                "Interpolation",
+                parts.iter().map(|part| match part {
+                    InterpolationPart::Text(text) => {
+                        node("Text", [literal(text)])
+                    }
+                    InterpolationPart::Hole(hole, format) => {
+                        node("Hole", [hole.dump(), literal(format.to_string())])
+                    }
+                }),
+            ),
+        };
+        node("Expression", [self.span.dump(), self.type_.dump(), kind])
+    }
+}
+
+impl Dump for DataConstructor {
+    fn dump(&self) -> Document {
+        node(
+            "DataConstructor",
+            [self.span.dump(), atom(&self.name), self.argument.dump()],
+        )
+    }
+}
+
+impl Dump for DataType {
+    fn dump(&self) -> Document {
+        let name = match self.kind {
+            DataKind::Data => "Data",
+            DataKind::NewType => "NewType",
+        };
+        node(
+            name,
+            [
+                self.span.dump(),
+                atom(&self.name),
+                node("variables", self.variables.iter().map(atom)),
+                self.constructors.dump(),
+            ],
+        )
+    }
+}
+
+impl Dump for Alias {
+    fn dump(&self) -> Document {
+        node(
+            "Alias",
+            [
+                self.span.dump(),
+                atom(&self.name),
+                node("variables", self.variables.iter().map(atom)),
+                self.type_.dump(),
+            ],
+        )
+    }
+}
+
+impl Dump for VariableDefinition {
+    fn dump(&self) -> Document {
+        node(
+            "VariableDefinition",
+            [
+                self.span.dump(),
+                atom(&self.name),
+                self.type_.dump(),
+                self.value.dump(),
+            ],
+        )
+    }
+}
+
+impl Dump for Sast {
+    fn dump(&self) -> Document {
+        node(
+            "Sast",
+            [
+                self.data_types.dump(),
+                self.aliases.dump(),
+                self.definitions.dump(),
+            ],
+        )
+    }
+}
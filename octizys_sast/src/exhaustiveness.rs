@@ -0,0 +1,778 @@
+//! Checks of exhaustiveness and redundancy of patterns.
+//!
+//! We follow the usefulness algorithm of Luc Maranget in
+//! "Warnings for pattern matching": a row of patterns is useful
+//! with respect to a matrix of rows if there is a value matched by
+//! the row and by none of the rows of the matrix.
+//! A arm is redundant if its pattern isn't useful with respect to
+//! the previous arms, and a case is exhaustive if the pattern `_`
+//! isn't useful with respect to all the arms.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use octizys_common::report::{ReportFormat, ReportKind, ReportTarget};
+use octizys_common::span::{Location, Span};
+use octizys_core::common::Identifier;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+
+use crate::_type::Type;
+use crate::expression::{
+    BasicExpression, ConstructorReference, Expression, ExpressionKind,
+    InterpolationPart, Pattern, PatternKind,
+};
+use crate::top::{DataType, Sast};
+
+/// The maximum number of missing patterns we show.
+const MAX_WITNESSES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternReport {
+    /// Some values aren't matched by any arm of a case.
+    NonExhaustiveCase { span: Span, missing: Vec<String> },
+    /// The pattern of a let can fail.
+    RefutableLet { span: Span, missing: Vec<String> },
+    /// The previous arms match every value this arm matches.
+    RedundantArm { span: Span },
+}
+
+impl PatternReport {
+    pub fn span(&self) -> Span {
+        match self {
+            PatternReport::NonExhaustiveCase { span, .. }
+            | PatternReport::RefutableLet { span, .. }
+            | PatternReport::RedundantArm { span } => *span,
+        }
+    }
+
+    /// Redundant arms don't stop the compilation.
+    pub fn kind(&self) -> ReportKind {
+        match self {
+            PatternReport::NonExhaustiveCase { .. }
+            | PatternReport::RefutableLet { .. } => ReportKind::Error,
+            PatternReport::RedundantArm { .. } => ReportKind::Warning,
+        }
+    }
+}
+
+impl ReportFormat for PatternReport {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            PatternReport::NonExhaustiveCase { .. } => {
+                NonLineBreakStr::new("NonExhaustiveCase")
+            }
+            PatternReport::RefutableLet { .. } => {
+                NonLineBreakStr::new("RefutableLet")
+            }
+            PatternReport::RedundantArm { .. } => {
+                NonLineBreakStr::new("RedundantArm")
+            }
+        }
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            PatternReport::NonExhaustiveCase { .. } => {
+                NonLineBreakStr::new("The case doesn't cover every value.")
+            }
+            PatternReport::RefutableLet { .. } => NonLineBreakStr::new(
+                "The pattern of the let doesn't cover every value.",
+            ),
+            PatternReport::RedundantArm { .. } => {
+                NonLineBreakStr::new("This arm is never used.")
+            }
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self {
+            PatternReport::NonExhaustiveCase { missing, .. }
+            | PatternReport::RefutableLet { missing, .. } => {
+                let mut description = String::from("Patterns not covered:\n  ");
+                description.push_str(&missing.join("\n  "));
+                description
+            }
+            PatternReport::RedundantArm { .. } => String::from(
+                "The previous arms match every value this arm matches.",
+            ),
+        };
+        Some(external_text(&description))
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Constructor {
+    Variant(ConstructorReference),
+    Tuple(usize),
+    /// The sorted labels of the record.
+    Record(Vec<Identifier>),
+    Constant(BasicExpression),
+}
+
+impl Constructor {
+    fn is(&self, other: &Constructor) -> bool {
+        match (self, other) {
+            (Constructor::Variant(a), Constructor::Variant(b)) => {
+                a.data == b.data && a.index == b.index
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+/// A pattern reduced to the constructors and wildcards, it is also
+/// used to represent the missing values.
+#[derive(Debug, Clone, PartialEq)]
+enum Deconstructed {
+    Wildcard,
+    Constructor(Constructor, Vec<Deconstructed>),
+}
+
+impl Deconstructed {
+    fn is_atomic(&self) -> bool {
+        match self {
+            Deconstructed::Constructor(Constructor::Variant(_), fields) => {
+                fields.is_empty()
+            }
+            _ => true,
+        }
+    }
+}
+
+fn display_constant(constant: &BasicExpression) -> String {
+    match constant {
+        BasicExpression::U64(n) => n.to_string(),
+        BasicExpression::I64(n) => n.to_string(),
+        BasicExpression::U32(n) => n.to_string(),
+        BasicExpression::I32(n) => n.to_string(),
+        BasicExpression::U16(n) => n.to_string(),
+        BasicExpression::I16(n) => n.to_string(),
+        BasicExpression::U8(n) => n.to_string(),
+        BasicExpression::I8(n) => n.to_string(),
+        BasicExpression::Float(n) => n.to_string(),
+        BasicExpression::Double(n) => n.to_string(),
+        BasicExpression::String(s) => format!("{:?}", s),
+        BasicExpression::Char(c) => format!("{:?}", c),
+    }
+}
+
+impl Display for Deconstructed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (constructor, fields) = match self {
+            Deconstructed::Wildcard => return write!(f, "_"),
+            Deconstructed::Constructor(constructor, fields) => {
+                (constructor, fields)
+            }
+        };
+        match constructor {
+            Constructor::Variant(reference) => {
+                write!(f, "{}", reference.name)?;
+                for field in fields {
+                    if field.is_atomic() {
+                        write!(f, " {}", field)?
+                    } else {
+                        write!(f, " ({})", field)?
+                    }
+                }
+                Ok(())
+            }
+            Constructor::Tuple(_) => {
+                let items: Vec<String> =
+                    fields.iter().map(|p| p.to_string()).collect();
+                write!(f, "({})", items.join(", "))
+            }
+            Constructor::Record(labels) => {
+                let items: Vec<String> = labels
+                    .iter()
+                    .zip(fields)
+                    .map(|(label, p)| format!("{} = {}", label, p))
+                    .collect();
+                write!(f, "{{{}}}", items.join(", "))
+            }
+            Constructor::Constant(constant) => {
+                write!(f, "{}", display_constant(constant))
+            }
+        }
+    }
+}
+
+type Row = Vec<Deconstructed>;
+
+struct Checker<'a> {
+    module: &'a Sast,
+    session: &'a Sast,
+    reports: Vec<PatternReport>,
+}
+
+impl<'a> Checker<'a> {
+    fn find_data(&self, name: &Identifier) -> Option<&'a DataType> {
+        self.module
+            .find_data(name.as_str())
+            .or_else(|| self.session.find_data(name.as_str()))
+    }
+
+    fn deconstruct(&self, pattern: &Pattern) -> Deconstructed {
+        match &pattern.kind {
+            PatternKind::Variable(_) | PatternKind::Discard => {
+                Deconstructed::Wildcard
+            }
+            PatternKind::Bind { pattern, .. } => self.deconstruct(pattern),
+            PatternKind::Constant(constant) => Deconstructed::Constructor(
+                Constructor::Constant(constant.clone()),
+                vec![],
+            ),
+            PatternKind::Constructor {
+                constructor,
+                argument,
+            } => Deconstructed::Constructor(
+                Constructor::Variant(constructor.clone()),
+                argument.iter().map(|p| self.deconstruct(p)).collect(),
+            ),
+            PatternKind::Tuple(items) => Deconstructed::Constructor(
+                Constructor::Tuple(items.len()),
+                items.iter().map(|p| self.deconstruct(p)).collect(),
+            ),
+            PatternKind::Record(fields) => {
+                // The labels come from the type to have the same
+                // constructor for every record pattern of a column.
+                let labels: Vec<Identifier> = match &pattern.type_ {
+                    Some(Type::Record(types)) => {
+                        types.iter().map(|(l, _)| l.clone()).collect()
+                    }
+                    _ => fields.iter().map(|(l, _)| l.clone()).collect(),
+                };
+                let items = labels
+                    .iter()
+                    .map(|label| {
+                        fields
+                            .iter()
+                            .find(|(l, _)| l == label)
+                            .map(|(_, p)| self.deconstruct(p))
+                            .unwrap_or(Deconstructed::Wildcard)
+                    })
+                    .collect();
+                Deconstructed::Constructor(Constructor::Record(labels), items)
+            }
+        }
+    }
+
+    /// All the constructors of a type, `None` for types with
+    /// infinite values or unknown types.
+    fn constructors(&self, type_: &Option<Type>) -> Option<Vec<Constructor>> {
+        match type_.as_ref()? {
+            Type::Named(name, _) => {
+                let data = self.find_data(name)?;
+                Some(
+                    data.constructors
+                        .iter()
+                        .enumerate()
+                        .map(|(index, c)| {
+                            Constructor::Variant(ConstructorReference {
+                                data: data.name.clone(),
+                                name: c.name.clone(),
+                                index: index as u64,
+                            })
+                        })
+                        .collect(),
+                )
+            }
+            Type::Tuple(items) => Some(vec![Constructor::Tuple(items.len())]),
+            Type::Record(fields) => Some(vec![Constructor::Record(
+                fields.iter().map(|(l, _)| l.clone()).collect(),
+            )]),
+            _ => None,
+        }
+    }
+
+    /// The types of the fields of a constructor in a column of the
+    /// given type.
+    fn fields(
+        &self,
+        constructor: &Constructor,
+        type_: &Option<Type>,
+    ) -> Vec<Option<Type>> {
+        match constructor {
+            Constructor::Variant(reference) => {
+                let Some(data) = self.find_data(&reference.data) else {
+                    return vec![];
+                };
+                let Some(argument) = data
+                    .constructors
+                    .get(reference.index as usize)
+                    .and_then(|c| c.argument.as_ref())
+                else {
+                    return vec![];
+                };
+                match type_ {
+                    Some(Type::Named(_, arguments)) => {
+                        let substitution: HashMap<Identifier, Type> = data
+                            .variables
+                            .iter()
+                            .cloned()
+                            .zip(arguments.iter().cloned())
+                            .collect();
+                        vec![Some(argument.substitute(&substitution))]
+                    }
+                    _ => vec![None],
+                }
+            }
+            Constructor::Tuple(size) => match type_ {
+                Some(Type::Tuple(items)) => {
+                    items.iter().map(|t| Some(t.clone())).collect()
+                }
+                _ => vec![None; *size],
+            },
+            Constructor::Record(labels) => match type_ {
+                Some(Type::Record(fields)) => {
+                    fields.iter().map(|(_, t)| Some(t.clone())).collect()
+                }
+                _ => vec![None; labels.len()],
+            },
+            Constructor::Constant(_) => vec![],
+        }
+    }
+
+    /// The rows that can match the constructor, with the fields of
+    /// the constructor in place of the first column.
+    fn specialize(
+        rows: &[Row],
+        constructor: &Constructor,
+        arity: usize,
+    ) -> Vec<Row> {
+        rows.iter()
+            .filter_map(|row| {
+                let mut out = match &row[0] {
+                    Deconstructed::Wildcard => {
+                        vec![Deconstructed::Wildcard; arity]
+                    }
+                    Deconstructed::Constructor(c, fields) => {
+                        if !c.is(constructor) {
+                            return None;
+                        }
+                        fields.clone()
+                    }
+                };
+                out.extend_from_slice(&row[1..]);
+                Some(out)
+            })
+            .collect()
+    }
+
+    /// The rows that start with a wildcard, without the wildcard.
+    fn default_rows(rows: &[Row]) -> Vec<Row> {
+        rows.iter()
+            .filter(|row| row[0] == Deconstructed::Wildcard)
+            .map(|row| row[1..].to_vec())
+            .collect()
+    }
+
+    fn head_constructors(rows: &[Row]) -> Vec<Constructor> {
+        let mut out: Vec<Constructor> = vec![];
+        for row in rows {
+            if let Deconstructed::Constructor(c, _) = &row[0] {
+                if !out.iter().any(|o| o.is(c)) {
+                    out.push(c.clone())
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns the constructors of the column if every one of them
+    /// appears in the rows.
+    fn complete_signature(
+        &self,
+        rows: &[Row],
+        type_: &Option<Type>,
+    ) -> Option<Vec<Constructor>> {
+        let all = self.constructors(type_)?;
+        let heads = Self::head_constructors(rows);
+        if all.iter().all(|c| heads.iter().any(|h| h.is(c))) {
+            Some(all)
+        } else {
+            None
+        }
+    }
+
+    fn is_useful(
+        &self,
+        rows: &[Row],
+        row: &[Deconstructed],
+        types: &[Option<Type>],
+    ) -> bool {
+        if row.is_empty() {
+            return rows.is_empty();
+        }
+        let specialized = |constructor: &Constructor,
+                           fields: &[Deconstructed]| {
+            let field_types = self.fields(constructor, &types[0]);
+            let arity = fields.len();
+            let mut new_types = field_types;
+            new_types.resize(arity, None);
+            new_types.extend_from_slice(&types[1..]);
+            let mut new_row = fields.to_vec();
+            new_row.extend_from_slice(&row[1..]);
+            self.is_useful(
+                &Self::specialize(rows, constructor, arity),
+                &new_row,
+                &new_types,
+            )
+        };
+        match &row[0] {
+            Deconstructed::Constructor(constructor, fields) => {
+                specialized(constructor, fields)
+            }
+            Deconstructed::Wildcard => {
+                match self.complete_signature(rows, &types[0]) {
+                    Some(all) => all.iter().any(|constructor| {
+                        let arity = self.fields(constructor, &types[0]).len();
+                        specialized(
+                            constructor,
+                            &vec![Deconstructed::Wildcard; arity],
+                        )
+                    }),
+                    None => self.is_useful(
+                        &Self::default_rows(rows),
+                        &row[1..],
+                        &types[1..],
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Values of the types not matched by any of the rows, at most
+    /// [`MAX_WITNESSES`] of them.
+    fn witnesses(&self, rows: &[Row], types: &[Option<Type>]) -> Vec<Row> {
+        if types.is_empty() {
+            return if rows.is_empty() {
+                vec![vec![]]
+            } else {
+                vec![]
+            };
+        }
+        let mut out = vec![];
+        if let Some(all) = self.complete_signature(rows, &types[0]) {
+            for constructor in all {
+                let mut new_types = self.fields(&constructor, &types[0]);
+                let arity = new_types.len();
+                new_types.extend_from_slice(&types[1..]);
+                let specialized = Self::specialize(rows, &constructor, arity);
+                for mut witness in self.witnesses(&specialized, &new_types) {
+                    let rest = witness.split_off(arity);
+                    let mut row = vec![Deconstructed::Constructor(
+                        constructor.clone(),
+                        witness,
+                    )];
+                    row.extend(rest);
+                    out.push(row);
+                    if out.len() >= MAX_WITNESSES {
+                        return out;
+                    }
+                }
+            }
+            return out;
+        }
+        let rest = self.witnesses(&Self::default_rows(rows), &types[1..]);
+        if rest.is_empty() {
+            return out;
+        }
+        let heads = Self::head_constructors(rows);
+        let missing: Vec<Deconstructed> = match self.constructors(&types[0]) {
+            Some(all) if !heads.is_empty() => all
+                .into_iter()
+                .filter(|c| !heads.iter().any(|h| h.is(c)))
+                .map(|c| {
+                    let arity = self.fields(&c, &types[0]).len();
+                    Deconstructed::Constructor(
+                        c,
+                        vec![Deconstructed::Wildcard; arity],
+                    )
+                })
+                .collect(),
+            _ => vec![Deconstructed::Wildcard],
+        };
+        for witness in rest {
+            for head in &missing {
+                let mut row = vec![head.clone()];
+                row.extend(witness.iter().cloned());
+                out.push(row);
+                if out.len() >= MAX_WITNESSES {
+                    return out;
+                }
+            }
+        }
+        out
+    }
+
+    fn missing(&self, rows: &[Row], type_: &Option<Type>) -> Vec<String> {
+        self.witnesses(rows, std::slice::from_ref(type_))
+            .into_iter()
+            .map(|row| row[0].to_string())
+            .collect()
+    }
+
+    fn case(
+        &mut self,
+        span: Span,
+        scrutinee: &Expression,
+        patterns: &[&Pattern],
+    ) {
+        let types = [scrutinee.type_.clone()];
+        let mut rows: Vec<Row> = vec![];
+        for pattern in patterns {
+            let row = vec![self.deconstruct(pattern)];
+            if !self.is_useful(&rows, &row, &types) {
+                self.reports
+                    .push(PatternReport::RedundantArm { span: pattern.span })
+            }
+            rows.push(row);
+        }
+        let missing = self.missing(&rows, &scrutinee.type_);
+        if !missing.is_empty() {
+            self.reports
+                .push(PatternReport::NonExhaustiveCase { span, missing })
+        }
+    }
+
+    fn let_pattern(&mut self, pattern: &Pattern) {
+        let rows = vec![vec![self.deconstruct(pattern)]];
+        let missing = self.missing(&rows, &pattern.type_);
+        if !missing.is_empty() {
+            self.reports.push(PatternReport::RefutableLet {
+                span: pattern.span,
+                missing,
+            })
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match &expression.kind {
+            ExpressionKind::BasicTerm(_)
+            | ExpressionKind::LocalVariable(_)
+            | ExpressionKind::GlobalVariable(_)
+            | ExpressionKind::Constructor(_) => (),
+            ExpressionKind::Function { body, .. } => self.expression(body),
+            ExpressionKind::Let { bindings, output } => {
+                for binding in bindings {
+                    self.let_pattern(&binding.pattern);
+                    self.expression(&binding.value)
+                }
+                self.expression(output)
+            }
+            ExpressionKind::Case {
+                expression: scrutinee,
+                cases,
+            } => {
+                self.expression(scrutinee);
+                let patterns: Vec<&Pattern> =
+                    cases.iter().map(|c| &c.pattern).collect();
+                self.case(expression.span, scrutinee, &patterns);
+                for case in cases {
+                    self.expression(&case.expression)
+                }
+            }
+            ExpressionKind::Application { head, arguments } => {
+                self.expression(head);
+                arguments.iter().for_each(|e| self.expression(e))
+            }
+            ExpressionKind::Tuple(items) => {
+                items.iter().for_each(|e| self.expression(e))
+            }
+            ExpressionKind::Record(fields) => {
+                fields.iter().for_each(|(_, e)| self.expression(e))
+            }
+            ExpressionKind::BinaryOperation { left, right, .. } => {
+                self.expression(left);
+                self.expression(right)
+            }
+            ExpressionKind::Negation(expression) => self.expression(expression),
+            ExpressionKind::Interpolation(parts) => {
+                for part in parts {
+                    if let InterpolationPart::Hole(hole, _) = part {
+                        self.expression(hole)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks the patterns of the definitions of a module after the
+/// inference, the data types can be in the module or the session.
+/// The reports are in order of appearance of the definitions.
+pub fn check_module(module: &Sast, session: &Sast) -> Vec<PatternReport> {
+    let mut checker = Checker {
+        module,
+        session,
+        reports: vec![],
+    };
+    for definition in &module.definitions {
+        checker.expression(&definition.value)
+    }
+    checker.reports
+}
+
+/// Checks the patterns of a expression after the inference.
+pub fn check_expression(
+    expression: &Expression,
+    session: &Sast,
+) -> Vec<PatternReport> {
+    let empty = Sast::default();
+    let mut checker = Checker {
+        module: &empty,
+        session,
+        reports: vec![],
+    };
+    checker.expression(expression);
+    checker.reports
+}
+
+#[cfg(test)]
+mod exhaustiveness_test {
+    use super::{check_expression, PatternReport};
+    use crate::_type::Type;
+    use crate::expression::{
+        BasicExpression, CaseCase, Expression, ExpressionKind, Pattern,
+        PatternKind,
+    };
+    use crate::top::Sast;
+    use octizys_common::span::Span;
+    use octizys_core::common::Identifier;
+    use octizys_core::primitives::{BOOL_NAME, FALSE_NAME, TRUE_NAME};
+
+    fn span() -> Span {
+        Span::from(((0, 0, 0), (0, 0, 0)))
+    }
+
+    fn bool_type() -> Type {
+        Type::Named(Identifier::make(String::from(BOOL_NAME)).unwrap(), vec![])
+    }
+
+    fn typed(kind: PatternKind, type_: Type) -> Pattern {
+        let mut pattern = Pattern::new(span(), kind);
+        pattern.type_ = Some(type_);
+        pattern
+    }
+
+    fn boolean(name: &str) -> Pattern {
+        let (constructor, _) = Sast::prelude().find_constructor(name).unwrap();
+        typed(
+            PatternKind::Constructor {
+                constructor,
+                argument: None,
+            },
+            bool_type(),
+        )
+    }
+
+    fn discard(type_: Type) -> Pattern {
+        typed(PatternKind::Discard, type_)
+    }
+
+    fn case(type_: Type, patterns: Vec<Pattern>) -> Expression {
+        let mut scrutinee = Expression::new(
+            span(),
+            ExpressionKind::LocalVariable(
+                Identifier::make(String::from("x")).unwrap(),
+            ),
+        );
+        scrutinee.type_ = Some(type_);
+        let cases = patterns
+            .into_iter()
+            .map(|pattern| CaseCase {
+                span: span(),
+                pattern,
+                expression: Expression::new(
+                    span(),
+                    ExpressionKind::BasicTerm(BasicExpression::U64(0)),
+                ),
+            })
+            .collect();
+        Expression::new(
+            span(),
+            ExpressionKind::Case {
+                expression: Box::new(scrutinee),
+                cases,
+            },
+        )
+    }
+
+    fn missing(reports: &[PatternReport]) -> Vec<String> {
+        match reports {
+            [PatternReport::NonExhaustiveCase { missing, .. }] => {
+                missing.clone()
+            }
+            _ => panic!("Expected a single missing case: {:?}", reports),
+        }
+    }
+
+    #[test]
+    fn exhaustive_bool() {
+        let expression =
+            case(bool_type(), vec![boolean(FALSE_NAME), boolean(TRUE_NAME)]);
+        assert_eq!(check_expression(&expression, &Sast::prelude()), vec![]);
+    }
+
+    #[test]
+    fn missing_constructor() {
+        let expression = case(bool_type(), vec![boolean(TRUE_NAME)]);
+        let reports = check_expression(&expression, &Sast::prelude());
+        assert_eq!(missing(&reports), vec![String::from(FALSE_NAME)]);
+    }
+
+    #[test]
+    fn missing_inside_tuple() {
+        let pair = Type::Tuple(vec![bool_type(), bool_type()]);
+        let tuple = |first, second| {
+            typed(PatternKind::Tuple(vec![first, second]), pair.clone())
+        };
+        let expression = case(
+            pair.clone(),
+            vec![
+                tuple(boolean(TRUE_NAME), discard(bool_type())),
+                tuple(boolean(FALSE_NAME), boolean(TRUE_NAME)),
+            ],
+        );
+        let reports = check_expression(&expression, &Sast::prelude());
+        assert_eq!(
+            missing(&reports),
+            vec![format!("({}, {})", FALSE_NAME, FALSE_NAME)]
+        );
+    }
+
+    #[test]
+    fn redundant_after_wildcard() {
+        let expression =
+            case(bool_type(), vec![discard(bool_type()), boolean(TRUE_NAME)]);
+        let reports = check_expression(&expression, &Sast::prelude());
+        assert!(matches!(
+            reports.as_slice(),
+            [PatternReport::RedundantArm { .. }]
+        ));
+    }
+
+    #[test]
+    fn constants_need_a_wildcard() {
+        let constant = typed(
+            PatternKind::Constant(BasicExpression::U64(0)),
+            Type::BasicType(crate::_type::BasicType::U64),
+        );
+        let expression = case(
+            Type::BasicType(crate::_type::BasicType::U64),
+            vec![constant],
+        );
+        let reports = check_expression(&expression, &Sast::prelude());
+        assert_eq!(missing(&reports), vec![String::from("_")]);
+    }
+}
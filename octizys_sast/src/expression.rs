@@ -1,12 +1,12 @@
-use octizys_common::identifier::Identifier;
-use octizys_common::module_logic_path::LogicPath;
-use octizys_common::span::Span;
+use std::collections::HashMap;
 
-use crate::_type::{self as T, VariableId};
+use octizys_common::span::Span;
+use octizys_core::common::Identifier;
+use octizys_cst::literals::FormatSpec;
 
-use crate::common::{Name, PathPrefix};
+use crate::_type::{BasicType, Type};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BasicExpression {
     U64(u64),
     I64(i64),
@@ -22,84 +22,375 @@ pub enum BasicExpression {
     Char(char),
 }
 
-#[derive(Debug)]
-pub struct BasicExpressionInfo {
-    pub span: Span,
-    pub term: BasicExpression,
+impl BasicExpression {
+    pub fn type_(&self) -> BasicType {
+        match self {
+            BasicExpression::U64(_) => BasicType::U64,
+            BasicExpression::I64(_) => BasicType::I64,
+            BasicExpression::U32(_) => BasicType::U32,
+            BasicExpression::I32(_) => BasicType::I32,
+            BasicExpression::U16(_) => BasicType::U16,
+            BasicExpression::I16(_) => BasicType::I16,
+            BasicExpression::U8(_) => BasicType::U8,
+            BasicExpression::I8(_) => BasicType::I8,
+            BasicExpression::Float(_) => BasicType::Float,
+            BasicExpression::Double(_) => BasicType::Double,
+            BasicExpression::String(_) => BasicType::String,
+            BasicExpression::Char(_) => BasicType::Char,
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct Function {
-    pub span: Span,
-    pub arguments: Vec<VariableId>,
-    pub output: Box<Expression>,
+/// A resolved reference to a constructor of a data type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstructorReference {
+    pub data: Identifier,
+    pub name: Identifier,
+    /// The position of the constructor in the definition of the data.
+    pub index: u64,
 }
 
-#[derive(Debug)]
-pub struct Annotation {
-    pub span: Span,
-    pub _type: T::Type,
+impl ConstructorReference {
+    pub fn rename(&mut self, map: &HashMap<Identifier, Identifier>) {
+        if let Some(data) = map.get(&self.data) {
+            self.data = data.clone();
+        }
+        if let Some(name) = map.get(&self.name) {
+            self.name = name.clone();
+        }
+    }
 }
 
-#[derive(Debug)]
-pub struct LetBinding {
-    pub name: (Span, Identifier),
-    pub value: Expression,
+/// The operators with a special meaning, all the other operators
+/// are translated as applications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    More,
+    MoreOrEqual,
+    And,
+    Or,
 }
 
-#[derive(Debug)]
-pub struct Let {
-    pub span: Span,
-    pub bindings: Vec<LetBinding>,
-    pub output: Box<Expression>,
+impl Operator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Remainder => "%",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::Less => "<",
+            Operator::LessOrEqual => "<=",
+            Operator::More => ">",
+            Operator::MoreOrEqual => ">=",
+            Operator::And => "&&",
+            Operator::Or => "||",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternKind {
+    Constant(BasicExpression),
+    Variable(Identifier),
+    Constructor {
+        constructor: ConstructorReference,
+        argument: Option<Box<Pattern>>,
+    },
+    Tuple(Vec<Pattern>),
+    /// The fields are sorted by label.
+    Record(Vec<(Identifier, Pattern)>),
+    Bind {
+        name: Identifier,
+        pattern: Box<Pattern>,
+    },
+    Discard,
 }
 
-#[derive(Debug)]
-pub struct PatternApplication {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
     pub span: Span,
-    pub prefix: Option<LogicPath>,
-    pub name: (Span, Identifier),
-    pub arguments: Vec<Pattern>,
+    /// Filled by the inference.
+    pub type_: Option<Type>,
+    pub kind: PatternKind,
+}
+
+impl Pattern {
+    pub fn new(span: Span, kind: PatternKind) -> Self {
+        Pattern {
+            span,
+            type_: None,
+            kind,
+        }
+    }
+
+    /// The variables bound by the pattern in order of appearance.
+    pub fn variables(&self) -> Vec<(Span, Identifier)> {
+        let mut acc = vec![];
+        self.variables_inner(&mut acc);
+        acc
+    }
+
+    /// Renames the constructors and the data types using the map.
+    pub fn rename(&mut self, map: &HashMap<Identifier, Identifier>) {
+        if let Some(type_) = &mut self.type_ {
+            type_.rename(map)
+        }
+        match &mut self.kind {
+            PatternKind::Constant(_)
+            | PatternKind::Variable(_)
+            | PatternKind::Discard => (),
+            PatternKind::Constructor {
+                constructor,
+                argument,
+            } => {
+                constructor.rename(map);
+                if let Some(argument) = argument {
+                    argument.rename(map)
+                }
+            }
+            PatternKind::Tuple(items) => {
+                items.iter_mut().for_each(|p| p.rename(map))
+            }
+            PatternKind::Record(fields) => {
+                fields.iter_mut().for_each(|(_, p)| p.rename(map))
+            }
+            PatternKind::Bind { pattern, .. } => pattern.rename(map),
+        }
+    }
+
+    fn variables_inner(&self, acc: &mut Vec<(Span, Identifier)>) {
+        match &self.kind {
+            PatternKind::Constant(_) | PatternKind::Discard => (),
+            PatternKind::Variable(name) => acc.push((self.span, name.clone())),
+            PatternKind::Constructor { argument, .. } => {
+                if let Some(argument) = argument {
+                    argument.variables_inner(acc)
+                }
+            }
+            PatternKind::Tuple(items) => {
+                items.iter().for_each(|p| p.variables_inner(acc))
+            }
+            PatternKind::Record(fields) => {
+                fields.iter().for_each(|(_, p)| p.variables_inner(acc))
+            }
+            PatternKind::Bind { name, pattern } => {
+                acc.push((self.span, name.clone()));
+                pattern.variables_inner(acc)
+            }
+        }
+    }
 }
 
-#[derive(Debug)]
-pub enum Pattern {
-    Constant(BasicExpressionInfo),
-    Variable(Span, Identifier),
-    Application(PatternApplication),
-    Hole(Span, Identifier),
-    Discard(Span),
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetBinding {
+    pub pattern: Pattern,
+    pub value: Expression,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CaseCase {
     pub span: Span,
     pub pattern: Pattern,
     pub expression: Expression,
 }
 
-#[derive(Debug)]
-pub struct Case {
-    pub span: Span,
-    pub expression: Box<Expression>,
-    pub cases: Vec<CaseCase>,
+/// A part of a interpolation string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterpolationPart {
+    Text(String),
+    /// The value of the expression is shown in the string with the
+    /// format specifier.
+    Hole(Box<Expression>, FormatSpec),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionKind {
+    BasicTerm(BasicExpression),
+    /// A variable bound by a function, a let or a pattern.
+    LocalVariable(Identifier),
+    /// A top level definition.
+    GlobalVariable(Identifier),
+    Constructor(ConstructorReference),
+    Function {
+        argument: (Span, Identifier),
+        body: Box<Expression>,
+    },
+    /// The bindings are in scope for the following bindings
+    /// and the output.
+    Let {
+        bindings: Vec<LetBinding>,
+        output: Box<Expression>,
+    },
+    Case {
+        expression: Box<Expression>,
+        cases: Vec<CaseCase>,
+    },
+    Application {
+        head: Box<Expression>,
+        arguments: Vec<Expression>,
+    },
+    Tuple(Vec<Expression>),
+    /// The fields are sorted by label.
+    Record(Vec<(Identifier, Expression)>),
+    BinaryOperation {
+        operator: (Span, Operator),
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// The negation of a signed integer or a floating point number.
+    Negation(Box<Expression>),
+    /// The parts are concatenated, the holes have primitive types.
+    Interpolation(Vec<InterpolationPart>),
 }
 
-#[derive(Debug)]
-pub struct Application {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
     pub span: Span,
-    pub head: Box<Expression>,
-    pub arguments: Vec<Expression>,
+    /// Filled by the inference.
+    pub type_: Option<Type>,
+    pub kind: ExpressionKind,
 }
 
-#[derive(Debug)]
-pub enum Expression {
-    BasicTerm(BasicExpressionInfo),
-    LocalVariable(VariableId),
-    ExternalVariable(Identifier, Option<LogicPath>),
-    Function(Function),
-    Annotation(Annotation),
-    Let(Let),
-    Case(Case),
-    Application(Application),
+impl Expression {
+    pub fn new(span: Span, kind: ExpressionKind) -> Self {
+        Expression {
+            span,
+            type_: None,
+            kind,
+        }
+    }
+
+    /// Tells if the expression can be generalized in a let, we
+    /// only generalize values to keep the evaluation order simple.
+    pub fn is_syntactic_value(&self) -> bool {
+        match &self.kind {
+            ExpressionKind::BasicTerm(_)
+            | ExpressionKind::LocalVariable(_)
+            | ExpressionKind::GlobalVariable(_)
+            | ExpressionKind::Constructor(_)
+            | ExpressionKind::Function { .. } => true,
+            ExpressionKind::Tuple(items) => {
+                items.iter().all(|e| e.is_syntactic_value())
+            }
+            ExpressionKind::Record(fields) => {
+                fields.iter().all(|(_, e)| e.is_syntactic_value())
+            }
+            _ => false,
+        }
+    }
+
+    /// Renames the global variables, the constructors and the data
+    /// types using the map.
+    pub fn rename(&mut self, map: &HashMap<Identifier, Identifier>) {
+        if let Some(type_) = &mut self.type_ {
+            type_.rename(map)
+        }
+        match &mut self.kind {
+            ExpressionKind::GlobalVariable(name) => {
+                if let Some(new) = map.get(name) {
+                    *name = new.clone();
+                }
+            }
+            ExpressionKind::BasicTerm(_) | ExpressionKind::LocalVariable(_) => {
+            }
+            ExpressionKind::Constructor(constructor) => constructor.rename(map),
+            ExpressionKind::Function { body, .. } => body.rename(map),
+            ExpressionKind::Let { bindings, output } => {
+                for binding in bindings {
+                    binding.pattern.rename(map);
+                    binding.value.rename(map)
+                }
+                output.rename(map)
+            }
+            ExpressionKind::Case { expression, cases } => {
+                expression.rename(map);
+                for case in cases {
+                    case.pattern.rename(map);
+                    case.expression.rename(map)
+                }
+            }
+            ExpressionKind::Application { head, arguments } => {
+                head.rename(map);
+                arguments.iter_mut().for_each(|e| e.rename(map))
+            }
+            ExpressionKind::Tuple(items) => {
+                items.iter_mut().for_each(|e| e.rename(map))
+            }
+            ExpressionKind::Record(fields) => {
+                fields.iter_mut().for_each(|(_, e)| e.rename(map))
+            }
+            ExpressionKind::BinaryOperation { left, right, .. } => {
+                left.rename(map);
+                right.rename(map)
+            }
+            ExpressionKind::Negation(expression) => expression.rename(map),
+            ExpressionKind::Interpolation(parts) => {
+                for part in parts {
+                    if let InterpolationPart::Hole(hole, _) = part {
+                        hole.rename(map)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Calls the function on every global variable referenced by
+    /// the expression.
+    pub fn globals(&self, f: &mut impl FnMut(&Identifier, Span)) {
+        match &self.kind {
+            ExpressionKind::GlobalVariable(name) => f(name, self.span),
+            ExpressionKind::BasicTerm(_)
+            | ExpressionKind::LocalVariable(_)
+            | ExpressionKind::Constructor(_) => (),
+            ExpressionKind::Function { body, .. } => body.globals(f),
+            ExpressionKind::Let { bindings, output } => {
+                for binding in bindings {
+                    binding.value.globals(f)
+                }
+                output.globals(f)
+            }
+            ExpressionKind::Case { expression, cases } => {
+                expression.globals(f);
+                for case in cases {
+                    case.expression.globals(f)
+                }
+            }
+            ExpressionKind::Application { head, arguments } => {
+                head.globals(f);
+                for argument in arguments {
+                    argument.globals(f)
+                }
+            }
+            ExpressionKind::Tuple(items) => {
+                items.iter().for_each(|e| e.globals(f))
+            }
+            ExpressionKind::Record(fields) => {
+                fields.iter().for_each(|(_, e)| e.globals(f))
+            }
+            ExpressionKind::BinaryOperation { left, right, .. } => {
+                left.globals(f);
+                right.globals(f)
+            }
+            ExpressionKind::Negation(expression) => expression.globals(f),
+            ExpressionKind::Interpolation(parts) => {
+                for part in parts {
+                    if let InterpolationPart::Hole(hole, _) = part {
+                        hole.globals(f)
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,1596 @@
+use std::collections::{HashMap, HashSet};
+
+use octizys_common::identifier::Identifier as CstIdentifier;
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::{Location, Span};
+use octizys_core::common::Identifier;
+use octizys_cst::base::{ImportedVariable, OperatorName, Token};
+use octizys_cst::expressions::{
+    Expression as CstExpression, ExpressionRecordItem,
+};
+use octizys_cst::literals::{
+    InterpolationComponent, UFloatingPointKind, UFloatingPointLiteral,
+    UintKind, UintLiteral,
+};
+use octizys_cst::patterns::{PatternMatch, PatternMatchRecordItem};
+use octizys_cst::top::{self as cst_top, Top, TopItem};
+use octizys_cst::types::Type as CstType;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+use octizys_text_store::store::{Store, StoreSymbol};
+
+use crate::_type::{BasicType, Type, TypeScheme};
+use crate::expression::{
+    BasicExpression, CaseCase, Expression, ExpressionKind, InterpolationPart,
+    LetBinding, Operator, Pattern, PatternKind,
+};
+use crate::modules::{ImportScope, ModuleName};
+use crate::top::{
+    Alias, DataConstructor, DataKind, DataType, Sast, VariableDefinition,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslationError {
+    UnknownVariable {
+        span: Span,
+        name: Identifier,
+    },
+    UnknownType {
+        span: Span,
+        name: Identifier,
+    },
+    /// A pattern applies something that isn't a constructor.
+    NotAConstructor {
+        span: Span,
+        name: Identifier,
+    },
+    ConstructorArity {
+        span: Span,
+        name: Identifier,
+        expected: usize,
+        found: usize,
+    },
+    TypeArity {
+        span: Span,
+        name: Identifier,
+        expected: usize,
+        found: usize,
+    },
+    /// Two items of the same kind share a name.
+    DuplicateName {
+        span: Span,
+        name: Identifier,
+        previous: Span,
+    },
+    /// A name is bound twice in the same pattern or type parameters.
+    DuplicateVariable {
+        span: Span,
+        name: Identifier,
+    },
+    DuplicateLabel {
+        span: Span,
+        label: Identifier,
+    },
+    MissingDefinition {
+        span: Span,
+        name: Identifier,
+    },
+    CyclicAlias {
+        span: Span,
+        name: Identifier,
+    },
+    LiteralOutOfRange {
+        span: Span,
+        type_: BasicType,
+    },
+    /// The prefix of a qualified name isn't a imported module.
+    UnknownModule {
+        span: Span,
+        module: ModuleName,
+    },
+    /// The name isn't exported by the module or it isn't in the
+    /// import list.
+    NotImported {
+        span: Span,
+        module: ModuleName,
+        name: Identifier,
+    },
+    /// The construct is valid syntax but the compiler can't
+    /// handle it yet.
+    Unsupported {
+        span: Span,
+        construct: &'static str,
+    },
+}
+
+impl TranslationError {
+    pub fn span(&self) -> Span {
+        match self {
+            TranslationError::UnknownVariable { span, .. }
+            | TranslationError::UnknownType { span, .. }
+            | TranslationError::NotAConstructor { span, .. }
+            | TranslationError::ConstructorArity { span, .. }
+            | TranslationError::TypeArity { span, .. }
+            | TranslationError::DuplicateName { span, .. }
+            | TranslationError::DuplicateVariable { span, .. }
+            | TranslationError::DuplicateLabel { span, .. }
+            | TranslationError::MissingDefinition { span, .. }
+            | TranslationError::CyclicAlias { span, .. }
+            | TranslationError::LiteralOutOfRange { span, .. }
+            | TranslationError::UnknownModule { span, .. }
+            | TranslationError::NotImported { span, .. }
+            | TranslationError::Unsupported { span, .. } => *span,
+        }
+    }
+}
+
+impl ReportFormat for TranslationError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            TranslationError::UnknownVariable { .. } => {
+                NonLineBreakStr::new("UnknownVariable")
+            }
+            TranslationError::UnknownType { .. } => {
+                NonLineBreakStr::new("UnknownType")
+            }
+            TranslationError::NotAConstructor { .. } => {
+                NonLineBreakStr::new("NotAConstructor")
+            }
+            TranslationError::ConstructorArity { .. } => {
+                NonLineBreakStr::new("ConstructorArity")
+            }
+            TranslationError::TypeArity { .. } => {
+                NonLineBreakStr::new("TypeArity")
+            }
+            TranslationError::DuplicateName { .. } => {
+                NonLineBreakStr::new("DuplicateName")
+            }
+            TranslationError::DuplicateVariable { .. } => {
+                NonLineBreakStr::new("DuplicateVariable")
+            }
+            TranslationError::DuplicateLabel { .. } => {
+                NonLineBreakStr::new("DuplicateLabel")
+            }
+            TranslationError::MissingDefinition { .. } => {
+                NonLineBreakStr::new("MissingDefinition")
+            }
+            TranslationError::CyclicAlias { .. } => {
+                NonLineBreakStr::new("CyclicAlias")
+            }
+            TranslationError::LiteralOutOfRange { .. } => {
+                NonLineBreakStr::new("LiteralOutOfRange")
+            }
+            TranslationError::UnknownModule { .. } => {
+                NonLineBreakStr::new("UnknownModule")
+            }
+            TranslationError::NotImported { .. } => {
+                NonLineBreakStr::new("NotImported")
+            }
+            TranslationError::Unsupported { .. } => {
+                NonLineBreakStr::new("Unsupported")
+            }
+        }
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            TranslationError::UnknownVariable { .. } => {
+                NonLineBreakStr::new("We couldn't find a variable.")
+            }
+            TranslationError::UnknownType { .. } => {
+                NonLineBreakStr::new("We couldn't find a type.")
+            }
+            TranslationError::NotAConstructor { .. } => NonLineBreakStr::new(
+                "Only constructors can have arguments in a pattern.",
+            ),
+            TranslationError::ConstructorArity { .. } => NonLineBreakStr::new(
+                "A constructor has the wrong number of arguments.",
+            ),
+            TranslationError::TypeArity { .. } => NonLineBreakStr::new(
+                "A type has the wrong number of arguments.",
+            ),
+            TranslationError::DuplicateName { .. } => {
+                NonLineBreakStr::new("A name is defined twice.")
+            }
+            TranslationError::DuplicateVariable { .. } => {
+                NonLineBreakStr::new("A variable is bound twice.")
+            }
+            TranslationError::DuplicateLabel { .. } => {
+                NonLineBreakStr::new("A record label is repeated.")
+            }
+            TranslationError::MissingDefinition { .. } => {
+                NonLineBreakStr::new("A declaration doesn't have a definition.")
+            }
+            TranslationError::CyclicAlias { .. } => {
+                NonLineBreakStr::new("An alias refers to itself.")
+            }
+            TranslationError::LiteralOutOfRange { .. } => {
+                NonLineBreakStr::new("A literal doesn't fit in its type.")
+            }
+            TranslationError::UnknownModule { .. } => {
+                NonLineBreakStr::new("A qualified name uses an unknown module.")
+            }
+            TranslationError::NotImported { .. } => {
+                NonLineBreakStr::new("A qualified name wasn't imported.")
+            }
+            TranslationError::Unsupported { .. } => {
+                NonLineBreakStr::new("This isn't supported yet.")
+            }
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self {
+            TranslationError::UnknownVariable { name, .. } => format!(
+                "The name {} isn't a local variable, a definition or a constructor.",
+                name
+            ),
+            TranslationError::UnknownType { name, .. } => format!(
+                "The name {} isn't a type variable, a data type or an alias.\nType variables must be introduced with forall.",
+                name
+            ),
+            TranslationError::NotAConstructor { name, .. } => {
+                format!("The name {} isn't a constructor.", name)
+            }
+            TranslationError::ConstructorArity {
+                name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "The constructor {} expects {} argument(s) but got {}.",
+                name, expected, found
+            ),
+            TranslationError::TypeArity {
+                name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "The type {} expects {} argument(s) but got {}.",
+                name, expected, found
+            ),
+            TranslationError::DuplicateName { name, previous, .. } => format!(
+                "The name {} was already defined at {}.",
+                name, previous
+            ),
+            TranslationError::DuplicateVariable { name, .. } => {
+                format!("The variable {} appears more than once.", name)
+            }
+            TranslationError::DuplicateLabel { label, .. } => {
+                format!("The label {} appears more than once.", label)
+            }
+            TranslationError::MissingDefinition { name, .. } => format!(
+                "The type of {} was declared, but we couldn't find its definition.",
+                name
+            ),
+            TranslationError::CyclicAlias { name, .. } => format!(
+                "Expanding the alias {} requires to expand it again, use a data type for recursive types.",
+                name
+            ),
+            TranslationError::LiteralOutOfRange { type_, .. } => format!(
+                "The value is out of the range of the type {}.",
+                type_.name()
+            ),
+            TranslationError::UnknownModule { module, .. } => format!(
+                "The prefix {} isn't the name or the alias of a imported module.",
+                module
+            ),
+            TranslationError::NotImported { module, name, .. } => format!(
+                "The module {} doesn't export {} or it isn't in the import list.",
+                module, name
+            ),
+            TranslationError::Unsupported { construct, .. } => {
+                format!("The compiler can't translate {} yet.", construct)
+            }
+        };
+        Some(external_text(&description))
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span()))
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<TranslationError>>;
+
+/// What a type name of the module being translated refers to.
+enum TypeName<'top> {
+    Data {
+        arity: usize,
+    },
+    /// The alias wasn't expanded yet.
+    PendingAlias(&'top cst_top::Alias),
+    Alias(Alias),
+}
+
+/// Translates the CST in the context of the already translated
+/// items of a session (the prelude, the previous inputs of the REPL or
+/// the modules imported by the file).
+pub struct Translator<'a> {
+    store: &'a Store,
+    session: &'a Sast,
+    /// The names of the session that belong to other modules are
+    /// only visible if they were imported.
+    imports: Option<&'a ImportScope>,
+    types: HashMap<Identifier, TypeName<'a>>,
+    /// The aliases currently being expanded.
+    expanding: Vec<Identifier>,
+    /// The data types already translated in the current input.
+    data_types: Vec<DataType>,
+    globals: Vec<Identifier>,
+    /// The item being translated.
+    current: Option<Identifier>,
+    /// The items referenced by other items.
+    used: HashSet<Identifier>,
+}
+
+fn sort_fields<T>(
+    fields: &mut [(Identifier, T)],
+    spans: &[Span],
+) -> Result<()> {
+    let mut seen: HashMap<&Identifier, ()> = HashMap::new();
+    for ((label, _), span) in fields.iter().zip(spans) {
+        if seen.insert(label, ()).is_some() {
+            return Err(Box::new(TranslationError::DuplicateLabel {
+                span: *span,
+                label: label.clone(),
+            }));
+        }
+    }
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(())
+}
+
+fn check_pattern_variables(pattern: &Pattern) -> Result<()> {
+    let variables = pattern.variables();
+    for (i, (span, name)) in variables.iter().enumerate() {
+        if variables[..i].iter().any(|(_, other)| other == name) {
+            return Err(Box::new(TranslationError::DuplicateVariable {
+                span: *span,
+                name: name.clone(),
+            }));
+        }
+    }
+    Ok(())
+}
+
+/// The literals without a suffix are `U64`, or `I64` if they are
+/// negative.
+/// The core language doesn't have the integers smaller than 64 bits
+/// yet, their literals are rejected here with their span.
+fn uint_value(
+    span: Span,
+    literal: &UintLiteral,
+    negative: bool,
+) -> Result<BasicExpression> {
+    let default = if negative {
+        UintKind::I64
+    } else {
+        UintKind::U64
+    };
+    let value = literal
+        .value()
+        .and_then(|v| i128::try_from(v).ok())
+        .map(|v| if negative { -v } else { v });
+    let (expression, type_) = match literal.kind.unwrap_or(default) {
+        UintKind::U8 => (
+            value
+                .and_then(|v| u8::try_from(v).ok())
+                .map(BasicExpression::U8),
+            BasicType::U8,
+        ),
+        UintKind::U16 => (
+            value
+                .and_then(|v| u16::try_from(v).ok())
+                .map(BasicExpression::U16),
+            BasicType::U16,
+        ),
+        UintKind::U32 => (
+            value
+                .and_then(|v| u32::try_from(v).ok())
+                .map(BasicExpression::U32),
+            BasicType::U32,
+        ),
+        UintKind::U64 => (
+            value
+                .and_then(|v| u64::try_from(v).ok())
+                .map(BasicExpression::U64),
+            BasicType::U64,
+        ),
+        UintKind::I8 => (
+            value
+                .and_then(|v| i8::try_from(v).ok())
+                .map(BasicExpression::I8),
+            BasicType::I8,
+        ),
+        UintKind::I16 => (
+            value
+                .and_then(|v| i16::try_from(v).ok())
+                .map(BasicExpression::I16),
+            BasicType::I16,
+        ),
+        UintKind::I32 => (
+            value
+                .and_then(|v| i32::try_from(v).ok())
+                .map(BasicExpression::I32),
+            BasicType::I32,
+        ),
+        UintKind::I64 => (
+            value
+                .and_then(|v| i64::try_from(v).ok())
+                .map(BasicExpression::I64),
+            BasicType::I64,
+        ),
+    };
+    let expression = expression.ok_or_else(|| {
+        Box::new(TranslationError::LiteralOutOfRange { span, type_ })
+    })?;
+    let construct = match type_ {
+        BasicType::U8 => "the literals of type U8",
+        BasicType::U16 => "the literals of type U16",
+        BasicType::U32 => "the literals of type U32",
+        BasicType::I8 => "the literals of type I8",
+        BasicType::I16 => "the literals of type I16",
+        BasicType::I32 => "the literals of type I32",
+        _ => return Ok(expression),
+    };
+    Err(Box::new(TranslationError::Unsupported { span, construct }))
+}
+
+fn float_value(
+    span: Span,
+    literal: &UFloatingPointLiteral,
+) -> Result<BasicExpression> {
+    let out_of_range =
+        |type_| Box::new(TranslationError::LiteralOutOfRange { span, type_ });
+    match literal.kind() {
+        UFloatingPointKind::F32 => literal
+            .to_f32()
+            .map(BasicExpression::Float)
+            .ok_or_else(|| out_of_range(BasicType::Float)),
+        UFloatingPointKind::F64 => literal
+            .to_f64()
+            .map(BasicExpression::Double)
+            .ok_or_else(|| out_of_range(BasicType::Double)),
+    }
+}
+
+fn operator(name: &OperatorName) -> Option<Operator> {
+    match name {
+        OperatorName::Plus => Some(Operator::Add),
+        OperatorName::Minus => Some(Operator::Subtract),
+        OperatorName::Star => Some(Operator::Multiply),
+        OperatorName::Div => Some(Operator::Divide),
+        OperatorName::Module => Some(Operator::Remainder),
+        OperatorName::Equality => Some(Operator::Equal),
+        OperatorName::NotEqual => Some(Operator::NotEqual),
+        OperatorName::LessThan => Some(Operator::Less),
+        OperatorName::LessOrEqual => Some(Operator::LessOrEqual),
+        OperatorName::MoreThan => Some(Operator::More),
+        OperatorName::MoreOrEqual => Some(Operator::MoreOrEqual),
+        OperatorName::And => Some(Operator::And),
+        OperatorName::Or => Some(Operator::Or),
+        _ => None,
+    }
+}
+
+fn definition_span(definition: &cst_top::Definition) -> Span {
+    definition.name.info.span + definition.value.span()
+}
+
+fn constructor_span(constructor: &cst_top::Constructor) -> Span {
+    match &constructor.type_ {
+        Some(t) => constructor.name.info.span + t.span(),
+        None => constructor.name.info.span,
+    }
+}
+
+impl<'a> Translator<'a> {
+    pub fn new(session: &'a Sast, store: &'a Store) -> Self {
+        Translator {
+            store,
+            session,
+            imports: None,
+            types: HashMap::new(),
+            expanding: vec![],
+            data_types: vec![],
+            globals: vec![],
+            current: None,
+            used: HashSet::new(),
+        }
+    }
+
+    fn identifier(&self, identifier: &CstIdentifier) -> Identifier {
+        let name = self
+            .store
+            .regular
+            .resolve(StoreSymbol::from(*identifier))
+            .unwrap_or_default();
+        // The lexer only produces valid identifiers.
+        Identifier::make(String::from(name)).unwrap()
+    }
+
+    fn token_name(&self, token: &Token<CstIdentifier>) -> Identifier {
+        self.identifier(&token.value)
+    }
+
+    /// Restricts the names of the session to the ones in scope.
+    pub fn with_imports(mut self, imports: &'a ImportScope) -> Self {
+        self.imports = Some(imports);
+        self
+    }
+
+    /// Remembers that a item was used, the uses inside of the item
+    /// itself (like in a recursive function) don't count.
+    fn mark_used(&mut self, name: &Identifier) {
+        if self.current.as_ref() != Some(name) {
+            self.used.insert(name.clone());
+        }
+    }
+
+    /// Tells if a name of the session can be used without a prefix.
+    fn visible(&self, name: &Identifier) -> bool {
+        self.imports.is_none_or(|scope| scope.is_visible(name))
+    }
+
+    /// The name in the session of a visible name.
+    fn resolve(&self, name: &Identifier) -> Identifier {
+        match self.imports {
+            Some(scope) => scope.resolve(name),
+            None => name.clone(),
+        }
+    }
+
+    /// The name that a qualified name refers to, after checking that
+    /// the module was imported with that prefix and exports the name.
+    fn qualified(&self, token: &Token<ImportedVariable>) -> Result<Identifier> {
+        let span = token.info.span;
+        let module = ModuleName::from_logic_path(&token.value.path, self.store);
+        let name = self.identifier(&token.value.name);
+        let scope = match self.imports {
+            Some(scope) if scope.has_qualifier(&module) => scope,
+            _ => {
+                return Err(Box::new(TranslationError::UnknownModule {
+                    span,
+                    module,
+                }))
+            }
+        };
+        scope.resolve_qualified(&module, &name).ok_or_else(|| {
+            Box::new(TranslationError::NotImported { span, module, name })
+        })
+    }
+
+    // ------------------------------ Types ------------------------------
+
+    fn type_parameters(
+        &self,
+        tokens: &[Token<CstIdentifier>],
+    ) -> Result<Vec<Identifier>> {
+        let mut variables: Vec<Identifier> = vec![];
+        for token in tokens {
+            let name = self.token_name(token);
+            if variables.contains(&name) {
+                return Err(Box::new(TranslationError::DuplicateVariable {
+                    span: token.info.span,
+                    name,
+                }));
+            }
+            variables.push(name);
+        }
+        Ok(variables)
+    }
+
+    fn check_arity(
+        span: Span,
+        name: &Identifier,
+        expected: usize,
+        arguments: &[Type],
+    ) -> Result<()> {
+        if expected == arguments.len() {
+            Ok(())
+        } else {
+            Err(Box::new(TranslationError::TypeArity {
+                span,
+                name: name.clone(),
+                expected,
+                found: arguments.len(),
+            }))
+        }
+    }
+
+    fn expand_alias(alias: &Alias, arguments: Vec<Type>) -> Type {
+        let map = alias.variables.iter().cloned().zip(arguments).collect();
+        alias.type_.substitute(&map)
+    }
+
+    /// Translates the pending alias and stores the result.
+    fn alias(&mut self, span: Span, name: &Identifier) -> Result<Alias> {
+        let pending = match self.types.get(name) {
+            Some(TypeName::Alias(alias)) => return Ok(alias.clone()),
+            Some(TypeName::PendingAlias(pending)) => *pending,
+            _ => unreachable!("alias called with a name that isn't an alias"),
+        };
+        if self.expanding.contains(name) {
+            return Err(Box::new(TranslationError::CyclicAlias {
+                span,
+                name: name.clone(),
+            }));
+        }
+        self.expanding.push(name.clone());
+        let outer = self.current.replace(name.clone());
+        let variables = self.type_parameters(&pending.left_part.variables)?;
+        let type_ = self.type_(&pending.type_, &variables)?;
+        self.current = outer;
+        self.expanding.pop();
+        let alias = Alias {
+            span: pending.left_part.name.info.span + pending.type_.span(),
+            name: name.clone(),
+            variables,
+            type_,
+        };
+        self.types
+            .insert(name.clone(), TypeName::Alias(alias.clone()));
+        Ok(alias)
+    }
+
+    fn named_type(
+        &mut self,
+        span: Span,
+        name: Identifier,
+        arguments: Vec<Type>,
+    ) -> Result<Type> {
+        if let Some(basic) = BasicType::from_name(name.as_str()) {
+            Self::check_arity(span, &name, 0, &arguments)?;
+            return Ok(Type::BasicType(basic));
+        }
+        if self.types.contains_key(&name) {
+            self.mark_used(&name);
+        }
+        match self.types.get(&name) {
+            Some(TypeName::Data { arity }) => {
+                Self::check_arity(span, &name, *arity, &arguments)?;
+                return Ok(Type::Named(name, arguments));
+            }
+            Some(_) => {
+                let alias = self.alias(span, &name)?;
+                Self::check_arity(
+                    span,
+                    &name,
+                    alias.variables.len(),
+                    &arguments,
+                )?;
+                return Ok(Self::expand_alias(&alias, arguments));
+            }
+            None => (),
+        }
+        if !self.visible(&name) {
+            return Err(Box::new(TranslationError::UnknownType { span, name }));
+        }
+        self.session_type(span, self.resolve(&name), arguments)
+    }
+
+    fn session_type(
+        &self,
+        span: Span,
+        name: Identifier,
+        arguments: Vec<Type>,
+    ) -> Result<Type> {
+        if let Some(data) = self.session.find_data(name.as_str()) {
+            Self::check_arity(span, &name, data.variables.len(), &arguments)?;
+            return Ok(Type::Named(name, arguments));
+        }
+        if let Some(alias) = self.session.find_alias(name.as_str()) {
+            Self::check_arity(span, &name, alias.variables.len(), &arguments)?;
+            return Ok(Self::expand_alias(alias, arguments));
+        }
+        Err(Box::new(TranslationError::UnknownType { span, name }))
+    }
+
+    /// Translates a type where the `variables` are in scope.
+    pub fn type_(
+        &mut self,
+        type_: &CstType,
+        variables: &[Identifier],
+    ) -> Result<Type> {
+        match type_ {
+            CstType::LocalVariable(token) => {
+                let name = self.token_name(token);
+                if variables.contains(&name) {
+                    Ok(Type::Variable(name))
+                } else {
+                    self.named_type(token.info.span, name, vec![])
+                }
+            }
+            CstType::ImportedVariable(token) => {
+                let name = self.qualified(token)?;
+                self.session_type(token.info.span, name, vec![])
+            }
+            CstType::Tuple(between) => {
+                let mut items = vec![];
+                for item in between.value.iter() {
+                    items.push(self.type_(item, variables)?);
+                }
+                Ok(Type::Tuple(items))
+            }
+            CstType::Record(between) => {
+                let mut fields = vec![];
+                let mut spans = vec![];
+                for item in between.value.iter() {
+                    fields.push((
+                        self.token_name(&item.variable),
+                        self.type_(&item.expression, variables)?,
+                    ));
+                    spans.push(item.variable.info.span);
+                }
+                sort_fields(&mut fields, &spans)?;
+                Ok(Type::Record(fields))
+            }
+            CstType::Parens(between) => self.type_(&between.value, variables),
+            CstType::Application {
+                start,
+                second,
+                remain,
+            } => {
+                let name = match &**start {
+                    CstType::LocalVariable(token) => {
+                        let name = self.token_name(token);
+                        if variables.contains(&name) {
+                            return Err(Box::new(
+                                TranslationError::Unsupported {
+                                    span: type_.span(),
+                                    construct: "applications of type variables",
+                                },
+                            ));
+                        }
+                        name
+                    }
+                    CstType::ImportedVariable(token) => {
+                        let name = self.qualified(token)?;
+                        let mut arguments =
+                            vec![self.type_(second, variables)?];
+                        for argument in remain {
+                            arguments.push(self.type_(argument, variables)?);
+                        }
+                        return self.session_type(
+                            type_.span(),
+                            name,
+                            arguments,
+                        );
+                    }
+                    _ => {
+                        return Err(Box::new(TranslationError::Unsupported {
+                            span: type_.span(),
+                            construct: "this kind of type application",
+                        }))
+                    }
+                };
+                let mut arguments = vec![self.type_(second, variables)?];
+                for argument in remain {
+                    arguments.push(self.type_(argument, variables)?);
+                }
+                self.named_type(type_.span(), name, arguments)
+            }
+            CstType::Arrow { first, remain } => {
+                let mut types = vec![self.type_(first, variables)?];
+                for item in remain {
+                    types.push(self.type_(&item.item, variables)?);
+                }
+                // Safe: there is always a first type.
+                let mut output = types.pop().unwrap();
+                while let Some(argument) = types.pop() {
+                    output = Type::function(argument, output);
+                }
+                Ok(output)
+            }
+            CstType::Scheme { .. } => {
+                Err(Box::new(TranslationError::Unsupported {
+                    span: type_.span(),
+                    construct: "forall inside of types",
+                }))
+            }
+        }
+    }
+
+    /// Translates a type signature, the variables must be introduced
+    /// by a `forall` at the start.
+    pub fn scheme(&mut self, type_: &CstType) -> Result<TypeScheme> {
+        match type_ {
+            CstType::Scheme {
+                first_variable,
+                remain_variables,
+                expression,
+                ..
+            } => {
+                let mut tokens = vec![first_variable.clone()];
+                tokens.extend(remain_variables.iter().cloned());
+                let variables = self.type_parameters(&tokens)?;
+                let type_ = self.type_(expression, &variables)?;
+                Ok(TypeScheme { variables, type_ })
+            }
+            _ => Ok(TypeScheme::monomorphic(self.type_(type_, &[])?)),
+        }
+    }
+
+    fn data_constructors(
+        &mut self,
+        constructors: Vec<&cst_top::Constructor>,
+        variables: &[Identifier],
+    ) -> Result<Vec<DataConstructor>> {
+        let mut out = vec![];
+        for constructor in constructors {
+            let argument = match &constructor.type_ {
+                Some(t) => Some(self.type_(t, variables)?),
+                None => None,
+            };
+            out.push(DataConstructor {
+                span: constructor_span(constructor),
+                name: self.token_name(&constructor.name),
+                argument,
+            });
+        }
+        Ok(out)
+    }
+
+    // --------------------------- Expressions ---------------------------
+
+    /// Using a constructor also uses its data type.
+    fn find_constructor(
+        &mut self,
+        name: &Identifier,
+    ) -> Option<(crate::expression::ConstructorReference, usize)> {
+        let arity = |data: &DataType, index: u64| {
+            usize::from(data.constructors[index as usize].argument.is_some())
+        };
+        let found = self.data_types.iter().find_map(|data| {
+            let index =
+                data.constructors.iter().position(|c| &c.name == name)?;
+            let reference = crate::expression::ConstructorReference {
+                data: data.name.clone(),
+                name: name.clone(),
+                index: index as u64,
+            };
+            Some((reference, arity(data, index as u64)))
+        });
+        if let Some((reference, arity)) = found {
+            self.mark_used(name);
+            self.mark_used(&reference.data);
+            return Some((reference, arity));
+        }
+        // A data type of the input hides the one of the session.
+        self.session_constructor(&self.resolve(name)).filter(
+            |(reference, _)| {
+                self.visible(name)
+                    && !self.data_types.iter().any(|d| d.name == reference.data)
+            },
+        )
+    }
+
+    fn session_constructor(
+        &self,
+        name: &Identifier,
+    ) -> Option<(crate::expression::ConstructorReference, usize)> {
+        self.session
+            .find_constructor(name.as_str())
+            .map(|(reference, data)| {
+                let arity = usize::from(
+                    data.constructors[reference.index as usize]
+                        .argument
+                        .is_some(),
+                );
+                (reference, arity)
+            })
+    }
+
+    /// A qualified name in a expression.
+    fn qualified_variable(
+        &self,
+        token: &Token<ImportedVariable>,
+    ) -> Result<ExpressionKind> {
+        let name = self.qualified(token)?;
+        if let Some((reference, _)) = self.session_constructor(&name) {
+            Ok(ExpressionKind::Constructor(reference))
+        } else if self.session.find_definition(name.as_str()).is_some() {
+            Ok(ExpressionKind::GlobalVariable(name))
+        } else {
+            Err(Box::new(TranslationError::UnknownVariable {
+                span: token.info.span,
+                name,
+            }))
+        }
+    }
+
+    fn variable(
+        &mut self,
+        span: Span,
+        name: Identifier,
+        locals: &[Identifier],
+    ) -> Result<ExpressionKind> {
+        if locals.contains(&name) {
+            Ok(ExpressionKind::LocalVariable(name))
+        } else if let Some((reference, _)) = self.find_constructor(&name) {
+            Ok(ExpressionKind::Constructor(reference))
+        } else if self.globals.contains(&name) {
+            self.mark_used(&name);
+            Ok(ExpressionKind::GlobalVariable(name))
+        } else if self.visible(&name)
+            && self
+                .session
+                .find_definition(self.resolve(&name).as_str())
+                .is_some()
+        {
+            Ok(ExpressionKind::GlobalVariable(self.resolve(&name)))
+        } else {
+            Err(Box::new(TranslationError::UnknownVariable { span, name }))
+        }
+    }
+
+    pub fn pattern(&mut self, pattern: &PatternMatch) -> Result<Pattern> {
+        let span = pattern.span();
+        let kind = match pattern {
+            PatternMatch::LocalVariable(token) => {
+                let name = self.token_name(token);
+                match self.find_constructor(&name) {
+                    Some((constructor, 0)) => PatternKind::Constructor {
+                        constructor,
+                        argument: None,
+                    },
+                    Some((_, expected)) => {
+                        return Err(Box::new(
+                            TranslationError::ConstructorArity {
+                                span,
+                                name,
+                                expected,
+                                found: 0,
+                            },
+                        ))
+                    }
+                    None => PatternKind::Variable(name),
+                }
+            }
+            PatternMatch::ImportedVariable(token) => {
+                let name = self.qualified(token)?;
+                match self.session_constructor(&name) {
+                    Some((constructor, 0)) => PatternKind::Constructor {
+                        constructor,
+                        argument: None,
+                    },
+                    Some((_, expected)) => {
+                        return Err(Box::new(
+                            TranslationError::ConstructorArity {
+                                span,
+                                name,
+                                expected,
+                                found: 0,
+                            },
+                        ))
+                    }
+                    None => {
+                        return Err(Box::new(
+                            TranslationError::NotAConstructor { span, name },
+                        ))
+                    }
+                }
+            }
+            PatternMatch::String(token) => PatternKind::Constant(
+                BasicExpression::String(token.value.decoded()),
+            ),
+            PatternMatch::Uint(token) => {
+                PatternKind::Constant(uint_value(span, &token.value, false)?)
+            }
+            PatternMatch::Char(token) => {
+                PatternKind::Constant(BasicExpression::Char(token.value.value))
+            }
+            PatternMatch::Negative { literal, .. } => {
+                PatternKind::Constant(uint_value(span, &literal.value, true)?)
+            }
+            PatternMatch::UFloat(_) => {
+                return Err(Box::new(TranslationError::Unsupported {
+                    span,
+                    construct: "floating point literals",
+                }))
+            }
+            PatternMatch::AnonHole(_) => PatternKind::Discard,
+            PatternMatch::Tuple(between) => {
+                let mut items = vec![];
+                for item in between.value.iter() {
+                    items.push(self.pattern(item)?);
+                }
+                PatternKind::Tuple(items)
+            }
+            PatternMatch::Record(between) => {
+                let mut fields = vec![];
+                let mut spans = vec![];
+                for item in between.value.iter() {
+                    match item {
+                        PatternMatchRecordItem::OnlyVariable { variable } => {
+                            let name = self.token_name(variable);
+                            fields.push((
+                                name.clone(),
+                                Pattern::new(
+                                    variable.info.span,
+                                    PatternKind::Variable(name),
+                                ),
+                            ));
+                            spans.push(variable.info.span);
+                        }
+                        PatternMatchRecordItem::WithPattern {
+                            variable,
+                            pattern,
+                            ..
+                        } => {
+                            fields.push((
+                                self.token_name(variable),
+                                self.pattern(pattern)?,
+                            ));
+                            spans.push(variable.info.span);
+                        }
+                    }
+                }
+                sort_fields(&mut fields, &spans)?;
+                PatternKind::Record(fields)
+            }
+            PatternMatch::Bind(bind) => PatternKind::Bind {
+                name: self.token_name(&bind.variable),
+                pattern: Box::new(self.pattern(&bind.pattern)?),
+            },
+            PatternMatch::Application {
+                start,
+                second,
+                remain,
+            } => {
+                let (head_span, name, found) = match &**start {
+                    PatternMatch::LocalVariable(token) => {
+                        let name = self.token_name(token);
+                        let found = self.find_constructor(&name);
+                        (token.info.span, name, found)
+                    }
+                    PatternMatch::ImportedVariable(token) => {
+                        let name = self.qualified(token)?;
+                        let found = self.session_constructor(&name);
+                        (token.info.span, name, found)
+                    }
+                    _ => {
+                        return Err(Box::new(TranslationError::Unsupported {
+                            span,
+                            construct: "this kind of pattern application",
+                        }))
+                    }
+                };
+                let (constructor, expected) = match found {
+                    Some(found) => found,
+                    None => {
+                        return Err(Box::new(
+                            TranslationError::NotAConstructor {
+                                span: head_span,
+                                name,
+                            },
+                        ))
+                    }
+                };
+                let found = 1 + remain.len();
+                if expected != found {
+                    return Err(Box::new(TranslationError::ConstructorArity {
+                        span,
+                        name,
+                        expected,
+                        found,
+                    }));
+                }
+                PatternKind::Constructor {
+                    constructor,
+                    argument: Some(Box::new(self.pattern(second)?)),
+                }
+            }
+            PatternMatch::Parens(between) => {
+                return self.pattern(&between.value)
+            }
+        };
+        Ok(Pattern::new(span, kind))
+    }
+
+    /// Translates a pattern and checks that the variables are unique.
+    fn binding_pattern(&mut self, pattern: &PatternMatch) -> Result<Pattern> {
+        let out = self.pattern(pattern)?;
+        check_pattern_variables(&out)?;
+        Ok(out)
+    }
+
+    /// Translates a expression where the `locals` are in scope,
+    /// the last local hides the previous ones with the same name.
+    pub fn expression(
+        &mut self,
+        expression: &CstExpression,
+        locals: &mut Vec<Identifier>,
+    ) -> Result<Expression> {
+        let span = expression.span();
+        let kind = match expression {
+            CstExpression::String(token) => ExpressionKind::BasicTerm(
+                BasicExpression::String(token.value.decoded()),
+            ),
+            // Without type classes only the values of primitive types
+            // can be shown.
+            CstExpression::InterpolationString(token) => {
+                let mut parts: Vec<InterpolationPart> = vec![];
+                for component in &token.value.value {
+                    let text = match component {
+                        InterpolationComponent::RegularString(text) => {
+                            text.clone()
+                        }
+                        InterpolationComponent::Scaped { value, .. } => {
+                            value.to_string()
+                        }
+                        InterpolationComponent::Interpolation {
+                            expression,
+                            format,
+                            ..
+                        } => {
+                            parts.push(InterpolationPart::Hole(
+                                Box::new(self.expression(expression, locals)?),
+                                format.clone().unwrap_or_default(),
+                            ));
+                            continue;
+                        }
+                    };
+                    match parts.last_mut() {
+                        Some(InterpolationPart::Text(previous)) => {
+                            previous.push_str(&text)
+                        }
+                        _ => parts.push(InterpolationPart::Text(text)),
+                    }
+                }
+                ExpressionKind::Interpolation(parts)
+            }
+            CstExpression::Uint(token) => ExpressionKind::BasicTerm(
+                uint_value(span, &token.value, false)?,
+            ),
+            CstExpression::UFloat(token) => {
+                ExpressionKind::BasicTerm(float_value(span, &token.value)?)
+            }
+            CstExpression::Char(token) => ExpressionKind::BasicTerm(
+                BasicExpression::Char(token.value.value),
+            ),
+            // The negated literals are constants, `-(5)` too.
+            CstExpression::Negation { expression, .. } => {
+                let mut negated = expression.as_ref();
+                while let CstExpression::Parens(between) = negated {
+                    negated = &between.value;
+                }
+                match negated {
+                    CstExpression::Uint(token) => ExpressionKind::BasicTerm(
+                        uint_value(span, &token.value, true)?,
+                    ),
+                    CstExpression::UFloat(token) => ExpressionKind::BasicTerm(
+                        match float_value(span, &token.value)? {
+                            BasicExpression::Float(f) => {
+                                BasicExpression::Float(-f)
+                            }
+                            BasicExpression::Double(d) => {
+                                BasicExpression::Double(-d)
+                            }
+                            other => other,
+                        },
+                    ),
+                    _ => ExpressionKind::Negation(Box::new(
+                        self.expression(expression, locals)?,
+                    )),
+                }
+            }
+            CstExpression::LocalVariable(token) => {
+                self.variable(span, self.token_name(token), locals)?
+            }
+            CstExpression::ImportedVariable(token) => {
+                self.qualified_variable(token)?
+            }
+            CstExpression::NamedHole(_) => {
+                return Err(Box::new(TranslationError::Unsupported {
+                    span,
+                    construct: "holes",
+                }))
+            }
+            CstExpression::Tuple(between) => {
+                let mut items = vec![];
+                for item in between.value.iter() {
+                    items.push(self.expression(item, locals)?);
+                }
+                ExpressionKind::Tuple(items)
+            }
+            CstExpression::Record(between) => {
+                let mut fields = vec![];
+                let mut spans = vec![];
+                for item in between.value.iter() {
+                    match item {
+                        ExpressionRecordItem::SingleVariable { variable } => {
+                            let name = self.token_name(variable);
+                            let kind = self.variable(
+                                variable.info.span,
+                                name.clone(),
+                                locals,
+                            )?;
+                            fields.push((
+                                name,
+                                Expression::new(variable.info.span, kind),
+                            ));
+                            spans.push(variable.info.span);
+                        }
+                        ExpressionRecordItem::Assignation {
+                            variable,
+                            expression,
+                            ..
+                        } => {
+                            fields.push((
+                                self.token_name(variable),
+                                self.expression(expression, locals)?,
+                            ));
+                            spans.push(variable.info.span);
+                        }
+                    }
+                }
+                sort_fields(&mut fields, &spans)?;
+                ExpressionKind::Record(fields)
+            }
+            CstExpression::Case(case) => {
+                let scrutinee = self.expression(&case.expression, locals)?;
+                let mut cases = vec![];
+                for item in case.cases.value.iter() {
+                    let pattern = self.binding_pattern(&item.pattern)?;
+                    let variables = pattern.variables();
+                    let previous = locals.len();
+                    locals.extend(variables.into_iter().map(|(_, name)| name));
+                    let result = self.expression(&item.expression, locals);
+                    locals.truncate(previous);
+                    cases.push(CaseCase {
+                        span: item.pattern.span() + item.expression.span(),
+                        pattern,
+                        expression: result?,
+                    });
+                }
+                ExpressionKind::Case {
+                    expression: Box::new(scrutinee),
+                    cases,
+                }
+            }
+            CstExpression::Parens(between) => {
+                return self.expression(&between.value, locals)
+            }
+            CstExpression::Selector(_)
+            | CstExpression::Interrogation { .. } => {
+                return Err(Box::new(TranslationError::Unsupported {
+                    span,
+                    construct: "record selectors",
+                }))
+            }
+            CstExpression::TypeArgument { .. } => {
+                return Err(Box::new(TranslationError::Unsupported {
+                    span,
+                    construct: "type arguments",
+                }))
+            }
+            CstExpression::Let(let_) => {
+                let previous = locals.len();
+                let result = self.let_(let_, locals);
+                locals.truncate(previous);
+                result?
+            }
+            CstExpression::BinaryOperator(binary) => {
+                let left = self.expression(&binary.left, locals)?;
+                let right = self.expression(&binary.right, locals)?;
+                match binary.name.value {
+                    OperatorName::DollarApplication => {
+                        ExpressionKind::Application {
+                            head: Box::new(left),
+                            arguments: vec![right],
+                        }
+                    }
+                    ref name => match operator(name) {
+                        Some(operator) => ExpressionKind::BinaryOperation {
+                            operator: (binary.name.info.span, operator),
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        },
+                        None => {
+                            return Err(Box::new(
+                                TranslationError::Unsupported {
+                                    span: binary.name.info.span,
+                                    construct: "this operator",
+                                },
+                            ))
+                        }
+                    },
+                }
+            }
+            CstExpression::Lambda(lambda) => {
+                let name = self.token_name(&lambda.variable);
+                locals.push(name.clone());
+                let body = self.expression(&lambda.expression, locals);
+                locals.pop();
+                ExpressionKind::Function {
+                    argument: (lambda.variable.info.span, name),
+                    body: Box::new(body?),
+                }
+            }
+            CstExpression::Application(application) => {
+                let head = self.expression(&application.start, locals)?;
+                let mut arguments = vec![];
+                for argument in &application.remain {
+                    arguments.push(self.expression(argument, locals)?);
+                }
+                ExpressionKind::Application {
+                    head: Box::new(head),
+                    arguments,
+                }
+            }
+        };
+        Ok(Expression::new(span, kind))
+    }
+
+    /// The bindings of a let are added to the locals.
+    fn let_(
+        &mut self,
+        let_: &octizys_cst::expressions::Let,
+        locals: &mut Vec<Identifier>,
+    ) -> Result<ExpressionKind> {
+        let mut bindings = vec![];
+        for binding in &let_.bindings {
+            let value = self.expression(&binding.value, locals)?;
+            let pattern = self.binding_pattern(&binding.pattern)?;
+            locals.extend(pattern.variables().into_iter().map(|(_, n)| n));
+            bindings.push(LetBinding { pattern, value });
+        }
+        let output = self.expression(&let_.expression, locals)?;
+        Ok(ExpressionKind::Let {
+            bindings,
+            output: Box::new(output),
+        })
+    }
+
+    fn definition(
+        &mut self,
+        definition: &cst_top::Definition,
+    ) -> Result<Expression> {
+        let span = definition_span(definition);
+        let mut locals: Vec<Identifier> = definition
+            .arguments
+            .iter()
+            .map(|token| self.token_name(token))
+            .collect();
+        let mut value = self.expression(&definition.value, &mut locals)?;
+        for token in definition.arguments.iter().rev() {
+            value = Expression::new(
+                span,
+                ExpressionKind::Function {
+                    argument: (token.info.span, self.token_name(token)),
+                    body: Box::new(value),
+                },
+            );
+        }
+        Ok(value)
+    }
+
+    // ------------------------------- Top -------------------------------
+
+    fn register_type(
+        &mut self,
+        spans: &mut HashMap<Identifier, Span>,
+        token: &Token<CstIdentifier>,
+        value: TypeName<'a>,
+    ) -> Result<()> {
+        let name = self.token_name(token);
+        if let Some(previous) = spans.insert(name.clone(), token.info.span) {
+            return Err(Box::new(TranslationError::DuplicateName {
+                span: token.info.span,
+                name,
+                previous,
+            }));
+        }
+        self.types.insert(name, value);
+        Ok(())
+    }
+
+    pub fn top(&mut self, top: &'a Top) -> Result<Sast> {
+        let items: Vec<&'a TopItem> = match &top.items {
+            Some(items) => items.iter().collect(),
+            None => vec![],
+        };
+        // First we register the names, this way the items can refer
+        // to the items after them.
+        let mut type_spans: HashMap<Identifier, Span> = HashMap::new();
+        let mut value_spans: HashMap<Identifier, Span> = HashMap::new();
+        let mut declarations: HashMap<Identifier, &cst_top::Declaration> =
+            HashMap::new();
+        let mut definitions = vec![];
+        for item in &items {
+            match item {
+                TopItem::Data(data) => self.register_type(
+                    &mut type_spans,
+                    &data.left_part.name,
+                    TypeName::Data {
+                        arity: data.left_part.variables.len(),
+                    },
+                )?,
+                TopItem::NewType(new_type) => self.register_type(
+                    &mut type_spans,
+                    &new_type.left_part.name,
+                    TypeName::Data {
+                        arity: new_type.left_part.variables.len(),
+                    },
+                )?,
+                TopItem::Alias(alias) => self.register_type(
+                    &mut type_spans,
+                    &alias.left_part.name,
+                    TypeName::PendingAlias(alias),
+                )?,
+                TopItem::Declaration(declaration) => {
+                    let name = self.token_name(&declaration.name);
+                    if let Some(previous) = declarations.get(&name) {
+                        return Err(Box::new(
+                            TranslationError::DuplicateName {
+                                span: declaration.name.info.span,
+                                name,
+                                previous: previous.name.info.span,
+                            },
+                        ));
+                    }
+                    declarations.insert(name, declaration);
+                }
+                TopItem::Definition(definition) => {
+                    let name = self.token_name(&definition.name);
+                    let span = definition.name.info.span;
+                    if let Some(previous) =
+                        value_spans.insert(name.clone(), span)
+                    {
+                        return Err(Box::new(
+                            TranslationError::DuplicateName {
+                                span,
+                                name,
+                                previous,
+                            },
+                        ));
+                    }
+                    self.globals.push(name);
+                    definitions.push(definition);
+                }
+            }
+        }
+        let mut aliases = vec![];
+        let mut constructor_spans: HashMap<Identifier, Span> = HashMap::new();
+        for item in &items {
+            match item {
+                TopItem::Alias(alias) => {
+                    let name = self.token_name(&alias.left_part.name);
+                    aliases.push(
+                        self.alias(alias.left_part.name.info.span, &name)?,
+                    )
+                }
+                TopItem::Data(data) => {
+                    let left = &data.left_part;
+                    self.current = Some(self.token_name(&left.name));
+                    let variables = self.type_parameters(&left.variables)?;
+                    let constructors = match &data.constructors {
+                        Some(constructors) => self.data_constructors(
+                            constructors.constructors.iter().collect(),
+                            &variables,
+                        )?,
+                        None => vec![],
+                    };
+                    let span = match &data.constructors {
+                        Some(c) => {
+                            left.statement_keyword.info.span
+                                + constructors
+                                    .last()
+                                    .map(|x| x.span)
+                                    .unwrap_or(c.eq.span)
+                        }
+                        None => {
+                            left.statement_keyword.info.span
+                                + left.name.info.span
+                        }
+                    };
+                    self.data_types.push(DataType {
+                        span,
+                        name: self.token_name(&left.name),
+                        variables,
+                        constructors,
+                        kind: DataKind::Data,
+                    })
+                }
+                TopItem::NewType(new_type) => {
+                    let left = &new_type.left_part;
+                    self.current = Some(self.token_name(&left.name));
+                    let variables = self.type_parameters(&left.variables)?;
+                    let constructors = self.data_constructors(
+                        vec![&new_type.constructor],
+                        &variables,
+                    )?;
+                    self.data_types.push(DataType {
+                        span: left.statement_keyword.info.span
+                            + constructor_span(&new_type.constructor),
+                        name: self.token_name(&left.name),
+                        variables,
+                        constructors,
+                        kind: DataKind::NewType,
+                    })
+                }
+                _ => (),
+            }
+        }
+        for data in &self.data_types {
+            for constructor in &data.constructors {
+                if let Some(previous) = constructor_spans
+                    .insert(constructor.name.clone(), constructor.span)
+                {
+                    return Err(Box::new(TranslationError::DuplicateName {
+                        span: constructor.span,
+                        name: constructor.name.clone(),
+                        previous,
+                    }));
+                }
+            }
+        }
+        let mut out_definitions = vec![];
+        for definition in definitions {
+            let name = self.token_name(&definition.name);
+            self.current = Some(name.clone());
+            let type_ = match declarations.remove(&name) {
+                Some(declaration) => Some(self.scheme(&declaration.type_)?),
+                None => None,
+            };
+            out_definitions.push(VariableDefinition {
+                span: definition_span(definition),
+                name,
+                value: self.definition(definition)?,
+                type_,
+            });
+        }
+        self.current = None;
+        if let Some((name, declaration)) = declarations
+            .into_iter()
+            .min_by_key(|(_, d)| d.name.info.span.start)
+        {
+            return Err(Box::new(TranslationError::MissingDefinition {
+                span: declaration.name.info.span,
+                name,
+            }));
+        }
+        Ok(Sast {
+            data_types: std::mem::take(&mut self.data_types),
+            aliases,
+            definitions: out_definitions,
+        })
+    }
+}
+
+/// Translates the items of a file, the result only contains the
+/// items of the file, the session is used to resolve the other names.
+pub fn translate_top(top: &Top, session: &Sast, store: &Store) -> Result<Sast> {
+    Translator::new(session, store).top(top)
+}
+
+/// Translates a module of a program, the session contains the
+/// modules translated before and the scope tells which of their
+/// names the module imports.
+/// Returns the names of the items used by the module too.
+pub fn translate_module(
+    top: &Top,
+    session: &Sast,
+    imports: &ImportScope,
+    store: &Store,
+) -> Result<(Sast, HashSet<Identifier>)> {
+    let mut translator = Translator::new(session, store).with_imports(imports);
+    let sast = translator.top(top)?;
+    Ok((sast, translator.used))
+}
+
+pub fn translate_expression(
+    expression: &CstExpression,
+    session: &Sast,
+    store: &Store,
+) -> Result<Expression> {
+    Translator::new(session, store).expression(expression, &mut vec![])
+}
@@ -1,134 +1,1310 @@
 use std::collections::HashMap;
 
-use octizys_common::{
-    identifier::Identifier, module_logic_path::LogicPath, span::Span,
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::{Location, Span};
+use octizys_core::common::Identifier;
+use octizys_core::primitives::BOOL_NAME;
+use octizys_cst::literals::FormatKind;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
 };
 
-use crate::{
-    _type::{self, Type, VariableId},
-    expression::{BasicExpression, Expression, Function},
+use crate::_type::{BasicType, Type, TypeScheme, VariableId};
+use crate::expression::{
+    ConstructorReference, Expression, ExpressionKind, InterpolationPart,
+    Operator, Pattern, PatternKind,
 };
+use crate::top::{DataType, Sast};
 
-#[derive(Debug)]
-pub enum TableValue {
-    Type(Identifier, Span, Type),
-    Term(Identifier, Span, Expression, Type),
-    Both(Identifier, Span, Type, (Expression, Type)),
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InferenceError {
+    TypeMismatch {
+        span: Span,
+        expected: Type,
+        found: Type,
+    },
+    /// Unifying the types would create a type that contains itself.
+    InfiniteType {
+        span: Span,
+        expected: Type,
+        found: Type,
+    },
+    NotAFunction {
+        span: Span,
+        found: Type,
+    },
+    /// The translation must resolve every name.
+    UnknownGlobal {
+        span: Span,
+        name: Identifier,
+    },
+    UnknownConstructor {
+        span: Span,
+        constructor: ConstructorReference,
+    },
+    /// Only the signed integers and the floating point numbers have
+    /// a negation.
+    NotNegatable {
+        span: Span,
+        found: Type,
+    },
+    /// The holes of the interpolation strings must be of a primitive
+    /// type until the language has a formatting class.
+    NotShowable {
+        span: Span,
+        found: Type,
+    },
+    /// The holes with a hexadecimal, binary or octal format must be
+    /// integers.
+    NotAnInteger {
+        span: Span,
+        found: Type,
+    },
 }
 
-#[derive(Debug)]
-pub struct Table {
-    table: HashMap<VariableId, TableValue>,
+impl InferenceError {
+    pub fn span(&self) -> Span {
+        match self {
+            InferenceError::TypeMismatch { span, .. }
+            | InferenceError::InfiniteType { span, .. }
+            | InferenceError::NotAFunction { span, .. }
+            | InferenceError::UnknownGlobal { span, .. }
+            | InferenceError::UnknownConstructor { span, .. }
+            | InferenceError::NotNegatable { span, .. }
+            | InferenceError::NotShowable { span, .. }
+            | InferenceError::NotAnInteger { span, .. } => *span,
+        }
+    }
 }
 
-#[derive(Debug)]
-pub enum InferenceError {
-    CantFindVariable(VariableId),
-    CantFindExternalVariable(Identifier, Option<LogicPath>),
+impl ReportFormat for InferenceError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            InferenceError::TypeMismatch { .. } => {
+                NonLineBreakStr::new("TypeMismatch")
+            }
+            InferenceError::InfiniteType { .. } => {
+                NonLineBreakStr::new("InfiniteType")
+            }
+            InferenceError::NotAFunction { .. } => {
+                NonLineBreakStr::new("NotAFunction")
+            }
+            InferenceError::UnknownGlobal { .. } => {
+                NonLineBreakStr::new("Internal:UnknownGlobal")
+            }
+            InferenceError::UnknownConstructor { .. } => {
+                NonLineBreakStr::new("Internal:UnknownConstructor")
+            }
+            InferenceError::NotNegatable { .. } => {
+                NonLineBreakStr::new("NotNegatable")
+            }
+            InferenceError::NotShowable { .. } => {
+                NonLineBreakStr::new("NotShowable")
+            }
+            InferenceError::NotAnInteger { .. } => {
+                NonLineBreakStr::new("NotAnInteger")
+            }
+        }
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        let common =
+            NonLineBreakStr::new("This is a bug in octizys, please report it!");
+        match self {
+            InferenceError::TypeMismatch { .. } => {
+                NonLineBreakStr::new("The types don't match.")
+            }
+            InferenceError::InfiniteType { .. } => {
+                NonLineBreakStr::new("A type would contain itself.")
+            }
+            InferenceError::NotAFunction { .. } => NonLineBreakStr::new(
+                "Something that isn't a function got arguments.",
+            ),
+            InferenceError::UnknownGlobal { .. } => common,
+            InferenceError::UnknownConstructor { .. } => common,
+            InferenceError::NotNegatable { .. } => {
+                NonLineBreakStr::new("The value can't be negated.")
+            }
+            InferenceError::NotShowable { .. } => NonLineBreakStr::new(
+                "The value can't be shown in a interpolation string.",
+            ),
+            InferenceError::NotAnInteger { .. } => NonLineBreakStr::new(
+                "The value can't be shown with the digits of the format.",
+            ),
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self {
+            InferenceError::TypeMismatch {
+                expected, found, ..
+            } => format!("Expected type: {}\nFound type: {}", expected, found),
+            InferenceError::InfiniteType {
+                expected, found, ..
+            } => format!(
+                "Can't make {} equal to {}, the second contains the first.",
+                expected, found
+            ),
+            InferenceError::NotAFunction { found, .. } => {
+                format!("The type {} isn't a function type.", found)
+            }
+            InferenceError::UnknownGlobal { name, .. } => {
+                format!("The definition {} doesn't have a type.", name)
+            }
+            InferenceError::UnknownConstructor { constructor, .. } => format!(
+                "The data type {} of the constructor {} doesn't exist.",
+                constructor.data, constructor.name
+            ),
+            InferenceError::NotNegatable { found, .. } => format!(
+                "Only the signed integers and the floating point numbers can be negated, this is a {}.",
+                found
+            ),
+            InferenceError::NotShowable { found, .. } => format!(
+                "Only the values of primitive types can be used in the holes, the other types can't be shown until the language has classes, this is a {}.",
+                found
+            ),
+            InferenceError::NotAnInteger { found, .. } => format!(
+                "Only the integers can be shown in hexadecimal, binary or octal, this is a {}.",
+                found
+            ),
+        };
+        Some(external_text(&description))
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        match self {
+            InferenceError::TypeMismatch { expected, .. } => {
+                Some(vec![expected.to_string()])
+            }
+            _ => None,
+        }
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span()))
+    }
 }
 
-pub fn infer<Context>(
-    context: Context,
-    local_table: Table,
-    expression: Expression,
-) -> Result<Type, InferenceError> {
-    todo!()
+type Result<T> = std::result::Result<T, Box<InferenceError>>;
+
+/// The failure of the unification without the context.
+enum UnifyError {
+    Mismatch,
+    Occurs,
 }
 
-pub fn unify(t1: Type, t2: Type) -> Option<InferenceError> {
-    todo!()
+/// The type environment of the variables bound inside of expressions.
+type Locals = Vec<(Identifier, TypeScheme)>;
+
+/// Hindley-Milner inference with levels to generalize.
+/// The inference variables are solved by the substitution.
+pub struct Inference<'a> {
+    data_types: Vec<&'a DataType>,
+    globals: HashMap<Identifier, TypeScheme>,
+    substitution: Vec<Option<Type>>,
+    levels: Vec<u32>,
+    level: u32,
+    /// Used to give unique names to generalized variables.
+    generalized: u32,
+    /// The types that weren't checked yet, they may be unknown until
+    /// the declaration is unified.
+    requirements: Vec<(Span, Type, Requirement)>,
 }
 
-const DEFAULT_SPAN: Span = Span {
-    start: octizys_common::span::Position { source_index: 0 },
-    end: octizys_common::span::Position { source_index: 0 },
-};
+/// What a type must be, the types without type classes that can be
+/// negated or shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Requirement {
+    /// The type of a negated expression.
+    Negatable,
+    /// The type of a interpolation hole.
+    Showable,
+    /// The type of a interpolation hole with a hexadecimal, binary or
+    /// octal format.
+    Integer,
+}
+
+impl<'a> Inference<'a> {
+    /// The items of `module` hide the ones of `session`.
+    pub fn new(module: &'a Sast, session: &'a Sast) -> Self {
+        let mut data_types: Vec<&'a DataType> =
+            module.data_types.iter().collect();
+        data_types.extend(
+            session
+                .data_types
+                .iter()
+                .filter(|d| module.find_data(d.name.as_str()).is_none()),
+        );
+        let mut globals = HashMap::new();
+        for definition in &session.definitions {
+            if let Some(scheme) = &definition.type_ {
+                globals.insert(definition.name.clone(), scheme.clone());
+            }
+        }
+        Inference {
+            data_types,
+            globals,
+            substitution: vec![],
+            levels: vec![],
+            level: 0,
+            generalized: 0,
+            requirements: vec![],
+        }
+    }
+
+    fn fresh(&mut self) -> Type {
+        let id = VariableId(self.substitution.len() as u32);
+        self.substitution.push(None);
+        self.levels.push(self.level);
+        Type::InferenceVariable(id)
+    }
+
+    /// Follows the substitution until a type that isn't a solved variable.
+    fn resolve(&self, type_: &Type) -> Type {
+        let mut current = type_.clone();
+        while let Type::InferenceVariable(id) = current {
+            match &self.substitution[id.0 as usize] {
+                Some(t) => current = t.clone(),
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// Replaces all the solved variables.
+    pub fn zonk(&self, type_: &Type) -> Type {
+        match self.resolve(type_) {
+            Type::Function(argument, output) => {
+                Type::function(self.zonk(&argument), self.zonk(&output))
+            }
+            Type::Tuple(items) => {
+                Type::Tuple(items.iter().map(|t| self.zonk(t)).collect())
+            }
+            Type::Record(fields) => Type::Record(
+                fields
+                    .iter()
+                    .map(|(label, t)| (label.clone(), self.zonk(t)))
+                    .collect(),
+            ),
+            Type::Named(name, arguments) => Type::Named(
+                name,
+                arguments.iter().map(|t| self.zonk(t)).collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Checks that the variable doesn't appear in the type and lowers
+    /// the levels of the variables in the type to the one of the variable.
+    fn occurs(&mut self, id: VariableId, type_: &Type) -> bool {
+        match self.resolve(type_) {
+            Type::InferenceVariable(other) => {
+                let level = self.levels[id.0 as usize];
+                let other_level = &mut self.levels[other.0 as usize];
+                *other_level = (*other_level).min(level);
+                other == id
+            }
+            Type::Function(argument, output) => {
+                self.occurs(id, &argument) || self.occurs(id, &output)
+            }
+            Type::Tuple(items) => items.iter().any(|t| self.occurs(id, t)),
+            Type::Record(fields) => {
+                fields.iter().any(|(_, t)| self.occurs(id, t))
+            }
+            Type::Named(_, arguments) => {
+                arguments.iter().any(|t| self.occurs(id, t))
+            }
+            Type::BasicType(_) | Type::Variable(_) => false,
+        }
+    }
+
+    fn unify_inner(
+        &mut self,
+        left: &Type,
+        right: &Type,
+    ) -> std::result::Result<(), UnifyError> {
+        let left = self.resolve(left);
+        let right = self.resolve(right);
+        match (&left, &right) {
+            (Type::InferenceVariable(a), Type::InferenceVariable(b))
+                if a == b =>
+            {
+                Ok(())
+            }
+            (Type::InferenceVariable(id), other)
+            | (other, Type::InferenceVariable(id)) => {
+                if self.occurs(*id, other) {
+                    return Err(UnifyError::Occurs);
+                }
+                self.substitution[id.0 as usize] = Some(other.clone());
+                Ok(())
+            }
+            (Type::BasicType(a), Type::BasicType(b)) if a == b => Ok(()),
+            (Type::Variable(a), Type::Variable(b)) if a == b => Ok(()),
+            (Type::Function(a1, o1), Type::Function(a2, o2)) => {
+                self.unify_inner(a1, a2)?;
+                self.unify_inner(o1, o2)
+            }
+            (Type::Tuple(items1), Type::Tuple(items2))
+                if items1.len() == items2.len() =>
+            {
+                for (t1, t2) in items1.iter().zip(items2) {
+                    self.unify_inner(t1, t2)?;
+                }
+                Ok(())
+            }
+            (Type::Record(fields1), Type::Record(fields2))
+                if fields1.len() == fields2.len()
+                    && fields1
+                        .iter()
+                        .zip(fields2)
+                        .all(|((l1, _), (l2, _))| l1 == l2) =>
+            {
+                for ((_, t1), (_, t2)) in fields1.iter().zip(fields2) {
+                    self.unify_inner(t1, t2)?;
+                }
+                Ok(())
+            }
+            (Type::Named(n1, arguments1), Type::Named(n2, arguments2))
+                if n1 == n2 && arguments1.len() == arguments2.len() =>
+            {
+                for (t1, t2) in arguments1.iter().zip(arguments2) {
+                    self.unify_inner(t1, t2)?;
+                }
+                Ok(())
+            }
+            _ => Err(UnifyError::Mismatch),
+        }
+    }
+
+    /// Makes the types equal, the span is the one of the expression
+    /// that has the `found` type.
+    pub fn unify(
+        &mut self,
+        span: Span,
+        expected: &Type,
+        found: &Type,
+    ) -> Result<()> {
+        match self.unify_inner(expected, found) {
+            Ok(()) => Ok(()),
+            Err(UnifyError::Mismatch) => {
+                Err(Box::new(InferenceError::TypeMismatch {
+                    span,
+                    expected: self.zonk(expected),
+                    found: self.zonk(found),
+                }))
+            }
+            Err(UnifyError::Occurs) => {
+                Err(Box::new(InferenceError::InfiniteType {
+                    span,
+                    expected: self.zonk(expected),
+                    found: self.zonk(found),
+                }))
+            }
+        }
+    }
+
+    fn instantiate(&mut self, scheme: &TypeScheme) -> Type {
+        let map = scheme
+            .variables
+            .iter()
+            .map(|v| (v.clone(), self.fresh()))
+            .collect();
+        scheme.type_.substitute(&map)
+    }
 
-pub fn check<Context>(
-    context: Context,
-    local_table: Table,
-    expression: Expression,
-    t: Type,
-) -> Option<InferenceError> {
-    match expression {
-        Expression::BasicTerm(e) => {
-            fn compare_basic(
-                t1: Type,
-                t2: crate::_type::BasicType,
-            ) -> Option<InferenceError> {
-                unify(
-                    t1,
-                    crate::_type::Type::BasicType {
-                        _type: t2,
-                        span: DEFAULT_SPAN,
-                    },
+    fn free_variables(&self, type_: &Type, acc: &mut Vec<VariableId>) {
+        match self.resolve(type_) {
+            Type::InferenceVariable(id) => {
+                if !acc.contains(&id) {
+                    acc.push(id)
+                }
+            }
+            Type::Function(argument, output) => {
+                self.free_variables(&argument, acc);
+                self.free_variables(&output, acc);
+            }
+            Type::Tuple(items) => {
+                items.iter().for_each(|t| self.free_variables(t, acc))
+            }
+            Type::Record(fields) => {
+                fields.iter().for_each(|(_, t)| self.free_variables(t, acc))
+            }
+            Type::Named(_, arguments) => {
+                arguments.iter().for_each(|t| self.free_variables(t, acc))
+            }
+            Type::BasicType(_) | Type::Variable(_) => (),
+        }
+    }
+
+    /// Checks the pending requirements, the unknown types that would
+    /// be generalized get a default: the negations are `I64` like the
+    /// negative literals and the holes are `String`.
+    fn check_requirements(&mut self) -> Result<()> {
+        let pending = std::mem::take(&mut self.requirements);
+        for (span, type_, requirement) in pending {
+            let found = match self.resolve(&type_) {
+                Type::InferenceVariable(id)
+                    if self.levels[id.0 as usize] <= self.level =>
+                {
+                    self.requirements.push((span, type_, requirement));
+                    continue;
+                }
+                Type::InferenceVariable(_) => {
+                    let default = match requirement {
+                        Requirement::Negatable => BasicType::I64,
+                        Requirement::Showable => BasicType::String,
+                        Requirement::Integer => BasicType::I64,
+                    };
+                    self.unify(span, &Type::BasicType(default), &type_)?;
+                    continue;
+                }
+                found => found,
+            };
+            match (requirement, found) {
+                (
+                    Requirement::Negatable,
+                    Type::BasicType(
+                        BasicType::I64
+                        | BasicType::I32
+                        | BasicType::I16
+                        | BasicType::I8
+                        | BasicType::Float
+                        | BasicType::Double,
+                    ),
                 )
+                | (Requirement::Showable, Type::BasicType(_))
+                | (
+                    Requirement::Integer,
+                    Type::BasicType(
+                        BasicType::U64
+                        | BasicType::I64
+                        | BasicType::U32
+                        | BasicType::I32
+                        | BasicType::U16
+                        | BasicType::I16
+                        | BasicType::U8
+                        | BasicType::I8,
+                    ),
+                ) => (),
+                (Requirement::Negatable, found) => {
+                    return Err(Box::new(InferenceError::NotNegatable {
+                        span,
+                        found: self.zonk(&found),
+                    }))
+                }
+                (Requirement::Showable, found) => {
+                    return Err(Box::new(InferenceError::NotShowable {
+                        span,
+                        found: self.zonk(&found),
+                    }))
+                }
+                (Requirement::Integer, found) => {
+                    return Err(Box::new(InferenceError::NotAnInteger {
+                        span,
+                        found: self.zonk(&found),
+                    }))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Quantifies the variables created at a deeper level than the
+    /// current one, they are solved with new rigid variables.
+    fn generalize(&mut self, type_: &Type) -> TypeScheme {
+        let mut free = vec![];
+        self.free_variables(type_, &mut free);
+        let mut variables = vec![];
+        for id in free {
+            if self.levels[id.0 as usize] > self.level {
+                self.generalized += 1;
+                // The `#` makes the name different of any user variable.
+                let name = Identifier::make(format!("t#{}", self.generalized))
+                    .unwrap();
+                self.substitution[id.0 as usize] =
+                    Some(Type::Variable(name.clone()));
+                variables.push(name);
+            }
+        }
+        TypeScheme {
+            variables,
+            type_: self.zonk(type_),
+        }
+    }
+
+    fn find_data(&self, name: &Identifier) -> Option<&'a DataType> {
+        self.data_types.iter().find(|d| &d.name == name).copied()
+    }
+
+    fn constructor_type(
+        &mut self,
+        span: Span,
+        constructor: &ConstructorReference,
+    ) -> Result<Type> {
+        let data = match self.find_data(&constructor.data) {
+            Some(data)
+                if (constructor.index as usize) < data.constructors.len() =>
+            {
+                data
+            }
+            _ => {
+                return Err(Box::new(InferenceError::UnknownConstructor {
+                    span,
+                    constructor: constructor.clone(),
+                }))
+            }
+        };
+        let scheme = data.constructor_scheme(constructor.index as usize);
+        Ok(self.instantiate(&scheme))
+    }
+
+    fn bool_type() -> Type {
+        Type::Named(Identifier::make(String::from(BOOL_NAME)).unwrap(), vec![])
+    }
+
+    fn check(
+        &mut self,
+        expression: &mut Expression,
+        expected: &Type,
+        locals: &mut Locals,
+    ) -> Result<()> {
+        let found = self.infer(expression, locals)?;
+        self.unify(expression.span, expected, &found)
+    }
+
+    /// Infers the type of the pattern and adds its variables.
+    pub fn infer_pattern(
+        &mut self,
+        pattern: &mut Pattern,
+        variables: &mut Locals,
+    ) -> Result<Type> {
+        let type_ = match &mut pattern.kind {
+            PatternKind::Constant(constant) => {
+                Type::BasicType(constant.type_())
             }
-            match e.term {
-                BasicExpression::U64(_) => {
-                    compare_basic(t, crate::_type::BasicType::U64)
+            PatternKind::Variable(name) => {
+                let type_ = self.fresh();
+                variables.push((
+                    name.clone(),
+                    TypeScheme::monomorphic(type_.clone()),
+                ));
+                type_
+            }
+            PatternKind::Constructor {
+                constructor,
+                argument,
+            } => {
+                let constructor_type =
+                    self.constructor_type(pattern.span, constructor)?;
+                match (argument, constructor_type) {
+                    (Some(argument), Type::Function(expected, output)) => {
+                        let found = self.infer_pattern(argument, variables)?;
+                        self.unify(argument.span, &expected, &found)?;
+                        *output
+                    }
+                    (_, type_) => type_,
+                }
+            }
+            PatternKind::Tuple(items) => {
+                let mut types = vec![];
+                for item in items {
+                    types.push(self.infer_pattern(item, variables)?);
                 }
-                BasicExpression::U32(_) => {
-                    compare_basic(t, crate::_type::BasicType::U32)
+                Type::Tuple(types)
+            }
+            PatternKind::Record(fields) => {
+                let mut types = vec![];
+                for (label, item) in fields {
+                    types.push((
+                        label.clone(),
+                        self.infer_pattern(item, variables)?,
+                    ));
                 }
-                BasicExpression::U16(_) => {
-                    compare_basic(t, crate::_type::BasicType::U16)
+                Type::Record(types)
+            }
+            PatternKind::Bind { name, pattern } => {
+                let type_ = self.infer_pattern(pattern, variables)?;
+                variables.push((
+                    name.clone(),
+                    TypeScheme::monomorphic(type_.clone()),
+                ));
+                type_
+            }
+            PatternKind::Discard => self.fresh(),
+        };
+        pattern.type_ = Some(type_.clone());
+        Ok(type_)
+    }
+
+    pub fn infer(
+        &mut self,
+        expression: &mut Expression,
+        locals: &mut Locals,
+    ) -> Result<Type> {
+        let span = expression.span;
+        let type_ = match &mut expression.kind {
+            ExpressionKind::BasicTerm(basic) => Type::BasicType(basic.type_()),
+            ExpressionKind::LocalVariable(name) => {
+                // The translation ensures the variable is bound.
+                let scheme = locals
+                    .iter()
+                    .rev()
+                    .find(|(local, _)| local == name)
+                    .map(|(_, scheme)| scheme.clone())
+                    .unwrap();
+                self.instantiate(&scheme)
+            }
+            ExpressionKind::GlobalVariable(name) => {
+                match self.globals.get(name).cloned() {
+                    Some(scheme) => self.instantiate(&scheme),
+                    None => {
+                        return Err(Box::new(InferenceError::UnknownGlobal {
+                            span,
+                            name: name.clone(),
+                        }))
+                    }
                 }
-                BasicExpression::U8(_) => {
-                    compare_basic(t, crate::_type::BasicType::U8)
+            }
+            ExpressionKind::Constructor(constructor) => {
+                self.constructor_type(span, constructor)?
+            }
+            ExpressionKind::Function { argument, body } => {
+                let argument_type = self.fresh();
+                locals.push((
+                    argument.1.clone(),
+                    TypeScheme::monomorphic(argument_type.clone()),
+                ));
+                let body_type = self.infer(body, locals);
+                locals.pop();
+                Type::function(argument_type, body_type?)
+            }
+            ExpressionKind::Let { bindings, output } => {
+                let previous = locals.len();
+                let result = self.infer_let(bindings, output, locals);
+                locals.truncate(previous);
+                result?
+            }
+            ExpressionKind::Case { expression, cases } => {
+                let scrutinee = self.infer(expression, locals)?;
+                let output = self.fresh();
+                for case in cases {
+                    let previous = locals.len();
+                    let pattern =
+                        self.infer_pattern(&mut case.pattern, locals)?;
+                    self.unify(case.pattern.span, &scrutinee, &pattern)?;
+                    let result =
+                        self.check(&mut case.expression, &output, locals);
+                    locals.truncate(previous);
+                    result?
                 }
-                BasicExpression::I64(_) => {
-                    compare_basic(t, crate::_type::BasicType::I64)
+                output
+            }
+            ExpressionKind::Application { head, arguments } => {
+                let mut function = self.infer(head, locals)?;
+                for argument in arguments {
+                    function = match self.resolve(&function) {
+                        Type::Function(expected, output) => {
+                            self.check(argument, &expected, locals)?;
+                            *output
+                        }
+                        Type::InferenceVariable(_) => {
+                            let argument_type = self.infer(argument, locals)?;
+                            let output = self.fresh();
+                            self.unify(
+                                head.span,
+                                &function,
+                                &Type::function(argument_type, output.clone()),
+                            )?;
+                            output
+                        }
+                        _ => {
+                            return Err(Box::new(
+                                InferenceError::NotAFunction {
+                                    span: head.span,
+                                    found: self.zonk(&function),
+                                },
+                            ))
+                        }
+                    }
                 }
-                BasicExpression::I32(_) => {
-                    compare_basic(t, crate::_type::BasicType::I32)
+                function
+            }
+            ExpressionKind::Tuple(items) => {
+                let mut types = vec![];
+                for item in items {
+                    types.push(self.infer(item, locals)?);
                 }
-                BasicExpression::I16(_) => {
-                    compare_basic(t, crate::_type::BasicType::I16)
+                Type::Tuple(types)
+            }
+            ExpressionKind::Record(fields) => {
+                let mut types = vec![];
+                for (label, item) in fields {
+                    types.push((label.clone(), self.infer(item, locals)?));
                 }
-                BasicExpression::I8(_) => {
-                    compare_basic(t, crate::_type::BasicType::I8)
+                Type::Record(types)
+            }
+            ExpressionKind::BinaryOperation {
+                operator,
+                left,
+                right,
+            } => {
+                let uint = Type::BasicType(BasicType::U64);
+                let bool_type = Self::bool_type();
+                let (operands, output) = match operator.1 {
+                    Operator::Add
+                    | Operator::Subtract
+                    | Operator::Multiply
+                    | Operator::Divide
+                    | Operator::Remainder => (uint.clone(), uint),
+                    Operator::Equal
+                    | Operator::NotEqual
+                    | Operator::Less
+                    | Operator::LessOrEqual
+                    | Operator::More
+                    | Operator::MoreOrEqual => (uint, bool_type),
+                    Operator::And | Operator::Or => {
+                        (bool_type.clone(), bool_type)
+                    }
+                };
+                self.check(left, &operands, locals)?;
+                self.check(right, &operands, locals)?;
+                output
+            }
+            ExpressionKind::Negation(negated) => {
+                let type_ = self.infer(negated, locals)?;
+                self.requirements.push((
+                    span,
+                    type_.clone(),
+                    Requirement::Negatable,
+                ));
+                type_
+            }
+            ExpressionKind::Interpolation(parts) => {
+                for part in parts {
+                    if let InterpolationPart::Hole(hole, format) = part {
+                        let type_ = self.infer(hole, locals)?;
+                        let requirement = match format.kind {
+                            None | Some(FormatKind::Debug) => {
+                                Requirement::Showable
+                            }
+                            Some(_) => Requirement::Integer,
+                        };
+                        self.requirements.push((hole.span, type_, requirement));
+                    }
                 }
-                BasicExpression::Float(_) => {
-                    compare_basic(t, crate::_type::BasicType::Float)
+                Type::BasicType(BasicType::String)
+            }
+        };
+        expression.type_ = Some(type_.clone());
+        Ok(type_)
+    }
+
+    /// The bindings are added to the locals.
+    fn infer_let(
+        &mut self,
+        bindings: &mut [crate::expression::LetBinding],
+        output: &mut Expression,
+        locals: &mut Locals,
+    ) -> Result<Type> {
+        for binding in bindings {
+            self.level += 1;
+            let value = self.infer(&mut binding.value, locals);
+            self.level -= 1;
+            let value = value?;
+            match &binding.pattern.kind {
+                // Unifying with the type of the pattern would move the
+                // variables of the value to the current level.
+                PatternKind::Variable(name)
+                    if binding.value.is_syntactic_value() =>
+                {
+                    self.check_requirements()?;
+                    let scheme = self.generalize(&value);
+                    binding.pattern.type_ = Some(value);
+                    locals.push((name.clone(), scheme));
                 }
-                BasicExpression::Double(_) => {
-                    compare_basic(t, crate::_type::BasicType::Double)
+                _ => {
+                    let mut variables = vec![];
+                    let pattern = self
+                        .infer_pattern(&mut binding.pattern, &mut variables)?;
+                    self.unify(binding.pattern.span, &pattern, &value)?;
+                    locals.extend(variables)
                 }
-                BasicExpression::String(_) => {
-                    compare_basic(t, crate::_type::BasicType::String)
+            }
+        }
+        self.infer(output, locals)
+    }
+
+    /// Writes the final types in the tree.
+    pub fn zonk_expression(&self, expression: &mut Expression) {
+        if let Some(t) = &expression.type_ {
+            expression.type_ = Some(self.zonk(t));
+        }
+        match &mut expression.kind {
+            ExpressionKind::BasicTerm(_)
+            | ExpressionKind::LocalVariable(_)
+            | ExpressionKind::GlobalVariable(_)
+            | ExpressionKind::Constructor(_) => (),
+            ExpressionKind::Function { body, .. } => self.zonk_expression(body),
+            ExpressionKind::Let { bindings, output } => {
+                for binding in bindings {
+                    self.zonk_pattern(&mut binding.pattern);
+                    self.zonk_expression(&mut binding.value);
                 }
-                BasicExpression::Char(_) => {
-                    compare_basic(t, crate::_type::BasicType::Char)
+                self.zonk_expression(output)
+            }
+            ExpressionKind::Case { expression, cases } => {
+                self.zonk_expression(expression);
+                for case in cases {
+                    self.zonk_pattern(&mut case.pattern);
+                    self.zonk_expression(&mut case.expression);
+                }
+            }
+            ExpressionKind::Application { head, arguments } => {
+                self.zonk_expression(head);
+                arguments.iter_mut().for_each(|e| self.zonk_expression(e))
+            }
+            ExpressionKind::Tuple(items) => {
+                items.iter_mut().for_each(|e| self.zonk_expression(e))
+            }
+            ExpressionKind::Record(fields) => {
+                fields.iter_mut().for_each(|(_, e)| self.zonk_expression(e))
+            }
+            ExpressionKind::BinaryOperation { left, right, .. } => {
+                self.zonk_expression(left);
+                self.zonk_expression(right)
+            }
+            ExpressionKind::Negation(negated) => self.zonk_expression(negated),
+            ExpressionKind::Interpolation(parts) => {
+                for part in parts {
+                    if let InterpolationPart::Hole(hole, _) = part {
+                        self.zonk_expression(hole)
+                    }
                 }
             }
         }
-        Expression::LocalVariable(vid) => match local_table.find(vid) {
-            Some(t2) => unify(t, t2),
-            None => Some(InferenceError::CantFindVariable(vid)),
-        },
-        Expression::ExternalVariable(name, path) => match context
-            .find(name, path)
-        {
-            Some(t2) => unify(t, t2.into()),
-            None => Some(InferenceError::CantFindExternalVariable(name, path)),
-        },
+    }
 
-        Expression::Function(Function {
-            span,
-            arguments,
-            output,
-        }) => {
-            let len = arguments.len();
-            match solve_up_to_arrow(t) {
-                Some(_type::Function {
-                    arguments: args2,
-                    output: output2,
-                    ..
-                }) => unify(t, args2),
-                _ => todo!(),
+    fn zonk_pattern(&self, pattern: &mut Pattern) {
+        if let Some(t) = &pattern.type_ {
+            pattern.type_ = Some(self.zonk(t));
+        }
+        match &mut pattern.kind {
+            PatternKind::Constant(_)
+            | PatternKind::Variable(_)
+            | PatternKind::Discard => (),
+            PatternKind::Constructor { argument, .. } => {
+                if let Some(argument) = argument {
+                    self.zonk_pattern(argument)
+                }
+            }
+            PatternKind::Tuple(items) => {
+                items.iter_mut().for_each(|p| self.zonk_pattern(p))
+            }
+            PatternKind::Record(fields) => {
+                fields.iter_mut().for_each(|(_, p)| self.zonk_pattern(p))
             }
+            PatternKind::Bind { pattern, .. } => self.zonk_pattern(pattern),
         }
-        _ => todo!(),
+    }
+
+    /// Infers a group of mutually recursive definitions, the
+    /// definitions with a declared type are checked against it.
+    fn infer_group(
+        &mut self,
+        module: &mut Sast,
+        group: &[usize],
+    ) -> Result<()> {
+        self.level += 1;
+        let monomorphic = self.check_group(module, group);
+        self.level -= 1;
+        let monomorphic = monomorphic?;
+        self.check_requirements()?;
+        for index in group {
+            if let Some(type_) = monomorphic.get(index) {
+                let scheme = self.generalize(type_);
+                let definition = &mut module.definitions[*index];
+                self.globals.insert(definition.name.clone(), scheme.clone());
+                definition.type_ = Some(scheme);
+            }
+        }
+        for index in group {
+            self.zonk_expression(&mut module.definitions[*index].value);
+        }
+        Ok(())
+    }
+
+    /// Returns the types of the definitions without declaration.
+    fn check_group(
+        &mut self,
+        module: &mut Sast,
+        group: &[usize],
+    ) -> Result<HashMap<usize, Type>> {
+        let mut monomorphic = HashMap::new();
+        for index in group {
+            let definition = &module.definitions[*index];
+            match &definition.type_ {
+                Some(scheme) => {
+                    self.globals
+                        .insert(definition.name.clone(), scheme.clone());
+                }
+                None => {
+                    let type_ = self.fresh();
+                    monomorphic.insert(*index, type_.clone());
+                    self.globals.insert(
+                        definition.name.clone(),
+                        TypeScheme::monomorphic(type_),
+                    );
+                }
+            }
+        }
+        for index in group {
+            let definition = &mut module.definitions[*index];
+            let expected = match &definition.type_ {
+                // The variables of the declaration are rigid.
+                Some(scheme) => scheme.type_.clone(),
+                None => monomorphic[index].clone(),
+            };
+            self.check(&mut definition.value, &expected, &mut vec![])?
+        }
+        Ok(monomorphic)
+    }
+}
+
+/// The strongly connected components of the definitions in
+/// dependency order (a component only uses the previous ones).
+fn definition_groups(module: &Sast) -> Vec<Vec<usize>> {
+    let indexes: HashMap<&Identifier, usize> = module
+        .definitions
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (&d.name, i))
+        .collect();
+    let edges: Vec<Vec<usize>> = module
+        .definitions
+        .iter()
+        .map(|d| {
+            let mut out = vec![];
+            d.value.globals(&mut |name, _| {
+                if let Some(i) = indexes.get(name) {
+                    out.push(*i)
+                }
+            });
+            out
+        })
+        .collect();
+    strongly_connected_components(&edges)
+}
+
+/// The strongly connected components of a graph given by the
+/// successors of every node, a component only has edges to itself
+/// and to the previous components.
+pub(crate) fn strongly_connected_components(
+    edges: &[Vec<usize>],
+) -> Vec<Vec<usize>> {
+    let mut tarjan = Tarjan {
+        edges,
+        index: vec![None; edges.len()],
+        low: vec![0; edges.len()],
+        on_stack: vec![false; edges.len()],
+        stack: vec![],
+        counter: 0,
+        components: vec![],
+    };
+    for node in 0..edges.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components
+}
+
+struct Tarjan<'e> {
+    edges: &'e [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    counter: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.counter);
+        self.low[node] = self.counter;
+        self.counter += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+        for &next in &self.edges[node] {
+            match self.index[next] {
+                None => {
+                    self.visit(next);
+                    self.low[node] = self.low[node].min(self.low[next]);
+                }
+                Some(index) if self.on_stack[next] => {
+                    self.low[node] = self.low[node].min(index);
+                }
+                _ => (),
+            }
+        }
+        if Some(self.low[node]) == self.index[node] {
+            let mut component = vec![];
+            while let Some(top) = self.stack.pop() {
+                self.on_stack[top] = false;
+                component.push(top);
+                if top == node {
+                    break;
+                }
+            }
+            component.reverse();
+            self.components.push(component);
+        }
+    }
+}
+
+/// Infers the types of the definitions of the module, after this
+/// every definition and expression has a type.
+pub fn infer_module(module: &mut Sast, session: &Sast) -> Result<()> {
+    let groups = definition_groups(module);
+    let snapshot = module.clone();
+    let mut inference = Inference::new(&snapshot, session);
+    for group in groups {
+        inference.infer_group(module, &group)?;
+    }
+    Ok(())
+}
+
+/// Infers the type of a expression in the context of a session
+/// where every definition already has a type.
+pub fn infer_expression(
+    expression: &mut Expression,
+    session: &Sast,
+) -> Result<TypeScheme> {
+    let empty = Sast::default();
+    let mut inference = Inference::new(&empty, session);
+    inference.level += 1;
+    let type_ = inference.infer(expression, &mut vec![]);
+    inference.level -= 1;
+    let type_ = type_?;
+    inference.check_requirements()?;
+    let scheme = inference.generalize(&type_);
+    inference.zonk_expression(expression);
+    Ok(scheme)
+}
+
+#[cfg(test)]
+mod inference_test {
+    use super::{infer_module, InferenceError};
+    use crate::_type::{BasicType, Type, TypeScheme};
+    use crate::expression::{
+        BasicExpression, Expression, ExpressionKind, Operator,
+    };
+    use crate::top::{Sast, VariableDefinition};
+    use octizys_common::span::Span;
+    use octizys_core::common::Identifier;
+
+    fn span() -> Span {
+        Span::from(((0, 0, 0), (0, 0, 0)))
+    }
+
+    fn identifier(s: &str) -> Identifier {
+        Identifier::make(String::from(s)).unwrap()
+    }
+
+    fn expression(kind: ExpressionKind) -> Expression {
+        Expression::new(span(), kind)
+    }
+
+    fn lambda(name: &str, body: Expression) -> Expression {
+        expression(ExpressionKind::Function {
+            argument: (span(), identifier(name)),
+            body: Box::new(body),
+        })
+    }
+
+    fn local(name: &str) -> Expression {
+        expression(ExpressionKind::LocalVariable(identifier(name)))
+    }
+
+    fn global(name: &str) -> Expression {
+        expression(ExpressionKind::GlobalVariable(identifier(name)))
+    }
+
+    fn definition(name: &str, value: Expression) -> VariableDefinition {
+        VariableDefinition {
+            span: span(),
+            name: identifier(name),
+            value,
+            type_: None,
+        }
+    }
+
+    fn infer(
+        definitions: Vec<VariableDefinition>,
+    ) -> Result<Sast, Box<InferenceError>> {
+        let mut module = Sast {
+            definitions,
+            ..Default::default()
+        };
+        infer_module(&mut module, &Sast::prelude())?;
+        Ok(module)
+    }
+
+    fn scheme_of(module: &Sast, name: &str) -> String {
+        module
+            .find_definition(name)
+            .and_then(|d| d.type_.as_ref())
+            .map(TypeScheme::normalize)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn identity_is_generalized() {
+        let module = infer(vec![
+            definition("id", lambda("x", local("x"))),
+            definition("constant", lambda("x", lambda("y", local("x")))),
+        ])
+        .unwrap();
+        assert_eq!(scheme_of(&module, "id"), "forall a . a -> a");
+        assert_eq!(scheme_of(&module, "constant"), "forall a b . a -> b -> a");
+    }
+
+    #[test]
+    fn polymorphic_use_of_global() {
+        // pair = (id 1, id "a")
+        let apply = |argument| {
+            expression(ExpressionKind::Application {
+                head: Box::new(global("id")),
+                arguments: vec![argument],
+            })
+        };
+        let module = infer(vec![
+            definition(
+                "pair",
+                expression(ExpressionKind::Tuple(vec![
+                    apply(expression(ExpressionKind::BasicTerm(
+                        BasicExpression::U64(1),
+                    ))),
+                    apply(expression(ExpressionKind::BasicTerm(
+                        BasicExpression::String(String::from("a")),
+                    ))),
+                ])),
+            ),
+            definition("id", lambda("x", local("x"))),
+        ])
+        .unwrap();
+        assert_eq!(scheme_of(&module, "pair"), "(U64, String)");
+    }
+
+    #[test]
+    fn operators_and_mismatch() {
+        let add = |left, right| {
+            expression(ExpressionKind::BinaryOperation {
+                operator: (span(), Operator::Add),
+                left: Box::new(left),
+                right: Box::new(right),
+            })
+        };
+        let module = infer(vec![definition(
+            "succ",
+            lambda(
+                "x",
+                add(
+                    local("x"),
+                    expression(ExpressionKind::BasicTerm(
+                        BasicExpression::U64(1),
+                    )),
+                ),
+            ),
+        )])
+        .unwrap();
+        assert_eq!(scheme_of(&module, "succ"), "U64 -> U64");
+        let error = infer(vec![definition(
+            "bad",
+            add(
+                expression(ExpressionKind::BasicTerm(BasicExpression::U64(1))),
+                expression(ExpressionKind::BasicTerm(BasicExpression::String(
+                    String::from("a"),
+                ))),
+            ),
+        )])
+        .unwrap_err();
+        assert_eq!(
+            *error,
+            InferenceError::TypeMismatch {
+                span: span(),
+                expected: Type::BasicType(BasicType::U64),
+                found: Type::BasicType(BasicType::String),
+            }
+        );
+    }
+
+    #[test]
+    fn self_application_is_infinite() {
+        let error = infer(vec![definition(
+            "omega",
+            lambda(
+                "x",
+                expression(ExpressionKind::Application {
+                    head: Box::new(local("x")),
+                    arguments: vec![local("x")],
+                }),
+            ),
+        )])
+        .unwrap_err();
+        assert!(matches!(*error, InferenceError::InfiniteType { .. }));
+    }
+
+    #[test]
+    fn declared_type_is_rigid() {
+        let mut bad = definition(
+            "bad",
+            lambda(
+                "x",
+                expression(ExpressionKind::BasicTerm(BasicExpression::U64(1))),
+            ),
+        );
+        let a = identifier("a");
+        bad.type_ = Some(TypeScheme {
+            variables: vec![a.clone()],
+            type_: Type::function(Type::Variable(a.clone()), Type::Variable(a)),
+        });
+        assert!(matches!(
+            infer(vec![bad]).map_err(|e| *e),
+            Err(InferenceError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn negation_of_signed_values() {
+        let negate = |name| {
+            definition(
+                name,
+                lambda(
+                    "x",
+                    expression(ExpressionKind::Negation(Box::new(local("x")))),
+                ),
+            )
+        };
+        let module = infer(vec![negate("default")]).unwrap();
+        assert_eq!(scheme_of(&module, "default"), "I64 -> I64");
+        let declared = |basic| {
+            let mut definition = negate("declared");
+            definition.type_ = Some(TypeScheme::monomorphic(Type::function(
+                Type::BasicType(basic),
+                Type::BasicType(basic),
+            )));
+            infer(vec![definition])
+        };
+        assert!(declared(BasicType::Double).is_ok());
+        assert!(matches!(
+            declared(BasicType::U64).map_err(|e| *e),
+            Err(InferenceError::NotNegatable {
+                found: Type::BasicType(BasicType::U64),
+                ..
+            })
+        ));
     }
 }
@@ -0,0 +1,752 @@
+//! Kind inference of the type declarations.
+//!
+//! The kinds are inferred over the CST before the translation, one
+//! group of mutually recursive declarations at a time. There is no
+//! kind polymorphism, the kinds that remain unknown after a group
+//! are defaulted to `*`.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use octizys_common::identifier::Identifier as CstIdentifier;
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::{Location, Span};
+use octizys_core::common::Identifier;
+use octizys_cst::base::Token;
+use octizys_cst::top::{Top, TopItem};
+use octizys_cst::types::Type as CstType;
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+use octizys_text_store::store::{Store, StoreSymbol};
+
+use crate::_type::BasicType;
+use crate::inference::strongly_connected_components;
+use crate::modules::ImportScope;
+use crate::top::Sast;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// The kind of the types of values.
+    Star,
+    Arrow(Box<Kind>, Box<Kind>),
+    Variable(u32),
+}
+
+impl Kind {
+    pub fn arrow(argument: Kind, output: Kind) -> Kind {
+        Kind::Arrow(Box::new(argument), Box::new(output))
+    }
+
+    /// The kind of a type with the given number of parameters of
+    /// kind `*`.
+    pub fn simple(arity: usize) -> Kind {
+        (0..arity).fold(Kind::Star, |acc, _| Kind::arrow(Kind::Star, acc))
+    }
+
+    /// The number of arguments a type of this kind takes.
+    pub fn arity(&self) -> usize {
+        match self {
+            Kind::Arrow(_, output) => 1 + output.arity(),
+            _ => 0,
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Star => write!(f, "*"),
+            Kind::Arrow(argument, output) => match **argument {
+                Kind::Arrow(_, _) => write!(f, "({}) -> {}", argument, output),
+                _ => write!(f, "{} -> {}", argument, output),
+            },
+            Kind::Variable(n) => write!(f, "k{}", n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KindError {
+    /// The name isn't a parameter of the declaration nor a type.
+    UnboundVariable { span: Span, name: Identifier },
+    /// A type got more arguments than it takes.
+    OverApplied {
+        span: Span,
+        name: Identifier,
+        expected: usize,
+        found: usize,
+    },
+    /// A type without all its arguments is used as the type of values.
+    UnderApplied {
+        span: Span,
+        name: Identifier,
+        expected: usize,
+        found: usize,
+    },
+    KindMismatch {
+        span: Span,
+        expected: Kind,
+        found: Kind,
+    },
+    /// Unifying the kinds would create a kind that contains itself.
+    InfiniteKind {
+        span: Span,
+        expected: Kind,
+        found: Kind,
+    },
+}
+
+impl KindError {
+    pub fn span(&self) -> Span {
+        match self {
+            KindError::UnboundVariable { span, .. }
+            | KindError::OverApplied { span, .. }
+            | KindError::UnderApplied { span, .. }
+            | KindError::KindMismatch { span, .. }
+            | KindError::InfiniteKind { span, .. } => *span,
+        }
+    }
+}
+
+fn arguments(n: usize) -> String {
+    if n == 1 {
+        String::from("1 argument")
+    } else {
+        format!("{} arguments", n)
+    }
+}
+
+impl ReportFormat for KindError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            KindError::UnboundVariable { .. } => {
+                NonLineBreakStr::new("UnboundTypeVariable")
+            }
+            KindError::OverApplied { .. } => {
+                NonLineBreakStr::new("TypeOverApplied")
+            }
+            KindError::UnderApplied { .. } => {
+                NonLineBreakStr::new("TypeUnderApplied")
+            }
+            KindError::KindMismatch { .. } => {
+                NonLineBreakStr::new("KindMismatch")
+            }
+            KindError::InfiniteKind { .. } => {
+                NonLineBreakStr::new("InfiniteKind")
+            }
+        }
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            KindError::UnboundVariable { .. } => {
+                NonLineBreakStr::new("We couldn't find a type variable.")
+            }
+            KindError::OverApplied { .. } => {
+                NonLineBreakStr::new("A type got too many arguments.")
+            }
+            KindError::UnderApplied { .. } => {
+                NonLineBreakStr::new("A type is missing arguments.")
+            }
+            KindError::KindMismatch { .. } => {
+                NonLineBreakStr::new("The kinds don't match.")
+            }
+            KindError::InfiniteKind { .. } => {
+                NonLineBreakStr::new("A kind would contain itself.")
+            }
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self {
+            KindError::UnboundVariable { name, .. } => format!(
+                "The name {} isn't a parameter of the declaration or a type.",
+                name
+            ),
+            KindError::OverApplied {
+                name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "The type {} takes {}, but it got {}.",
+                name,
+                arguments(*expected),
+                found
+            ),
+            KindError::UnderApplied {
+                name,
+                expected,
+                found,
+                ..
+            } => format!(
+                "The type {} needs {}, but it got {}.",
+                name,
+                arguments(*expected),
+                found
+            ),
+            KindError::KindMismatch {
+                expected, found, ..
+            } => format!("Expected kind: {}\nFound kind: {}", expected, found),
+            KindError::InfiniteKind {
+                expected, found, ..
+            } => format!(
+                "Can't make {} equal to {}, the second contains the first.",
+                expected, found
+            ),
+        };
+        Some(external_text(&description))
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        match self {
+            KindError::KindMismatch { expected, .. } => {
+                Some(vec![expected.to_string()])
+            }
+            _ => None,
+        }
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span()))
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<KindError>>;
+
+/// A type declaration of the top with the parts that matter for
+/// the kinds.
+struct Declaration<'t> {
+    name: Identifier,
+    variables: &'t [Token<CstIdentifier>],
+    /// The types of the constructors or the body of the alias.
+    types: Vec<&'t CstType>,
+}
+
+struct KindInference<'a> {
+    store: &'a Store,
+    session: &'a Sast,
+    /// The names of the session that the top imports.
+    imports: Option<&'a ImportScope>,
+    /// The kinds of the declarations of the top.
+    kinds: HashMap<Identifier, Kind>,
+    substitution: Vec<Option<Kind>>,
+}
+
+impl<'a> KindInference<'a> {
+    fn token_name(&self, token: &Token<CstIdentifier>) -> Identifier {
+        let name = self
+            .store
+            .regular
+            .resolve(StoreSymbol::from(token.value))
+            .unwrap_or_default();
+        // The lexer only produces valid identifiers.
+        Identifier::make(String::from(name)).unwrap()
+    }
+
+    /// The name in the session of a name of the top.
+    fn session_name(&self, name: &Identifier) -> Identifier {
+        match self.imports {
+            Some(scope) => scope.resolve(name),
+            None => name.clone(),
+        }
+    }
+
+    fn fresh(&mut self) -> Kind {
+        self.substitution.push(None);
+        Kind::Variable(self.substitution.len() as u32 - 1)
+    }
+
+    fn resolve(&self, kind: &Kind) -> Kind {
+        match kind {
+            Kind::Variable(n) => match &self.substitution[*n as usize] {
+                Some(bound) => self.resolve(bound),
+                None => kind.clone(),
+            },
+            _ => kind.clone(),
+        }
+    }
+
+    /// Applies the substitution keeping the unknown kinds.
+    fn apply(&self, kind: &Kind) -> Kind {
+        match self.resolve(kind) {
+            Kind::Arrow(argument, output) => {
+                Kind::arrow(self.apply(&argument), self.apply(&output))
+            }
+            other => other,
+        }
+    }
+
+    /// Applies the substitution, the unknown kinds are `*`.
+    fn zonk(&self, kind: &Kind) -> Kind {
+        match self.resolve(kind) {
+            Kind::Star | Kind::Variable(_) => Kind::Star,
+            Kind::Arrow(argument, output) => {
+                Kind::arrow(self.zonk(&argument), self.zonk(&output))
+            }
+        }
+    }
+
+    fn occurs(&self, variable: u32, kind: &Kind) -> bool {
+        match self.resolve(kind) {
+            Kind::Star => false,
+            Kind::Variable(n) => n == variable,
+            Kind::Arrow(argument, output) => {
+                self.occurs(variable, &argument)
+                    || self.occurs(variable, &output)
+            }
+        }
+    }
+
+    fn unify(
+        &mut self,
+        span: Span,
+        expected: &Kind,
+        found: &Kind,
+    ) -> Result<()> {
+        let expected = self.resolve(expected);
+        let found = self.resolve(found);
+        match (&expected, &found) {
+            (Kind::Star, Kind::Star) => Ok(()),
+            (Kind::Variable(a), Kind::Variable(b)) if a == b => Ok(()),
+            (Kind::Variable(n), other) | (other, Kind::Variable(n)) => {
+                if self.occurs(*n, other) {
+                    return Err(Box::new(KindError::InfiniteKind {
+                        span,
+                        expected: self.apply(&expected),
+                        found: self.apply(&found),
+                    }));
+                }
+                self.substitution[*n as usize] = Some(other.clone());
+                Ok(())
+            }
+            (Kind::Arrow(a1, o1), Kind::Arrow(a2, o2)) => {
+                self.unify(span, a1, a2)?;
+                self.unify(span, o1, o2)
+            }
+            _ => Err(Box::new(KindError::KindMismatch {
+                span,
+                expected: self.apply(&expected),
+                found: self.apply(&found),
+            })),
+        }
+    }
+
+    fn lookup(
+        &self,
+        token: &Token<CstIdentifier>,
+        scope: &HashMap<Identifier, Kind>,
+    ) -> Result<(Identifier, Kind)> {
+        let name = self.token_name(token);
+        let kind = if let Some(kind) = scope.get(&name) {
+            kind.clone()
+        } else if let Some(kind) = self.kinds.get(&name) {
+            kind.clone()
+        } else if BasicType::from_name(name.as_str()).is_some() {
+            Kind::Star
+        } else if let Some(data) =
+            self.session.find_data(self.session_name(&name).as_str())
+        {
+            Kind::simple(data.variables.len())
+        } else if let Some(alias) =
+            self.session.find_alias(self.session_name(&name).as_str())
+        {
+            Kind::simple(alias.variables.len())
+        } else {
+            return Err(Box::new(KindError::UnboundVariable {
+                span: token.info.span,
+                name,
+            }));
+        };
+        Ok((name, kind))
+    }
+
+    /// Checks that the kind of a type with `found` arguments is the
+    /// expected one, reporting the missing arguments in terms of
+    /// the name of the head.
+    fn check_result(
+        &mut self,
+        span: Span,
+        name: Option<&Identifier>,
+        kind: &Kind,
+        expected: &Kind,
+        found: usize,
+    ) -> Result<()> {
+        match (name, self.resolve(kind), self.resolve(expected)) {
+            (Some(name), Kind::Arrow(_, _), Kind::Star) => {
+                Err(Box::new(KindError::UnderApplied {
+                    span,
+                    name: name.clone(),
+                    expected: found + self.zonk(kind).arity(),
+                    found,
+                }))
+            }
+            _ => self.unify(span, expected, kind),
+        }
+    }
+
+    fn check(
+        &mut self,
+        type_: &CstType,
+        expected: &Kind,
+        scope: &HashMap<Identifier, Kind>,
+    ) -> Result<()> {
+        let span = type_.span();
+        match type_ {
+            CstType::LocalVariable(token) => {
+                let (name, kind) = self.lookup(token, scope)?;
+                self.check_result(span, Some(&name), &kind, expected, 0)
+            }
+            // The translation reports them.
+            CstType::ImportedVariable(_) => Ok(()),
+            CstType::Tuple(between) => {
+                for item in between.value.iter() {
+                    self.check(item, &Kind::Star, scope)?;
+                }
+                self.unify(span, expected, &Kind::Star)
+            }
+            CstType::Record(between) => {
+                for item in between.value.iter() {
+                    self.check(&item.expression, &Kind::Star, scope)?;
+                }
+                self.unify(span, expected, &Kind::Star)
+            }
+            CstType::Parens(between) => {
+                self.check(&between.value, expected, scope)
+            }
+            CstType::Application {
+                start,
+                second,
+                remain,
+            } => {
+                let (name, mut kind) = match &**start {
+                    CstType::LocalVariable(token) => {
+                        let (name, kind) = self.lookup(token, scope)?;
+                        (Some(name), kind)
+                    }
+                    other => {
+                        let kind = self.fresh();
+                        self.check(other, &kind, scope)?;
+                        (None, kind)
+                    }
+                };
+                let arguments: Vec<&CstType> =
+                    std::iter::once(&**second).chain(remain.iter()).collect();
+                for (index, argument) in arguments.iter().enumerate() {
+                    let (argument_kind, output) = match self.resolve(&kind) {
+                        Kind::Arrow(argument_kind, output) => {
+                            (*argument_kind, *output)
+                        }
+                        Kind::Variable(_) => {
+                            let argument_kind = self.fresh();
+                            let output = self.fresh();
+                            self.unify(
+                                start.span(),
+                                &kind,
+                                &Kind::arrow(
+                                    argument_kind.clone(),
+                                    output.clone(),
+                                ),
+                            )?;
+                            (argument_kind, output)
+                        }
+                        Kind::Star => {
+                            return Err(Box::new(match name {
+                                Some(name) => KindError::OverApplied {
+                                    span,
+                                    name,
+                                    expected: index,
+                                    found: arguments.len(),
+                                },
+                                None => KindError::KindMismatch {
+                                    span: start.span(),
+                                    expected: Kind::arrow(
+                                        Kind::Star,
+                                        Kind::Star,
+                                    ),
+                                    found: Kind::Star,
+                                },
+                            }))
+                        }
+                    };
+                    self.check(argument, &argument_kind, scope)?;
+                    kind = output;
+                }
+                self.check_result(
+                    span,
+                    name.as_ref(),
+                    &kind,
+                    expected,
+                    arguments.len(),
+                )
+            }
+            CstType::Arrow { first, remain } => {
+                self.check(first, &Kind::Star, scope)?;
+                for item in remain {
+                    self.check(&item.item, &Kind::Star, scope)?;
+                }
+                self.unify(span, expected, &Kind::Star)
+            }
+            CstType::Scheme {
+                first_variable,
+                remain_variables,
+                expression,
+                ..
+            } => {
+                let mut inner = scope.clone();
+                for token in
+                    std::iter::once(first_variable).chain(remain_variables)
+                {
+                    let kind = self.fresh();
+                    inner.insert(self.token_name(token), kind);
+                }
+                self.check(expression, expected, &inner)
+            }
+        }
+    }
+
+    /// Infers the kinds of a group of mutually recursive declarations.
+    fn group(&mut self, declarations: &[&Declaration]) -> Result<()> {
+        let mut scopes = vec![];
+        for declaration in declarations {
+            let mut scope = HashMap::new();
+            let mut parameters = vec![];
+            for token in declaration.variables {
+                let kind = self.fresh();
+                parameters.push(kind.clone());
+                scope.insert(self.token_name(token), kind);
+            }
+            let kind = parameters
+                .into_iter()
+                .rev()
+                .fold(Kind::Star, |acc, p| Kind::arrow(p, acc));
+            self.kinds.insert(declaration.name.clone(), kind);
+            scopes.push(scope);
+        }
+        for (declaration, scope) in declarations.iter().zip(&scopes) {
+            for type_ in &declaration.types {
+                self.check(type_, &Kind::Star, scope)?;
+            }
+        }
+        for declaration in declarations {
+            let kind = self.zonk(&self.kinds[&declaration.name]);
+            self.kinds.insert(declaration.name.clone(), kind);
+        }
+        Ok(())
+    }
+}
+
+/// Calls the function on every name used by the type.
+fn type_names(type_: &CstType, f: &mut impl FnMut(&Token<CstIdentifier>)) {
+    match type_ {
+        CstType::LocalVariable(token) => f(token),
+        CstType::ImportedVariable(_) => (),
+        CstType::Tuple(between) => {
+            between.value.iter().for_each(|t| type_names(t, f))
+        }
+        CstType::Record(between) => between
+            .value
+            .iter()
+            .for_each(|item| type_names(&item.expression, f)),
+        CstType::Parens(between) => type_names(&between.value, f),
+        CstType::Application {
+            start,
+            second,
+            remain,
+        } => {
+            type_names(start, f);
+            type_names(second, f);
+            remain.iter().for_each(|t| type_names(t, f))
+        }
+        CstType::Arrow { first, remain } => {
+            type_names(first, f);
+            remain.iter().for_each(|item| type_names(&item.item, f))
+        }
+        CstType::Scheme { expression, .. } => type_names(expression, f),
+    }
+}
+
+/// Infers the kinds of the type declarations of the top and checks
+/// every type of the top with them.
+/// Returns the kinds of the declared types.
+pub fn check_kinds(
+    top: &Top,
+    session: &Sast,
+    store: &Store,
+) -> Result<HashMap<Identifier, Kind>> {
+    inference_kinds(top, session, None, store)
+}
+
+/// Checks the kinds of a module of a program, the scope tells the
+/// names that the module imports from the session.
+pub fn check_module_kinds(
+    top: &Top,
+    session: &Sast,
+    imports: &ImportScope,
+    store: &Store,
+) -> Result<HashMap<Identifier, Kind>> {
+    inference_kinds(top, session, Some(imports), store)
+}
+
+fn inference_kinds(
+    top: &Top,
+    session: &Sast,
+    imports: Option<&ImportScope>,
+    store: &Store,
+) -> Result<HashMap<Identifier, Kind>> {
+    let mut inference = KindInference {
+        store,
+        session,
+        imports,
+        kinds: HashMap::new(),
+        substitution: vec![],
+    };
+    let items: Vec<&TopItem> = match &top.items {
+        Some(items) => items.iter().collect(),
+        None => vec![],
+    };
+    let mut declarations = vec![];
+    let mut signatures = vec![];
+    for item in items {
+        match item {
+            TopItem::Data(data) => declarations.push(Declaration {
+                name: inference.token_name(&data.left_part.name),
+                variables: &data.left_part.variables,
+                types: data
+                    .constructors
+                    .iter()
+                    .flat_map(|c| c.constructors.iter())
+                    .filter_map(|c| c.type_.as_ref())
+                    .collect(),
+            }),
+            TopItem::NewType(new_type) => declarations.push(Declaration {
+                name: inference.token_name(&new_type.left_part.name),
+                variables: &new_type.left_part.variables,
+                types: new_type.constructor.type_.iter().collect(),
+            }),
+            TopItem::Alias(alias) => declarations.push(Declaration {
+                name: inference.token_name(&alias.left_part.name),
+                variables: &alias.left_part.variables,
+                types: vec![&alias.type_],
+            }),
+            TopItem::Declaration(declaration) => {
+                signatures.push(&declaration.type_)
+            }
+            TopItem::Definition(_) => (),
+        }
+    }
+    let indexes: HashMap<&Identifier, usize> = declarations
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (&d.name, i))
+        .collect();
+    let edges: Vec<Vec<usize>> = declarations
+        .iter()
+        .map(|declaration| {
+            let mut out = vec![];
+            for type_ in &declaration.types {
+                type_names(type_, &mut |token| {
+                    let name = inference.token_name(token);
+                    if let Some(i) = indexes.get(&name) {
+                        out.push(*i)
+                    }
+                })
+            }
+            out
+        })
+        .collect();
+    for component in strongly_connected_components(&edges) {
+        let group: Vec<&Declaration> =
+            component.iter().map(|i| &declarations[*i]).collect();
+        inference.group(&group)?;
+    }
+    for signature in signatures {
+        inference.check(signature, &Kind::Star, &HashMap::new())?;
+    }
+    Ok(inference.kinds)
+}
+
+#[cfg(test)]
+mod kinds_test {
+    use super::{check_kinds, Kind, KindError};
+    use crate::top::Sast;
+    use octizys_core::common::Identifier;
+    use octizys_parser::parser::parse_string;
+    use octizys_text_store::store::Store;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    fn kinds(source: &str) -> Result<HashMap<Identifier, Kind>, KindError> {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let top = parse_string(source, None, store.clone()).unwrap();
+        let result = check_kinds(&top, &Sast::prelude(), &store.borrow());
+        result.map_err(|e| *e)
+    }
+
+    fn kind_of(source: &str, name: &str) -> Kind {
+        let kinds = kinds(source).unwrap();
+        kinds[&Identifier::make(String::from(name)).unwrap()].clone()
+    }
+
+    #[test]
+    fn higher_kinded_parameter() {
+        let kind = kind_of("data Wrap f a = Wrap (f a);", "Wrap");
+        let expected = Kind::arrow(
+            Kind::arrow(Kind::Star, Kind::Star),
+            Kind::arrow(Kind::Star, Kind::Star),
+        );
+        assert_eq!(kind, expected);
+    }
+
+    #[test]
+    fn mutually_recursive_group() {
+        let source = "data Tree f = Node (f (Forest f));\
+                      data Forest f = Forest (f (Tree f));";
+        let expected =
+            Kind::arrow(Kind::arrow(Kind::Star, Kind::Star), Kind::Star);
+        assert_eq!(kind_of(source, "Tree"), expected);
+        assert_eq!(kind_of(source, "Forest"), expected);
+    }
+
+    #[test]
+    fn unused_parameter_defaults_to_star() {
+        assert_eq!(kind_of("data Proxy a = Proxy;", "Proxy"), Kind::simple(1));
+    }
+
+    #[test]
+    fn over_application() {
+        let error =
+            kinds("data Maybe a = Nothing | Just a; alias T = Maybe U64 U64;")
+                .unwrap_err();
+        assert!(matches!(
+            error,
+            KindError::OverApplied {
+                expected: 1,
+                found: 2,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn under_application() {
+        let error = kinds("data Maybe a = Nothing | Just a; data T = T Maybe;")
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            KindError::UnderApplied {
+                expected: 1,
+                found: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn unbound_variable() {
+        let error = kinds("data T a = T b;").unwrap_err();
+        assert!(matches!(error, KindError::UnboundVariable { .. }));
+    }
+}
@@ -1,6 +1,10 @@
 pub mod _type;
-pub mod common;
+pub mod dump;
+pub mod exhaustiveness;
 pub mod expression;
+pub mod from_cst;
 pub mod inference;
-pub mod inference2;
+pub mod kinds;
+pub mod lowering;
+pub mod modules;
 pub mod top;
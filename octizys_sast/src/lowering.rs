@@ -0,0 +1,1234 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::span::{Location, Span};
+use octizys_core::common::{Identifier, Label, Record, Variable};
+use octizys_core::core::{Definition, Module, TypeDefinition};
+use octizys_core::primitives::{
+    bool_type, Primitive, BOOL_NAME, FALSE_INDEX, FALSE_NAME, TRUE_INDEX,
+    TRUE_NAME,
+};
+use octizys_core::terms::{CaseAlternative, Term};
+use octizys_core::types::Type as CoreType;
+use octizys_cst::literals::{FormatAlign, FormatKind, FormatSpec};
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+
+use crate::_type::{BasicType, Type, TypeScheme};
+use crate::expression::{
+    BasicExpression, CaseCase, Expression, ExpressionKind, InterpolationPart,
+    LetBinding, Operator, Pattern, PatternKind,
+};
+use crate::top::{DataKind, DataType, Sast};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoweringError {
+    /// The core language doesn't have a representation of the type.
+    UnsupportedType {
+        span: Span,
+        type_: Type,
+    },
+    UnsupportedPattern {
+        span: Span,
+    },
+    /// The inference must give a type to every expression.
+    MissingType {
+        span: Span,
+    },
+    /// The exhaustiveness check must reject the patterns that
+    /// don't cover every value.
+    UncoveredValue {
+        span: Span,
+    },
+}
+
+impl LoweringError {
+    pub fn span(&self) -> Span {
+        match self {
+            LoweringError::UnsupportedType { span, .. }
+            | LoweringError::UnsupportedPattern { span }
+            | LoweringError::MissingType { span }
+            | LoweringError::UncoveredValue { span } => *span,
+        }
+    }
+}
+
+impl ReportFormat for LoweringError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            LoweringError::UnsupportedType { .. } => {
+                NonLineBreakStr::new("UnsupportedType")
+            }
+            LoweringError::UnsupportedPattern { .. } => {
+                NonLineBreakStr::new("UnsupportedPattern")
+            }
+            LoweringError::MissingType { .. } => {
+                NonLineBreakStr::new("Internal:MissingType")
+            }
+            LoweringError::UncoveredValue { .. } => {
+                NonLineBreakStr::new("Internal:UncoveredValue")
+            }
+        }
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            LoweringError::UnsupportedType { .. } => {
+                NonLineBreakStr::new("We can't run code with this type yet.")
+            }
+            LoweringError::UnsupportedPattern { .. } => {
+                NonLineBreakStr::new("We can't run this pattern yet.")
+            }
+            LoweringError::MissingType { .. }
+            | LoweringError::UncoveredValue { .. } => NonLineBreakStr::new(
+                "This is a bug in octizys, please report it!",
+            ),
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self {
+            LoweringError::UnsupportedType { type_, .. } => format!(
+                "The interpreter doesn't support values of type {}.",
+                type_
+            ),
+            LoweringError::UnsupportedPattern { .. } => String::from(
+                "Only the constants of type U64, I64 and Char can be used in patterns.",
+            ),
+            LoweringError::MissingType { .. } => {
+                String::from("The expression doesn't have a type.")
+            }
+            LoweringError::UncoveredValue { .. } => String::from(
+                "The patterns don't cover every value, we don't know what to do with the rest.",
+            ),
+        };
+        Some(external_text(&description))
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span()))
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<LoweringError>>;
+
+fn identifier(name: &str) -> Identifier {
+    // The names of the prelude are valid identifiers.
+    Identifier::make(String::from(name)).unwrap()
+}
+
+fn label(name: &Identifier) -> Label {
+    // Every identifier is a valid label.
+    Label::make(String::from(name.as_str())).unwrap()
+}
+
+fn record<T>(fields: Vec<(Label, T)>) -> Record<T> {
+    // The fields were sorted and checked by the translation.
+    Record::make(fields).unwrap()
+}
+
+/// A value being matched, it is the local at `level` counting from
+/// the outermost.
+#[derive(Debug, Clone)]
+struct Occurrence {
+    level: usize,
+    type_: Type,
+    core_type: CoreType,
+}
+
+/// What a row does when its patterns match.
+#[derive(Debug, Clone, Copy)]
+enum Action<'e> {
+    Arm(&'e Expression),
+    /// The remaining bindings and the output of a let.
+    Let(&'e [LetBinding], &'e Expression),
+}
+
+/// A row of the matrix of patterns, `None` matches everything.
+#[derive(Debug, Clone)]
+struct Row<'e> {
+    patterns: Vec<Option<&'e Pattern>>,
+    /// The variables of the patterns already matched.
+    bindings: Vec<(Identifier, Occurrence)>,
+    action: Action<'e>,
+}
+
+/// Translates a type checked [`Sast`] to the core language.
+/// The local variables become De Bruijn indexes.
+struct Lowering<'a> {
+    sast: &'a Sast,
+    /// The variables of the scheme of the definition being lowered.
+    type_variables: Vec<Identifier>,
+    /// The local variables in scope, `None` is a value
+    /// the user can't refer to.
+    locals: Vec<Option<Identifier>>,
+}
+
+impl<'a> Lowering<'a> {
+    fn type_(&self, span: Span, type_: &Type) -> Result<CoreType> {
+        match type_ {
+            Type::BasicType(BasicType::U64) => Ok(CoreType::Uint),
+            Type::BasicType(BasicType::I64) => Ok(CoreType::Int),
+            Type::BasicType(BasicType::String) => Ok(CoreType::String),
+            Type::BasicType(BasicType::Char) => Ok(CoreType::Char),
+            Type::BasicType(_) => {
+                Err(Box::new(LoweringError::UnsupportedType {
+                    span,
+                    type_: type_.clone(),
+                }))
+            }
+            Type::Variable(name) => {
+                match self.type_variables.iter().rev().position(|v| v == name) {
+                    Some(index) => {
+                        Ok(CoreType::Variable(Variable::Free(index as u32)))
+                    }
+                    None => {
+                        Ok(CoreType::Variable(Variable::Named(name.clone())))
+                    }
+                }
+            }
+            // A variable the inference left unsolved isn't constrained,
+            // any type works, we choose the simplest.
+            Type::InferenceVariable(_) => Ok(CoreType::Unit),
+            Type::Function(argument, output) => Ok(CoreType::Arrow(
+                Box::new(self.type_(span, argument)?),
+                Box::new(self.type_(span, output)?),
+            )),
+            Type::Tuple(items) => {
+                let mut out = vec![];
+                for item in items {
+                    out.push(self.type_(span, item)?);
+                }
+                Ok(CoreType::Tuple(out))
+            }
+            Type::Record(fields) => {
+                let mut out = vec![];
+                for (name, item) in fields {
+                    out.push((label(name), self.type_(span, item)?));
+                }
+                Ok(CoreType::Record(record(out)))
+            }
+            Type::Named(name, arguments) => {
+                let mut out = CoreType::Variable(Variable::Named(name.clone()));
+                for argument in arguments {
+                    out = CoreType::Application(
+                        Box::new(out),
+                        Box::new(self.type_(span, argument)?),
+                    );
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    /// A data type is a [`CoreType::Sum`] with the argument of every
+    /// constructor in a tuple.
+    fn data(&mut self, data: &DataType) -> Result<TypeDefinition> {
+        self.type_variables = data.variables.clone();
+        let mut items = vec![];
+        for constructor in &data.constructors {
+            let arguments = match &constructor.argument {
+                Some(argument) => vec![self.type_(constructor.span, argument)?],
+                None => vec![],
+            };
+            items.push(CoreType::Tuple(arguments));
+        }
+        let mut sum = CoreType::Sum(data.name.clone(), items);
+        if data.kind == DataKind::NewType {
+            sum = CoreType::NewType(data.name.clone(), Box::new(sum));
+        }
+        let type_ = if data.variables.is_empty() {
+            sum
+        } else {
+            CoreType::Forall(data.variables.len() as u64, Box::new(sum))
+        };
+        Ok(TypeDefinition {
+            name: data.name.clone(),
+            type_,
+        })
+    }
+
+    fn scheme(&mut self, span: Span, scheme: &TypeScheme) -> Result<CoreType> {
+        self.type_variables = scheme.variables.clone();
+        let type_ = self.type_(span, &scheme.type_)?;
+        if scheme.variables.is_empty() {
+            Ok(type_)
+        } else {
+            Ok(CoreType::Forall(
+                scheme.variables.len() as u64,
+                Box::new(type_),
+            ))
+        }
+    }
+
+    fn expression_type(&self, expression: &Expression) -> Result<CoreType> {
+        match &expression.type_ {
+            Some(type_) => self.type_(expression.span, type_),
+            None => Err(Box::new(LoweringError::MissingType {
+                span: expression.span,
+            })),
+        }
+    }
+
+    fn local_index(&self, name: &Identifier) -> u64 {
+        // The translation ensures the variable is in scope.
+        self.locals
+            .iter()
+            .rev()
+            .position(|local| local.as_ref() == Some(name))
+            .unwrap() as u64
+    }
+
+    /// Lowers the expression with more locals in scope.
+    fn with_locals(
+        &mut self,
+        locals: Vec<Option<Identifier>>,
+        expression: &Expression,
+    ) -> Result<Term> {
+        let previous = self.locals.len();
+        self.locals.extend(locals);
+        let result = self.expression(expression);
+        self.locals.truncate(previous);
+        result
+    }
+
+    fn basic(&self, span: Span, basic: &BasicExpression) -> Result<Term> {
+        match basic {
+            BasicExpression::U64(n) => Ok(Term::Uint(*n)),
+            BasicExpression::I64(n) => Ok(Term::Int(*n)),
+            BasicExpression::String(s) => Ok(Term::String(s.clone())),
+            BasicExpression::Char(c) => Ok(Term::Char(*c)),
+            _ => Err(Box::new(LoweringError::UnsupportedType {
+                span,
+                type_: Type::BasicType(basic.type_()),
+            })),
+        }
+    }
+
+    /// The string that shows the value of a interpolation hole with the
+    /// format specifier. Like in Rust the precision truncates the
+    /// strings and the numbers are aligned to the right by default.
+    /// The language doesn't have classes yet, so the holes call the
+    /// primitives of their type instead of the methods of a formatting
+    /// class and the types of the users can't be shown.
+    fn shown(
+        &mut self,
+        hole: &Expression,
+        format: &FormatSpec,
+    ) -> Result<Term> {
+        let type_ = self.expression_type(hole)?;
+        let call = |primitive, arguments| {
+            Term::Primitive(primitive, arguments, CoreType::String)
+        };
+        let radix = match format.kind {
+            Some(FormatKind::LowerHex | FormatKind::UpperHex) => Some(16),
+            Some(FormatKind::Binary) => Some(2),
+            Some(FormatKind::Octal) => Some(8),
+            Some(FormatKind::Debug) | None => None,
+        };
+        let value = self.expression(hole)?;
+        let shown = match (&type_, radix) {
+            (CoreType::String, None) => value,
+            (CoreType::Uint, None) => call(Primitive::ShowUint, vec![value]),
+            (CoreType::Int, None) => call(Primitive::ShowInt, vec![value]),
+            (CoreType::Char, None)
+                if format.kind == Some(FormatKind::Debug) =>
+            {
+                call(Primitive::DebugChar, vec![value])
+            }
+            (CoreType::Char, None) => call(Primitive::ShowChar, vec![value]),
+            (CoreType::Uint, Some(radix)) => {
+                call(Primitive::ShowUintRadix, vec![Term::Uint(radix), value])
+            }
+            (CoreType::Int, Some(radix)) => {
+                call(Primitive::ShowIntRadix, vec![Term::Uint(radix), value])
+            }
+            _ => {
+                return Err(Box::new(LoweringError::UnsupportedType {
+                    span: hole.span,
+                    // The type was lowered above.
+                    type_: hole.type_.clone().unwrap(),
+                }));
+            }
+        };
+        let is_number = matches!(type_, CoreType::Uint | CoreType::Int);
+        let shown = match (format.kind, format.precision) {
+            (Some(FormatKind::UpperHex), _) => {
+                call(Primitive::UppercaseString, vec![shown])
+            }
+            (Some(FormatKind::Debug), _) if type_ == CoreType::String => {
+                call(Primitive::DebugString, vec![shown])
+            }
+            (None, Some(precision)) if !is_number => call(
+                Primitive::TruncateString,
+                vec![Term::Uint(precision as u64), shown],
+            ),
+            _ => shown,
+        };
+        let Some(width) = format.width else {
+            return Ok(shown);
+        };
+        let width = Term::Uint(width as u64);
+        // The zeros go after the sign and replace the alignment.
+        if format.zero && is_number {
+            return Ok(call(Primitive::PadZeros, vec![width, shown]));
+        }
+        let align = match format.align {
+            Some(FormatAlign::Left) => Primitive::AlignLeft,
+            Some(FormatAlign::Center) => Primitive::AlignCenter,
+            Some(FormatAlign::Right) => Primitive::AlignRight,
+            None if is_number => Primitive::AlignRight,
+            None => Primitive::AlignLeft,
+        };
+        let fill = Term::String(format.fill.unwrap_or(' ').to_string());
+        Ok(call(align, vec![fill, width, shown]))
+    }
+
+    fn bool_constructor(index: u64) -> Term {
+        let name = if index == TRUE_INDEX {
+            TRUE_NAME
+        } else {
+            FALSE_NAME
+        };
+        let type_ = CoreType::Variable(Variable::Named(identifier(BOOL_NAME)));
+        Term::Constructor(identifier(name), index, vec![], type_)
+    }
+
+    /// `let name = value in body` where the body was already lowered
+    /// with the name in scope.
+    fn bind(
+        value: Term,
+        value_type: CoreType,
+        body: Term,
+        type_: CoreType,
+    ) -> Term {
+        let function_type =
+            CoreType::Arrow(Box::new(value_type), Box::new(type_.clone()));
+        Term::Application(
+            Box::new(Term::Function(Arc::new(body), function_type)),
+            Box::new(value),
+            type_,
+        )
+    }
+
+    /// Lowers the body with the values in scope, every value can use
+    /// the previous ones.
+    fn bind_all(
+        values: Vec<(Term, CoreType)>,
+        body: Term,
+        type_: &CoreType,
+    ) -> Term {
+        values
+            .into_iter()
+            .rev()
+            .fold(body, |body, (value, value_type)| {
+                Self::bind(value, value_type, body, type_.clone())
+            })
+    }
+
+    fn occurrence_variable(&self, occurrence: &Occurrence) -> Term {
+        let index = self.locals.len() - 1 - occurrence.level;
+        Term::Variable(index as u64, occurrence.core_type.clone())
+    }
+
+    /// Adds a local without name for a value of the matched type.
+    fn push_occurrence(
+        &mut self,
+        span: Span,
+        type_: Type,
+    ) -> Result<Occurrence> {
+        let core_type = self.type_(span, &type_)?;
+        self.locals.push(None);
+        Ok(Occurrence {
+            level: self.locals.len() - 1,
+            type_,
+            core_type,
+        })
+    }
+
+    /// Moves the variables and the binds of the row to its bindings
+    /// until every pattern is `None` or can fail.
+    fn simplify(columns: &[Occurrence], row: &mut Row) {
+        for (column, pattern) in columns.iter().zip(row.patterns.iter_mut()) {
+            while let Some(p) = pattern {
+                match &p.kind {
+                    PatternKind::Variable(name) => {
+                        row.bindings.push((name.clone(), column.clone()));
+                        *pattern = None;
+                    }
+                    PatternKind::Bind {
+                        name,
+                        pattern: inner,
+                    } => {
+                        row.bindings.push((name.clone(), column.clone()));
+                        *pattern = Some(inner);
+                    }
+                    PatternKind::Discard => *pattern = None,
+                    _ => break,
+                }
+            }
+        }
+    }
+
+    /// The value of a row whose patterns matched, the variables of the
+    /// patterns are in scope.
+    fn action(&mut self, row: &Row, type_: &CoreType) -> Result<Term> {
+        let previous = self.locals.len();
+        let mut values = vec![];
+        for (name, occurrence) in &row.bindings {
+            values.push((
+                self.occurrence_variable(occurrence),
+                occurrence.core_type.clone(),
+            ));
+            self.locals.push(Some(name.clone()));
+        }
+        let body = match row.action {
+            Action::Arm(expression) => self.expression(expression),
+            Action::Let(bindings, output) => self.let_(bindings, output),
+        };
+        self.locals.truncate(previous);
+        Ok(Self::bind_all(values, body?, type_))
+    }
+
+    /// Compiles the rows to a decision tree, every column is the
+    /// value of a local. The first row that can't fail is chosen,
+    /// otherwise we inspect the first column where the first row can
+    /// fail.
+    fn compile<'e>(
+        &mut self,
+        span: Span,
+        columns: Vec<Occurrence>,
+        mut rows: Vec<Row<'e>>,
+        type_: &CoreType,
+    ) -> Result<Term> {
+        for row in rows.iter_mut() {
+            Self::simplify(&columns, row);
+        }
+        let Some(first) = rows.first() else {
+            return Err(Box::new(LoweringError::UncoveredValue { span }));
+        };
+        let Some(column) = first.patterns.iter().position(|p| p.is_some())
+        else {
+            return self.action(first, type_);
+        };
+        // The first row can fail in the column.
+        let pattern = first.patterns[column].unwrap();
+        match &pattern.kind {
+            PatternKind::Tuple(_) | PatternKind::Record(_) => {
+                self.split(span, columns, rows, column, type_)
+            }
+            PatternKind::Constructor { .. } => {
+                self.switch(span, columns, rows, column, type_)
+            }
+            PatternKind::Constant(_) => {
+                self.compare(span, columns, rows, column, type_)
+            }
+            PatternKind::Variable(_)
+            | PatternKind::Bind { .. }
+            | PatternKind::Discard => {
+                unreachable!("The rows were simplified")
+            }
+        }
+    }
+
+    /// Replaces a column of tuples or records by a column for each
+    /// item or field.
+    fn split<'e>(
+        &mut self,
+        span: Span,
+        mut columns: Vec<Occurrence>,
+        rows: Vec<Row<'e>>,
+        column: usize,
+        type_: &CoreType,
+    ) -> Result<Term> {
+        let occurrence = columns.remove(column);
+        let parts: Vec<(Option<Label>, Type)> = match &occurrence.type_ {
+            Type::Tuple(items) => {
+                items.iter().map(|t| (None, t.clone())).collect()
+            }
+            Type::Record(fields) => fields
+                .iter()
+                .map(|(name, t)| (Some(label(name)), t.clone()))
+                .collect(),
+            _ => return Err(Box::new(LoweringError::MissingType { span })),
+        };
+        let previous = self.locals.len();
+        let mut values = vec![];
+        let mut new_columns = vec![];
+        for (index, (field, part_type)) in parts.iter().enumerate() {
+            let variable = Box::new(self.occurrence_variable(&occurrence));
+            let new_column = self.push_occurrence(span, part_type.clone())?;
+            let core_type = new_column.core_type.clone();
+            let value = match field {
+                None => Term::TupleProjection(
+                    variable,
+                    index as u64,
+                    core_type.clone(),
+                ),
+                Some(field) => Term::RecordProjection(
+                    variable,
+                    field.clone(),
+                    core_type.clone(),
+                ),
+            };
+            values.push((value, core_type));
+            new_columns.push(new_column);
+        }
+        let new_rows = rows
+            .into_iter()
+            .map(|mut row| {
+                let parts: Vec<Option<&'e Pattern>> =
+                    match row.patterns.remove(column).map(|p| &p.kind) {
+                        Some(PatternKind::Tuple(items)) => {
+                            items.iter().map(Some).collect()
+                        }
+                        Some(PatternKind::Record(fields)) => parts
+                            .iter()
+                            .map(|(field, _)| {
+                                fields
+                                    .iter()
+                                    .find(|(name, _)| {
+                                        Some(label(name)) == *field
+                                    })
+                                    .map(|(_, p)| p)
+                            })
+                            .collect(),
+                        _ => vec![None; parts.len()],
+                    };
+                row.patterns.splice(0..0, parts);
+                row
+            })
+            .collect();
+        new_columns.extend(columns);
+        let body = self.compile(span, new_columns, new_rows, type_);
+        self.locals.truncate(previous);
+        Ok(Self::bind_all(values, body?, type_))
+    }
+
+    /// A [`Term::Case`] over the constructors of the data type of the
+    /// column.
+    fn switch<'e>(
+        &mut self,
+        span: Span,
+        mut columns: Vec<Occurrence>,
+        rows: Vec<Row<'e>>,
+        column: usize,
+        type_: &CoreType,
+    ) -> Result<Term> {
+        let occurrence = columns.remove(column);
+        let (data, arguments) = match &occurrence.type_ {
+            Type::Named(name, arguments) => {
+                match self.sast.find_data(name.as_str()) {
+                    Some(data) => (data, arguments),
+                    None => {
+                        return Err(Box::new(LoweringError::MissingType {
+                            span,
+                        }))
+                    }
+                }
+            }
+            _ => return Err(Box::new(LoweringError::MissingType { span })),
+        };
+        let substitution: HashMap<Identifier, Type> = data
+            .variables
+            .iter()
+            .cloned()
+            .zip(arguments.iter().cloned())
+            .collect();
+        let scrutinee = self.occurrence_variable(&occurrence);
+        let mut alternatives = vec![];
+        for (index, constructor) in data.constructors.iter().enumerate() {
+            let index = index as u64;
+            let argument = constructor
+                .argument
+                .as_ref()
+                .map(|t| t.substitute(&substitution));
+            let mut new_rows = vec![];
+            for row in &rows {
+                let cell = match row.patterns[column].map(|p| &p.kind) {
+                    None => None,
+                    Some(PatternKind::Constructor {
+                        constructor,
+                        argument,
+                    }) if constructor.index == index => argument.as_deref(),
+                    Some(_) => continue,
+                };
+                let mut row = row.clone();
+                row.patterns.remove(column);
+                if argument.is_some() {
+                    row.patterns.insert(0, cell);
+                }
+                new_rows.push(row);
+            }
+            let previous = self.locals.len();
+            let mut new_columns = vec![];
+            if let Some(argument) = argument {
+                new_columns.push(self.push_occurrence(span, argument)?);
+            }
+            let arguments = new_columns.len() as u64;
+            new_columns.extend(columns.iter().cloned());
+            let value = self.compile(span, new_columns, new_rows, type_);
+            self.locals.truncate(previous);
+            alternatives.push(CaseAlternative {
+                constructor: index,
+                arguments,
+                value: value?,
+            });
+        }
+        Ok(Term::Case(Box::new(scrutinee), alternatives, type_.clone()))
+    }
+
+    /// Compares the column with every constant of the column, the
+    /// rows without a constant are the default.
+    fn compare<'e>(
+        &mut self,
+        span: Span,
+        mut columns: Vec<Occurrence>,
+        rows: Vec<Row<'e>>,
+        column: usize,
+        type_: &CoreType,
+    ) -> Result<Term> {
+        let mut constants: Vec<(Span, &BasicExpression)> = vec![];
+        for row in &rows {
+            match row.patterns[column].map(|p| (p.span, &p.kind)) {
+                Some((_, PatternKind::Constant(constant)))
+                    if constants.iter().any(|(_, c)| *c == constant) => {}
+                Some((span, PatternKind::Constant(constant))) => {
+                    constants.push((span, constant))
+                }
+                Some((span, _)) => {
+                    return Err(Box::new(LoweringError::UnsupportedPattern {
+                        span,
+                    }))
+                }
+                None => (),
+            }
+        }
+        let occurrence = columns.remove(column);
+        let specialize = |constant: Option<&BasicExpression>| -> Vec<Row<'e>> {
+            let mut out = vec![];
+            for row in &rows {
+                let keep = match row.patterns[column].map(|p| &p.kind) {
+                    None => true,
+                    Some(PatternKind::Constant(c)) => Some(c) == constant,
+                    Some(_) => false,
+                };
+                if keep {
+                    let mut row = row.clone();
+                    row.patterns.remove(column);
+                    out.push(row);
+                }
+            }
+            out
+        };
+        let mut out =
+            self.compile(span, columns.clone(), specialize(None), type_)?;
+        for (constant_span, constant) in constants.into_iter().rev() {
+            let equal = match constant {
+                BasicExpression::U64(_) => Primitive::EqualUint,
+                BasicExpression::I64(_) => Primitive::EqualInt,
+                BasicExpression::Char(_) => Primitive::EqualChar,
+                _ => {
+                    return Err(Box::new(LoweringError::UnsupportedPattern {
+                        span: constant_span,
+                    }))
+                }
+            };
+            let on_true = self.compile(
+                span,
+                columns.clone(),
+                specialize(Some(constant)),
+                type_,
+            )?;
+            let condition = Term::Primitive(
+                equal,
+                vec![
+                    self.occurrence_variable(&occurrence),
+                    self.basic(constant_span, constant)?,
+                ],
+                bool_type(),
+            );
+            out = Term::Case(
+                Box::new(condition),
+                vec![
+                    CaseAlternative {
+                        constructor: FALSE_INDEX,
+                        arguments: 0,
+                        value: out,
+                    },
+                    CaseAlternative {
+                        constructor: TRUE_INDEX,
+                        arguments: 0,
+                        value: on_true,
+                    },
+                ],
+                type_.clone(),
+            );
+        }
+        Ok(out)
+    }
+
+    /// Matches the value of the local 0 with the patterns of the cases.
+    fn case_alternatives(
+        &mut self,
+        span: Span,
+        scrutinee: Occurrence,
+        cases: &[CaseCase],
+        type_: &CoreType,
+    ) -> Result<Term> {
+        let rows = cases
+            .iter()
+            .map(|case| Row {
+                patterns: vec![Some(&case.pattern)],
+                bindings: vec![],
+                action: Action::Arm(&case.expression),
+            })
+            .collect();
+        self.compile(span, vec![scrutinee], rows, type_)
+    }
+
+    fn let_<'e>(
+        &mut self,
+        bindings: &'e [LetBinding],
+        output: &'e Expression,
+    ) -> Result<Term> {
+        let Some((binding, remain)) = bindings.split_first() else {
+            return self.expression(output);
+        };
+        let output_type = self.expression_type(output)?;
+        if let PatternKind::Variable(name) = &binding.pattern.kind {
+            let (value, value_type) = if binding.value.is_syntactic_value() {
+                self.generalized(&binding.value)?
+            } else {
+                (
+                    self.expression(&binding.value)?,
+                    self.expression_type(&binding.value)?,
+                )
+            };
+            self.locals.push(Some(name.clone()));
+            let body = self.let_(remain, output);
+            self.locals.pop();
+            return Ok(Self::bind(value, value_type, body?, output_type));
+        }
+        let value = self.expression(&binding.value)?;
+        let value_type = match &binding.value.type_ {
+            Some(type_) => type_.clone(),
+            None => {
+                return Err(Box::new(LoweringError::MissingType {
+                    span: binding.value.span,
+                }))
+            }
+        };
+        let occurrence =
+            self.push_occurrence(binding.value.span, value_type)?;
+        let core_type = occurrence.core_type.clone();
+        let row = Row {
+            patterns: vec![Some(&binding.pattern)],
+            bindings: vec![],
+            action: Action::Let(remain, output),
+        };
+        let body = self.compile(
+            binding.pattern.span,
+            vec![occurrence],
+            vec![row],
+            &output_type,
+        );
+        self.locals.pop();
+        Ok(Self::bind(value, core_type, body?, output_type))
+    }
+
+    /// The inference generalizes the variables of a let bound value
+    /// that aren't in scope, they become the parameters of a
+    /// [`Term::TypeFunction`].
+    fn generalized(&mut self, value: &Expression) -> Result<(Term, CoreType)> {
+        let mut variables = vec![];
+        if let Some(type_) = &value.type_ {
+            type_.rigid_variables(&mut variables);
+        }
+        variables.retain(|v| !self.type_variables.contains(v));
+        let binders = variables.len() as u64;
+        let previous = self.type_variables.len();
+        self.type_variables.extend(variables);
+        let result = self
+            .expression(value)
+            .and_then(|term| Ok((term, self.expression_type(value)?)));
+        self.type_variables.truncate(previous);
+        let (term, type_) = result?;
+        if binders == 0 {
+            return Ok((term, type_));
+        }
+        let type_ = CoreType::Forall(binders, Box::new(type_));
+        Ok((Term::TypeFunction(Box::new(term), type_.clone()), type_))
+    }
+
+    fn expression(&mut self, expression: &Expression) -> Result<Term> {
+        let span = expression.span;
+        let type_ = self.expression_type(expression)?;
+        match &expression.kind {
+            ExpressionKind::BasicTerm(basic) => self.basic(span, basic),
+            ExpressionKind::LocalVariable(name) => {
+                Ok(Term::Variable(self.local_index(name), type_))
+            }
+            ExpressionKind::GlobalVariable(name) => {
+                Ok(Term::GlobalVariable(name.clone(), type_))
+            }
+            ExpressionKind::Constructor(constructor) => match &type_ {
+                // A constructor with argument is a function.
+                CoreType::Arrow(argument, output) => Ok(Term::Function(
+                    Arc::new(Term::Constructor(
+                        constructor.name.clone(),
+                        constructor.index,
+                        vec![Term::Variable(0, (**argument).clone())],
+                        (**output).clone(),
+                    )),
+                    type_,
+                )),
+                _ => Ok(Term::Constructor(
+                    constructor.name.clone(),
+                    constructor.index,
+                    vec![],
+                    type_,
+                )),
+            },
+            ExpressionKind::Function { argument, body } => {
+                let body =
+                    self.with_locals(vec![Some(argument.1.clone())], body)?;
+                Ok(Term::Function(Arc::new(body), type_))
+            }
+            ExpressionKind::Let { bindings, output } => {
+                self.let_(bindings, output)
+            }
+            ExpressionKind::Case { expression, cases } => {
+                let scrutinee_type = match &expression.type_ {
+                    Some(t) => t.clone(),
+                    None => {
+                        return Err(Box::new(LoweringError::MissingType {
+                            span: expression.span,
+                        }))
+                    }
+                };
+                let scrutinee = self.expression(expression)?;
+                if cases.is_empty() {
+                    return Ok(Term::Case(Box::new(scrutinee), vec![], type_));
+                }
+                let occurrence =
+                    self.push_occurrence(expression.span, scrutinee_type)?;
+                let core_scrutinee_type = occurrence.core_type.clone();
+                let body =
+                    self.case_alternatives(span, occurrence, cases, &type_);
+                self.locals.pop();
+                Ok(Self::bind(scrutinee, core_scrutinee_type, body?, type_))
+            }
+            ExpressionKind::Application { head, arguments } => {
+                let mut out = self.expression(head)?;
+                let mut current = head.type_.clone();
+                for argument in arguments {
+                    current = match current {
+                        Some(Type::Function(_, output)) => Some(*output),
+                        _ => None,
+                    };
+                    let partial = match &current {
+                        Some(t) => self.type_(span, t)?,
+                        None => type_.clone(),
+                    };
+                    out = Term::Application(
+                        Box::new(out),
+                        Box::new(self.expression(argument)?),
+                        partial,
+                    );
+                }
+                Ok(out)
+            }
+            ExpressionKind::Tuple(items) => {
+                let mut out = vec![];
+                for item in items {
+                    out.push(self.expression(item)?);
+                }
+                Ok(Term::Tuple(out, type_))
+            }
+            ExpressionKind::Record(fields) => {
+                let mut out = vec![];
+                for (name, item) in fields {
+                    out.push((label(name), self.expression(item)?));
+                }
+                Ok(Term::Record(record(out), type_))
+            }
+            ExpressionKind::BinaryOperation {
+                operator,
+                left,
+                right,
+            } => {
+                let left = self.expression(left)?;
+                let right = self.expression(right)?;
+                let primitive = match operator.1 {
+                    Operator::Add => Primitive::AddUint,
+                    Operator::Subtract => Primitive::SubtractUint,
+                    Operator::Multiply => Primitive::MultiplyUint,
+                    Operator::Divide => Primitive::DivideUint,
+                    Operator::Remainder => Primitive::RemainderUint,
+                    Operator::Equal => Primitive::EqualUint,
+                    Operator::NotEqual => Primitive::NotEqualUint,
+                    Operator::Less => Primitive::LessUint,
+                    Operator::LessOrEqual => Primitive::LessOrEqualUint,
+                    Operator::More => Primitive::MoreUint,
+                    Operator::MoreOrEqual => Primitive::MoreOrEqualUint,
+                    // The right side is only evaluated if needed.
+                    Operator::And | Operator::Or => {
+                        let (on_false, on_true) = if operator.1 == Operator::And
+                        {
+                            (Self::bool_constructor(FALSE_INDEX), right)
+                        } else {
+                            (right, Self::bool_constructor(TRUE_INDEX))
+                        };
+                        return Ok(Term::Case(
+                            Box::new(left),
+                            vec![
+                                CaseAlternative {
+                                    constructor: FALSE_INDEX,
+                                    arguments: 0,
+                                    value: on_false,
+                                },
+                                CaseAlternative {
+                                    constructor: TRUE_INDEX,
+                                    arguments: 0,
+                                    value: on_true,
+                                },
+                            ],
+                            type_,
+                        ));
+                    }
+                };
+                Ok(Term::Primitive(primitive, vec![left, right], type_))
+            }
+            ExpressionKind::Interpolation(parts) => {
+                let mut out: Option<Term> = None;
+                for part in parts {
+                    let term = match part {
+                        InterpolationPart::Text(text) => {
+                            Term::String(text.clone())
+                        }
+                        InterpolationPart::Hole(hole, format) => {
+                            self.shown(hole, format)?
+                        }
+                    };
+                    out = Some(match out {
+                        None => term,
+                        Some(previous) => Term::Primitive(
+                            Primitive::ConcatString,
+                            vec![previous, term],
+                            CoreType::String,
+                        ),
+                    });
+                }
+                Ok(out.unwrap_or_else(|| Term::String(String::new())))
+            }
+            ExpressionKind::Negation(negated) => match type_ {
+                CoreType::Int => Ok(Term::Primitive(
+                    Primitive::SubtractInt,
+                    vec![Term::Int(0), self.expression(negated)?],
+                    type_,
+                )),
+                _ => Err(Box::new(LoweringError::UnsupportedType {
+                    span,
+                    // The type was lowered above.
+                    type_: expression.type_.clone().unwrap(),
+                })),
+            },
+        }
+    }
+}
+
+/// Lowers the definitions of a type checked [`Sast`], the data
+/// types must be in the same [`Sast`].
+pub fn lower_sast(sast: &Sast) -> Result<Module> {
+    let mut lowering = Lowering {
+        sast,
+        type_variables: vec![],
+        locals: vec![],
+    };
+    let mut types = vec![];
+    for data in &sast.data_types {
+        types.push(lowering.data(data)?);
+    }
+    let mut definitions = vec![];
+    for definition in &sast.definitions {
+        let type_ = match &definition.type_ {
+            Some(scheme) => lowering.scheme(definition.span, scheme)?,
+            None => {
+                return Err(Box::new(LoweringError::MissingType {
+                    span: definition.span,
+                }))
+            }
+        };
+        definitions.push(Definition {
+            name: definition.name.clone(),
+            value: lowering.expression(&definition.value)?,
+            type_,
+        });
+    }
+    Ok(Module { types, definitions })
+}
+
+/// Lowers a type checked expression that can use the items of the
+/// session.
+pub fn lower_expression(
+    expression: &Expression,
+    scheme: &TypeScheme,
+    session: &Sast,
+) -> Result<Term> {
+    let mut lowering = Lowering {
+        sast: session,
+        type_variables: scheme.variables.clone(),
+        locals: vec![],
+    };
+    lowering.expression(expression)
+}
+
+#[cfg(test)]
+mod lowering_test {
+    use super::lower_sast;
+    use crate::from_cst::{translate_top, TranslationError};
+    use crate::inference::infer_module;
+    use crate::top::Sast;
+    use octizys_core::checker::check_module;
+    use octizys_core::core::Module;
+    use octizys_core::evaluation::Interpreter;
+    use octizys_core::terms::Term;
+    use octizys_core::types::Type;
+    use octizys_parser::parser::parse_string;
+    use octizys_text_store::store::Store;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn lower(source: &str) -> Module {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let top = parse_string(source, None, store.clone()).unwrap();
+        let session = Sast::prelude();
+        let mut sast = translate_top(&top, &session, &store.borrow()).unwrap();
+        infer_module(&mut sast, &session).unwrap();
+        let mut full = session.clone();
+        full.extend(sast);
+        let module = lower_sast(&full).unwrap();
+        check_module(&module).unwrap();
+        module
+    }
+
+    fn run(source: &str) -> String {
+        let module = lower(source);
+        Interpreter::new(&module).run_main().unwrap().to_string()
+    }
+
+    fn count_cases(term: &Term) -> usize {
+        match term {
+            Term::Case(scrutinee, alternatives, _) => {
+                1 + count_cases(scrutinee)
+                    + alternatives
+                        .iter()
+                        .map(|a| count_cases(&a.value))
+                        .sum::<usize>()
+            }
+            Term::Function(body, _) => count_cases(body),
+            Term::TypeFunction(body, _) => count_cases(body),
+            Term::Application(f, x, _) => count_cases(f) + count_cases(x),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn nested_constructors() {
+        let source = "data Maybe a = Nothing | Just a;\
+            f m = case m of { Just (Just x) -> x, Just Nothing -> 1, Nothing -> 2 };\
+            main = (f (Just (Just 5)), f (Just Nothing), f Nothing);";
+        assert_eq!(run(source), "(5, 1, 2)");
+        // One switch for each level of constructors.
+        let module = lower(source);
+        let f = module.find("f").unwrap();
+        assert_eq!(count_cases(&f.value), 2);
+    }
+
+    #[test]
+    fn tuples_records_and_constants() {
+        let source = "data List a = Nil | Cons (a, List a);\
+            zip a b = case (a, b) of { (Cons (x, xs), Cons (y, ys)) -> Cons ((x, y), zip xs ys), _ -> Nil };\
+            fib n = case n of { 0 -> 0, 1 -> 1, m -> fib (m - 1) + fib (m - 2) };\
+            main = let { p = p, q = all@(q1, _) } = { p = 7, q = (8, 9) };\
+                in (p, q1, all, fib 10, zip (Cons (1, Nil)) (Cons (2, Cons (3, Nil))));";
+        assert_eq!(run(source), "(7, 8, (8, 9), 55, Cons ((1, 2), Nil))");
+    }
+
+    #[test]
+    fn negative_literals() {
+        assert_eq!(
+            run("main = (-5, -9_223_372_036_854_775_808, 10 - 3);"),
+            "(-5, -9223372036854775808, 7)"
+        );
+        let store = Rc::new(RefCell::new(Store::default()));
+        let session = Sast::prelude();
+        let translate = |source: &str| {
+            let top = parse_string(source, None, store.clone()).unwrap();
+            translate_top(&top, &session, &store.borrow()).map(|_| ())
+        };
+        assert!(translate("main = case 1 of { 1 -> 0 };").is_ok());
+        for source in ["main = 255u8;", "main = 1u16;", "main = 1u32;"] {
+            assert!(matches!(
+                translate(source).map_err(|e| *e),
+                Err(TranslationError::Unsupported { .. })
+            ));
+        }
+        assert!(matches!(
+            translate("main = case 1i8 of { -1i8 -> 0 };").map_err(|e| *e),
+            Err(TranslationError::Unsupported { .. })
+        ));
+        assert!(matches!(
+            translate("main = -129i8;").map_err(|e| *e),
+            Err(TranslationError::LiteralOutOfRange { .. })
+        ));
+        assert!(translate("main = -1u8;").is_err());
+        assert!(translate(
+            "main = case 1 of { -9_223_372_036_854_775_809 -> 0 };"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn signed_constant_patterns() {
+        let source = "sign : I64 -> U64;\
+            sign x = case x of { -1 -> 0, 0i64 -> 1, -9_223_372_036_854_775_808 -> 2, _ -> 3 };\
+            main = (sign (-1), sign 0i64, sign (-9_223_372_036_854_775_808), sign (-7));";
+        assert_eq!(run(source), "(0, 1, 2, 3)");
+    }
+
+    #[test]
+    fn char_literals() {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let session = Sast::prelude();
+        let source = r"main = case 'a' of { 'a' -> '\n', '\'' -> ' ', _ -> '\u{1F600}' };";
+        let top = parse_string(source, None, store.clone()).unwrap();
+        assert!(translate_top(&top, &session, &store.borrow()).is_ok());
+        assert!(parse_string("main = 'ab';", None, store.clone()).is_err());
+        assert!(parse_string(r"main = '\u{D800}';", None, store).is_err());
+
+        assert_eq!(run("main = 'a';"), "'a'");
+        assert_eq!(
+            run(r"f c = case c of { 'a' -> '\n', '\'' -> ' ', _ -> c };
+                main = (f 'a', f '\'', f 'z');"),
+            r"('\n', ' ', 'z')"
+        );
+    }
+
+    #[test]
+    fn data_types_become_sums() {
+        let module =
+            lower("data Maybe a = Nothing | Just a; newtype Age = Age U64;");
+        let maybe = &module.find_type("Maybe").unwrap().type_;
+        assert_eq!(maybe.to_string(), "forall t0 . Maybe[(), (t0)]");
+        let age = &module.find_type("Age").unwrap().type_;
+        assert!(matches!(age, Type::NewType(_, _)));
+    }
+}
@@ -0,0 +1,1067 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use octizys_common::identifier::Identifier as CstIdentifier;
+use octizys_common::logic_path::LogicPath;
+use octizys_common::report::{ReportFormat, ReportTarget};
+use octizys_common::source_map::FileId;
+use octizys_common::span::{Location, Span};
+use octizys_core::common::Identifier;
+use octizys_cst::top::{Constructor, Top, TopItem};
+use octizys_pretty::{
+    combinators::external_text, document::Document, store::NonLineBreakStr,
+};
+use octizys_text_store::store::{Store, StoreSymbol};
+
+/// The extension of the source files, the module `a::b::c` is the
+/// file `a/b/c.oct` inside a source root.
+pub const SOURCE_EXTENSION: &str = "oct";
+
+/// The name of a module, the [`LogicPath`] used to import it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModuleName(Vec<Identifier>);
+
+impl ModuleName {
+    pub fn new(parts: Vec<Identifier>) -> Self {
+        ModuleName(parts)
+    }
+
+    pub fn from_logic_path(path: &LogicPath, store: &Store) -> Self {
+        let parts: &Vec<CstIdentifier> = path.into();
+        ModuleName(parts.iter().map(|p| identifier(store, p)).collect())
+    }
+
+    /// The name of a file relative to the source root that
+    /// contains it, `None` if the file isn't inside the root or the
+    /// name of a directory isn't a valid module name.
+    pub fn from_file(root: &Path, file: &Path) -> Option<Self> {
+        let relative = file.strip_prefix(root).ok()?.with_extension("");
+        let mut parts = vec![];
+        for component in relative.components() {
+            let part = component.as_os_str().to_str()?;
+            parts.push(Identifier::make(String::from(part)).ok()?);
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(ModuleName(parts))
+        }
+    }
+
+    /// The path of the file relative to a source root.
+    pub fn relative_path(&self) -> PathBuf {
+        let mut path: PathBuf = self.0.iter().map(|p| p.as_str()).collect();
+        path.set_extension(SOURCE_EXTENSION);
+        path
+    }
+}
+
+impl Display for ModuleName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, part) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "::")?;
+            }
+            write!(f, "{}", part)?;
+        }
+        Ok(())
+    }
+}
+
+fn identifier(store: &Store, identifier: &CstIdentifier) -> Identifier {
+    let name = store
+        .regular
+        .resolve(StoreSymbol::from(*identifier))
+        .unwrap_or_default();
+    // The lexer only produces valid identifiers.
+    Identifier::make(String::from(name)).unwrap()
+}
+
+/// The directories where we look for the imported modules, in order.
+#[derive(Debug, Clone, Default)]
+pub struct SourceRoots(Vec<PathBuf>);
+
+impl SourceRoots {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        SourceRoots(roots)
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.0
+    }
+
+    /// The files that can contain the module, in order of preference.
+    pub fn candidates(&self, name: &ModuleName) -> Vec<PathBuf> {
+        let relative = name.relative_path();
+        self.0.iter().map(|root| root.join(&relative)).collect()
+    }
+
+    /// The first file of the candidates that exists.
+    pub fn locate(&self, name: &ModuleName) -> Option<PathBuf> {
+        self.candidates(name)
+            .into_iter()
+            .find(|path| path.is_file())
+    }
+}
+
+/// A `import` of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDeclaration {
+    pub span: Span,
+    pub module: ModuleName,
+    pub module_span: Span,
+    /// The imported names can be used without the module prefix.
+    pub unqualified: bool,
+    /// If absent, all the exported names are imported.
+    pub names: Option<Vec<(Span, Identifier)>>,
+    /// The prefix to use instead of the module name.
+    pub alias: Option<ModuleName>,
+}
+
+impl ImportDeclaration {
+    /// The prefix of the qualified names that refer to the module.
+    pub fn qualifier(&self) -> &ModuleName {
+        self.alias.as_ref().unwrap_or(&self.module)
+    }
+}
+
+/// The imports of a file in order of appearance.
+pub fn imports(top: &Top, store: &Store) -> Vec<ImportDeclaration> {
+    let Some(imports) = &top.imports else {
+        return vec![];
+    };
+    imports
+        .iter()
+        .map(|import| {
+            let names = import.import_list.as_ref().map(|list| {
+                list.value
+                    .iter()
+                    .map(|name| {
+                        (name.info.span, identifier(store, &name.value))
+                    })
+                    .collect()
+            });
+            let end = match (&import.qualified_path, &import.import_list) {
+                (Some(alias), _) => alias.path.info.span,
+                (None, Some(list)) => list.right.span,
+                (None, None) => import.logic_path.info.span,
+            };
+            ImportDeclaration {
+                span: import.import.span + end,
+                module: ModuleName::from_logic_path(
+                    &import.logic_path.value,
+                    store,
+                ),
+                module_span: import.logic_path.info.span,
+                unqualified: import.unqualified.is_some(),
+                names,
+                alias: import.qualified_path.as_ref().map(|alias| {
+                    ModuleName::from_logic_path(&alias.path.value, store)
+                }),
+            }
+        })
+        .collect()
+}
+
+/// What a top level name refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Type,
+    Constructor,
+    Value,
+}
+
+impl ItemKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemKind::Type => "type",
+            ItemKind::Constructor => "constructor",
+            ItemKind::Value => "value",
+        }
+    }
+}
+
+/// A item that other modules can't use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateItem {
+    pub span: Span,
+    pub name: Identifier,
+    pub kind: ItemKind,
+    /// The data type of a constructor.
+    pub owner: Option<Identifier>,
+}
+
+/// The top level names of a module. Types and values live in
+/// different namespaces, the constructors are values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleInterface {
+    pub types: Vec<(Span, Identifier)>,
+    pub values: Vec<(Span, Identifier)>,
+    /// The names that other modules can import.
+    pub exported: Vec<Identifier>,
+    pub private: Vec<PrivateItem>,
+}
+
+impl ModuleInterface {
+    pub fn exports(&self, name: &Identifier) -> bool {
+        self.exported.contains(name)
+    }
+
+    /// Tells if the module has a item with the name, in any namespace.
+    pub fn defines(&self, name: &Identifier) -> bool {
+        self.types
+            .iter()
+            .chain(&self.values)
+            .any(|(_, other)| other == name)
+    }
+
+    fn add(
+        &mut self,
+        span: Span,
+        name: Identifier,
+        kind: ItemKind,
+        public: bool,
+        owner: Option<Identifier>,
+    ) {
+        match kind {
+            ItemKind::Type => self.types.push((span, name.clone())),
+            ItemKind::Constructor | ItemKind::Value => {
+                self.values.push((span, name.clone()))
+            }
+        }
+        if public {
+            self.exported.push(name)
+        } else {
+            self.private.push(PrivateItem {
+                span,
+                name,
+                kind,
+                owner,
+            })
+        }
+    }
+
+    fn add_constructors(
+        &mut self,
+        store: &Store,
+        owner: &Identifier,
+        owner_public: bool,
+        constructors: Vec<&Constructor>,
+    ) {
+        let marked = constructors.iter().any(|c| c.public.is_some());
+        for constructor in constructors {
+            let public =
+                constructor.public.is_some() || (owner_public && !marked);
+            self.add(
+                constructor.name.info.span,
+                identifier(store, &constructor.name.value),
+                ItemKind::Constructor,
+                public,
+                Some(owner.clone()),
+            );
+        }
+    }
+}
+
+/// Collects the names of the items of a file and the ones that are
+/// exported, that is, the items marked as `public`.
+/// A value is exported if its definition or its declaration is marked.
+/// The constructors of a public data type are exported if none of
+/// them is marked as `public`, otherwise only the marked ones are.
+pub fn interface(top: &Top, store: &Store) -> ModuleInterface {
+    let mut result = ModuleInterface::default();
+    let Some(items) = &top.items else {
+        return result;
+    };
+    let declared_public: Vec<Identifier> = items
+        .iter()
+        .filter_map(|item| match item {
+            TopItem::Declaration(declaration)
+                if declaration.public.is_some() =>
+            {
+                Some(identifier(store, &declaration.name.value))
+            }
+            _ => None,
+        })
+        .collect();
+    for item in items.iter() {
+        match item {
+            TopItem::Data(data) => {
+                let left = &data.left_part;
+                let public = left.public.is_some();
+                let name = identifier(store, &left.name.value);
+                result.add(
+                    left.name.info.span,
+                    name.clone(),
+                    ItemKind::Type,
+                    public,
+                    None,
+                );
+                result.add_constructors(
+                    store,
+                    &name,
+                    public,
+                    data.constructors
+                        .iter()
+                        .flat_map(|c| c.constructors.iter())
+                        .collect(),
+                );
+            }
+            TopItem::NewType(new_type) => {
+                let left = &new_type.left_part;
+                let public = left.public.is_some();
+                let name = identifier(store, &left.name.value);
+                result.add(
+                    left.name.info.span,
+                    name.clone(),
+                    ItemKind::Type,
+                    public,
+                    None,
+                );
+                result.add_constructors(
+                    store,
+                    &name,
+                    public,
+                    vec![&new_type.constructor],
+                );
+            }
+            TopItem::Alias(alias) => {
+                let left = &alias.left_part;
+                result.add(
+                    left.name.info.span,
+                    identifier(store, &left.name.value),
+                    ItemKind::Type,
+                    left.public.is_some(),
+                    None,
+                );
+            }
+            TopItem::Declaration(_) => (),
+            TopItem::Definition(definition) => {
+                let name = identifier(store, &definition.name.value);
+                let public = definition.public.is_some()
+                    || declared_public.contains(&name);
+                result.add(
+                    definition.name.info.span,
+                    name,
+                    ItemKind::Value,
+                    public,
+                    None,
+                )
+            }
+        }
+    }
+    result
+}
+
+/// A private item that its module never uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedItem {
+    pub span: Span,
+    pub name: Identifier,
+    pub kind: ItemKind,
+}
+
+impl ReportFormat for UnusedItem {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        NonLineBreakStr::new("UnusedItem")
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        NonLineBreakStr::new("A private item is never used.")
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        Some(external_text(&format!(
+            "The {} {} isn't public and its module doesn't use it.\nMark it as public or remove it.",
+            self.kind.name(),
+            self.name
+        )))
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span))
+    }
+}
+
+/// The private items of a module that aren't in the `used` names.
+/// The constructors of a unused type aren't reported again and the
+/// `main` of the entry module is used by the interpreter.
+pub fn unused_items(
+    interface: &ModuleInterface,
+    used: &HashSet<Identifier>,
+    entry: bool,
+) -> Vec<UnusedItem> {
+    let unused = |name: &Identifier| {
+        !used.contains(name)
+            && interface.private.iter().any(|p| &p.name == name)
+    };
+    interface
+        .private
+        .iter()
+        .filter(|item| unused(&item.name))
+        .filter(|item| !(entry && item.name.as_str() == "main"))
+        .filter(|item| !item.owner.as_ref().is_some_and(unused))
+        .map(|item| UnusedItem {
+            span: item.span,
+            name: item.name.clone(),
+            kind: item.kind,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleError {
+    ModuleNotFound {
+        span: Span,
+        module: ModuleName,
+        searched: Vec<PathBuf>,
+    },
+    /// The modules of the cycle in import order, the span is the
+    /// import that closes the cycle.
+    ImportCycle { span: Span, cycle: Vec<ModuleName> },
+    NotExported {
+        span: Span,
+        module: ModuleName,
+        name: Identifier,
+    },
+    /// A name used without a prefix in a module refers to items of
+    /// two modules.
+    ConflictingName {
+        span: Span,
+        name: Identifier,
+        other: ModuleName,
+    },
+}
+
+impl ModuleError {
+    pub fn span(&self) -> Span {
+        match self {
+            ModuleError::ModuleNotFound { span, .. }
+            | ModuleError::ImportCycle { span, .. }
+            | ModuleError::NotExported { span, .. }
+            | ModuleError::ConflictingName { span, .. } => *span,
+        }
+    }
+}
+
+impl ReportFormat for ModuleError {
+    fn get_report_name(&self) -> NonLineBreakStr {
+        match self {
+            ModuleError::ModuleNotFound { .. } => {
+                NonLineBreakStr::new("ModuleNotFound")
+            }
+            ModuleError::ImportCycle { .. } => {
+                NonLineBreakStr::new("ImportCycle")
+            }
+            ModuleError::NotExported { .. } => {
+                NonLineBreakStr::new("NotExported")
+            }
+            ModuleError::ConflictingName { .. } => {
+                NonLineBreakStr::new("ConflictingName")
+            }
+        }
+    }
+
+    fn get_short_description(&self) -> NonLineBreakStr {
+        match self {
+            ModuleError::ModuleNotFound { .. } => {
+                NonLineBreakStr::new("We couldn't find a imported module.")
+            }
+            ModuleError::ImportCycle { .. } => {
+                NonLineBreakStr::new("The modules import each other.")
+            }
+            ModuleError::NotExported { .. } => NonLineBreakStr::new(
+                "A imported name isn't exported by the module.",
+            ),
+            ModuleError::ConflictingName { .. } => {
+                NonLineBreakStr::new("A name refers to items of two modules.")
+            }
+        }
+    }
+
+    fn get_long_description(&self, _target: &ReportTarget) -> Option<Document> {
+        let description = match self {
+            ModuleError::ModuleNotFound {
+                module, searched, ..
+            } => {
+                let mut description =
+                    format!("The module {} isn't in the source roots.", module);
+                if !searched.is_empty() {
+                    description.push_str("\nWe looked for:");
+                    for path in searched {
+                        description.push_str(&format!("\n  {}", path.display()));
+                    }
+                }
+                description
+            }
+            ModuleError::ImportCycle { cycle, .. } => {
+                let names: Vec<String> =
+                    cycle.iter().map(|m| m.to_string()).collect();
+                format!(
+                    "A module can't depend on itself, the cycle is:\n  {} -> {}",
+                    names.join(" -> "),
+                    names[0]
+                )
+            }
+            ModuleError::NotExported { module, name, .. } => format!(
+                "The module {} doesn't export {}, only the items marked as public can be imported.",
+                module, name
+            ),
+            ModuleError::ConflictingName { name, other, .. } => format!(
+                "The name {} is also imported from or defined in the module {}.\nUse a qualified import to tell them apart.",
+                name, other
+            ),
+        };
+        Some(external_text(&description))
+    }
+
+    fn get_expected(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn get_location_maybe(&self) -> Option<Location> {
+        Some(Location::Span(self.span()))
+    }
+}
+
+type Result<T> = std::result::Result<T, Box<ModuleError>>;
+
+/// A parsed module of a program.
+#[derive(Debug)]
+pub struct ModuleNode {
+    pub name: ModuleName,
+    pub file: FileId,
+    pub top: Top,
+    pub imports: Vec<ImportDeclaration>,
+    pub interface: ModuleInterface,
+}
+
+/// The modules of a program, the first one is the entry point.
+/// The dependencies of a module are the modules it imports.
+#[derive(Debug, Default)]
+pub struct ModuleGraph {
+    modules: Vec<ModuleNode>,
+}
+
+/// The names of other modules that a module can use, together with
+/// the names they have after the linking.
+/// The default scope is the one of a program with a single module.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportScope {
+    /// The names defined by other modules.
+    foreign: HashSet<Identifier>,
+    unqualified: HashMap<Identifier, Identifier>,
+    qualified: HashMap<ModuleName, HashMap<Identifier, Identifier>>,
+}
+
+impl ImportScope {
+    /// A name of the session that can be used without a prefix.
+    pub fn is_visible(&self, name: &Identifier) -> bool {
+        !self.foreign.contains(name) || self.unqualified.contains_key(name)
+    }
+
+    /// The name in the session of a visible name.
+    pub fn resolve(&self, name: &Identifier) -> Identifier {
+        self.unqualified
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.clone())
+    }
+
+    /// Tells if the module is imported with the given prefix.
+    pub fn has_qualifier(&self, qualifier: &ModuleName) -> bool {
+        self.qualified.contains_key(qualifier)
+    }
+
+    /// A name that can be used with the given prefix.
+    pub fn is_qualified_visible(
+        &self,
+        qualifier: &ModuleName,
+        name: &Identifier,
+    ) -> bool {
+        self.resolve_qualified(qualifier, name).is_some()
+    }
+
+    /// The name in the session of a name used with the given prefix.
+    pub fn resolve_qualified(
+        &self,
+        qualifier: &ModuleName,
+        name: &Identifier,
+    ) -> Option<Identifier> {
+        self.qualified.get(qualifier)?.get(name).cloned()
+    }
+}
+
+impl ModuleGraph {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a module, returns its index.
+    pub fn add(&mut self, module: ModuleNode) -> usize {
+        self.modules.push(module);
+        self.modules.len() - 1
+    }
+
+    pub fn find(&self, name: &ModuleName) -> Option<usize> {
+        self.modules.iter().position(|m| &m.name == name)
+    }
+
+    pub fn get(&self, index: usize) -> &ModuleNode {
+        &self.modules[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// The indexes of the imported modules together with the import,
+    /// the missing modules are skipped.
+    fn dependencies(
+        &self,
+        index: usize,
+    ) -> impl Iterator<Item = (usize, &ImportDeclaration)> {
+        self.modules[index]
+            .imports
+            .iter()
+            .filter_map(|import| Some((self.find(&import.module)?, import)))
+    }
+
+    /// The modules in an order where every module comes after the
+    /// modules it imports.
+    pub fn order(&self) -> Result<Vec<usize>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            New,
+            Visiting,
+            Done,
+        }
+        let mut state = vec![State::New; self.modules.len()];
+        let mut order = vec![];
+        // The modules being visited and the next import to follow.
+        let mut stack: Vec<(usize, usize)> = vec![];
+        for start in 0..self.modules.len() {
+            if state[start] != State::New {
+                continue;
+            }
+            state[start] = State::Visiting;
+            stack.push((start, 0));
+            while let Some((current, next)) = stack.pop() {
+                let dependency = self.dependencies(current).nth(next);
+                let Some((dependency, import)) = dependency else {
+                    state[current] = State::Done;
+                    order.push(current);
+                    continue;
+                };
+                stack.push((current, next + 1));
+                match state[dependency] {
+                    State::Done => (),
+                    State::New => {
+                        state[dependency] = State::Visiting;
+                        stack.push((dependency, 0));
+                    }
+                    State::Visiting => {
+                        let position = stack
+                            .iter()
+                            .position(|(m, _)| *m == dependency)
+                            .unwrap_or_default();
+                        let cycle = stack[position..]
+                            .iter()
+                            .map(|(m, _)| self.modules[*m].name.clone())
+                            .collect();
+                        return Err(Box::new(ModuleError::ImportCycle {
+                            span: import.module_span,
+                            cycle,
+                        }));
+                    }
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Checks that the imported names are exported and that the
+    /// names used without a prefix in a module refer to a single item.
+    pub fn check(&self) -> Result<()> {
+        for index in 0..self.modules.len() {
+            for (dependency, import) in self.dependencies(index) {
+                let target = &self.modules[dependency];
+                for (span, name) in import.names.iter().flatten() {
+                    if !target.interface.exports(name) {
+                        return Err(Box::new(ModuleError::NotExported {
+                            span: *span,
+                            module: target.name.clone(),
+                            name: name.clone(),
+                        }));
+                    }
+                }
+            }
+        }
+        for index in 0..self.modules.len() {
+            self.check_unqualified(index)?;
+        }
+        Ok(())
+    }
+
+    /// The names of the module can't be imported without a prefix and
+    /// two imports can't bring different items with the same name.
+    fn check_unqualified(&self, index: usize) -> Result<()> {
+        let module = &self.modules[index];
+        let mut seen: HashMap<Identifier, (usize, Identifier)> = HashMap::new();
+        for (dependency, import) in self.dependencies(index) {
+            if !import.unqualified {
+                continue;
+            }
+            let target = &self.modules[dependency];
+            let names: Vec<(Span, Identifier)> = match &import.names {
+                Some(names) => names.clone(),
+                None => target
+                    .interface
+                    .exported
+                    .iter()
+                    .map(|name| (import.module_span, name.clone()))
+                    .collect(),
+            };
+            for (span, name) in names {
+                let conflict = |other: &ModuleNode| {
+                    Box::new(ModuleError::ConflictingName {
+                        span,
+                        name: name.clone(),
+                        other: other.name.clone(),
+                    })
+                };
+                if module.interface.defines(&name) {
+                    return Err(conflict(module));
+                }
+                let linked = self.linked_name(dependency, &name);
+                match seen.get(&name) {
+                    Some((other, previous)) if *previous != linked => {
+                        return Err(conflict(&self.modules[*other]))
+                    }
+                    _ => {
+                        seen.insert(name, (dependency, linked));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The name of a item of a module after the linking, when other
+    /// module defines the same name it is prefixed with the module.
+    /// The names of the entry module are kept.
+    pub fn linked_name(&self, index: usize, name: &Identifier) -> Identifier {
+        let shared = self
+            .modules
+            .iter()
+            .enumerate()
+            .any(|(other, m)| other != index && m.interface.defines(name));
+        if index == 0 || !shared {
+            return name.clone();
+        }
+        // The names of the core aren't checked, the prefix can't clash
+        // with a name of the source.
+        Identifier::make(format!("{}::{}", self.modules[index].name, name))
+            .unwrap()
+    }
+
+    /// The names of the items of a module that change in the linking.
+    pub fn renames(&self, index: usize) -> HashMap<Identifier, Identifier> {
+        let interface = &self.modules[index].interface;
+        interface
+            .types
+            .iter()
+            .chain(&interface.values)
+            .filter_map(|(_, name)| {
+                let linked = self.linked_name(index, name);
+                (&linked != name).then(|| (name.clone(), linked))
+            })
+            .collect()
+    }
+
+    /// The names of the other modules that a module can use.
+    pub fn scope(&self, index: usize) -> ImportScope {
+        let mut scope = ImportScope::default();
+        for (other, module) in self.modules.iter().enumerate() {
+            if other != index {
+                scope.foreign.extend(
+                    module
+                        .interface
+                        .types
+                        .iter()
+                        .chain(&module.interface.values)
+                        .map(|(_, name)| name.clone()),
+                );
+            }
+        }
+        for (dependency, import) in self.dependencies(index) {
+            let target = &self.modules[dependency];
+            let names: Vec<Identifier> = match &import.names {
+                Some(names) => names.iter().map(|(_, n)| n.clone()).collect(),
+                None => target.interface.exported.clone(),
+            };
+            let qualified = scope
+                .qualified
+                .entry(import.qualifier().clone())
+                .or_default();
+            for name in names {
+                let linked = self.linked_name(dependency, &name);
+                if import.unqualified {
+                    scope.unqualified.insert(name.clone(), linked.clone());
+                }
+                qualified.insert(name, linked);
+            }
+        }
+        scope
+    }
+}
+
+#[cfg(test)]
+mod modules_test {
+    use super::*;
+    use octizys_parser::parser::parse_string;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn graph(files: &[(&str, &str)]) -> ModuleGraph {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let mut graph = ModuleGraph::new();
+        for (name, source) in files {
+            let top = parse_string(source, None, store.clone()).unwrap();
+            let store = store.borrow();
+            let name = ModuleName::new(
+                name.split("::")
+                    .map(|p| Identifier::make(String::from(p)).unwrap())
+                    .collect(),
+            );
+            graph.add(ModuleNode {
+                name,
+                file: FileId::DETACHED,
+                imports: imports(&top, &store),
+                interface: interface(&top, &store),
+                top,
+            });
+        }
+        graph
+    }
+
+    fn names(graph: &ModuleGraph, order: Vec<usize>) -> Vec<String> {
+        order
+            .into_iter()
+            .map(|i| graph.get(i).name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn dependencies_come_first() {
+        let graph = graph(&[
+            ("main", "import a::b::;\nimport c::;\nmain = 1;"),
+            ("c", "import a::b::;\npublic data C = C;"),
+            ("a::b", "public data B = B;"),
+        ]);
+        let order = graph.order().unwrap();
+        assert_eq!(names(&graph, order), vec!["a::b", "c", "main"]);
+        assert!(graph.check().is_ok());
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let graph = graph(&[
+            ("main", "import a::;\nmain = 1;"),
+            ("a", "import b::;\npublic data A = A;"),
+            ("b", "import a::;\npublic data B = B;"),
+        ]);
+        match *graph.order().unwrap_err() {
+            ModuleError::ImportCycle { cycle, .. } => {
+                let cycle: Vec<String> =
+                    cycle.iter().map(|m| m.to_string()).collect();
+                assert_eq!(cycle, vec!["a", "b"])
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn only_public_items_are_exported() {
+        let graph = graph(&[
+            ("main", "import unqualified a::(Color, Red, Hidden);\nmain = 1;"),
+            (
+                "a",
+                "public data Color = Red | Green;\ndata Hidden = Hidden;\nhelper = 1;",
+            ),
+        ]);
+        let a = graph.get(1);
+        let exported: Vec<&str> =
+            a.interface.exported.iter().map(|n| n.as_str()).collect();
+        assert_eq!(exported, vec!["Color", "Red", "Green"]);
+        match *graph.check().unwrap_err() {
+            ModuleError::NotExported { name, .. } => {
+                assert_eq!(name.as_str(), "Hidden")
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn public_values_and_constructors() {
+        let graph = graph(&[(
+            "a",
+            "public data Shape = public Circle | Square;\npublic area s = 1;\nhelper = 2;",
+        )]);
+        let exported: Vec<&str> = graph
+            .get(0)
+            .interface
+            .exported
+            .iter()
+            .map(|n| n.as_str())
+            .collect();
+        assert_eq!(exported, vec!["Shape", "Circle", "area"]);
+    }
+
+    #[test]
+    fn public_declarations() {
+        let source = "public inc : U64 -> U64;\ninc x = helper x;\n\
+                      helper : U64 -> U64;\nhelper x = x + 1;";
+        let public = graph(&[
+            ("main", "import unqualified a::;\nmain = inc 1;"),
+            ("a", source),
+        ]);
+        let exported: Vec<&str> = public
+            .get(1)
+            .interface
+            .exported
+            .iter()
+            .map(|n| n.as_str())
+            .collect();
+        assert_eq!(exported, vec!["inc"]);
+        public.check().unwrap();
+        let scope = public.scope(0);
+        let name = |s: &str| Identifier::make(String::from(s)).unwrap();
+        let a = ModuleName::new(vec![name("a")]);
+        assert!(scope.is_visible(&name("inc")));
+        assert!(!scope.is_visible(&name("helper")));
+        assert!(!scope.is_qualified_visible(&a, &name("helper")));
+        let private =
+            graph(&[("main", "import a::(helper);\nmain = 1;"), ("a", source)]);
+        match *private.check().unwrap_err() {
+            ModuleError::NotExported { name, .. } => {
+                assert_eq!(name.as_str(), "helper")
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn unused_private_items() {
+        let store = Rc::new(RefCell::new(Store::default()));
+        let source = "data Unused = Unused;\ndata Used = Used;\nfact n = fact n;\nhelper = Used;\nmain = helper;";
+        let top = parse_string(source, None, store.clone()).unwrap();
+        let store = store.borrow();
+        let (_, used) = crate::from_cst::translate_module(
+            &top,
+            &crate::top::Sast::prelude(),
+            &ImportScope::default(),
+            &store,
+        )
+        .unwrap();
+        let unused: Vec<(String, ItemKind)> =
+            unused_items(&interface(&top, &store), &used, true)
+                .into_iter()
+                .map(|item| (item.name.to_string(), item.kind))
+                .collect();
+        assert_eq!(
+            unused,
+            vec![
+                (String::from("Unused"), ItemKind::Type),
+                (String::from("fact"), ItemKind::Value)
+            ]
+        );
+    }
+
+    #[test]
+    fn scope_of_imports() {
+        let graph = graph(&[
+            (
+                "main",
+                "import unqualified a::(Red);\nimport b:: as c::;\nmain = 1;",
+            ),
+            ("a", "public data Color = Red | Green;"),
+            ("b", "public data Shape = Circle;\nhelper = 1;"),
+        ]);
+        let scope = graph.scope(0);
+        let name = |s: &str| Identifier::make(String::from(s)).unwrap();
+        let c = ModuleName::new(vec![name("c")]);
+        let a = ModuleName::new(vec![name("a")]);
+        assert!(scope.is_visible(&name("Red")));
+        assert!(!scope.is_visible(&name("Green")));
+        assert!(!scope.is_visible(&name("Circle")));
+        assert!(scope.is_visible(&name("main")));
+        assert!(scope.is_qualified_visible(&a, &name("Red")));
+        assert!(scope.is_qualified_visible(&c, &name("Circle")));
+        assert!(!scope.is_qualified_visible(&c, &name("helper")));
+        assert!(!scope.has_qualifier(&ModuleName::new(vec![name("b")])));
+    }
+
+    #[test]
+    fn conflicting_names() {
+        let local = graph(&[
+            ("main", "import unqualified a::;\nhelper = 2;\nmain = 1;"),
+            ("a", "public helper = 1;"),
+        ]);
+        match *local.check().unwrap_err() {
+            ModuleError::ConflictingName { name, other, .. } => {
+                assert_eq!(name.as_str(), "helper");
+                assert_eq!(other.to_string(), "main");
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+        let imported = graph(&[
+            (
+                "main",
+                "import unqualified a::(helper);\n\
+                 import unqualified b::(helper);\nmain = 1;",
+            ),
+            ("a", "public helper = 1;"),
+            ("b", "public helper = 2;"),
+        ]);
+        match *imported.check().unwrap_err() {
+            ModuleError::ConflictingName { name, other, .. } => {
+                assert_eq!(name.as_str(), "helper");
+                assert_eq!(other.to_string(), "a");
+            }
+            error => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    #[test]
+    fn private_names_are_linked_per_module() {
+        let graph = graph(&[
+            ("main", "import a::;\nimport b::;\nhelper = 3;\nmain = 1;"),
+            ("a", "helper = 1;\npublic one = helper;"),
+            ("b", "helper = 2;\npublic two = helper;"),
+        ]);
+        graph.check().unwrap();
+        let name = |s: &str| Identifier::make(String::from(s)).unwrap();
+        let linked = |index, s| graph.linked_name(index, &name(s)).to_string();
+        assert_eq!(linked(0, "helper"), "helper");
+        assert_eq!(linked(1, "helper"), "a::helper");
+        assert_eq!(linked(2, "helper"), "b::helper");
+        assert_eq!(linked(1, "one"), "one");
+        let scope = graph.scope(0);
+        let a = ModuleName::new(vec![name("a")]);
+        assert_eq!(
+            scope.resolve_qualified(&a, &name("one")),
+            Some(name("one"))
+        );
+        assert_eq!(scope.resolve(&name("helper")), name("helper"));
+    }
+}
@@ -1,61 +1,204 @@
-use octizys_common::module_logic_path::ModuleLogicPath;
+use std::collections::HashMap;
+
 use octizys_common::span::Span;
 use octizys_core::common::Identifier;
+use octizys_core::primitives::{BOOL_NAME, FALSE_NAME, TRUE_NAME};
 
-use crate::_type;
-use crate::expression;
+use crate::_type::{Type, TypeScheme};
+use crate::expression::{ConstructorReference, Expression};
 
-#[derive(Debug)]
-pub struct Sast {
-    pub imports: Vec<Import>,
-    pub exports: Vec<Export>,
-    pub data_types: Vec<DataType>,
-    pub new_types: Vec<NewType>,
-    pub alias: Vec<Alias>,
-    pub declarations: Vec<VariableDeclaration>,
-    pub definitions: Vec<VariableDefinition>,
-}
-
-#[derive(Debug)]
-pub struct Import {
-    pub span: Span,
-    pub prefix: Option<ModuleLogicPath>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    Data,
+    NewType,
 }
 
-#[derive(Debug)]
-pub struct Export {
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataConstructor {
     pub span: Span,
-    pub prefix: Option<ModuleLogicPath>,
+    pub name: Identifier,
+    /// The type can use the variables of the [`DataType`].
+    pub argument: Option<Type>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DataType {
     pub span: Span,
-    pub _type: _type::SumType,
+    pub name: Identifier,
+    pub variables: Vec<Identifier>,
+    pub constructors: Vec<DataConstructor>,
+    pub kind: DataKind,
 }
 
-#[derive(Debug)]
-pub struct NewType {
-    pub span: Span,
-    pub _type: _type::NewType,
+impl DataType {
+    /// The type of values built with the constructors.
+    pub fn applied_type(&self) -> Type {
+        Type::Named(
+            self.name.clone(),
+            self.variables
+                .iter()
+                .map(|v| Type::Variable(v.clone()))
+                .collect(),
+        )
+    }
+
+    /// The scheme of the constructor seen as a value, a constructor
+    /// with argument is a function.
+    pub fn constructor_scheme(&self, index: usize) -> TypeScheme {
+        let output = self.applied_type();
+        let type_ = match &self.constructors[index].argument {
+            Some(argument) => Type::function(argument.clone(), output),
+            None => output,
+        };
+        TypeScheme {
+            variables: self.variables.clone(),
+            type_,
+        }
+    }
 }
 
-#[derive(Debug)]
+/// The aliases are expanded during the translation, we keep them
+/// to expand them in later inputs of the REPL.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Alias {
     pub span: Span,
-    pub _type: _type::Alias,
+    pub name: Identifier,
+    pub variables: Vec<Identifier>,
+    pub type_: Type,
 }
 
-#[derive(Debug)]
-pub struct VariableDeclaration {
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableDefinition {
     pub span: Span,
-    pub name: (Span, Identifier),
-    pub _type: _type::Type,
+    pub name: Identifier,
+    pub value: Expression,
+    /// The declared type, after the inference it is always present.
+    pub type_: Option<TypeScheme>,
 }
 
-#[derive(Debug)]
-pub struct VariableDefinition {
-    pub span: Span,
-    pub name: (Span, Identifier),
-    pub function: expression::Function,
+/// The simplified tree of a module, the names are already resolved.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Sast {
+    pub data_types: Vec<DataType>,
+    pub aliases: Vec<Alias>,
+    pub definitions: Vec<VariableDefinition>,
+}
+
+fn identifier(name: &str) -> Identifier {
+    // The names of the prelude are valid identifiers.
+    Identifier::make(String::from(name)).unwrap()
+}
+
+impl Sast {
+    /// The definitions every module can use.
+    /// The constructors of `Bool` must be in the order of the
+    /// indexes in [`octizys_core::primitives`].
+    pub fn prelude() -> Self {
+        let span = Span::from(((0, 0, 0), (0, 0, 0)));
+        let bool_data = DataType {
+            span,
+            name: identifier(BOOL_NAME),
+            variables: vec![],
+            constructors: vec![
+                DataConstructor {
+                    span,
+                    name: identifier(FALSE_NAME),
+                    argument: None,
+                },
+                DataConstructor {
+                    span,
+                    name: identifier(TRUE_NAME),
+                    argument: None,
+                },
+            ],
+            kind: DataKind::Data,
+        };
+        Sast {
+            data_types: vec![bool_data],
+            aliases: vec![],
+            definitions: vec![],
+        }
+    }
+
+    pub fn find_data(&self, name: &str) -> Option<&DataType> {
+        self.data_types.iter().find(|d| d.name.as_str() == name)
+    }
+
+    pub fn find_alias(&self, name: &str) -> Option<&Alias> {
+        self.aliases.iter().find(|a| a.name.as_str() == name)
+    }
+
+    pub fn find_definition(&self, name: &str) -> Option<&VariableDefinition> {
+        self.definitions.iter().find(|d| d.name.as_str() == name)
+    }
+
+    pub fn find_constructor(
+        &self,
+        name: &str,
+    ) -> Option<(ConstructorReference, &DataType)> {
+        self.data_types.iter().find_map(|data| {
+            data.constructors
+                .iter()
+                .position(|c| c.name.as_str() == name)
+                .map(|index| {
+                    (
+                        ConstructorReference {
+                            data: data.name.clone(),
+                            name: data.constructors[index].name.clone(),
+                            index: index as u64,
+                        },
+                        data,
+                    )
+                })
+        })
+    }
+
+    /// Renames the items and their uses using the map, the other
+    /// names are kept.
+    pub fn rename(&mut self, map: &HashMap<Identifier, Identifier>) {
+        let rename = |name: &mut Identifier| {
+            if let Some(new) = map.get(name) {
+                *name = new.clone();
+            }
+        };
+        for data in &mut self.data_types {
+            rename(&mut data.name);
+            for constructor in &mut data.constructors {
+                rename(&mut constructor.name);
+                if let Some(argument) = &mut constructor.argument {
+                    argument.rename(map)
+                }
+            }
+        }
+        for alias in &mut self.aliases {
+            rename(&mut alias.name);
+            alias.type_.rename(map);
+        }
+        for definition in &mut self.definitions {
+            rename(&mut definition.name);
+            definition.value.rename(map);
+            if let Some(type_) = &mut definition.type_ {
+                type_.rename(map)
+            }
+        }
+    }
+
+    /// Adds the items of other, an item with the same name of
+    /// an existing one replaces it.
+    pub fn extend(&mut self, other: Sast) {
+        for data in other.data_types {
+            self.data_types.retain(|d| d.name != data.name);
+            self.aliases.retain(|a| a.name != data.name);
+            self.data_types.push(data);
+        }
+        for alias in other.aliases {
+            self.data_types.retain(|d| d.name != alias.name);
+            self.aliases.retain(|a| a.name != alias.name);
+            self.aliases.push(alias);
+        }
+        for definition in other.definitions {
+            self.definitions.retain(|d| d.name != definition.name);
+            self.definitions.push(definition);
+        }
+    }
 }
@@ -3,11 +3,6 @@ use konst::string;
 use string_interner::DefaultStringInterner;
 use string_interner::DefaultSymbol;
 
-//TODO: change this to a better approximate using graphemes
-pub fn approximate_string_width(s: &str) -> usize {
-    s.chars().count()
-}
-
 /// Purpose: Build non line break strings at compilation time.
 /// # Panics!
 /// The exposed builder [`NonLineBreakStr::new`] panics if there
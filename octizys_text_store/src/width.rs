@@ -0,0 +1,113 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+static TAB_WIDTH: AtomicUsize = AtomicUsize::new(DEFAULT_TAB_WIDTH);
+
+/// Set the amount of columns between two tab stops.
+/// A value of 0 is replaced by [`DEFAULT_TAB_WIDTH`].
+///
+/// This is global as the widths of the documents are computed at
+/// construction and we want all of them to agree on it, set it
+/// before building any document.
+pub fn set_tab_width(width: usize) {
+    let width = if width == 0 { DEFAULT_TAB_WIDTH } else { width };
+    TAB_WIDTH.store(width, Ordering::Relaxed);
+}
+
+pub fn tab_width() -> usize {
+    TAB_WIDTH.load(Ordering::Relaxed)
+}
+
+/// The amount of columns that a single grapheme uses in a terminal.
+/// East Asian wide characters and emoji use two columns, combining
+/// marks don't add width to their base character.
+fn grapheme_width(grapheme: &str) -> usize {
+    // Sequences joined by zero width joiners are rendered as a single
+    // wide glyph by terminals that support them.
+    grapheme.width().min(2)
+}
+
+/// The amount of columns used to display the string if it starts at
+/// `start_column`. Tabs advance to the next tab stop.
+/// Line breaks aren't handled, they count as zero columns.
+pub fn display_width_at(s: &str, start_column: usize) -> usize {
+    let tab = tab_width();
+    let mut column = start_column;
+    for grapheme in s.graphemes(true) {
+        if grapheme == "\t" {
+            column += tab - column % tab;
+        } else {
+            column += grapheme_width(grapheme);
+        }
+    }
+    column - start_column
+}
+
+/// The amount of columns used to display the string if it starts
+/// at the beginning of a line.
+pub fn display_width(s: &str) -> usize {
+    display_width_at(s, 0)
+}
+
+/// Replaces the tabs by spaces, assuming the string starts at
+/// `start_column`, the resulting string has the same
+/// [`display_width_at`] as the original.
+pub fn expand_tabs(s: &str, start_column: usize) -> String {
+    if !s.contains('\t') {
+        return String::from(s);
+    }
+    let tab = tab_width();
+    let mut column = start_column;
+    let mut out = String::with_capacity(s.len());
+    for grapheme in s.graphemes(true) {
+        if grapheme == "\t" {
+            let spaces = tab - column % tab;
+            out.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            out.push_str(grapheme);
+            column += grapheme_width(grapheme);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod width_test {
+    use super::{display_width, display_width_at, expand_tabs};
+
+    #[test]
+    fn ascii() {
+        assert_eq!(display_width("hello"), 5);
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn wide() {
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("a😀b"), 4);
+    }
+
+    #[test]
+    fn combining() {
+        // e + combining acute accent
+        assert_eq!(display_width("e\u{301}"), 1);
+        // family emoji joined with zero width joiners
+        assert_eq!(display_width("👨\u{200D}👩\u{200D}👧"), 2);
+    }
+
+    #[test]
+    fn tabs() {
+        // Only the default tab width is used to avoid depending on the
+        // global state modified by other tests.
+        assert_eq!(display_width("\t"), 4);
+        assert_eq!(display_width("ab\t"), 4);
+        assert_eq!(display_width_at("\t", 3), 1);
+        assert_eq!(display_width("ab\tc"), 5);
+        assert_eq!(expand_tabs("a\tb", 0), "a   b");
+    }
+}